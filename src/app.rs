@@ -13,28 +13,38 @@ use iced_code_editor::CodeEditor;
 use iced_term::Terminal as IcedTerminal;
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::autocomplete::engine::Autocomplete;
 use crate::config::preferences::{self as prefs, EditorPreferences};
+use crate::config::project::{self, ProjectSettings};
 use crate::scripting::{self, EditorCommand};
 use crate::features::command_input::CommandInput;
 use crate::features::command_palette::CommandPalette;
 use crate::features::file_tree::FileTree;
 use crate::features::find_replace::FindReplace;
+use crate::features::fonts;
 use crate::features::fuzzy_finder::FuzzyFinder;
+use crate::features::output;
+use crate::features::snippets::{self, SnippetConfig};
+use crate::features::tasks::TaskSpec;
 use crate::features::terminal::Terminal;
 use crate::features::updater::UpdateInfo;
 use crate::message::Message;
 use crate::theme::*;
 use crate::ui::{
-    editor_container_style, empty_editor, file_finder_item_style, file_finder_panel_style,
-    search_input_style, search_panel_style, sidebar_editor_separator_style, status_bar_style,
-    tab_bar_style, tab_button_style, tab_close_button_style, tree_button_style, view_sidebar,
+    context_menu_style, editor_container_style, empty_editor, file_finder_item_style,
+    file_finder_panel_style, search_input_style, search_panel_style,
+    sidebar_editor_separator_style, status_bar_style, tab_bar_style, tab_button_style,
+    tab_close_button_style, tree_button_style, view_sidebar,
 };
 use crate::wakatime::{self, WakaTimeConfig};
 
 mod commands;
+mod editing;
+mod events;
 mod lifecycle;
 mod subscription;
 mod update;
@@ -52,6 +62,8 @@ pub enum TabKind {
     },
     /// markdown preview for an editor tab.
     Preview { md_items: Vec<markdown::Item> },
+    /// Read-only view of an image or binary file.
+    Viewer(crate::features::file_viewer::Viewer),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,6 +77,7 @@ impl std::fmt::Debug for TabKind {
         match self {
             TabKind::Editor { .. } => f.debug_struct("Editor").finish_non_exhaustive(),
             TabKind::Preview { .. } => f.debug_struct("Preview").finish_non_exhaustive(),
+            TabKind::Viewer(viewer) => f.debug_tuple("Viewer").field(viewer).finish(),
         }
     }
 }
@@ -74,6 +87,8 @@ pub struct Tab {
     pub path: PathBuf,
     pub name: String,
     pub kind: TabKind,
+    /// Pinned tabs stay leftmost and are kept by "Close Others".
+    pub pinned: bool,
 }
 
 pub struct MarkdownPreviewPane {
@@ -81,11 +96,28 @@ pub struct MarkdownPreviewPane {
     pub state: MarkState,
 }
 
-/// toast notification metadata.
+/// Text shown in the panel below the editor, such as formatter errors and
+/// task output.
 #[derive(Debug, Clone)]
-pub struct Notification {
-    pub message: String,
-    pub shown_at: Instant,
+pub struct OutputPanel {
+    pub title: String,
+    pub lines: Vec<Vec<output::Segment>>,
+    ansi: output::AnsiState,
+}
+
+impl OutputPanel {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            lines: Vec::new(),
+            ansi: output::AnsiState::default(),
+        }
+    }
+
+    pub fn push_line(&mut self, line: &str) {
+        let segments = output::parse_line(line, &mut self.ansi);
+        self.lines.push(segments);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -98,13 +130,76 @@ struct PendingHoverRequest {
 }
 
 const HOVER_TRIGGER_DELAY: Duration = Duration::from_secs(2);
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
 
 pub struct App {
     tabs: Vec<Tab>,
     active_tab: Option<usize>,
+    /// Encodings of open files not read as plain UTF-8.
+    file_encodings: HashMap<PathBuf, crate::features::encoding::Encoding>,
+    /// Swap files of unsaved buffers, for crash recovery.
+    recovery: crate::features::recovery::Recovery,
+    /// Buffers left by a session that crashed, offered for restoring.
+    pending_recovery: Vec<crate::features::recovery::Recovered>,
+    /// Color literal being edited with the color picker.
+    color_picking: Option<crate::features::colors::Picking>,
+    /// Places left by jumping, for Go Back and Go Forward.
+    jumps: crate::features::jumps::JumpList,
+    /// Text a jump just landed on, highlighted for a moment.
+    jump_flash: Option<crate::features::jumps::JumpFlash>,
+    scroll_animation: Option<crate::features::smooth_scroll::ScrollAnimation>,
+    /// Horizontal scroll of editors by path, for drawing over their text.
+    editor_scroll_x: HashMap<PathBuf, f32>,
+    /// Container around the active editor, for scrolling it back to where
+    /// a tab was left.
+    editor_container_id: iced::widget::Id,
+    /// Cursor positions of closed files, restored when they are reopened.
+    last_positions: crate::config::positions::LastPositions,
+    /// Line copied or cut without a selection, pasted back as a whole
+    /// line above the cursor while the clipboard still holds it.
+    copied_line: Option<String>,
+    /// Column Up and Down try to return to, kept while moving through
+    /// shorter lines. `usize::MAX` keeps the cursor at line ends.
+    goal_column: Option<usize>,
+    /// Monospaced font families offered in the settings, listed when they open.
+    font_families: Vec<String>,
+    /// Words accepted by the spell checker.
+    spelling: crate::features::spelling::Dictionary,
+    /// Rename waiting for confirmation, with its preview.
+    pending_rename: Option<crate::features::rename::Rename>,
+    /// Comparison shown in place of the editor.
+    diff_view: Option<crate::features::diff::DiffView>,
+    /// File whose saved versions the local history panel lists, and the
+    /// versions, newest first.
+    local_history: Option<(PathBuf, Vec<crate::features::local_history::Version>)>,
+    /// Modification times of open files when last read or written, to
+    /// notice other programs changing them.
+    disk_stamps: HashMap<PathBuf, std::time::SystemTime>,
+    /// Misspelled word whose suggestions are being offered.
+    pending_spelling: Option<crate::features::spelling::Suggestions>,
+    /// Local editing time, shown in the activity dashboard.
+    activity: crate::features::activity::Activity,
+    activity_visible: bool,
+    /// Line endings of open files read with CRLF.
+    file_line_endings: HashMap<PathBuf, crate::features::line_endings::LineEnding>,
+    /// Paths of visited tabs, most recent first.
+    tab_history: Vec<PathBuf>,
+    /// Paths of closed tabs, most recently closed last.
+    closed_tabs: Vec<PathBuf>,
+    /// Highlighted row of the buffer switcher while it is open.
+    buffer_switcher: Option<usize>,
+    /// Whether the list of every open tab, for tabs scrolled out of the
+    /// tab bar, is open.
+    tab_list_open: bool,
 
     cursor_line: usize,
     cursor_col: usize,
+    last_editor_click: Option<(Instant, (usize, usize))>,
+    /// Where the active editor's selection started (0-based line, column).
+    selection_anchor: Option<(usize, usize)>,
+    /// Ranges picked by Expand Selection, innermost first, as byte offsets.
+    expanded_selections: Vec<(usize, usize)>,
+    keyboard_modifiers: iced::keyboard::Modifiers,
 
     file_tree: Option<FileTree>,
 
@@ -126,16 +221,29 @@ pub struct App {
     file_finder_selected: usize,
 
     all_workspace_files: Vec<(String, PathBuf)>,
+    git_branch: Option<String>,
+    git_panel_visible: bool,
+    git_changes: Vec<crate::features::git::GitChange>,
+    git_commit_message: String,
+    git_error: Option<String>,
     recent_files: Vec<PathBuf>,
+    recent_folders: Vec<PathBuf>,
 
     file_finder_input_id: iced::widget::Id,
     fuzzy_finder: FuzzyFinder,
 
     command_palette: CommandPalette,
+    /// Normalized key chords mapped to actions by init.lua.
+    user_keymaps: HashMap<String, String>,
+    /// Commands defined by init.lua, by name.
+    user_commands: HashMap<String, Vec<String>>,
+    autocommands: Vec<scripting::AutoCommand>,
     command_palette_selected: usize,
     command_palette_input_id: iced::widget::Id,
 
     markdown_preview: Option<MarkdownPreviewPane>,
+    markdown_preview_scroll_id: iced::widget::Id,
+    preview_scroll_locked: bool,
 
     terminal: Terminal,
     terminal_pane: Option<IcedTerminal>,
@@ -149,10 +257,17 @@ pub struct App {
 
     command_input: CommandInput,
     command_input_id: iced::widget::Id,
+    /// Column edit waiting for its text from the command input.
+    pending_block_edit: Option<crate::message::BlockEdit>,
 
     settings_open: bool,
     settings_section: String,
     editor_preferences: EditorPreferences,
+    /// Settings of the open folder, applied over `editor_preferences`.
+    project_settings: ProjectSettings,
+    /// The user's own preferences, without the open folder's settings.
+    /// This is what gets saved.
+    user_preferences: EditorPreferences,
     active_theme_name: String,
     theme_dropdown_open: bool,
 
@@ -160,8 +275,16 @@ pub struct App {
     wakatime_api_key_hovered: bool,
     last_wakatime_entity: Option<String>,
     last_wakatime_sent_at: Option<Instant>,
-
-    notification: Option<Notification>,
+    snippets: SnippetConfig,
+
+    notifications: crate::features::notifications::Notifications,
+    output_panel: Option<OutputPanel>,
+    /// Tasks of the open folder, offered in the command palette.
+    project_tasks: Vec<TaskSpec>,
+    /// Handle of the running task, aborting it kills the process.
+    running_task: Option<iced::task::Handle>,
+    /// Position to move to once a file being opened has loaded.
+    pending_cursor: Option<(PathBuf, usize, usize)>,
     update_banner: Option<UpdateInfo>,
 
     lsp: crate::features::lsp::LspManager,
@@ -170,8 +293,20 @@ pub struct App {
     lsp_enabled: bool,
     lsp_server_keys: HashMap<PathBuf, &'static str>,
     pending_hover_request: Option<PendingHoverRequest>,
+    /// Queries waiting for typing to pause, aborted when typed over.
+    debounced: HashMap<crate::message::Debounce, iced::task::Handle>,
+    /// Workspace find count in progress, aborted when the query changes.
+    /// Setting the flag stops its scan on the worker pool.
+    find_count: Option<(iced::task::Handle, Arc<AtomicBool>)>,
 
     pending_sensitive_open: Option<PathBuf>,
+    pending_large_open: Option<(PathBuf, u64)>,
+    pending_large_paste: Option<String>,
+    /// Number of unsaved files while the quit prompt is shown.
+    pending_quit: Option<usize>,
+    /// Matches and files a workspace Replace All is waiting to change.
+    pending_replace_all: Option<(usize, usize)>,
+    large_file_mode_paths: std::collections::HashSet<PathBuf>,
 
     autocomplete: Autocomplete,
 
@@ -213,11 +348,46 @@ impl Default for App {
             }
         };
 
+        let recent = crate::config::recent::load_recent();
+
+        let mut command_palette = CommandPalette::default();
+        command_palette.set_usage(crate::config::command_usage::load_command_usage());
+
         let mut app = Self {
             tabs: Vec::new(),
             active_tab: None,
+            file_encodings: HashMap::new(),
+            recovery: Default::default(),
+            pending_recovery: crate::features::recovery::orphaned(),
+            color_picking: None,
+            jumps: Default::default(),
+            jump_flash: None,
+            scroll_animation: None,
+            editor_scroll_x: HashMap::new(),
+            editor_container_id: iced::widget::Id::unique(),
+            last_positions: crate::config::positions::load_positions(),
+            goal_column: None,
+            copied_line: None,
+            font_families: Vec::new(),
+            spelling: crate::features::spelling::load(),
+            pending_spelling: None,
+            pending_rename: None,
+            diff_view: None,
+            local_history: None,
+            disk_stamps: HashMap::new(),
+            activity: crate::features::activity::load(),
+            activity_visible: false,
+            file_line_endings: HashMap::new(),
+            tab_history: Vec::new(),
+            closed_tabs: Vec::new(),
+            buffer_switcher: None,
+            tab_list_open: false,
             cursor_line: 1,
             cursor_col: 1,
+            last_editor_click: None,
+            selection_anchor: None,
+            expanded_selections: Vec::new(),
+            keyboard_modifiers: iced::keyboard::Modifiers::default(),
             file_tree: None,
             sidebar_visible: true,
             sidebar_width: SIDEBAR_DEFAULT_WIDTH,
@@ -233,15 +403,26 @@ impl Default for App {
             file_finder_results: Vec::new(),
             file_finder_selected: 0,
             all_workspace_files: Vec::new(),
-            recent_files: Vec::new(),
+            git_branch: None,
+            git_panel_visible: false,
+            git_changes: Vec::new(),
+            git_commit_message: String::new(),
+            git_error: None,
+            recent_files: recent.files,
+            recent_folders: recent.folders,
             file_finder_input_id: iced::widget::Id::unique(),
             fuzzy_finder: FuzzyFinder::default(),
 
-            command_palette: CommandPalette::default(),
+            command_palette,
+            user_keymaps: HashMap::new(),
+            user_commands: HashMap::new(),
+            autocommands: Vec::new(),
             command_palette_selected: 0,
             command_palette_input_id: iced::widget::Id::unique(),
 
             markdown_preview: None,
+            markdown_preview_scroll_id: iced::widget::Id::unique(),
+            preview_scroll_locked: false,
 
             terminal: Terminal::default(),
             terminal_pane: {
@@ -274,18 +455,26 @@ impl Default for App {
             find_replace: FindReplace::default(),
             find_input_id: iced::widget::Id::unique(),
             replace_input_id: iced::widget::Id::unique(),
-            command_input: CommandInput::default(),
+            command_input: CommandInput::with_history(crate::config::history::load_history()),
             command_input_id: iced::widget::Id::unique(),
+            pending_block_edit: None,
             settings_open: false,
             settings_section: "general".to_string(),
+            user_preferences: editor_preferences.clone(),
             editor_preferences,
+            project_settings: ProjectSettings::default(),
             active_theme_name,
             theme_dropdown_open: false,
             wakatime: wakatime::load(),
             wakatime_api_key_hovered: false,
             last_wakatime_entity: None,
             last_wakatime_sent_at: None,
-            notification: None,
+            snippets: snippets::load(),
+            notifications: Default::default(),
+            output_panel: None,
+            project_tasks: Vec::new(),
+            running_task: None,
+            pending_cursor: None,
             update_banner: None,
             lsp: crate::features::lsp::LspManager::new(),
             lsp_diagnostics: HashMap::new(),
@@ -293,7 +482,14 @@ impl Default for App {
             lsp_enabled: true,
             lsp_server_keys: HashMap::new(),
             pending_hover_request: None,
+            debounced: HashMap::new(),
+            find_count: None,
             pending_sensitive_open: None,
+            pending_large_open: None,
+            pending_large_paste: None,
+            pending_quit: None,
+            pending_replace_all: None,
+            large_file_mode_paths: std::collections::HashSet::new(),
             autocomplete: Autocomplete::new(),
             developer_logs: VecDeque::new(),
             developer_panel_visible: false,
@@ -349,18 +545,26 @@ impl App {
     pub(super) fn configured_code_editor(&self, content: &str, syntax: &str) -> CodeEditor {
         let mut editor = iced_code_editor::CodeEditor::new(content, syntax);
         editor.set_theme(theme().editor_style);
-        editor.set_font(iced::Font {
-            family: iced::font::Family::Name("Fira Code"),
-            weight: iced::font::Weight::Normal,
-            ..iced::Font::DEFAULT
-        });
+        editor.set_font(fonts::editor_font(&self.editor_preferences.font_family));
         editor.set_search_replace_enabled(false);
         editor.set_line_numbers_enabled(true);
-        editor.set_wrap_enabled(false);
-        editor.set_font_size(13.0, true);
+        editor.set_wrap_enabled(self.editor_preferences.word_wrap);
+        editor.set_font_size(self.editor_preferences.font_size, true);
         editor
     }
 
+    /// Applies the font preferences to every open editor.
+    pub(super) fn apply_editor_font_to_tabs(&mut self) {
+        let font = fonts::editor_font(&self.editor_preferences.font_family);
+        let size = self.editor_preferences.font_size;
+        for tab in &mut self.tabs {
+            if let TabKind::Editor { code_editor, .. } = &mut tab.kind {
+                code_editor.set_font(font);
+                code_editor.set_font_size(size, true);
+            }
+        }
+    }
+
     pub(super) fn apply_editor_theme_to_tabs(&mut self) {
         let editor_style = theme().editor_style;
 
@@ -406,6 +610,25 @@ impl App {
             EditorCommand::SetSidebarWidth(width) => {
                 self.sidebar_width = width.clamp(SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH);
             }
+            EditorCommand::MapKey { keys, action } => {
+                match crate::features::keymap::normalize(&keys) {
+                    Some(chord) => {
+                        self.user_keymaps.insert(chord, action);
+                    }
+                    None => eprintln!("Lua keymap error: unknown key '{keys}'"),
+                }
+            }
+            EditorCommand::AddCommand { name, actions } => {
+                self.command_palette
+                    .add_user_command(crate::features::command_palette::Command {
+                        name: name.clone(),
+                        description: "Defined in init.lua".to_string(),
+                    });
+                self.user_commands.insert(name, actions);
+            }
+            EditorCommand::AddAutoCommand(autocommand) => {
+                self.autocommands.push(autocommand);
+            }
         }
     }
 }
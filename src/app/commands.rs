@@ -16,13 +16,89 @@
 //! a command action
 
 use super::*;
+use crate::features::line_endings::LineEnding;
+use crate::features::{command_input, command_palette};
+use crate::message::{BlockEdit, Clipboard, LineEdit, QuitAction, Transform, Zoom};
 
 impl App {
+    /// Runs a palette command, a user command or a `:` command line.
+    /// User commands can't run other user commands, so `allow_user` is false
+    /// for their own actions.
+    pub(super) fn run_action(&mut self, action: &str, allow_user: bool) -> iced::Task<Message> {
+        if let Some(line) = action.strip_prefix(':') {
+            return match command_input::parse(line) {
+                Some(command) => self.run_ex_command(command),
+                None => {
+                    self.notifications
+                        .info(format!("Unknown command: {action}"));
+                    iced::Task::none()
+                }
+            };
+        }
+        if !allow_user && self.user_commands.contains_key(action) {
+            eprintln!("Lua command error: '{action}' can't run another user command");
+            return iced::Task::none();
+        }
+        self.execute_palette_command(action)
+    }
+
     pub(super) fn execute_palette_command(&mut self, command: &str) -> iced::Task<Message> {
         match command {
-            "Toggle Sidebar" => {
+            "Toggle Sidebar" | "Toggle File Tree" => {
                 self.sidebar_visible = !self.sidebar_visible;
             }
+            "Compare Active File With..." => {
+                return iced::Task::done(Message::CompareActiveFileDialog);
+            }
+            "Compare Active File With Saved" => {
+                return iced::Task::done(Message::CompareActiveFile(None));
+            }
+            "Toggle Hidden Files" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleHiddenFiles);
+            }
+            "Reveal Active File in File Tree" | "Copy Path" | "Copy Relative Path" => {
+                let Some(path) = self
+                    .active_tab
+                    .and_then(|idx| self.tabs.get(idx))
+                    .map(|tab| tab.path.clone())
+                else {
+                    return iced::Task::none();
+                };
+                return iced::Task::done(match command {
+                    "Copy Path" => Message::CopyPath {
+                        path,
+                        relative: false,
+                    },
+                    "Copy Relative Path" => Message::CopyPath {
+                        path,
+                        relative: true,
+                    },
+                    _ => Message::RevealInTree(path),
+                });
+            }
+            "Cut" => {
+                return iced::Task::perform(async {}, |_| Message::Clipboard(Clipboard::Cut));
+            }
+            "Copy" => {
+                return iced::Task::perform(async {}, |_| Message::Clipboard(Clipboard::Copy));
+            }
+            "Paste" => {
+                return iced::Task::perform(async {}, |_| Message::Clipboard(Clipboard::Paste));
+            }
+            "Find in Files" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleSearch);
+            }
+            "Reload Theme" => {
+                return iced::Task::perform(async {}, |_| Message::SettingsReloadTheme);
+            }
+            "Quit" => {
+                return iced::Task::perform(async {}, |_| Message::QuitRequested);
+            }
+            "Save and Quit" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::QuitConfirm(QuitAction::SaveAndQuit)
+                });
+            }
             "Open File" => {
                 return iced::Task::perform(async {}, |_| Message::OpenFileDialog);
             }
@@ -48,6 +124,7 @@ impl App {
                         code_editor: editor,
                         buffer: crate::features::editor_buffer::EditorBuffer::from_text(""),
                     },
+                    pinned: false,
                 });
                 self.active_tab = Some(self.tabs.len() - 1);
                 self.vim_refresh_cursor_style();
@@ -69,15 +146,255 @@ impl App {
             "Save As" => {
                 return iced::Task::perform(async {}, |_| Message::SaveAs);
             }
+            "Go Back" => {
+                return iced::Task::done(Message::JumpBack);
+            }
+            "Go Forward" => {
+                return iced::Task::done(Message::JumpForward);
+            }
+            "Share Snippet" => {
+                return iced::Task::done(Message::ShareSnippet);
+            }
+            "Export as HTML" => {
+                return iced::Task::done(Message::ExportHtml);
+            }
             "Toggle Fullscreen" => {
                 return iced::Task::perform(async {}, |_| {
                     Message::ToggleFullscreen(window::Mode::Fullscreen)
                 });
             }
+            "Source Control" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleGitPanel);
+            }
             "Render Markdown" => {
                 return iced::Task::perform(async {}, |_| Message::PreviewMarkdown);
             }
-            _ => {}
+            "Toggle Preview Scroll Lock" => {
+                return iced::Task::perform(async {}, |_| Message::TogglePreviewScrollLock);
+            }
+            "Indent Lines" => {
+                return iced::Task::perform(async {}, |_| Message::EditLines(LineEdit::Indent));
+            }
+            "Outdent Lines" => {
+                return iced::Task::perform(async {}, |_| Message::EditLines(LineEdit::Outdent));
+            }
+            "Reindent Lines" => {
+                return iced::Task::perform(async {}, |_| Message::EditLines(LineEdit::Reindent));
+            }
+            "Go to Line" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleCommandInput);
+            }
+            "Format Document" => {
+                return iced::Task::perform(async {}, |_| Message::FormatDocument);
+            }
+            "Toggle Word Wrap" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleWordWrap);
+            }
+            "Toggle Comment" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::EditLines(LineEdit::ToggleComment)
+                });
+            }
+            "Reflow Long Lines" => {
+                return iced::Task::perform(async {}, |_| Message::EditLines(LineEdit::Reflow));
+            }
+            "Expand Selection" => {
+                return iced::Task::perform(async {}, |_| Message::ExpandSelection);
+            }
+            "Shrink Selection" => {
+                return iced::Task::perform(async {}, |_| Message::ShrinkSelection);
+            }
+            "Delete Surrounding Pair" => {
+                return iced::Task::perform(async {}, |_| Message::DeleteSurroundingPair);
+            }
+            "Insert Before Block" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::PromptBlockEdit(BlockEdit::Insert)
+                });
+            }
+            "Append After Block" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::PromptBlockEdit(BlockEdit::Append)
+                });
+            }
+            "Move Line Up" => {
+                return iced::Task::perform(async {}, |_| Message::MoveLines {
+                    up: true,
+                    duplicate: false,
+                });
+            }
+            "Move Line Down" => {
+                return iced::Task::perform(async {}, |_| Message::MoveLines {
+                    up: false,
+                    duplicate: false,
+                });
+            }
+            "Duplicate Line" => {
+                return iced::Task::perform(async {}, |_| Message::MoveLines {
+                    up: false,
+                    duplicate: true,
+                });
+            }
+            "Duplicate Selection" => {
+                return iced::Task::perform(async {}, |_| Message::DuplicateSelection);
+            }
+            "Join Lines" => {
+                return iced::Task::perform(async {}, |_| Message::JoinLines);
+            }
+            "Sort Lines Ascending" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::SortAscending)
+                });
+            }
+            "Sort Lines Descending" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::SortDescending)
+                });
+            }
+            "Sort Lines Unique" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::SortUnique)
+                });
+            }
+            "Reverse Lines" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::Reverse)
+                });
+            }
+            "Transform to Uppercase" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::UpperCase)
+                });
+            }
+            "Transform to Lowercase" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::LowerCase)
+                });
+            }
+            "Transform to Title Case" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::TitleCase)
+                });
+            }
+            "Base64 Encode" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::Base64Encode)
+                });
+            }
+            "Base64 Decode" => {
+                return iced::Task::perform(async {}, |_| {
+                    Message::TransformSelection(Transform::Base64Decode)
+                });
+            }
+            "Increment Number" => {
+                return iced::Task::perform(async {}, |_| Message::StepNumbers {
+                    delta: 1,
+                    sequential: false,
+                });
+            }
+            "Decrement Number" => {
+                return iced::Task::perform(async {}, |_| Message::StepNumbers {
+                    delta: -1,
+                    sequential: false,
+                });
+            }
+            "Increment Numbers Sequentially" => {
+                return iced::Task::perform(async {}, |_| Message::StepNumbers {
+                    delta: 1,
+                    sequential: true,
+                });
+            }
+            "Switch Buffer" => {
+                return iced::Task::perform(async {}, |_| Message::BufferSwitcherNext(1));
+            }
+            "Pin Tab" => {
+                if let Some(idx) = self.active_tab {
+                    return iced::Task::done(Message::TogglePinTab(idx));
+                }
+            }
+            "Reopen Closed Tab" => {
+                return iced::Task::perform(async {}, |_| Message::ReopenClosedTab);
+            }
+            "Notification History" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleNotificationHistory);
+            }
+            "Clear Notifications" => {
+                return iced::Task::perform(async {}, |_| Message::ClearNotifications);
+            }
+            "Rename Symbol" => {
+                return iced::Task::perform(async {}, |_| Message::StartRename);
+            }
+            "Go to Definition" => {
+                return iced::Task::perform(async {}, |_| Message::GoToDefinition);
+            }
+            "Toggle Spell Check" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleSpellCheck);
+            }
+            "Next Misspelling" => {
+                return iced::Task::perform(async {}, |_| Message::NextMisspelling);
+            }
+            "Spelling Suggestions" => {
+                return iced::Task::perform(async {}, |_| Message::ShowSpellingSuggestions);
+            }
+            "Pick Color" => {
+                let (line, col) = (self.cursor_line - 1, self.cursor_col - 1);
+                return iced::Task::perform(async {}, move |_| Message::PickColor(line, col));
+            }
+            "Toggle Smooth Scrolling" => {
+                return iced::Task::done(Message::ToggleSmoothScroll);
+            }
+            "Toggle Sticky Scroll" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleStickyScroll);
+            }
+            "Render Whitespace" => {
+                return iced::Task::perform(async {}, |_| Message::CycleRenderWhitespace);
+            }
+            "Zoom In" => {
+                return iced::Task::perform(async {}, |_| Message::Zoom(Zoom::In));
+            }
+            "Zoom Out" => {
+                return iced::Task::perform(async {}, |_| Message::Zoom(Zoom::Out));
+            }
+            "Reset Zoom" => {
+                return iced::Task::perform(async {}, |_| Message::Zoom(Zoom::Reset));
+            }
+            "Word Count" => {
+                return iced::Task::perform(async {}, |_| Message::ShowWordCount);
+            }
+            "Local History" => {
+                return iced::Task::done(Message::ToggleLocalHistory);
+            }
+            "Show Activity" => {
+                return iced::Task::perform(async {}, |_| Message::ToggleActivity);
+            }
+            "Convert Line Endings to LF" => {
+                return iced::Task::perform(async {}, |_| Message::SetLineEnding(LineEnding::Lf));
+            }
+            "Convert Line Endings to CRLF" => {
+                return iced::Task::perform(async {}, |_| Message::SetLineEnding(LineEnding::Crlf));
+            }
+            "Convert to UTF-8" => {
+                return iced::Task::perform(async {}, |_| Message::ConvertToUtf8);
+            }
+            "Go to Symbol" => {
+                return iced::Task::perform(async {}, |_| Message::GoToSymbol);
+            }
+            "Stop Task" => {
+                return iced::Task::perform(async {}, |_| Message::StopTask);
+            }
+            name => {
+                if let Some(task) = name.strip_prefix(command_palette::TASK_PREFIX) {
+                    let task = task.to_string();
+                    return iced::Task::perform(async {}, move |_| Message::RunTask(task.clone()));
+                }
+                if let Some(actions) = self.user_commands.get(name).cloned() {
+                    let tasks: Vec<_> = actions
+                        .iter()
+                        .map(|action| self.run_action(action, false))
+                        .collect();
+                    return iced::Task::batch(tasks);
+                }
+            }
         }
         self.vim_refresh_cursor_style();
         iced::Task::none()
@@ -0,0 +1,509 @@
+//! Text helpers shared by editor commands.
+//!
+//! The code editor widget only exposes message based editing, so these
+//! helpers drive it with cursor and selection messages.
+//!
+//! Columns are counted in characters, like the widget's cursor. Convert
+//! them with `byte_index` before slicing a line.
+
+use iced_code_editor::{ArrowDirection, CodeEditor, Message as EditorMessage};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::features::{pairs, tags};
+
+/// Selects the text between two `(line, col)` positions, both 0-based.
+///
+/// Every cursor move makes the widget work out its scroll, so whole lines
+/// are crossed with End and Right rather than a character at a time.
+pub(super) fn select_range(
+    code_editor: &mut CodeEditor,
+    content: &str,
+    start: (usize, usize),
+    end: (usize, usize),
+) {
+    let (start, end) = if start <= end {
+        (start, end)
+    } else {
+        (end, start)
+    };
+    let _ = code_editor.update(&EditorMessage::GotoPosition(start.0, start.1));
+    for _ in start.0..end.0 {
+        let _ = code_editor.update(&EditorMessage::End(true));
+        let _ = code_editor.update(&EditorMessage::ArrowKey(ArrowDirection::Right, true));
+    }
+    let from = if start.0 == end.0 { start.1 } else { 0 };
+    let line_len = content
+        .split('\n')
+        .nth(end.0)
+        .map_or(0, |line| line.chars().count());
+    if end.1 >= line_len && end.1 > from {
+        let _ = code_editor.update(&EditorMessage::End(true));
+    } else {
+        for _ in from..end.1 {
+            let _ = code_editor.update(&EditorMessage::ArrowKey(ArrowDirection::Right, true));
+        }
+    }
+}
+
+/// First and last line touched by `selection`, or the cursor line. A
+/// selection ending at the start of a line does not include that line.
+pub(super) fn line_span(
+    selection: Option<((usize, usize), (usize, usize))>,
+    cursor: (usize, usize),
+) -> (usize, usize) {
+    match selection {
+        Some((start, end)) if end.1 == 0 && end.0 > start.0 => (start.0, end.0 - 1),
+        Some((start, end)) => (start.0, end.0),
+        None => (cursor.0, cursor.0),
+    }
+}
+
+/// Byte offset of the character at column `col` of `line`, or the length
+/// of the line past its end.
+pub(super) fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices()
+        .nth(col)
+        .map_or(line.len(), |(idx, _)| idx)
+}
+
+/// The word characters right before column `col`, like a prefix being
+/// completed.
+pub(super) fn word_before(line: &str, col: usize) -> &str {
+    let before = &line[..byte_index(line, col)];
+    let start = before
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map_or(before.len(), |(idx, _)| idx);
+    &before[start..]
+}
+
+/// Column after the grapheme cluster at `col`, so an emoji sequence or a
+/// letter with its accents is stepped over as one. `None` at line end.
+pub(super) fn next_grapheme(line: &str, col: usize) -> Option<usize> {
+    let grapheme = line[byte_index(line, col)..].graphemes(true).next()?;
+    Some(col + grapheme.chars().count())
+}
+
+/// Column before the grapheme cluster ending at `col`, `None` at line start.
+pub(super) fn previous_grapheme(line: &str, col: usize) -> Option<usize> {
+    let grapheme = line[..byte_index(line, col)].graphemes(true).next_back()?;
+    Some(col - grapheme.chars().count())
+}
+
+/// Moves or deletes across a whole grapheme cluster when the widget,
+/// which steps one character at a time, would stop inside one: in an
+/// emoji sequence or between a letter and its combining accents.
+///
+/// `None` leaves the event to the widget.
+pub(super) fn step_grapheme(
+    code_editor: &mut CodeEditor,
+    content: &str,
+    event: &EditorMessage,
+) -> Option<iced::Task<EditorMessage>> {
+    let (line, col) = code_editor.cursor_position();
+    let text = content.lines().nth(line).unwrap_or_default();
+    let task = match event {
+        EditorMessage::ArrowKey(ArrowDirection::Right, false) => {
+            let to = next_grapheme(text, col).filter(|to| to - col > 1)?;
+            code_editor.update(&EditorMessage::GotoPosition(line, to))
+        }
+        EditorMessage::ArrowKey(ArrowDirection::Left, false) => {
+            let to = previous_grapheme(text, col).filter(|to| col - to > 1)?;
+            code_editor.update(&EditorMessage::GotoPosition(line, to))
+        }
+        EditorMessage::Backspace => {
+            let from = previous_grapheme(text, col).filter(|from| col - from > 1)?;
+            replace_range(code_editor, content, (line, from), (line, col), "")
+        }
+        EditorMessage::Delete => {
+            let to = next_grapheme(text, col).filter(|to| to - col > 1)?;
+            replace_range(code_editor, content, (line, col), (line, to), "")
+        }
+        _ => return None,
+    };
+    Some(task)
+}
+
+/// Character range of the word under `col`, if any.
+pub(super) fn word_bounds(line: &str, col: usize) -> Option<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let at = if chars.get(col).is_some_and(|c| is_word(*c)) {
+        col
+    } else if col > 0 && chars.get(col - 1).is_some_and(|c| is_word(*c)) {
+        col - 1
+    } else {
+        return None;
+    };
+
+    let start = chars[..at]
+        .iter()
+        .rposition(|c| !is_word(*c))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = chars[at..]
+        .iter()
+        .position(|c| !is_word(*c))
+        .map(|i| at + i)
+        .unwrap_or(chars.len());
+    Some((start, end))
+}
+
+/// Converts a 0-based `(line, col)` position into a byte offset.
+pub(super) fn position_to_offset(content: &str, position: (usize, usize)) -> usize {
+    let mut offset = 0;
+    for (idx, line) in content.split('\n').enumerate() {
+        if idx == position.0 {
+            return offset + byte_index(line, position.1);
+        }
+        offset += line.len() + 1;
+    }
+    content.len()
+}
+
+/// Converts a byte offset into a 0-based `(line, col)` position.
+pub(super) fn offset_to_position(content: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(content.len());
+    let before = &content[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count())
+}
+
+/// Line `line` of `content` with its line break, as copied without a
+/// selection, and the range removing it when cut. The last line takes
+/// the line break before it instead.
+pub(super) fn whole_line(content: &str, line: usize) -> (String, (usize, usize), (usize, usize)) {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let line = line.min(lines.len() - 1);
+    let text = format!("{}\n", lines[line]);
+    let range = if line + 1 < lines.len() {
+        ((line, 0), (line + 1, 0))
+    } else if line > 0 {
+        (
+            (line - 1, lines[line - 1].chars().count()),
+            (line, lines[line].chars().count()),
+        )
+    } else {
+        ((0, 0), (0, lines[0].chars().count()))
+    };
+    (text, range.0, range.1)
+}
+
+/// Joins `lines` into one, like Vim's `J`: the leading whitespace of each
+/// joined line becomes a single space, left out next to an empty line or
+/// before a `)`. Also returns the column of the last join.
+pub(super) fn join_lines(lines: &[&str]) -> (String, usize) {
+    let mut joined = lines.first().copied().unwrap_or_default().to_string();
+    let mut col = joined.chars().count();
+    for line in lines.iter().skip(1) {
+        let line = line.trim_start();
+        let trimmed_len = joined.trim_end().len();
+        joined.truncate(trimmed_len);
+        col = joined.chars().count();
+        if !joined.is_empty() && !line.is_empty() && !line.starts_with(')') {
+            joined.push(' ');
+        }
+        joined.push_str(line);
+    }
+    (joined, col)
+}
+
+/// Replaces the text between two positions with `text` as a single edit.
+pub(super) fn replace_range(
+    code_editor: &mut CodeEditor,
+    content: &str,
+    start: (usize, usize),
+    end: (usize, usize),
+    text: &str,
+) -> iced::Task<EditorMessage> {
+    select_range(code_editor, content, start, end);
+    if text.is_empty() {
+        code_editor.update(&EditorMessage::DeleteSelection)
+    } else {
+        // An empty paste would read the clipboard instead
+        code_editor.update(&EditorMessage::Paste(text.to_string()))
+    }
+}
+
+/// Replaces `content` with `new_text` by editing only the lines between
+/// their shared head and tail, so undo stays a single small step. The cursor
+/// stays on the same line of the unchanged text.
+pub(super) fn replace_changed_lines(
+    code_editor: &mut CodeEditor,
+    content: &str,
+    new_text: &str,
+) -> Option<iced::Task<EditorMessage>> {
+    if content == new_text {
+        return None;
+    }
+    let old: Vec<&str> = content.split('\n').collect();
+    let new: Vec<&str> = new_text.split('\n').collect();
+    let head = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let tail = old[head..]
+        .iter()
+        .rev()
+        .zip(new[head..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_end, new_end) = (old.len() - tail, new.len() - tail);
+    let replacement = new[head..new_end].join("\n");
+    let line_len = |idx: usize| old[idx].chars().count();
+
+    let cursor = code_editor.cursor_position();
+    let task = if old_end == head {
+        // Only new lines, inserted before the tail
+        if head < old.len() {
+            replace_range(
+                code_editor,
+                content,
+                (head, 0),
+                (head, 0),
+                &format!("{replacement}\n"),
+            )
+        } else {
+            let end = (head - 1, line_len(head - 1));
+            replace_range(code_editor, content, end, end, &format!("\n{replacement}"))
+        }
+    } else if new_end == head {
+        // Only removed lines, taken out with one of their line breaks
+        if old_end < old.len() {
+            replace_range(code_editor, content, (head, 0), (old_end, 0), "")
+        } else {
+            let start = (head - 1, line_len(head - 1));
+            replace_range(
+                code_editor,
+                content,
+                start,
+                (old_end - 1, line_len(old_end - 1)),
+                "",
+            )
+        }
+    } else {
+        let end = (old_end - 1, line_len(old_end - 1));
+        replace_range(code_editor, content, (head, 0), end, &replacement)
+    };
+
+    let line = if cursor.0 >= old_end {
+        cursor.0 - old_end + new_end
+    } else {
+        cursor.0.min(new.len() - 1)
+    };
+    let col = cursor.1.min(new[line].chars().count());
+    let _ = code_editor.update(&EditorMessage::GotoPosition(line, col));
+    Some(task)
+}
+
+/// Tracks where the selection started, since the editor widget keeps its
+/// selection private. Returns the anchor after `event` is applied; clicks are
+/// handled by the caller once the cursor has moved.
+pub(super) fn selection_anchor_after(
+    event: &EditorMessage,
+    anchor: Option<(usize, usize)>,
+    cursor_before: (usize, usize),
+) -> Option<(usize, usize)> {
+    match event {
+        EditorMessage::ArrowKey(_, true) | EditorMessage::Home(true) | EditorMessage::End(true) => {
+            Some(anchor.unwrap_or(cursor_before))
+        }
+        EditorMessage::CharacterInput(_)
+        | EditorMessage::Backspace
+        | EditorMessage::Delete
+        | EditorMessage::Enter
+        | EditorMessage::Tab
+        | EditorMessage::FocusNavigationTab
+        | EditorMessage::FocusNavigationShiftTab
+        | EditorMessage::Paste(_)
+        | EditorMessage::DeleteSelection
+        | EditorMessage::ArrowKey(_, false)
+        | EditorMessage::Home(false)
+        | EditorMessage::End(false)
+        | EditorMessage::CtrlHome
+        | EditorMessage::CtrlEnd
+        | EditorMessage::PageUp
+        | EditorMessage::PageDown
+        | EditorMessage::GotoPosition(..)
+        | EditorMessage::Undo
+        | EditorMessage::Redo
+        | EditorMessage::ImeCommit(_)
+        | EditorMessage::JumpClick(_) => None,
+        _ => anchor,
+    }
+}
+
+fn char_at(content: &str, (line, col): (usize, usize)) -> Option<char> {
+    content.split('\n').nth(line)?.chars().nth(col)
+}
+
+/// Closes brackets and quotes as they are typed, types over a closer that is
+/// already under the cursor, and removes the closer of an empty pair on
+/// Backspace.
+///
+/// `before` and `cursor_before` describe the document before `event` was
+/// applied to `code_editor`.
+pub(super) fn apply_pair_edits(
+    code_editor: &mut CodeEditor,
+    event: &EditorMessage,
+    before: &str,
+    cursor_before: (usize, usize),
+    pairs: &[(char, char)],
+) -> Option<iced::Task<EditorMessage>> {
+    let (line, col) = cursor_before;
+    let next = char_at(before, cursor_before);
+    let prev = col
+        .checked_sub(1)
+        .and_then(|col| char_at(before, (line, col)));
+
+    match *event {
+        EditorMessage::CharacterInput(ch) => {
+            if next == Some(ch) && pairs.iter().any(|&(_, close)| close == ch) {
+                // Type over the closer instead of doubling it
+                return Some(code_editor.update(&EditorMessage::Delete));
+            }
+            let &(_, close) = pairs.iter().find(|&&(open, _)| open == ch)?;
+            if !pairs::should_close(ch, prev, next) {
+                return None;
+            }
+            let cursor = code_editor.cursor_position();
+            let _ = code_editor.update(&EditorMessage::Paste(close.to_string()));
+            Some(code_editor.update(&EditorMessage::GotoPosition(cursor.0, cursor.1)))
+        }
+        EditorMessage::Backspace => {
+            let (prev, next) = (prev?, next?);
+            pairs
+                .contains(&(prev, next))
+                .then(|| code_editor.update(&EditorMessage::Delete))
+        }
+        _ => None,
+    }
+}
+
+/// Completes `</` with the innermost open tag and mirrors edits of an
+/// opening tag name onto its closing tag.
+///
+/// `before` and `cursor_before` describe the document before `event` was
+/// applied to `code_editor`.
+pub(super) fn apply_tag_edits(
+    code_editor: &mut CodeEditor,
+    event: &EditorMessage,
+    before: &str,
+    cursor_before: (usize, usize),
+) -> Option<iced::Task<EditorMessage>> {
+    let cursor = code_editor.cursor_position();
+
+    if let EditorMessage::CharacterInput('/') = event {
+        let after = code_editor.content();
+        let head = &after[..position_to_offset(&after, cursor)];
+        let name = tags::unclosed_tag(head.strip_suffix("</")?)?;
+        return Some(code_editor.update(&EditorMessage::Paste(format!("{name}>"))));
+    }
+
+    // Rule out other events before looking for the tag, which scans the text
+    let may_edit_name = match event {
+        EditorMessage::CharacterInput(ch) => tags::is_name_char(*ch),
+        EditorMessage::Backspace | EditorMessage::Delete => true,
+        _ => false,
+    };
+    if !may_edit_name {
+        return None;
+    }
+
+    let offset_before = position_to_offset(before, cursor_before);
+    let pair = tags::tag_pair_at(before, offset_before)?;
+    let edits_name = match event {
+        EditorMessage::Backspace => offset_before > pair.open_start,
+        EditorMessage::Delete => offset_before < pair.open_start + pair.name.len(),
+        _ => true,
+    };
+    if !edits_name {
+        return None;
+    }
+
+    let after = code_editor.content();
+    let new_name = tags::name_at(&after, pair.open_start);
+    if new_name.is_empty() || new_name == pair.name {
+        return None;
+    }
+
+    // The edit happened before the closing tag, so it moved by the length change
+    let close_start = (pair.close_start + after.len()).checked_sub(before.len())?;
+    if tags::name_at(&after, close_start) != pair.name {
+        return None;
+    }
+
+    let start = offset_to_position(&after, close_start);
+    let end = offset_to_position(&after, close_start + pair.name.len());
+    let new_name = new_name.to_string();
+    let _ = replace_range(code_editor, &after, start, end, &new_name);
+    Some(code_editor.update(&EditorMessage::GotoPosition(cursor.0, cursor.1)))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_over_grapheme_clusters() {
+        // "e" with a combining accent, then a family emoji joined by ZWJs
+        let line = "ae\u{301}b 👨\u{200d}👩\u{200d}👧 énd";
+        assert_eq!(next_grapheme(line, 1), Some(3));
+        assert_eq!(previous_grapheme(line, 3), Some(1));
+        assert_eq!(next_grapheme(line, 5), Some(10));
+        assert_eq!(previous_grapheme(line, 10), Some(5));
+        assert_eq!(next_grapheme(line, line.chars().count()), None);
+        assert_eq!(previous_grapheme(line, 0), None);
+
+        assert_eq!(byte_index("héllo", 2), 3);
+        assert_eq!(byte_index("héllo", 9), 6);
+        assert_eq!(word_before("let café_au = 1", 11), "café_au");
+        assert_eq!(word_before("x.ünï", 5), "ünï");
+    }
+
+    #[test]
+    fn replaces_ranges() {
+        let content = "one\ntwo\nthree";
+        let replaced = |start, end| {
+            let mut editor = CodeEditor::new(content, "txt");
+            let _ = replace_range(&mut editor, content, start, end, "X");
+            editor.content()
+        };
+        assert_eq!(replaced((0, 1), (0, 2)), "oXe\ntwo\nthree");
+        assert_eq!(replaced((0, 1), (2, 2)), "oXree");
+        assert_eq!(replaced((2, 5), (0, 0)), "X");
+        assert_eq!(replaced((1, 0), (1, 3)), "one\nX\nthree");
+    }
+
+    #[test]
+    fn copies_whole_lines() {
+        let content = "one\ntwo\nthree";
+        assert_eq!(
+            whole_line(content, 1),
+            ("two\n".to_string(), (1, 0), (2, 0))
+        );
+        assert_eq!(
+            whole_line(content, 2),
+            ("three\n".to_string(), (1, 3), (2, 5))
+        );
+        assert_eq!(
+            whole_line("only", 0),
+            ("only\n".to_string(), (0, 0), (0, 4))
+        );
+    }
+
+    #[test]
+    fn joins_lines_collapsing_whitespace() {
+        assert_eq!(
+            join_lines(&["let x = foo(  ", "    a,", "\tb", ")"]),
+            ("let x = foo( a, b)".to_string(), 17)
+        );
+        assert_eq!(join_lines(&["one", "", "two"]), ("one two".to_string(), 3));
+        assert_eq!(join_lines(&["", "  two"]), ("two".to_string(), 0));
+    }
+}
@@ -0,0 +1,72 @@
+//! Editor events that built-in features and init.lua autocommands react to.
+//!
+//! `emit` runs the built-in reactions first and the autocommands after, so
+//! an autocommand sees the buffer the way the built-in ones left it.
+
+use super::*;
+use crate::scripting::EditorEvent;
+
+impl App {
+    /// Runs the reactions to `event` for the file or folder at `path`.
+    pub(super) fn emit(
+        &mut self,
+        event: EditorEvent,
+        path: &std::path::Path,
+    ) -> iced::Task<Message> {
+        let task = match event {
+            EditorEvent::BufOpen => {
+                self.remember_disk_stamp(path);
+                self.send_wakatime_heartbeat(path, false);
+                self.report_conflicts(path);
+                iced::Task::none()
+            }
+            EditorEvent::BufWritePre => match self.open_tab_index(path) {
+                Some(idx) => self.apply_save_actions(idx),
+                None => iced::Task::none(),
+            },
+            EditorEvent::BufWritePost => {
+                self.remember_disk_stamp(path);
+                self.send_wakatime_heartbeat(path, true);
+                self.activity.save_if_dirty();
+                iced::Task::none()
+            }
+            EditorEvent::FolderOpened => iced::Task::none(),
+        };
+        iced::Task::batch([task, self.run_autocommands(event, path)])
+    }
+
+    /// Tells when the file opened at `path` has merge conflicts left in it.
+    fn report_conflicts(&mut self, path: &std::path::Path) {
+        let Some(tab) = self.open_tab_index(path).map(|idx| &self.tabs[idx]) else {
+            return;
+        };
+        let TabKind::Editor { buffer, .. } = &tab.kind else {
+            return;
+        };
+        let count = crate::features::conflicts::find_in_buffer(buffer).len();
+        if count > 0 {
+            let conflicts = if count == 1 { "conflict" } else { "conflicts" };
+            let message = format!("{} has {count} merge {conflicts}", tab.name);
+            self.notifications.warning(message);
+        }
+    }
+
+    /// Runs the init.lua autocommands for `event` that match `path`.
+    fn run_autocommands(
+        &mut self,
+        event: EditorEvent,
+        path: &std::path::Path,
+    ) -> iced::Task<Message> {
+        let actions: Vec<String> = self
+            .autocommands
+            .iter()
+            .filter(|autocommand| autocommand.matches(event, path))
+            .map(|autocommand| autocommand.action.clone())
+            .collect();
+        let tasks: Vec<_> = actions
+            .iter()
+            .map(|action| self.run_action(action, true))
+            .collect();
+        iced::Task::batch(tasks)
+    }
+}
@@ -4,9 +4,26 @@
 use super::*;
 
 impl App {
-    /// Creates the application state and schedules an initial update check.
+    /// Creates the application state, opens the paths given on the command
+    /// line and schedules an initial update check.
     pub fn new() -> (Self, iced::Task<Message>) {
         let app = Self::default();
+        let open_task = iced::Task::batch(crate::cli::targets().into_iter().map(|target| {
+            let message = if target.path.is_dir() {
+                Message::FolderOpened(target.path)
+            } else {
+                let (line, column) = target.position.unwrap_or((1, 1));
+                Message::OpenLocation {
+                    path: target.path,
+                    line,
+                    column,
+                }
+            };
+            iced::Task::done(message)
+        }));
+        if !app.editor_preferences.check_for_updates {
+            return (app, open_task);
+        }
         let task =
             iced::Task::perform(
                 crate::features::updater::check_for_update(),
@@ -15,6 +32,6 @@ impl App {
                     None => Message::DismissUpdateBanner,
                 },
             );
-        (app, task)
+        (app, iced::Task::batch([open_task, task]))
     }
 }
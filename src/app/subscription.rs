@@ -1,4 +1,5 @@
 use super::*;
+use crate::features::recovery;
 use std::time::Duration;
 
 impl App {
@@ -9,9 +10,30 @@ impl App {
             crate::subscriptions::keyboard::input_debug(),
             crate::subscriptions::mouse::sidebar_resize(),
             crate::subscriptions::window::resizes(),
+            crate::subscriptions::window::close_requests(),
+            crate::subscriptions::window::file_drops(),
+            crate::subscriptions::window::focus_changes(),
             iced::time::every(Duration::from_millis(150)).map(|_| Message::LspTick),
+            iced::time::every(Duration::from_secs(recovery::INTERVAL_SECS))
+                .map(|_| Message::RecoveryTick),
         ];
 
+        if !self.notifications.toasts().is_empty() {
+            subs.push(
+                iced::time::every(Duration::from_millis(500)).map(|_| Message::NotificationTick),
+            );
+        }
+
+        if self.scroll_animation.is_some() {
+            subs.push(
+                iced::time::every(Duration::from_millis(16)).map(|_| Message::ScrollAnimationTick),
+            );
+        }
+
+        if !self.user_keymaps.is_empty() {
+            subs.push(crate::subscriptions::keyboard::user_keymaps());
+        }
+
         if let Some(term) = &self.terminal_pane {
             subs.push(term.subscription().map(Message::TerminalEvent));
         }
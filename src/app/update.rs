@@ -1,6 +1,56 @@
 use super::*;
-use crate::autocomplete::engine::Autocomplete;
+use crate::features::activity;
+use crate::features::colors;
+use crate::features::command_input::{ExCommand, LineRange, Substitute};
+use crate::features::conflicts;
+use crate::features::ctags;
+use crate::features::diff::DiffView;
+use crate::features::encoding::{self, Encoding};
+use crate::features::exclusions::{self, Exclusions};
+use crate::features::file_io::{self, FileError};
+use crate::features::file_viewer;
+use crate::features::find_replace::{self, Scope};
+use crate::features::jumps::{self, JumpFlash, Location};
+use crate::features::line_endings::{self, LineEnding};
+use crate::features::local_history;
+use crate::features::notifications::Severity;
+use crate::features::numbers;
+use crate::features::output::{self, Link};
+use crate::features::recovery;
+use crate::features::rename;
+use crate::features::smooth_scroll::{self, ScrollAnimation};
+use crate::features::spelling::{self, Misspelling};
+use crate::features::tasks::{self, TaskEvent};
+use crate::features::text_stats;
+use crate::features::transforms;
+use crate::features::whitespace::RenderWhitespace;
+use crate::features::workers;
+use crate::features::{comments, formatter, fuzzy_finder, indentation, line_length, symbols};
+use crate::message::{
+    BlockEdit, Clipboard, CloseTabs, Debounce, LargeFileAction, LineEdit, QuitAction, Transform,
+    Zoom,
+};
+use crate::scripting::EditorEvent;
 use iced_code_editor::Message as EditorMessage;
+use std::collections::HashSet;
+
+/// How many closed tabs can be reopened.
+const MAX_CLOSED_TABS: usize = 20;
+
+/// Lists the files of the workspace at `root` in the background.
+fn index_workspace(root: PathBuf, exclusions: Exclusions) -> iced::Task<Message> {
+    workers::perform(
+        move || {
+            let files = crate::features::search::collect_all_files(&root, &exclusions);
+            let finder_files = crate::features::fuzzy_finder::list_files(&root, &exclusions);
+            (root, files, finder_files)
+        },
+        |(root, files, finder_files)| Message::WorkspaceIndexed(root, files, finder_files),
+    )
+}
+
+/// Least time between two WakaTime heartbeats for the same file.
+const WAKATIME_INTERVAL: std::time::Duration = std::time::Duration::from_secs(120);
 
 impl App {
     fn should_confirm_sensitive_open(path: &std::path::Path) -> bool {
@@ -46,17 +96,1755 @@ impl App {
         }
     }
 
+    /// Index of the tab already showing `path`, if any.
+    ///
+    /// Paths are compared after canonicalization so the same file reached
+    /// through a relative path or a symlink is still treated as open.
+    pub(super) fn open_tab_index(&self, path: &std::path::Path) -> Option<usize> {
+        if let Some(idx) = self.tabs.iter().position(|t| t.path == path) {
+            return Some(idx);
+        }
+        let canonical = std::fs::canonicalize(path).ok()?;
+        self.tabs
+            .iter()
+            .position(|t| std::fs::canonicalize(&t.path).is_ok_and(|p| p == canonical))
+    }
+
+    fn harvest_buffer_words_task(path: PathBuf, content: String) -> iced::Task<Message> {
+        workers::perform(
+            move || crate::autocomplete::buffer_words::harvest(&content),
+            move |words| Message::BufferWordsHarvested(path.clone(), words),
+        )
+    }
+
     fn open_path_task(path: PathBuf) -> iced::Task<Message> {
-        iced::Task::perform(
-            async move {
-                let content = std::fs::read_to_string(&path)
-                    .unwrap_or_else(|_| String::from("Could not read file"));
-                (path, content)
+        workers::perform(
+            move || {
+                let bytes = match file_io::read(&path) {
+                    Ok(bytes) => bytes,
+                    Err(err) => return Message::FileOpenFailed(path, err),
+                };
+                match file_viewer::for_file(&path, &bytes) {
+                    Some(viewer) => Message::FileViewerOpened(path, viewer),
+                    None => {
+                        let (content, encoding) = encoding::decode(&bytes);
+                        Message::FileOpened(path, content, encoding)
+                    }
+                }
             },
-            |(path, content)| Message::FileOpened(path, content),
+            |message| message,
+        )
+    }
+
+    /// Language of a file, judged from its path and, when it's open, its
+    /// first line.
+    fn language_of(&self, path: &std::path::Path) -> crate::language::Language {
+        let first_line = self
+            .tabs
+            .iter()
+            .find(|tab| tab.path == path)
+            .and_then(|tab| match &tab.kind {
+                TabKind::Editor { buffer, .. } => Some(buffer.line(0)),
+                _ => None,
+            })
+            .unwrap_or_default();
+        crate::language::detect(path, &first_line)
+    }
+
+    /// Sends a WakaTime heartbeat for `path`. Non-write heartbeats for the
+    /// file of the previous one are skipped for two minutes, as WakaTime
+    /// asks of its plugins.
+    pub(super) fn send_wakatime_heartbeat(&mut self, path: &std::path::Path, is_write: bool) {
+        if !path.is_absolute() {
+            return;
+        }
+        let entity = path.to_string_lossy().to_string();
+        let recently_sent = self.last_wakatime_entity.as_ref() == Some(&entity)
+            && self
+                .last_wakatime_sent_at
+                .is_some_and(|sent| sent.elapsed() < WAKATIME_INTERVAL);
+        if recently_sent && !is_write {
+            return;
+        }
+
+        let language = match self.language_of(path) {
+            crate::language::Language::PlainText => None,
+            language => Some(language.name()),
+        };
+        let project = self
+            .file_tree
+            .as_ref()
+            .and_then(|tree| tree.root.file_name())
+            .map(|name| name.to_string_lossy().to_string());
+        let heartbeat = wakatime::client::Heartbeat {
+            entity: &entity,
+            language,
+            project: project.as_deref(),
+            is_write,
+        };
+        if let Err(err) = wakatime::client::send_heartbeat(&heartbeat, &self.wakatime) {
+            self.dev_log(format!("WakaTime: could not run wakatime-cli: {err}"));
+        }
+        self.last_wakatime_entity = Some(entity);
+        self.last_wakatime_sent_at = Some(Instant::now());
+    }
+
+    /// Encoding a file is saved with, UTF-8 unless it was read otherwise.
+    pub(super) fn encoding_of(&self, path: &std::path::Path) -> Encoding {
+        self.file_encodings.get(path).copied().unwrap_or_default()
+    }
+
+    fn set_encoding(&mut self, path: PathBuf, encoding: Encoding) {
+        if encoding == Encoding::Utf8 {
+            self.file_encodings.remove(&path);
+        } else {
+            self.file_encodings.insert(path, encoding);
+        }
+    }
+
+    /// Line ending style a file is saved with, LF unless it was read with
+    /// CRLF.
+    pub(super) fn line_ending_of(&self, path: &std::path::Path) -> LineEnding {
+        self.file_line_endings
+            .get(path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn set_line_ending(&mut self, path: PathBuf, ending: LineEnding) {
+        if ending == LineEnding::Lf {
+            self.file_line_endings.remove(&path);
+        } else {
+            self.file_line_endings.insert(path, ending);
+        }
+    }
+
+    /// Bytes every save writes to disk for `text`, in the file's line ending
+    /// style and encoding, with the final newline the buffer doesn't keep
+    /// when `insert_final_newline` is on.
+    fn disk_bytes(&self, path: &std::path::Path, text: &str) -> Result<Vec<u8>, FileError> {
+        let text = formatter::tidy(text, false, self.editor_preferences.insert_final_newline);
+        let text = line_endings::convert(&text, self.line_ending_of(path));
+        encoding::encode(&text, self.encoding_of(path)).map_err(FileError::Encoding)
+    }
+
+    fn remember_visited_tab(&mut self, path: PathBuf) {
+        self.tab_history.retain(|p| p != &path);
+        self.tab_history.insert(0, path);
+    }
+
+    fn remember_closed_tab(&mut self, path: PathBuf) {
+        self.tab_history.retain(|p| p != &path);
+        // Untitled buffers that were never saved can't be reopened
+        if path.is_file() {
+            self.closed_tabs.retain(|p| p != &path);
+            self.closed_tabs.push(path);
+            if self.closed_tabs.len() > MAX_CLOSED_TABS {
+                self.closed_tabs.remove(0);
+            }
+        }
+    }
+
+    /// Keeps where the cursors of the given tabs are, to put them back when
+    /// their files are opened again.
+    fn remember_positions(&mut self, indices: impl IntoIterator<Item = usize>) {
+        for idx in indices {
+            let Some(tab) = self.tabs.get(idx) else {
+                continue;
+            };
+            if let TabKind::Editor { code_editor, .. } = &tab.kind {
+                if tab.path.is_file() {
+                    let (line, column) = code_editor.cursor_position();
+                    self.last_positions
+                        .remember(&tab.path, (line + 1, column + 1));
+                }
+            }
+        }
+        let _ = crate::config::positions::save_positions(&self.last_positions);
+    }
+
+    /// Scrolls the active editor back to where it was left.
+    fn restore_active_scroll(&self) -> iced::Task<Message> {
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor { code_editor, .. } = &tab.kind else {
+            return iced::Task::none();
+        };
+        crate::ui::restore_scroll(
+            self.editor_container_id.clone(),
+            code_editor.viewport_scroll(),
+            self.editor_scroll_x.get(&tab.path).copied().unwrap_or(0.0),
         )
     }
 
+    /// Tab indices ordered by when they were last visited, tabs that were
+    /// never visited coming last.
+    pub(super) fn tabs_by_recency(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = self
+            .tab_history
+            .iter()
+            .filter_map(|path| self.tabs.iter().position(|tab| &tab.path == path))
+            .collect();
+        for idx in 0..self.tabs.len() {
+            if !order.contains(&idx) {
+                order.push(idx);
+            }
+        }
+        order
+    }
+
+    /// Lists the open buffers in the output panel, as `:ls` does.
+    fn list_buffers(&mut self) {
+        let mut panel = OutputPanel::new("Buffers".to_string());
+        for (idx, tab) in self.tabs.iter().enumerate() {
+            let active = if self.active_tab == Some(idx) {
+                "%a"
+            } else {
+                "  "
+            };
+            let modified = match &tab.kind {
+                TabKind::Editor { code_editor, .. } if code_editor.is_modified() => " [+]",
+                _ => "",
+            };
+            let name = match self.file_tree.as_ref() {
+                Some(tree) => tab.path.strip_prefix(&tree.root).unwrap_or(&tab.path),
+                None => tab.path.as_path(),
+            };
+            panel.push_line(&format!(
+                "{:>3} {active} {}{modified}",
+                idx + 1,
+                name.display()
+            ));
+        }
+        self.output_panel = Some(panel);
+    }
+
+    /// Gives the fuzzy finder the active file's symbols for `@` queries.
+    fn index_symbols(&mut self) {
+        let source = self
+            .active_tab
+            .and_then(|idx| self.tabs.get(idx))
+            .and_then(|tab| match &tab.kind {
+                TabKind::Editor { code_editor, .. } => {
+                    Some((tab.path.clone(), code_editor.content()))
+                }
+                _ => None,
+            });
+        let symbols = source.as_ref().map_or_else(Vec::new, |(path, content)| {
+            let language = crate::language::detect(path, content.lines().next().unwrap_or(""));
+            symbols::extract(content, language)
+        });
+        self.fuzzy_finder.set_symbol_source(source, symbols);
+    }
+
+    /// Reloads the open folder's tasks for the command palette.
+    fn refresh_project_tasks(&mut self) {
+        self.project_tasks = match self.file_tree.as_ref().map(|tree| tasks::load(&tree.root)) {
+            Some(Ok(tasks)) => tasks,
+            Some(Err(err)) => {
+                self.notifications.error(err);
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        self.command_palette.set_tasks(
+            self.project_tasks
+                .iter()
+                .map(|task| (task.name.as_str(), task.command.as_str())),
+        );
+    }
+
+    /// Where the cursor of the active editor is.
+    fn current_location(&self) -> Option<Location> {
+        let tab = self.tabs.get(self.active_tab?)?;
+        let TabKind::Editor { code_editor, .. } = &tab.kind else {
+            return None;
+        };
+        let (line, column) = code_editor.cursor_position();
+        Some(Location {
+            path: tab.path.clone(),
+            line: line + 1,
+            column: column + 1,
+        })
+    }
+
+    /// Opens `path`, or switches to its tab, and moves to a 1-based position.
+    fn open_at(&mut self, path: PathBuf, line: usize, column: usize) -> iced::Task<Message> {
+        if let Some(idx) = self.open_tab_index(&path) {
+            let select_task = self.update(Message::TabSelected(idx));
+            return iced::Task::batch([select_task, self.go_to_position(line, column)]);
+        }
+        self.pending_cursor = Some((path.clone(), line, column));
+        self.update(Message::FileClicked(path))
+    }
+
+    fn remember_recent_file(&mut self, path: PathBuf) {
+        crate::config::recent::push_recent(&mut self.recent_files, path);
+        let _ = crate::config::recent::save_recent(&self.recent_folders, &self.recent_files);
+    }
+
+    /// Notes when `path` was last changed, to tell apart later changes made
+    /// by other programs.
+    pub(super) fn remember_disk_stamp(&mut self, path: &std::path::Path) {
+        if let Ok(modified) = std::fs::metadata(path).and_then(|meta| meta.modified()) {
+            self.disk_stamps.insert(path.to_path_buf(), modified);
+        }
+    }
+
+    /// Shows the editor tab at `idx` compared with `theirs`.
+    fn open_diff(
+        &mut self,
+        idx: usize,
+        theirs_label: String,
+        theirs: &str,
+        against_disk: bool,
+    ) -> iced::Task<Message> {
+        let Some(tab) = self.tabs.get(idx) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor { code_editor, .. } = &tab.kind else {
+            return iced::Task::none();
+        };
+        let diff = DiffView::new(
+            tab.path.clone(),
+            theirs_label,
+            against_disk,
+            &code_editor.content(),
+            theirs,
+        );
+        if diff.hunks.is_empty() {
+            self.notifications
+                .info(format!("{} has no differences", tab.name));
+            return iced::Task::none();
+        }
+        self.diff_view = Some(diff);
+        self.update(Message::TabSelected(idx))
+    }
+
+    /// Writes the hunks of `diff` taken from the other side into its buffer.
+    fn apply_diff(&mut self, diff: DiffView) -> iced::Task<Message> {
+        let Some(idx) = self.open_tab_index(&diff.path) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = self.tabs[idx].kind
+        else {
+            return iced::Task::none();
+        };
+        if !diff.is_mine(&code_editor.content()) {
+            self.notifications
+                .error("The file changed while comparing, compare it again");
+            return iced::Task::none();
+        }
+        let mut tasks = Vec::new();
+        for (range, text) in diff.edits() {
+            let content = code_editor.content();
+            let start = super::editing::offset_to_position(&content, range.start);
+            let end = super::editing::offset_to_position(&content, range.end);
+            tasks.push(super::editing::replace_range(
+                code_editor,
+                &content,
+                start,
+                end,
+                &text,
+            ));
+        }
+        // Taking every change from disk leaves nothing to save
+        if diff.against_disk && diff.take_theirs.iter().all(|take| *take) {
+            code_editor.mark_saved();
+        }
+        buffer.set_text(&code_editor.content());
+        self.expanded_selections.clear();
+        iced::Task::batch(tasks).map(Message::CodeEditorEvent)
+    }
+
+    /// Reloads open files other programs changed since they were read or
+    /// saved. Files with unsaved changes are compared with the new version
+    /// instead, one at a time.
+    fn check_disk_changes(&mut self) -> iced::Task<Message> {
+        let changed: Vec<usize> = (0..self.tabs.len())
+            .filter(|&idx| {
+                let path = &self.tabs[idx].path;
+                let modified = std::fs::metadata(path).and_then(|meta| meta.modified());
+                matches!(self.tabs[idx].kind, TabKind::Editor { .. })
+                    && matches!(
+                        (self.disk_stamps.get(path), modified),
+                        (Some(stamp), Ok(modified)) if *stamp != modified
+                    )
+            })
+            .collect();
+
+        let mut tasks = Vec::new();
+        for idx in changed {
+            let path = self.tabs[idx].path.clone();
+            self.remember_disk_stamp(&path);
+            let Ok(bytes) = file_io::read(&path) else {
+                continue;
+            };
+            let (text, _) = encoding::decode(&bytes);
+            let text = line_endings::convert(&text, LineEnding::Lf);
+            let tab = &mut self.tabs[idx];
+            let TabKind::Editor {
+                ref mut code_editor,
+                ref mut buffer,
+            } = tab.kind
+            else {
+                continue;
+            };
+            if code_editor.content() == text {
+                continue;
+            }
+            if !code_editor.is_modified() {
+                tasks.push(code_editor.reset(&text).map(Message::CodeEditorEvent));
+                buffer.set_text(&text);
+                let name = tab.name.clone();
+                self.notifications
+                    .info(format!("Reloaded {name}, it changed on disk"));
+            } else if self.diff_view.is_none() {
+                self.notifications.warning(format!(
+                    "{} changed on disk, pick the changes to keep",
+                    tab.name
+                ));
+                tasks.push(self.open_diff(idx, "On disk".to_string(), &text, true));
+            }
+        }
+        iced::Task::batch(tasks)
+    }
+
+    /// Ordered start and end of the active editor's selection, if any.
+    pub(super) fn active_selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.selection_anchor?;
+        let tab = self.tabs.get(self.active_tab?)?;
+        let TabKind::Editor { code_editor, .. } = &tab.kind else {
+            return None;
+        };
+        let cursor = code_editor.cursor_position();
+        match anchor.cmp(&cursor) {
+            std::cmp::Ordering::Less => Some((anchor, cursor)),
+            std::cmp::Ordering::Greater => Some((cursor, anchor)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Misspelled words on a 0-based line of the active editor, none while
+    /// spell checking is off.
+    pub(super) fn misspellings_on(&self, line: usize) -> Vec<Misspelling> {
+        if !self.editor_preferences.spell_check {
+            return Vec::new();
+        }
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+            return Vec::new();
+        };
+        let TabKind::Editor { buffer, .. } = &tab.kind else {
+            return Vec::new();
+        };
+        if line >= buffer.line_count() {
+            return Vec::new();
+        }
+        let language = crate::language::detect(&tab.path, &buffer.line(0));
+        let text = buffer.line(line);
+        self.spelling
+            .check_line(text.trim_end_matches(['\n', '\r']), language)
+    }
+
+    /// Jumps to the definition of the symbol at `point`, or at the cursor.
+    /// Files with a language server ask it; others look in the current file
+    /// and then in the folder's tags file or files, in the background.
+    fn go_to_definition(&mut self, point: Option<iced::Point>) -> iced::Task<Message> {
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ..
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+        if self.lsp_server_keys.contains_key(&tab.path) {
+            match point {
+                Some(point) => {
+                    code_editor.lsp_request_definition_at(point);
+                }
+                None => code_editor.lsp_request_definition(),
+            }
+            return iced::Task::none();
+        }
+
+        let (line, col) = match point {
+            Some(point) => match code_editor.lsp_position_at_point(point) {
+                Some(position) => (position.line as usize, position.character as usize),
+                None => return iced::Task::none(),
+            },
+            None => code_editor.cursor_position(),
+        };
+        let content = code_editor.content();
+        let Some(line_text) = content.lines().nth(line) else {
+            return iced::Task::none();
+        };
+        let Some((start, end)) = super::editing::word_bounds(line_text, col) else {
+            return iced::Task::none();
+        };
+        let name: String = line_text.chars().skip(start).take(end - start).collect();
+
+        let local = ctags::in_text(&tab.path, &content, &name);
+        if !local.is_empty() {
+            return self.update(Message::DefinitionsFound(name, local));
+        }
+        let root = self.file_tree.as_ref().map(|tree| tree.root.clone());
+        let files: Vec<PathBuf> = self
+            .all_workspace_files
+            .iter()
+            .map(|(_, path)| path.clone())
+            .collect();
+        workers::perform(
+            move || {
+                let found = ctags::find(root.as_deref(), &files, &name);
+                (name, found)
+            },
+            |(name, found)| Message::DefinitionsFound(name, found),
+        )
+    }
+
+    /// The word under the cursor of the active editor.
+    fn word_at_cursor(&self) -> Option<String> {
+        let TabKind::Editor { code_editor, .. } = &self.tabs.get(self.active_tab?)?.kind else {
+            return None;
+        };
+        let (line, col) = code_editor.cursor_position();
+        let content = code_editor.content();
+        let line = content.lines().nth(line)?;
+        let (start, end) = super::editing::word_bounds(line, col)?;
+        Some(line.chars().skip(start).take(end - start).collect())
+    }
+
+    /// Previews renaming the symbol at the cursor to `to` in the active file.
+    /// The `rename` request of language servers can't be sent through the
+    /// editor's LSP client, so this is a whole-word rename of the file.
+    fn prepare_rename(&mut self, to: String) -> iced::Task<Message> {
+        let Some(from) = self.word_at_cursor() else {
+            self.notifications.info("No symbol at the cursor to rename");
+            return iced::Task::none();
+        };
+        if !rename::is_identifier(&to) {
+            self.notifications.error(format!("Not a valid name: {to}"));
+            return iced::Task::none();
+        }
+        if from == to {
+            return iced::Task::none();
+        }
+        let Some(TabKind::Editor { code_editor, .. }) = self
+            .active_tab
+            .and_then(|idx| self.tabs.get(idx))
+            .map(|tab| &tab.kind)
+        else {
+            return iced::Task::none();
+        };
+        self.pending_rename = Some(rename::Rename::new(&code_editor.content(), &from, &to));
+        iced::Task::none()
+    }
+
+    /// Byte range of the active editor's selection, if any.
+    fn selection_offsets(&self, content: &str) -> Option<(usize, usize)> {
+        let (start, end) = self.active_selection()?;
+        Some((
+            super::editing::position_to_offset(content, start),
+            super::editing::position_to_offset(content, end),
+        ))
+    }
+
+    /// Text of the active editor's selection, if any.
+    pub(super) fn selected_text(&self) -> Option<String> {
+        let TabKind::Editor { code_editor, .. } = &self.tabs.get(self.active_tab?)?.kind else {
+            return None;
+        };
+        let content = code_editor.content();
+        let (start, end) = self.selection_offsets(&content)?;
+        content.get(start..end).map(str::to_string)
+    }
+
+    /// Paths and text of every open editor.
+    fn open_editor_texts(&self) -> Vec<(PathBuf, String)> {
+        self.tabs
+            .iter()
+            .filter_map(|tab| match &tab.kind {
+                TabKind::Editor { code_editor, .. } => {
+                    Some((tab.path.clone(), code_editor.content()))
+                }
+                TabKind::Preview { .. } | TabKind::Viewer(_) => None,
+            })
+            .collect()
+    }
+
+    /// Recounts find matches in the current scope. Workspace counts run in
+    /// the background and arrive as `FindCounted`.
+    fn refresh_find_matches(&mut self) -> iced::Task<Message> {
+        self.find_replace.files_matched = 0;
+        // Dropping the handle aborts the count for the previous query
+        if let Some((_, cancelled)) = self.find_count.take() {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+        match self.find_replace.scope {
+            Scope::Selection | Scope::File => {
+                let text = self
+                    .active_tab
+                    .and_then(|idx| self.tabs.get(idx))
+                    .and_then(|tab| match &tab.kind {
+                        TabKind::Editor { code_editor, .. } => Some(code_editor.content()),
+                        TabKind::Preview { .. } | TabKind::Viewer(_) => None,
+                    })
+                    .unwrap_or_default();
+                self.find_replace.find_matches(&text);
+                iced::Task::none()
+            }
+            Scope::OpenFiles => {
+                let counts: Vec<usize> = self
+                    .open_editor_texts()
+                    .iter()
+                    .map(|(_, text)| self.find_replace.count_in(text))
+                    .filter(|&count| count > 0)
+                    .collect();
+                self.find_replace.match_count = counts.iter().sum();
+                self.find_replace.files_matched = counts.len();
+                iced::Task::none()
+            }
+            Scope::Workspace => {
+                let open: HashMap<PathBuf, String> = self.open_editor_texts().into_iter().collect();
+                let files: Vec<PathBuf> = self
+                    .all_workspace_files
+                    .iter()
+                    .map(|(_, path)| path.clone())
+                    .collect();
+                let find = self.find_replace.clone();
+                let cancelled = Arc::new(AtomicBool::new(false));
+                let flag = Arc::clone(&cancelled);
+                let (task, handle) = workers::perform(
+                    move || {
+                        let (matches, files) = find.count_in_files(&files, &open, &flag);
+                        (find.find_text, matches, files)
+                    },
+                    |(query, matches, files)| Message::FindCounted {
+                        query,
+                        matches,
+                        files,
+                    },
+                )
+                .abortable();
+                self.find_count = Some((handle.abort_on_drop(), cancelled));
+                task
+            }
+        }
+    }
+
+    /// Replaces every match in the open editors, or in those of the open
+    /// folder, returning how many matches and files changed.
+    fn replace_in_open_editors(&mut self, workspace_only: bool) -> (usize, usize) {
+        let workspace: HashSet<&PathBuf> = self
+            .all_workspace_files
+            .iter()
+            .map(|(_, path)| path)
+            .collect();
+        let (mut matches, mut files) = (0, 0);
+        for tab in &mut self.tabs {
+            if workspace_only && !workspace.contains(&tab.path) {
+                continue;
+            }
+            let TabKind::Editor {
+                ref mut code_editor,
+                ref mut buffer,
+            } = tab.kind
+            else {
+                continue;
+            };
+            let content = code_editor.content();
+            let mut text = content.clone();
+            let count = self.find_replace.replace_in(&mut text);
+            if count > 0 {
+                // An edit rather than a reset, so the tab stays unsaved and undoable
+                let _ = super::editing::replace_changed_lines(code_editor, &content, &text);
+                buffer.set_text(&text);
+                matches += count;
+                files += 1;
+            }
+        }
+        (matches, files)
+    }
+
+    /// Applies `edit` to the selected lines, or the cursor line, as a single
+    /// undoable change. A multi-line selection is kept on the edited lines.
+    pub(super) fn rewrite_selected_lines(&mut self, edit: LineEdit) -> iced::Task<Message> {
+        let selection = self.active_selection();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let cursor = code_editor.cursor_position();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (first, last) = super::editing::line_span(selection, cursor);
+        if last >= lines.len() {
+            return iced::Task::none();
+        }
+
+        let unit = self.editor_preferences.indent_unit_for(&content);
+        let language = crate::language::detect(&tab.path, lines[0]);
+        let new_lines: Vec<String> = match edit {
+            LineEdit::Indent | LineEdit::Outdent => lines[first..=last]
+                .iter()
+                .map(|line| indentation::shift(line, &unit, edit == LineEdit::Outdent))
+                .collect(),
+            LineEdit::Reindent => indentation::reindent(&lines, first, last, &unit, language),
+            LineEdit::ToggleComment => comments::toggle(&lines[first..=last], language),
+            LineEdit::Reflow => {
+                let tab_size = self.editor_preferences.tab_size;
+                let limit =
+                    line_length::limit_for(&self.editor_preferences.line_length_limits, language)
+                        .unwrap_or(line_length::FALLBACK_LIMIT);
+                lines[first..=last]
+                    .iter()
+                    .flat_map(|line| line_length::reflow(line, limit, tab_size, language))
+                    .collect()
+            }
+        };
+        if new_lines
+            .iter()
+            .zip(&lines[first..=last])
+            .all(|(new, old)| new == old)
+        {
+            return iced::Task::none();
+        }
+
+        let end = (last, lines[last].chars().count());
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            (first, 0),
+            end,
+            &new_lines.join("\n"),
+        );
+        let after = code_editor.content();
+        let new_last_len = new_lines.last().map_or(0, |line| line.chars().count());
+        let new_last = first + new_lines.len() - 1;
+        if selection.is_some() {
+            super::editing::select_range(code_editor, &after, (first, 0), (new_last, new_last_len));
+            self.selection_anchor = Some((first, 0));
+        } else if new_last != last {
+            // Reflowing split the line, so move to the end of the new lines
+            let _ = code_editor.update(&EditorMessage::GotoPosition(new_last, new_last_len));
+        } else {
+            // Keep the cursor on the same character of the edited line
+            let old_len = lines[cursor.0].chars().count() as isize;
+            let new_len = new_lines[cursor.0 - first].chars().count() as isize;
+            let col = (cursor.1 as isize + new_len - old_len).max(0) as usize;
+            let _ = code_editor.update(&EditorMessage::GotoPosition(cursor.0, col));
+        }
+        buffer.set_text(&after);
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Moves the selected lines, or the cursor line, past the line above or
+    /// below them. With `duplicate` the lines are copied instead, and the
+    /// cursor follows the copy. The selection and column are kept.
+    pub(super) fn move_selected_lines(&mut self, up: bool, duplicate: bool) -> iced::Task<Message> {
+        let selection = self.active_selection();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let cursor = code_editor.cursor_position();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (first, last) = super::editing::line_span(selection, cursor);
+        if last >= lines.len() {
+            return iced::Task::none();
+        }
+        let block = &lines[first..=last];
+        let count = last - first + 1;
+
+        // Lines replaced, their new text and how far the cursor moves
+        let (from, to, new_lines, delta): (usize, usize, Vec<&str>, isize) = if duplicate {
+            let copied = [block, block].concat();
+            (first, last, copied, if up { 0 } else { count as isize })
+        } else if up {
+            if first == 0 {
+                return iced::Task::none();
+            }
+            let moved = [block, &lines[first - 1..first]].concat();
+            (first - 1, last, moved, -1)
+        } else {
+            if last + 1 >= lines.len() {
+                return iced::Task::none();
+            }
+            let moved = [&lines[last + 1..last + 2], block].concat();
+            (first, last + 1, moved, 1)
+        };
+
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            (from, 0),
+            (to, lines[to].chars().count()),
+            &new_lines.join("\n"),
+        );
+        let after = code_editor.content();
+        let shift = |(line, col): (usize, usize)| ((line as isize + delta) as usize, col);
+        match (selection, self.selection_anchor) {
+            (Some(_), Some(anchor)) => {
+                let (anchor, cursor) = (shift(anchor), shift(cursor));
+                super::editing::select_range(code_editor, &after, anchor, cursor);
+                self.selection_anchor = Some(anchor.min(cursor));
+            }
+            _ => {
+                let (line, col) = shift(cursor);
+                let _ = code_editor.update(&EditorMessage::GotoPosition(line, col));
+            }
+        }
+        buffer.set_text(&after);
+        self.expanded_selections.clear();
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Copies the selected text right after itself and selects the copy.
+    /// Without a selection the cursor line is copied below it instead.
+    pub(super) fn duplicate_selection(&mut self) -> iced::Task<Message> {
+        let Some((start, end)) = self.active_selection().filter(|(start, end)| start != end) else {
+            return self.move_selected_lines(false, true);
+        };
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let end_offset = super::editing::position_to_offset(&content, end);
+        let text = &content[super::editing::position_to_offset(&content, start)..end_offset];
+        let task = super::editing::replace_range(code_editor, &content, end, end, text);
+        let after = code_editor.content();
+        let copy_end = super::editing::offset_to_position(&after, end_offset + text.len());
+        super::editing::select_range(code_editor, &after, end, copy_end);
+        self.selection_anchor = Some(end);
+        buffer.set_text(&after);
+        self.expanded_selections.clear();
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Joins the selected lines into one, or the cursor line with the line
+    /// below it, like Vim's `J`. The cursor lands on the last join.
+    pub(super) fn join_selected_lines(&mut self) -> iced::Task<Message> {
+        let selection = self.active_selection();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let cursor = code_editor.cursor_position();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (first, last) = super::editing::line_span(selection, cursor);
+        // Joining a single line takes the line below it along
+        let last = last.max(first + 1);
+        if last >= lines.len() {
+            return iced::Task::none();
+        }
+
+        let (joined, col) = super::editing::join_lines(&lines[first..=last]);
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            (first, 0),
+            (last, lines[last].chars().count()),
+            &joined,
+        );
+        let _ = code_editor.update(&EditorMessage::GotoPosition(first, col));
+        self.selection_anchor = None;
+        buffer.set_text(&code_editor.content());
+        self.expanded_selections.clear();
+        self.cursor_line = first + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Rewrites the selection, or the whole buffer without one, and selects
+    /// the result. Line transforms take every line the selection touches.
+    pub(super) fn transform_selection(&mut self, transform: Transform) -> iced::Task<Message> {
+        let selection = self.active_selection().filter(|(start, end)| start != end);
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let whole = (
+            (0, 0),
+            (lines.len() - 1, lines[lines.len() - 1].chars().count()),
+        );
+        let (start, end) = match selection {
+            Some(_) if transform.works_on_lines() => {
+                let (first, last) =
+                    super::editing::line_span(selection, code_editor.cursor_position());
+                ((first, 0), (last, lines[last].chars().count()))
+            }
+            Some(range) => range,
+            None => whole,
+        };
+
+        let start_offset = super::editing::position_to_offset(&content, start);
+        let text = &content[start_offset..super::editing::position_to_offset(&content, end)];
+        let Some(new_text) = transforms::apply(transform, text) else {
+            self.notifications
+                .warning("Could not decode the text as base64");
+            return iced::Task::none();
+        };
+        if new_text == text {
+            return iced::Task::none();
+        }
+
+        let task = super::editing::replace_range(code_editor, &content, start, end, &new_text);
+        let after = code_editor.content();
+        if selection.is_some() {
+            let new_end = super::editing::offset_to_position(&after, start_offset + new_text.len());
+            super::editing::select_range(code_editor, &after, start, new_end);
+            self.selection_anchor = Some(start);
+        } else {
+            let _ = code_editor.update(&EditorMessage::GotoPosition(0, 0));
+            self.selection_anchor = None;
+        }
+        buffer.set_text(&after);
+        self.expanded_selections.clear();
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Steps the first number under or after the cursor by `delta`, or the
+    /// first number of each selected line. `sequential` steps the numbers
+    /// of a selection by `delta`, twice `delta` and so on, like Vim's
+    /// `g Ctrl+A`.
+    pub(super) fn step_numbers(&mut self, delta: i64, sequential: bool) -> iced::Task<Message> {
+        let selection = self.active_selection().filter(|(start, end)| start != end);
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let cursor = code_editor.cursor_position();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (first, last) = super::editing::line_span(selection, cursor);
+        if last >= lines.len() {
+            return iced::Task::none();
+        }
+
+        let mut new_lines: Vec<String> = Vec::with_capacity(last - first + 1);
+        let mut amount = delta;
+        let mut last_digit = None;
+        for (line_idx, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+            let from = match selection {
+                Some((start, _)) if line_idx == first => start.1,
+                Some(_) => 0,
+                None => cursor.1,
+            };
+            let Some((range, text)) = numbers::step(line, from, amount) else {
+                new_lines.push(line.to_string());
+                continue;
+            };
+            let start = super::editing::byte_index(line, range.start);
+            let end = super::editing::byte_index(line, range.end);
+            new_lines.push(format!("{}{text}{}", &line[..start], &line[end..]));
+            last_digit = Some((line_idx, range.start + text.chars().count() - 1));
+            if sequential {
+                amount = amount.saturating_add(delta);
+            }
+        }
+        let Some(last_digit) = last_digit else {
+            return iced::Task::none();
+        };
+
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            (first, 0),
+            (last, lines[last].chars().count()),
+            &new_lines.join("\n"),
+        );
+        // Like Vim, the cursor goes back to the start of a selection, or
+        // else to the last digit of the number
+        let (line, col) = selection.map_or(last_digit, |(start, _)| start);
+        let _ = code_editor.update(&EditorMessage::GotoPosition(line, col));
+        self.selection_anchor = None;
+        buffer.set_text(&code_editor.content());
+        self.expanded_selections.clear();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Grows the selection to the next enclosing word, string, bracket,
+    /// line or document scope.
+    pub(super) fn expand_selection(&mut self) -> iced::Task<Message> {
+        let selection = self.active_selection();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ..
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let cursor = code_editor.cursor_position();
+        let (start, end) = selection.unwrap_or((cursor, cursor));
+        let current = (
+            super::editing::position_to_offset(&content, start),
+            super::editing::position_to_offset(&content, end),
+        );
+        // Start over when the selection was changed by other means
+        if self.expanded_selections.last() != Some(&current) {
+            self.expanded_selections = vec![current];
+        }
+        let Some(next) = crate::features::selection::expand(&content, current.0, current.1) else {
+            return iced::Task::none();
+        };
+        self.expanded_selections.push(next);
+        self.select_offsets(next)
+    }
+
+    /// Returns to the selection before the last Expand Selection.
+    pub(super) fn shrink_selection(&mut self) -> iced::Task<Message> {
+        if self.expanded_selections.len() < 2 {
+            return iced::Task::none();
+        }
+        self.expanded_selections.pop();
+        match self.expanded_selections.last() {
+            Some(&range) => self.select_offsets(range),
+            None => iced::Task::none(),
+        }
+    }
+
+    /// Wraps the selection in the pair opened by `open`, keeping the wrapped
+    /// text selected. Returns `None` when `open` does not start a pair.
+    fn surround_selection(&mut self, open: char) -> Option<iced::Task<Message>> {
+        let (start, end) = self.active_selection()?;
+        let tab = self.tabs.get_mut(self.active_tab?)?;
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return None;
+        };
+
+        let content = code_editor.content();
+        let language = crate::language::detect(&tab.path, content.lines().next().unwrap_or(""));
+        let pairs = if self.editor_preferences.auto_close_brackets {
+            crate::features::pairs::pairs_for(language, &self.editor_preferences.auto_close_exclude)
+        } else {
+            &[]
+        };
+        let &(_, close) = pairs.iter().find(|&&(o, _)| o == open)?;
+
+        let start_offset = super::editing::position_to_offset(&content, start);
+        let end_offset = super::editing::position_to_offset(&content, end);
+        let wrapped = format!("{open}{}{close}", &content[start_offset..end_offset]);
+        let task = super::editing::replace_range(code_editor, &content, start, end, &wrapped);
+        buffer.set_text(&code_editor.content());
+        self.expanded_selections.clear();
+        let inner = (start_offset + open.len_utf8(), end_offset + open.len_utf8());
+        Some(iced::Task::batch([
+            task.map(Message::CodeEditorEvent),
+            self.select_offsets(inner),
+        ]))
+    }
+
+    /// Removes the innermost brackets or quotes around the selection or the
+    /// cursor, as a single edit.
+    pub(super) fn delete_surrounding_pair(&mut self) -> iced::Task<Message> {
+        let selection = self.active_selection();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let cursor = code_editor.cursor_position();
+        let (start, end) = selection.unwrap_or((cursor, cursor));
+        let start_offset = super::editing::position_to_offset(&content, start);
+        let end_offset = super::editing::position_to_offset(&content, end);
+        let Some((open, close)) =
+            crate::features::selection::surrounding_pair(&content, start_offset, end_offset)
+        else {
+            return iced::Task::none();
+        };
+
+        // Both pair characters are ASCII, so they are one byte each
+        let inner = content[open + 1..close].to_string();
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            super::editing::offset_to_position(&content, open),
+            super::editing::offset_to_position(&content, close + 1),
+            &inner,
+        );
+        buffer.set_text(&code_editor.content());
+        self.expanded_selections.clear();
+        let range = if selection.is_some() {
+            (start_offset - 1, end_offset - 1)
+        } else {
+            (start_offset - 1, start_offset - 1)
+        };
+        iced::Task::batch([
+            task.map(Message::CodeEditorEvent),
+            self.select_offsets(range),
+        ])
+    }
+
+    /// Adds `text` to each line of the rectangle spanned by the selection's
+    /// anchor and cursor, as a single edit.
+    fn apply_block_edit(&mut self, edit: BlockEdit, text: &str) -> iced::Task<Message> {
+        let Some((start, end)) = self.active_selection() else {
+            return iced::Task::none();
+        };
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (left, right) = (start.1.min(end.1), start.1.max(end.1));
+        let new_lines = match edit {
+            BlockEdit::Insert => {
+                crate::features::block::insert_column(&lines[start.0..=end.0], left, text, false)
+            }
+            BlockEdit::Append => {
+                crate::features::block::insert_column(&lines[start.0..=end.0], right, text, true)
+            }
+        };
+        let last_len = lines[end.0].chars().count();
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            (start.0, 0),
+            (end.0, last_len),
+            &new_lines.join("\n"),
+        );
+        // Leave the cursor after the text on the block's last line
+        let col = match edit {
+            BlockEdit::Insert => left,
+            BlockEdit::Append => right,
+        } + text.chars().count();
+        let _ = code_editor.update(&EditorMessage::GotoPosition(end.0, col));
+        buffer.set_text(&code_editor.content());
+        self.selection_anchor = None;
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Selects a byte range of the active editor, or places the cursor when
+    /// it is empty.
+    fn select_offsets(&mut self, (start, end): (usize, usize)) -> iced::Task<Message> {
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ..
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let start = super::editing::offset_to_position(&content, start);
+        let end = super::editing::offset_to_position(&content, end);
+        if start == end {
+            let _ = code_editor.update(&EditorMessage::GotoPosition(start.0, start.1));
+            self.selection_anchor = None;
+        } else {
+            super::editing::select_range(code_editor, &content, start, end);
+            self.selection_anchor = Some(start);
+        }
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        iced::Task::none()
+    }
+
+    pub(super) fn run_ex_command(&mut self, cmd: ExCommand) -> iced::Task<Message> {
+        match cmd {
+            ExCommand::Palette(name) => self.execute_palette_command(&name),
+            ExCommand::GoToLine(line) => self.go_to_line(line),
+            ExCommand::Substitute(range, substitute) => self.substitute_lines(range, &substitute),
+            ExCommand::Delete(range) => self.delete_lines(range),
+            ExCommand::Edit(path) => self.edit_path(&path),
+            ExCommand::Rename(name) => self.prepare_rename(name),
+            ExCommand::ListBuffers => {
+                self.list_buffers();
+                iced::Task::none()
+            }
+            ExCommand::Buffer(number) if (1..=self.tabs.len()).contains(&number) => {
+                self.update(Message::TabSelected(number - 1))
+            }
+            ExCommand::Buffer(number) => {
+                self.notifications.warning(format!("No buffer {number}"));
+                iced::Task::none()
+            }
+        }
+    }
+
+    /// Copies the selection to the clipboard, or the whole cursor line
+    /// without one, and removes it when `cut`.
+    fn copy_to_clipboard(&mut self, cut: bool) -> iced::Task<Message> {
+        let selection = self.active_selection();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+        let content = code_editor.content();
+        let (text, start, end) = match selection {
+            Some((start, end)) => {
+                let from = super::editing::position_to_offset(&content, start);
+                let to = super::editing::position_to_offset(&content, end);
+                (content[from..to].to_string(), start, end)
+            }
+            None => super::editing::whole_line(&content, code_editor.cursor_position().0),
+        };
+        self.copied_line = selection.is_none().then(|| text.clone());
+        let copy = iced::clipboard::write(text);
+        if !cut {
+            return copy;
+        }
+
+        let task = super::editing::replace_range(code_editor, &content, start, end, "");
+        if selection.is_none() {
+            // Start of the line that took the place of the cut one, or of
+            // the new last line
+            let _ = code_editor.update(&EditorMessage::GotoPosition(start.0, 0));
+        }
+        buffer.set_text(&code_editor.content());
+        self.selection_anchor = None;
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        iced::Task::batch([copy, task.map(Message::CodeEditorEvent)])
+    }
+
+    /// Pastes a line copied without a selection as a whole line above the
+    /// cursor, like it was taken. `None` leaves other text to the editor.
+    fn paste_copied_line(&mut self, text: &str) -> Option<iced::Task<Message>> {
+        if self.copied_line.as_deref() != Some(text) || self.active_selection().is_some() {
+            return None;
+        }
+        let tab = self.tabs.get_mut(self.active_tab?)?;
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return None;
+        };
+        let content = code_editor.content();
+        let (line, col) = code_editor.cursor_position();
+        let task = super::editing::replace_range(code_editor, &content, (line, 0), (line, 0), text);
+        let line = line + text.matches('\n').count();
+        let task = iced::Task::batch([
+            task,
+            code_editor.update(&EditorMessage::GotoPosition(line, col)),
+        ]);
+        buffer.set_text(&code_editor.content());
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        Some(task.map(Message::CodeEditorEvent))
+    }
+
+    /// Moves the cursor to the start of a 1-based line and scrolls it into
+    /// view. Lines past the end go to the last line.
+    pub(super) fn go_to_line(&mut self, line: usize) -> iced::Task<Message> {
+        self.go_to_position(line, 1)
+    }
+
+    /// Moves the cursor of the active editor to a 1-based position.
+    pub(super) fn go_to_position(&mut self, line: usize, column: usize) -> iced::Task<Message> {
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ..
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let from = code_editor.viewport_scroll();
+        let task = code_editor.update(&EditorMessage::GotoPosition(
+            line.saturating_sub(1),
+            column.saturating_sub(1),
+        ));
+        code_editor.request_focus();
+        self.selection_anchor = None;
+        self.expanded_selections.clear();
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+
+        let ms = self.editor_preferences.smooth_scroll_ms;
+        let (line_height, viewport_height) =
+            (code_editor.line_height(), code_editor.viewport_height());
+        // Wrapped lines don't sit at multiples of the line height
+        if ms > 0 && !code_editor.wrap_enabled() && line_height > 0.0 && viewport_height > 0.0 {
+            self.scroll_animation = ScrollAnimation::new(
+                tab.path.clone(),
+                (line, col),
+                from,
+                line_height,
+                viewport_height,
+                Duration::from_millis(ms),
+                self.editor_preferences.scroll_easing,
+            );
+            if self.scroll_animation.is_some() {
+                // Ticks scroll there instead of the editor's own jump
+                return iced::Task::none();
+            }
+        }
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Runs `:s` over a line range as a single undoable change and reports
+    /// how many replacements were made.
+    fn substitute_lines(
+        &mut self,
+        range: LineRange,
+        substitute: &Substitute,
+    ) -> iced::Task<Message> {
+        let regex = match substitute.regex() {
+            Ok(regex) => regex,
+            Err(err) => {
+                self.notifications.error(format!("Invalid pattern: {err}"));
+                return iced::Task::none();
+            }
+        };
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (first, last) = range.resolve(code_editor.cursor_position().0, lines.len());
+        let (new_lines, count) = substitute.apply(&regex, &lines[first..=last]);
+        self.notifications.info(match count {
+            0 => format!("Pattern not found: {}", substitute.pattern),
+            1 => "1 substitution".to_string(),
+            n => format!("{n} substitutions"),
+        });
+        if count == 0 {
+            return iced::Task::none();
+        }
+
+        let task = super::editing::replace_range(
+            code_editor,
+            &content,
+            (first, 0),
+            (last, lines[last].chars().count()),
+            &new_lines.join("\n"),
+        );
+        // Like vim, leave the cursor on the last line that changed
+        let changed = (first..=last)
+            .rev()
+            .find(|&idx| new_lines[idx - first] != lines[idx])
+            .unwrap_or(last);
+        let _ = code_editor.update(&EditorMessage::GotoPosition(changed, 0));
+        buffer.set_text(&code_editor.content());
+        self.selection_anchor = None;
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Deletes a range of whole lines as a single undoable change.
+    fn delete_lines(&mut self, range: LineRange) -> iced::Task<Message> {
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+
+        let content = code_editor.content();
+        let lines: Vec<&str> = content.split('\n').collect();
+        let (first, last) = range.resolve(code_editor.cursor_position().0, lines.len());
+        // Take the line break after the range, or before it at the end of
+        // the buffer
+        let (start, end) = if last + 1 < lines.len() {
+            ((first, 0), (last + 1, 0))
+        } else if first > 0 {
+            (
+                (first - 1, lines[first - 1].chars().count()),
+                (last, lines[last].chars().count()),
+            )
+        } else {
+            ((0, 0), (last, lines[last].chars().count()))
+        };
+        let task = super::editing::replace_range(code_editor, &content, start, end, "");
+        let line = first.min(code_editor.content().split('\n').count() - 1);
+        let _ = code_editor.update(&EditorMessage::GotoPosition(line, 0));
+        buffer.set_text(&code_editor.content());
+        self.selection_anchor = None;
+        let (line, col) = code_editor.cursor_position();
+        self.cursor_line = line + 1;
+        self.cursor_col = col + 1;
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Opens a path typed after `:e`, relative to the open folder. A path
+    /// that doesn't exist yet opens an empty buffer, created on save.
+    fn edit_path(&mut self, input: &str) -> iced::Task<Message> {
+        let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+        let path = crate::features::paths::resolve(input, root);
+        if path.is_dir() {
+            self.notifications
+                .warning(format!("{} is a folder", path.display()));
+            return iced::Task::none();
+        }
+        if path.exists() {
+            return self.update(Message::FileClicked(path));
+        }
+        if let Some(idx) = self.open_tab_index(&path) {
+            return self.update(Message::TabSelected(idx));
+        }
+        self.notifications
+            .info(format!("New file: {}", path.display()));
+        self.update(Message::FileOpened(path, String::new(), Encoding::Utf8))
+    }
+
+    /// Writes an editor tab to its path, creating missing folders.
+    ///
+    /// The editor widget drops a file's final newline when loading it, so
+    /// `insert_final_newline` is applied here rather than to the buffer.
+    fn write_tab(&self, idx: usize) -> iced::Task<Message> {
+        let Some(tab) = self.tabs.get(idx) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref code_editor, ..
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+        let path = tab.path.clone();
+        let bytes = self.disk_bytes(&path, &code_editor.content());
+        let backup = self.editor_preferences.backups;
+        workers::perform(
+            move || {
+                // Buffers opened with `:e` may not have their folder yet,
+                // `file_io::write` creates it
+                let result = bytes.and_then(|bytes| {
+                    file_io::write(&path, &bytes, backup)?;
+                    // Losing a history entry shouldn't fail the save
+                    let _ = local_history::record(&path, &bytes);
+                    Ok(())
+                });
+                (path, result)
+            },
+            |(path, result)| Message::FileSaved(path, result),
+        )
+    }
+
+    /// Replaces the text of an editor tab as one undoable edit, keeping the
+    /// cursor on the same line.
+    fn replace_tab_text(&mut self, idx: usize, text: &str) -> iced::Task<Message> {
+        let is_active = self.active_tab == Some(idx);
+        let Some(tab) = self.tabs.get_mut(idx) else {
+            return iced::Task::none();
+        };
+        let TabKind::Editor {
+            ref mut code_editor,
+            ref mut buffer,
+        } = tab.kind
+        else {
+            return iced::Task::none();
+        };
+        let content = code_editor.content();
+        // Formatters end their output with a newline the buffer doesn't keep
+        let text = match text.strip_suffix('\n') {
+            Some(trimmed) if !content.ends_with('\n') => trimmed,
+            _ => text,
+        };
+        let Some(task) = super::editing::replace_changed_lines(code_editor, &content, text) else {
+            return iced::Task::none();
+        };
+        buffer.set_text(text);
+        if is_active {
+            self.selection_anchor = None;
+            let (line, col) = code_editor.cursor_position();
+            self.cursor_line = line + 1;
+            self.cursor_col = col + 1;
+        }
+        task.map(Message::CodeEditorEvent)
+    }
+
+    /// Trims trailing whitespace from the buffer when that is configured.
+    pub(super) fn apply_save_actions(&mut self, idx: usize) -> iced::Task<Message> {
+        if !self.editor_preferences.trim_trailing_whitespace {
+            return iced::Task::none();
+        }
+        let Some(TabKind::Editor { code_editor, .. }) = self.tabs.get(idx).map(|tab| &tab.kind)
+        else {
+            return iced::Task::none();
+        };
+        let text = formatter::tidy(&code_editor.content(), true, false);
+        self.replace_tab_text(idx, &text)
+    }
+
+    /// Swaps the settings of the previous folder for those of `root`, put
+    /// over the user's own.
+    fn load_project_settings(&mut self, root: &std::path::Path) {
+        self.project_settings
+            .restore(&mut self.editor_preferences, &self.user_preferences);
+        self.project_settings = match project::load_project_settings(root) {
+            Ok(settings) => settings,
+            Err(err) => {
+                self.notifications
+                    .error(format!("Could not read .pinel/config.toml: {err}"));
+                ProjectSettings::default()
+            }
+        };
+        self.project_settings.apply(&mut self.editor_preferences);
+    }
+
+    /// What the file tree, finder and search leave out of the folder at
+    /// `root`: the user's `exclude` patterns and the project's.
+    fn exclusions_for(&self, root: &std::path::Path) -> Exclusions {
+        let patterns: Vec<String> = exclusions::parse(&self.editor_preferences.exclude)
+            .chain(self.project_settings.exclude.iter().cloned())
+            .collect();
+        Exclusions::new(root, &patterns).with_hidden(self.editor_preferences.show_hidden_files)
+    }
+
+    /// Saves the preferences, without the overrides of the open folder.
+    /// Overridden settings keep the user's own value; edits made in
+    /// Settings update both.
+    fn save_preferences(&mut self) -> Result<(), std::io::Error> {
+        let mut user = self.editor_preferences.clone();
+        self.project_settings
+            .restore(&mut user, &self.user_preferences);
+        self.user_preferences = user;
+        prefs::save_preferences(&self.user_preferences)
+    }
+
+    /// Pipes a tab through its language's formatter, or `None` when the
+    /// language has no formatter configured.
+    fn format_tab(&self, idx: usize, save: bool) -> Option<iced::Task<Message>> {
+        let tab = self.tabs.get(idx)?;
+        let TabKind::Editor {
+            ref code_editor, ..
+        } = tab.kind
+        else {
+            return None;
+        };
+        let content = code_editor.content();
+        let language = crate::language::detect(&tab.path, content.lines().next().unwrap_or(""));
+        let command = formatter::command_for(&self.editor_preferences.formatters, language)?;
+        let path = tab.path.clone();
+        Some(workers::perform(
+            move || {
+                let result = formatter::run(&command, &path, &content);
+                (path, result)
+            },
+            move |(path, result)| Message::DocumentFormatted { path, result, save },
+        ))
+    }
+
+    fn unsaved_tab_indices(&self) -> Vec<usize> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .filter(|(_, tab)| {
+                matches!(&tab.kind, TabKind::Editor { code_editor, .. } if code_editor.is_modified())
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Writes every modified tab to disk before quitting. Stops at the first
+    /// untitled tab or failed write, showing it, and returns whether all
+    /// tabs were saved.
+    fn save_unsaved_tabs(&mut self) -> bool {
+        let backup = self.editor_preferences.backups;
+        for idx in self.unsaved_tab_indices() {
+            let bytes = match &self.tabs[idx].kind {
+                TabKind::Editor { code_editor, .. } => {
+                    self.disk_bytes(&self.tabs[idx].path, &code_editor.content())
+                }
+                TabKind::Preview { .. } | TabKind::Viewer(_) => continue,
+            };
+            let tab = &mut self.tabs[idx];
+            let TabKind::Editor {
+                ref mut code_editor,
+                ..
+            } = tab.kind
+            else {
+                continue;
+            };
+            let failure = if tab.path.as_os_str() == "untitled" {
+                Some(format!("Save {} before quitting", tab.name))
+            } else {
+                bytes
+                    .and_then(|bytes| file_io::write(&tab.path, &bytes, backup))
+                    .map_err(|err| err.to_string())
+                    .err()
+                    .map(|e| format!("Could not save {}: {e}", tab.name))
+            };
+            match failure {
+                Some(message) => {
+                    self.active_tab = Some(idx);
+                    self.notifications.error(message);
+                    return false;
+                }
+                None => code_editor.mark_saved(),
+            }
+        }
+        true
+    }
+
+    /// Opens `path`, asking first when it is over the large file threshold.
+    fn open_path_checked(&mut self, path: PathBuf) -> iced::Task<Message> {
+        let threshold = self
+            .editor_preferences
+            .large_file_threshold_mb
+            .saturating_mul(1024 * 1024);
+        if threshold > 0 {
+            if let Ok(meta) = std::fs::metadata(&path) {
+                if meta.len() > threshold {
+                    self.pending_large_open = Some((path, meta.len()));
+                    return iced::Task::none();
+                }
+            }
+        }
+        Self::open_path_task(path)
+    }
+
+    /// Opens the first megabyte of `path` in an unsaved tab, so saving it can
+    /// never overwrite the original with truncated content.
+    fn open_preview_tab(&mut self, path: &std::path::Path) {
+        const PREVIEW_BYTES: u64 = 1024 * 1024;
+
+        let bytes = match file_io::read_prefix(path, PREVIEW_BYTES) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.notifications.error(err.to_string());
+                return;
+            }
+        };
+        let content = String::from_utf8_lossy(&bytes).to_string();
+        let name = format!(
+            "{} (preview)",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+
+        self.tabs.push(Tab {
+            path: PathBuf::from("untitled"),
+            name,
+            kind: TabKind::Editor {
+                code_editor: self.configured_code_editor(&content, "txt"),
+                buffer: crate::features::editor_buffer::EditorBuffer::from_text(&content),
+            },
+            pinned: false,
+        });
+        self.active_tab = Some(self.tabs.len() - 1);
+        self.remember_visited_tab(self.tabs[self.tabs.len() - 1].path.clone());
+        self.cursor_line = 1;
+        self.cursor_col = 1;
+        self.vim_refresh_cursor_style();
+    }
+
     pub(super) fn vim_refresh_cursor_style(&mut self) {
         if self.terminal_open && self.focused_pane == FocusPane::Terminal {
             if let Some(idx) = self.active_tab {
@@ -85,6 +1873,16 @@ impl App {
         }
     }
 
+    fn git_status_task(&self) -> iced::Task<Message> {
+        let Some(root) = self.file_tree.as_ref().map(|tree| tree.root.clone()) else {
+            return iced::Task::none();
+        };
+        workers::perform(
+            move || crate::features::git::status(&root),
+            Message::GitStatusLoaded,
+        )
+    }
+
     pub(super) fn toggle_terminal_panel(&mut self) -> iced::Task<Message> {
         if self.terminal_pane.is_none() {
             if let Some(ref tree) = self.file_tree {
@@ -118,6 +1916,24 @@ impl App {
     /// * `message` - The event to process.
     pub fn update(&mut self, message: Message) -> iced::Task<Message> {
         match message {
+            // Keys acting on find matches or finder results see the ones for
+            // what was just typed
+            Message::FindNext | Message::FindPrev | Message::ReplaceOne | Message::ReplaceAll
+                if self.debounced.contains_key(&Debounce::Find) =>
+            {
+                let flushed = self.flush_debounced(Debounce::Find);
+                iced::Task::batch([flushed, self.update(message)])
+            }
+            Message::FuzzyFinderNavigate(_) | Message::FuzzyFinderSelect
+                if self.debounced.contains_key(&Debounce::FuzzyFinder) =>
+            {
+                let flushed = self.flush_debounced(Debounce::FuzzyFinder);
+                iced::Task::batch([flushed, self.update(message)])
+            }
+            Message::Debounced(what) => {
+                self.debounced.remove(&what);
+                self.run_debounced(what)
+            }
             Message::FocusEditor => {
                 self.focused_pane = FocusPane::Editor;
                 if let Some(idx) = self.active_tab {
@@ -140,9 +1956,104 @@ impl App {
                 if let Some(term) = &self.terminal_pane {
                     return iced::widget::operation::focus(term.widget_id().clone());
                 }
-                iced::Task::none()
-            }
-            Message::CodeEditorEvent(event) => {
+                iced::Task::none()
+            }
+            Message::CodeEditorEvent(event) => {
+                // Confirm before pasting very large clipboard contents
+                if let EditorMessage::Paste(text) = &event {
+                    let limit = self.editor_preferences.large_paste_lines;
+                    if limit > 0 && text.lines().count() > limit {
+                        self.pending_large_paste = Some(text.clone());
+                        return iced::Task::none();
+                    }
+                }
+
+                match &event {
+                    // Without a selection the widget copies nothing, take the line
+                    EditorMessage::Copy if self.active_selection().is_none() => {
+                        return self.copy_to_clipboard(false);
+                    }
+                    EditorMessage::Copy => self.copied_line = None,
+                    EditorMessage::Paste(text) => {
+                        if let Some(task) = self.paste_copied_line(text) {
+                            return task;
+                        }
+                    }
+                    _ => {}
+                }
+
+                if let EditorMessage::HorizontalScrolled(viewport) = &event {
+                    if let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) {
+                        self.editor_scroll_x
+                            .insert(tab.path.clone(), viewport.absolute_offset().x);
+                    }
+                }
+
+                if !keeps_goal_column(&event) {
+                    self.goal_column = None;
+                }
+
+                // Shift+Tab outdents, and Tab indents when several lines are selected.
+                // Shift+Alt+Right and Left expand and shrink the selection.
+                let alt = self.keyboard_modifiers.alt();
+                match event {
+                    EditorMessage::ArrowKey(iced_code_editor::ArrowDirection::Right, true)
+                        if alt =>
+                    {
+                        return self.expand_selection();
+                    }
+                    EditorMessage::ArrowKey(iced_code_editor::ArrowDirection::Left, true)
+                        if alt =>
+                    {
+                        return self.shrink_selection();
+                    }
+                    // Alt+Up/Down move lines, with Shift they duplicate them
+                    EditorMessage::ArrowKey(
+                        dir @ (iced_code_editor::ArrowDirection::Up
+                        | iced_code_editor::ArrowDirection::Down),
+                        duplicate,
+                    ) if alt => {
+                        let up = matches!(dir, iced_code_editor::ArrowDirection::Up);
+                        return self.move_selected_lines(up, duplicate);
+                    }
+                    EditorMessage::FocusNavigationShiftTab => {
+                        return self.rewrite_selected_lines(LineEdit::Outdent);
+                    }
+                    // Ctrl+Click
+                    EditorMessage::JumpClick(point) => {
+                        return self.go_to_definition(Some(point));
+                    }
+                    // Typing an opening bracket or quote wraps the selection
+                    EditorMessage::CharacterInput(ch) if self.active_selection().is_some() => {
+                        if let Some(task) = self.surround_selection(ch) {
+                            return task;
+                        }
+                    }
+                    EditorMessage::Tab | EditorMessage::FocusNavigationTab
+                        if self
+                            .active_selection()
+                            .is_some_and(|(start, end)| start.0 != end.0) =>
+                    {
+                        return self.rewrite_selected_lines(LineEdit::Indent);
+                    }
+                    _ => {}
+                }
+
+                // Keys acting on the suggestions see the ones for what was just typed
+                if self.autocomplete.active
+                    && matches!(
+                        event,
+                        EditorMessage::Enter
+                            | EditorMessage::ArrowKey(
+                                iced_code_editor::ArrowDirection::Up
+                                    | iced_code_editor::ArrowDirection::Down,
+                                false
+                            )
+                    )
+                {
+                    let _ = self.flush_debounced(Debounce::Autocomplete);
+                }
+
                 // Autocomplete keyboard navigation — intercept before editor processing
                 if self.autocomplete.active && !self.lsp_enabled {
                     if let EditorMessage::ArrowKey(dir, false) = &event {
@@ -165,7 +2076,7 @@ impl App {
                     let mut lsp_path: Option<PathBuf> = None;
                     let mut lsp_content: Option<String> = None;
                     let mut cursor_sync: Option<(EditorMessage, String, String)> = None;
-                    let mut autocomplete_refresh: Option<(EditorMessage, String, PathBuf)> = None;
+                    let mut autocomplete_refresh: Option<EditorMessage> = None;
                     let mut manual_cursor_update: Option<(usize, usize)> = None;
                     let mut hover_candidate: Option<(
                         PathBuf,
@@ -173,8 +2084,19 @@ impl App {
                         iced::Point,
                     )> = None;
                     let cursor_line_before = self.cursor_line;
+                    // Only these events can stop inside a grapheme cluster
+                    let steps_graphemes =
+                        !alt && matches!(
+                            event,
+                            EditorMessage::ArrowKey(
+                                iced_code_editor::ArrowDirection::Left
+                                    | iced_code_editor::ArrowDirection::Right,
+                                false
+                            ) | EditorMessage::Backspace
+                                | EditorMessage::Delete
+                        ) && self.active_selection().is_none();
                     let tab_size = self.editor_preferences.tab_size.max(1);
-                    let indent_unit = self.editor_preferences.indent_unit();
+                    let scrolloff = self.editor_preferences.scrolloff;
 
                     if let Some(tab) = self.tabs.get_mut(idx) {
                         if let TabKind::Editor {
@@ -182,6 +2104,12 @@ impl App {
                             ref mut buffer,
                         } = tab.kind
                         {
+                            self.selection_anchor = super::editing::selection_anchor_after(
+                                &event,
+                                self.selection_anchor,
+                                code_editor.cursor_position(),
+                            );
+
                             // Accept local autocomplete on Enter
                             if !self.lsp_enabled
                                 && self.autocomplete.active
@@ -190,7 +2118,7 @@ impl App {
                             {
                                 if let Some(suggestion) = self.autocomplete.get_selected().cloned()
                                 {
-                                    let prefix_len = self.autocomplete.prefix.len();
+                                    let prefix_len = self.autocomplete.prefix.chars().count();
                                     for _ in 0..prefix_len {
                                         let _ = code_editor.update(&EditorMessage::Backspace);
                                     }
@@ -201,8 +2129,9 @@ impl App {
                                     let after = code_editor.content();
                                     buffer.set_text(&after);
                                     self.cursor_col = self.cursor_col.saturating_sub(prefix_len)
-                                        + suggestion.text.len();
+                                        + suggestion.text.chars().count();
                                     self.autocomplete.cancel();
+                                    self.debounced.remove(&Debounce::Autocomplete);
                                     lsp_path = Some(tab.path.clone());
                                     lsp_content = Some(after);
                                     mapped_task = Some(iced::Task::none());
@@ -220,19 +2149,12 @@ impl App {
                                         .lines()
                                         .nth(self.cursor_line.saturating_sub(1))
                                         .unwrap_or("");
-                                    let before_cursor =
-                                        &line[..self.cursor_col.saturating_sub(1).min(line.len())];
-
-                                    let chars: Vec<char> = before_cursor.chars().collect();
-                                    let word_start = chars
-                                        .iter()
-                                        .enumerate()
-                                        .rev()
-                                        .find(|(_, c)| !c.is_alphanumeric() && **c != '_')
-                                        .map(|(i, _)| i + 1)
-                                        .unwrap_or(0);
-
-                                    let prefix_len = before_cursor.len() - word_start;
+                                    let prefix_len = super::editing::word_before(
+                                        line,
+                                        self.cursor_col.saturating_sub(1),
+                                    )
+                                    .chars()
+                                    .count();
                                     for _ in 0..prefix_len {
                                         let _ = code_editor.update(&EditorMessage::Backspace);
                                     }
@@ -242,10 +2164,11 @@ impl App {
                                     }
                                     let after = code_editor.content();
                                     buffer.set_text(&after);
-                                    self.cursor_col =
-                                        self.cursor_col.saturating_sub(prefix_len) + selected.len();
+                                    self.cursor_col = self.cursor_col.saturating_sub(prefix_len)
+                                        + selected.chars().count();
                                     self.lsp_overlay = iced_code_editor::LspOverlayState::new();
                                     self.autocomplete.cancel();
+                                    self.debounced.remove(&Debounce::Autocomplete);
                                     lsp_path = Some(tab.path.clone());
                                     lsp_content = Some(after);
                                     mapped_task = Some(iced::Task::none());
@@ -258,13 +2181,33 @@ impl App {
                                 && !self.lsp_overlay.completion_visible
                             {
                                 let before = code_editor.content();
+                                let (line, col) = code_editor.cursor_position();
+                                let language = crate::language::detect(
+                                    &tab.path,
+                                    before.lines().next().unwrap_or(""),
+                                );
+                                let indent_unit = self.editor_preferences.indent_unit_for(&before);
                                 let indent = smart_indent_for_enter(
                                     &before,
-                                    cursor_line_before,
+                                    (line, col),
                                     &indent_unit,
+                                    language,
                                 );
-                                let insert = format!("\n{indent}");
-                                let task = code_editor.update(&EditorMessage::Paste(insert));
+                                let mut tasks = Vec::new();
+                                if let Some(closer_indent) =
+                                    split_bracket_indent(&before, (line, col))
+                                {
+                                    // Put the closing bracket on its own line below the cursor
+                                    let insert = format!("\n{indent}\n{closer_indent}");
+                                    tasks.push(code_editor.update(&EditorMessage::Paste(insert)));
+                                    tasks.push(code_editor.update(&EditorMessage::GotoPosition(
+                                        line + 1,
+                                        indent.chars().count(),
+                                    )));
+                                } else {
+                                    let insert = format!("\n{indent}");
+                                    tasks.push(code_editor.update(&EditorMessage::Paste(insert)));
+                                }
                                 let after = code_editor.content();
                                 buffer.set_text(&after);
                                 let indent_cols = indent_visual_width(&indent, tab_size);
@@ -272,7 +2215,8 @@ impl App {
                                     Some((cursor_line_before.saturating_add(1), indent_cols + 1));
                                 lsp_path = Some(tab.path.clone());
                                 lsp_content = Some(after);
-                                mapped_task = Some(task.map(Message::CodeEditorEvent));
+                                mapped_task =
+                                    Some(iced::Task::batch(tasks).map(Message::CodeEditorEvent));
                             }
 
                             if mapped_task.is_none()
@@ -281,7 +2225,9 @@ impl App {
                                     EditorMessage::Tab | EditorMessage::FocusNavigationTab
                                 )
                             {
-                                let indent = self.editor_preferences.indent_unit();
+                                let indent = self
+                                    .editor_preferences
+                                    .indent_unit_for(&code_editor.content());
                                 let mut tasks = Vec::new();
 
                                 for ch in indent.chars() {
@@ -303,29 +2249,142 @@ impl App {
                                     Some(iced::Task::batch(tasks).map(Message::CodeEditorEvent));
                             }
 
+                            // Ticks, hovers and scrolls cannot change the text, so
+                            // the buffer is not copied and compared for them
+                            if mapped_task.is_none() && is_passive(&event) {
+                                let task = code_editor.update(&event);
+                                if let EditorMessage::MouseHover(point) = event {
+                                    if self.lsp_enabled {
+                                        if let Some((position, anchor_point)) =
+                                            code_editor.lsp_hover_anchor_at_point(point)
+                                        {
+                                            hover_candidate =
+                                                Some((tab.path.clone(), position, anchor_point));
+                                        }
+                                    }
+                                }
+                                mapped_task = Some(task.map(Message::CodeEditorEvent));
+                            }
+
+                            if mapped_task.is_none() && steps_graphemes {
+                                let before = code_editor.content();
+                                if let Some(task) =
+                                    super::editing::step_grapheme(code_editor, &before, &event)
+                                {
+                                    let after = code_editor.content();
+                                    buffer.set_text(&after);
+                                    let (line, col) = code_editor.cursor_position();
+                                    manual_cursor_update = Some((line + 1, col + 1));
+                                    lsp_path = Some(tab.path.clone());
+                                    lsp_content = Some(after.clone());
+                                    cursor_sync = Some((event.clone(), before, after));
+                                    if !self.lsp_enabled {
+                                        autocomplete_refresh = Some(event.clone());
+                                    }
+                                    mapped_task = Some(task.map(Message::CodeEditorEvent));
+                                }
+                            }
+
                             if mapped_task.is_none() {
                                 let before = code_editor.content();
+                                let cursor_before = code_editor.cursor_position();
                                 let mut tasks = Vec::new();
                                 let task = code_editor.update(&event);
+                                let task = match goal_column_task(
+                                    code_editor,
+                                    buffer,
+                                    &event,
+                                    cursor_before,
+                                    &mut self.goal_column,
+                                ) {
+                                    Some(goal) => {
+                                        let (line, col) = code_editor.cursor_position();
+                                        manual_cursor_update = Some((line + 1, col + 1));
+                                        iced::Task::batch([task, goal])
+                                    }
+                                    None => task,
+                                };
+                                // Keep `scrolloff` lines of context around the cursor
+                                // after vertical navigation.
+                                let task = match scrolloff_task(code_editor, &event, scrolloff) {
+                                    Some(probe) => probe,
+                                    None => task,
+                                };
                                 tasks.push(task);
+                                if let EditorMessage::MouseClick(_) = event {
+                                    let position = code_editor.cursor_position();
+                                    let is_double_click =
+                                        self.last_editor_click.is_some_and(|(at, last)| {
+                                            last == position
+                                                && at.elapsed() <= super::DOUBLE_CLICK_INTERVAL
+                                        });
+                                    self.selection_anchor = Some(position);
+                                    if is_double_click {
+                                        // Double click selects the word under the cursor
+                                        let line = buffer.line(position.0);
+                                        if let Some((start, end)) =
+                                            super::editing::word_bounds(&line, position.1)
+                                        {
+                                            super::editing::select_range(
+                                                code_editor,
+                                                &before,
+                                                (position.0, start),
+                                                (position.0, end),
+                                            );
+                                            self.selection_anchor = Some((position.0, start));
+                                        }
+                                        self.last_editor_click = None;
+                                    } else {
+                                        self.last_editor_click = Some((Instant::now(), position));
+                                    }
+                                }
+                                let language = crate::language::detect(
+                                    &tab.path,
+                                    before.lines().next().unwrap_or(""),
+                                );
+                                let pairs = if self.editor_preferences.auto_close_brackets {
+                                    crate::features::pairs::pairs_for(
+                                        language,
+                                        &self.editor_preferences.auto_close_exclude,
+                                    )
+                                } else {
+                                    &[]
+                                };
+                                if let Some(task) = super::editing::apply_pair_edits(
+                                    code_editor,
+                                    &event,
+                                    &before,
+                                    cursor_before,
+                                    pairs,
+                                ) {
+                                    tasks.push(task);
+                                    let (line, col) = code_editor.cursor_position();
+                                    manual_cursor_update = Some((line + 1, col + 1));
+                                }
+                                let is_tag_language = tab
+                                    .path
+                                    .extension()
+                                    .and_then(|e| e.to_str())
+                                    .is_some_and(crate::features::tags::is_tag_language);
+                                if is_tag_language {
+                                    if let Some(task) = super::editing::apply_tag_edits(
+                                        code_editor,
+                                        &event,
+                                        &before,
+                                        cursor_before,
+                                    ) {
+                                        tasks.push(task);
+                                        let (line, col) = code_editor.cursor_position();
+                                        manual_cursor_update = Some((line + 1, col + 1));
+                                    }
+                                }
                                 let after = code_editor.content();
                                 buffer.set_text(&after);
                                 lsp_path = Some(tab.path.clone());
                                 lsp_content = Some(after.clone());
                                 cursor_sync = Some((event.clone(), before.clone(), after.clone()));
                                 if !self.lsp_enabled {
-                                    autocomplete_refresh =
-                                        Some((event.clone(), after.clone(), tab.path.clone()));
-                                }
-                                if let EditorMessage::MouseHover(point) = event {
-                                    if self.lsp_enabled {
-                                        if let Some((position, anchor_point)) =
-                                            code_editor.lsp_hover_anchor_at_point(point)
-                                        {
-                                            hover_candidate =
-                                                Some((tab.path.clone(), position, anchor_point));
-                                        }
-                                    }
+                                    autocomplete_refresh = Some(event.clone());
                                 }
 
                                 mapped_task =
@@ -390,8 +2449,12 @@ impl App {
                             self.lsp_overlay.clear_hover();
                         }
                     }
-                    if let Some((event, after, path)) = autocomplete_refresh {
-                        self.refresh_autocomplete_for_event(&event, &after, &path);
+                    if let Some(event) = autocomplete_refresh {
+                        let refresh = self.refresh_autocomplete_for_event(&event);
+                        mapped_task = Some(match mapped_task {
+                            Some(task) => iced::Task::batch([task, refresh]),
+                            None => refresh,
+                        });
                     }
                     if let Some((line, col)) = manual_cursor_update {
                         if let Some(content) = lsp_content.as_ref() {
@@ -411,20 +2474,10 @@ impl App {
                     }
 
                     if let Some(path) = lsp_path {
-                        let entity = path.to_string_lossy().to_string();
-                        let should_send =
-                            match (&self.last_wakatime_entity, &self.last_wakatime_sent_at) {
-                                (Some(last_entity), Some(last_time)) => {
-                                    &entity != last_entity || last_time.elapsed().as_secs() >= 120
-                                }
-                                _ => true,
-                            };
-                        if should_send {
-                            let _ =
-                                wakatime::client::send_heartbeat(&entity, false, &self.wakatime);
-                            self.last_wakatime_entity = Some(entity);
-                            self.last_wakatime_sent_at = Some(Instant::now());
-                        }
+                        self.send_wakatime_heartbeat(&path, false);
+                        let language = self.language_of(&path).name();
+                        self.activity
+                            .record(&path.to_string_lossy(), language, activity::now());
 
                         // LSP: only request completion on actual text-change events
                         if self.lsp_enabled {
@@ -458,7 +2511,30 @@ impl App {
                         }
                     }
 
-                    self.sync_markdown_preview_from_active_editor();
+                    if !is_passive(&event) {
+                        self.sync_markdown_preview_from_active_editor();
+                    }
+
+                    // Keep a locked markdown preview at the same relative position
+                    if let EditorMessage::Scrolled(viewport) = &event {
+                        let y = viewport.relative_offset().y;
+                        if self.preview_scroll_locked
+                            && self.markdown_preview.is_some()
+                            && y.is_finite()
+                        {
+                            let snap = iced::widget::operation::snap_to(
+                                self.markdown_preview_scroll_id.clone(),
+                                iced::widget::operation::RelativeOffset {
+                                    x: None,
+                                    y: Some(y.clamp(0.0, 1.0)),
+                                },
+                            );
+                            mapped_task = Some(match mapped_task {
+                                Some(task) => iced::Task::batch([task, snap]),
+                                None => snap,
+                            });
+                        }
+                    }
 
                     if let Some(task) = mapped_task {
                         return task;
@@ -467,6 +2543,12 @@ impl App {
                 iced::Task::none()
             }
             Message::CodeEditorContentChanged => iced::Task::none(),
+            Message::BufferWordsHarvested(path, words) => {
+                if self.tabs.iter().any(|tab| tab.path == path) {
+                    self.autocomplete.document_words.set_buffer(path, words);
+                }
+                iced::Task::none()
+            }
             Message::LspOverlay(event) => {
                 match event {
                     iced_code_editor::LspOverlayMessage::CompletionSelected(index) => {
@@ -486,19 +2568,12 @@ impl App {
                                             .lines()
                                             .nth(self.cursor_line.saturating_sub(1))
                                             .unwrap_or("");
-                                        let before_cursor = &line_text[..self
-                                            .cursor_col
-                                            .saturating_sub(1)
-                                            .min(line_text.len())];
-                                        let chars: Vec<char> = before_cursor.chars().collect();
-                                        let word_start = chars
-                                            .iter()
-                                            .enumerate()
-                                            .rev()
-                                            .find(|(_, c)| !c.is_alphanumeric() && **c != '_')
-                                            .map(|(i, _)| i + 1)
-                                            .unwrap_or(0);
-                                        let prefix_len = before_cursor.len() - word_start;
+                                        let prefix_len = super::editing::word_before(
+                                            line_text,
+                                            self.cursor_col.saturating_sub(1),
+                                        )
+                                        .chars()
+                                        .count();
 
                                         // Delete prefix
                                         for _ in 0..prefix_len {
@@ -513,7 +2588,7 @@ impl App {
 
                                         self.cursor_col =
                                             self.cursor_col.saturating_sub(prefix_len)
-                                                + completion.len();
+                                                + completion.chars().count();
                                     }
                                 }
                             }
@@ -560,16 +2635,14 @@ impl App {
                 if let Some(ref mut tree) = self.file_tree {
                     tree.select(path.clone());
                 }
-                if let Some(idx) = self.tabs.iter().position(|t| t.path == path) {
-                    self.active_tab = Some(idx);
-                    self.vim_refresh_cursor_style();
-                    return iced::Task::none();
+                if let Some(idx) = self.open_tab_index(&path) {
+                    return self.update(Message::TabSelected(idx));
                 }
                 if Self::should_confirm_sensitive_open(&path) {
                     self.pending_sensitive_open = Some(path);
                     return iced::Task::none();
                 }
-                Self::open_path_task(path)
+                self.open_path_checked(path)
             }
             Message::OpenFileDialog => iced::Task::perform(
                 async {
@@ -603,6 +2676,12 @@ impl App {
                     }
                     self.lsp_diagnostics.remove(&path);
                     self.lsp_server_keys.remove(&path);
+                    self.file_encodings.remove(&path);
+                    self.file_line_endings.remove(&path);
+                    self.large_file_mode_paths.remove(&path);
+                    self.autocomplete.document_words.remove_buffer(&path);
+                    self.remember_positions([idx]);
+                    self.remember_closed_tab(path);
                     self.tabs.remove(idx);
                     if self.tabs.is_empty() {
                         self.active_tab = None;
@@ -617,8 +2696,170 @@ impl App {
                 self.lsp_overlay = iced_code_editor::LspOverlayState::new();
                 self.pending_hover_request = None;
                 self.vim_refresh_cursor_style();
+                self.restore_active_scroll()
+            }
+            Message::CloseTabs(which) => {
+                let kept = match which {
+                    CloseTabs::Others(idx) => self.tabs.get(idx).map(|tab| tab.path.clone()),
+                    _ => None,
+                };
+                let unsaved = self.unsaved_tab_indices();
+                let closing: Vec<usize> = (0..self.tabs.len())
+                    .filter(|idx| !self.tabs[*idx].pinned)
+                    .filter(|idx| match which {
+                        CloseTabs::Others(keep) => *idx != keep,
+                        CloseTabs::ToTheRight(of) => *idx > of,
+                        CloseTabs::Saved => true,
+                    })
+                    .collect();
+                let kept_unsaved = closing.iter().filter(|idx| unsaved.contains(idx)).count();
+                // From the right, so the indices left to close stay valid
+                for idx in closing.into_iter().rev() {
+                    if !unsaved.contains(&idx) {
+                        let _ = self.update(Message::TabClosed(idx));
+                    }
+                }
+                if kept_unsaved > 0 && which != CloseTabs::Saved {
+                    self.notifications.info(format!(
+                        "Kept {kept_unsaved} tabs with unsaved changes open"
+                    ));
+                }
+                match kept.and_then(|path| self.open_tab_index(&path)) {
+                    Some(idx) => self.update(Message::TabSelected(idx)),
+                    None => iced::Task::none(),
+                }
+            }
+            Message::CompareActiveFileDialog => iced::Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Compare With")
+                        .pick_file()
+                        .await
+                        .map(|handle| handle.path().to_path_buf())
+                },
+                |result| match result {
+                    Some(path) => Message::CompareActiveFile(Some(path)),
+                    None => Message::FileTreeRefresh,
+                },
+            ),
+            Message::CompareActiveFile(other) => {
+                let Some(idx) = self.active_tab else {
+                    return iced::Task::none();
+                };
+                let against_disk = other.is_none();
+                let path = other.unwrap_or_else(|| self.tabs[idx].path.clone());
+                let bytes = match file_io::read(&path) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        self.notifications
+                            .error(format!("Could not read {}: {err}", path.display()));
+                        return iced::Task::none();
+                    }
+                };
+                let (text, _) = encoding::decode(&bytes);
+                let text = line_endings::convert(&text, LineEnding::Lf);
+                let label = if against_disk {
+                    "On disk".to_string()
+                } else {
+                    path.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                };
+                self.open_diff(idx, label, &text, against_disk)
+            }
+            Message::DiffHunkChoice { hunk, take_theirs } => {
+                if let Some(choice) = self
+                    .diff_view
+                    .as_mut()
+                    .and_then(|diff| diff.take_theirs.get_mut(hunk))
+                {
+                    *choice = take_theirs;
+                }
+                iced::Task::none()
+            }
+            Message::DiffClose => {
+                self.diff_view = None;
+                iced::Task::none()
+            }
+            Message::DiffApply => match self.diff_view.take() {
+                Some(diff) => self.apply_diff(diff),
+                None => iced::Task::none(),
+            },
+            Message::ToggleLocalHistory => {
+                if self.local_history.take().is_some() {
+                    return iced::Task::none();
+                }
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+                    return iced::Task::none();
+                };
+                let versions = local_history::list(&tab.path);
+                self.local_history = Some((tab.path.clone(), versions));
+                iced::Task::none()
+            }
+            Message::CompareWithVersion(version) | Message::RestoreVersion(version) => {
+                let Some((path, versions)) = self.local_history.take() else {
+                    return iced::Task::none();
+                };
+                let Some(version) = versions.get(version) else {
+                    return iced::Task::none();
+                };
+                let Some(idx) = self.open_tab_index(&path) else {
+                    return iced::Task::none();
+                };
+                let text = match version.read() {
+                    Ok(text) => text,
+                    Err(err) => {
+                        self.notifications
+                            .error(format!("Could not read the saved version: {err}"));
+                        return iced::Task::none();
+                    }
+                };
+                let label = format!(
+                    "Saved {}",
+                    local_history::age_label(version.saved_at, std::time::SystemTime::now())
+                );
+                if matches!(message, Message::CompareWithVersion(_)) {
+                    return self.open_diff(idx, label, &text, false);
+                }
+                let TabKind::Editor { code_editor, .. } = &self.tabs[idx].kind else {
+                    return iced::Task::none();
+                };
+                // Restoring takes every change of the version
+                let mut diff = DiffView::new(path, label, false, &code_editor.content(), &text);
+                diff.take_theirs.fill(true);
+                self.apply_diff(diff)
+            }
+            Message::TogglePinTab(idx) => {
+                if idx >= self.tabs.len() {
+                    return iced::Task::none();
+                }
+                let mut tab = self.tabs.remove(idx);
+                tab.pinned = !tab.pinned;
+                let to = self.tabs.iter().take_while(|tab| tab.pinned).count();
+                self.tabs.insert(to, tab);
+                // Follow the active tab to where the move left it
+                self.active_tab = self.active_tab.map(|active| {
+                    if active == idx {
+                        return to;
+                    }
+                    let active = if active > idx { active - 1 } else { active };
+                    if active >= to {
+                        active + 1
+                    } else {
+                        active
+                    }
+                });
+                iced::Task::none()
+            }
+            Message::ToggleTabList => {
+                self.tab_list_open = !self.tab_list_open;
                 iced::Task::none()
             }
+            Message::TabListClicked(idx) => {
+                self.tab_list_open = false;
+                self.update(Message::TabSelected(idx))
+            }
             Message::CloseActiveTab => {
                 if let Some(idx) = self.active_tab {
                     let path = self.tabs[idx].path.clone();
@@ -639,6 +2880,12 @@ impl App {
 
                     self.lsp_diagnostics.remove(&path);
                     self.lsp_server_keys.remove(&path);
+                    self.file_encodings.remove(&path);
+                    self.file_line_endings.remove(&path);
+                    self.large_file_mode_paths.remove(&path);
+                    self.autocomplete.document_words.remove_buffer(&path);
+                    self.remember_positions([idx]);
+                    self.remember_closed_tab(path);
                     self.tabs.remove(idx);
                     if self.tabs.is_empty() {
                         self.active_tab = None;
@@ -649,31 +2896,49 @@ impl App {
                 self.lsp_overlay = iced_code_editor::LspOverlayState::new();
                 self.pending_hover_request = None;
                 self.vim_refresh_cursor_style();
+                self.restore_active_scroll()
+            }
+            Message::FileViewerOpened(path, viewer) => {
+                if let Some(idx) = self.open_tab_index(&path) {
+                    return self.update(Message::TabSelected(idx));
+                }
+                self.remember_recent_file(path.clone());
+                self.pending_cursor = None;
+                let name = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                self.tabs.push(Tab {
+                    path: path.clone(),
+                    name,
+                    kind: TabKind::Viewer(viewer),
+                    pinned: false,
+                });
+                self.active_tab = Some(self.tabs.len() - 1);
+                self.remember_visited_tab(path);
+                self.cancel_autocomplete();
                 iced::Task::none()
             }
-            Message::FileOpened(path, content) => {
-                if let Some(idx) = self.tabs.iter().position(|t| t.path == path) {
-                    self.active_tab = Some(idx);
-                    self.vim_refresh_cursor_style();
-                    return iced::Task::none();
+            Message::FileOpened(path, content, encoding) => {
+                // The file may have been opened while this read was in flight
+                if let Some(idx) = self.open_tab_index(&path) {
+                    return self.update(Message::TabSelected(idx));
                 }
+                self.selection_anchor = None;
 
-                let effective_content = if content.is_empty() && path.exists() {
-                    std::fs::read_to_string(&path).unwrap_or_default()
+                let (effective_content, encoding) = if content.is_empty() && path.exists() {
+                    match file_io::read(&path) {
+                        Ok(bytes) => encoding::decode(&bytes),
+                        Err(err) => return self.update(Message::FileOpenFailed(path, err)),
+                    }
                 } else {
-                    content
+                    (content, encoding)
                 };
+                self.set_encoding(path.clone(), encoding);
+                self.set_line_ending(path.clone(), line_endings::detect(&effective_content));
 
-                self.recent_files.retain(|p| p != &path);
-                self.recent_files.insert(0, path.clone());
-                if self.recent_files.len() > 20 {
-                    self.recent_files.truncate(20);
-                }
-
-                let entity = path.to_string_lossy().to_string();
-                let _ = wakatime::client::send_heartbeat(&entity, false, &self.wakatime);
-                self.last_wakatime_entity = Some(entity);
-                self.last_wakatime_sent_at = Some(Instant::now());
+                self.remember_recent_file(path.clone());
 
                 let name = path
                     .file_name()
@@ -681,20 +2946,29 @@ impl App {
                     .to_string_lossy()
                     .to_string();
                 let opened_path = path.clone();
-                let ext = path
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("txt")
-                    .to_string();
+                let large_file_mode = self.large_file_mode_paths.contains(&path);
+                let harvest_task = if large_file_mode {
+                    iced::Task::none()
+                } else {
+                    Self::harvest_buffer_words_task(path.clone(), effective_content.clone())
+                };
+                // Large file mode skips highlighting and the language server
+                let syntax = if large_file_mode {
+                    "txt"
+                } else {
+                    let first_line = effective_content.lines().next().unwrap_or("");
+                    crate::language::detect(&path, first_line).syntax()
+                };
                 self.tabs.push(Tab {
                     path,
                     name,
                     kind: TabKind::Editor {
-                        code_editor: { self.configured_code_editor(&effective_content, &ext) },
+                        code_editor: { self.configured_code_editor(&effective_content, syntax) },
                         buffer: crate::features::editor_buffer::EditorBuffer::from_text(
                             &effective_content,
                         ),
                     },
+                    pinned: false,
                 });
 
                 // Detach LSP from all existing tabs before switching to the new one
@@ -709,14 +2983,15 @@ impl App {
                 }
 
                 self.active_tab = Some(self.tabs.len() - 1);
+                self.remember_visited_tab(self.tabs[self.tabs.len() - 1].path.clone());
                 self.cursor_line = 1;
                 self.cursor_col = 1;
-                self.autocomplete.cancel();
+                self.cancel_autocomplete();
                 self.pending_hover_request = None;
                 self.vim_refresh_cursor_style();
 
                 // Attach LSP client to the editor
-                if self.lsp_enabled && opened_path.is_absolute() {
+                if self.lsp_enabled && opened_path.is_absolute() && !large_file_mode {
                     if let Some(language) = iced_code_editor::lsp_language_for_path(&opened_path) {
                         self.dev_log(format!(
                             "LSP: Attempting to attach {} server for {}",
@@ -764,9 +3039,48 @@ impl App {
                         opened_path.is_absolute()
                     ));
                 }
-                iced::Task::none()
+                let autocommand_task = match self.tabs.last() {
+                    Some(tab) => {
+                        let path = tab.path.clone();
+                        self.emit(EditorEvent::BufOpen, &path)
+                    }
+                    None => iced::Task::none(),
+                };
+                // A file opened before goes back to where its cursor was left
+                let opened = self.tabs.last().map(|t| t.path.clone());
+                let cursor_task = match self.pending_cursor.take() {
+                    Some((path, line, column)) if Some(&path) == opened.as_ref() => {
+                        self.go_to_position(line, column)
+                    }
+                    _ => match opened.and_then(|path| self.last_positions.get(&path)) {
+                        Some((line, column)) => self.go_to_position(line, column),
+                        None => iced::Task::none(),
+                    },
+                };
+                // Start at the top, not where the tab before was scrolled
+                let scroll_task =
+                    crate::ui::restore_scroll(self.editor_container_id.clone(), 0.0, 0.0)
+                        .chain(cursor_task);
+                iced::Task::batch([harvest_task, autocommand_task, scroll_task])
             }
             Message::TabSelected(idx) => {
+                self.selection_anchor = None;
+                let switched = self.active_tab != Some(idx);
+                // Re-harvest the tab being left, it may have been edited
+                let harvest_task = self
+                    .active_tab
+                    .filter(|&active| active != idx)
+                    .and_then(|active| self.tabs.get(active))
+                    .and_then(|tab| match &tab.kind {
+                        TabKind::Editor { code_editor, .. } => {
+                            Some(Self::harvest_buffer_words_task(
+                                tab.path.clone(),
+                                code_editor.content(),
+                            ))
+                        }
+                        TabKind::Preview { .. } | TabKind::Viewer(_) => None,
+                    })
+                    .unwrap_or_else(iced::Task::none);
                 if idx < self.tabs.len() {
                     // Detach LSP from all tabs first
                     for tab in &mut self.tabs {
@@ -780,6 +3094,8 @@ impl App {
                     }
 
                     self.active_tab = Some(idx);
+                    let path = self.tabs[idx].path.clone();
+                    self.remember_visited_tab(path);
 
                     // Get path and server_key before mutable borrow
                     let (tab_path, has_lsp) = if let Some(tab) = self.tabs.get(idx) {
@@ -835,6 +3151,9 @@ impl App {
                         } = tab.kind
                         {
                             code_editor.request_focus();
+                            let (line, col) = code_editor.cursor_position();
+                            self.cursor_line = line + 1;
+                            self.cursor_col = col + 1;
 
                             if let Some((client, document, server_key)) = lsp_client_data {
                                 code_editor.set_lsp_enabled(true);
@@ -849,62 +3168,510 @@ impl App {
                     self.vim_refresh_cursor_style();
                     self.pending_hover_request = None;
                 }
+                if !switched {
+                    return harvest_task;
+                }
+                iced::Task::batch([harvest_task, self.restore_active_scroll()])
+            }
+            Message::FileTreeRefresh => {
+                if let Some(ref mut tree) = self.file_tree {
+                    tree.refresh();
+                    self.git_branch = crate::features::status_line::git_branch(&tree.root);
+                }
+                iced::Task::none()
+            }
+            Message::OpenFolderDialog => iced::Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Open Folder")
+                        .pick_folder()
+                        .await
+                        .map(|handle| handle.path().to_path_buf())
+                },
+                |result| match result {
+                    Some(path) => Message::FolderOpened(path),
+                    None => Message::FileTreeRefresh,
+                },
+            ),
+            Message::OpenLocation { path, line, column } => {
+                if self.fuzzy_finder.open {
+                    self.fuzzy_finder.close();
+                }
+                if !path.exists() {
+                    return self.edit_path(&path.to_string_lossy());
+                }
+                self.open_at(path, line, column)
+            }
+            Message::RecoveryTick => {
+                let snapshots = self
+                    .unsaved_tab_indices()
+                    .into_iter()
+                    .filter_map(|idx| {
+                        let tab = &self.tabs[idx];
+                        let TabKind::Editor { code_editor, .. } = &tab.kind else {
+                            return None;
+                        };
+                        let untitled = tab.path.as_os_str() == "untitled";
+                        Some(recovery::Snapshot {
+                            // Untitled buffers share a path, tell them apart
+                            key: if untitled {
+                                format!("untitled-{idx}")
+                            } else {
+                                tab.path.to_string_lossy().to_string()
+                            },
+                            path: (!untitled).then_some(tab.path.as_path()),
+                            content: code_editor.content(),
+                        })
+                    })
+                    .collect();
+                if let Err(err) = self.recovery.sync(snapshots) {
+                    self.dev_log(format!("Recovery: could not write swap files: {err}"));
+                }
+                iced::Task::none()
+            }
+            Message::RestoreRecovery(restore) => {
+                let recovered = std::mem::take(&mut self.pending_recovery);
+                let mut tasks = Vec::new();
+                for buffer in recovered {
+                    if restore {
+                        let task = match &buffer.path {
+                            Some(path) => self.update(Message::FileOpened(
+                                path.clone(),
+                                String::new(),
+                                Encoding::Utf8,
+                            )),
+                            None => self.update(Message::NewFile),
+                        };
+                        tasks.push(task);
+                        let idx = buffer
+                            .path
+                            .as_ref()
+                            .and_then(|path| self.open_tab_index(path))
+                            .unwrap_or(self.tabs.len().saturating_sub(1));
+                        tasks.push(self.replace_tab_text(idx, &buffer.content));
+                    }
+                    buffer.remove();
+                }
+                iced::Task::batch(tasks)
+            }
+            Message::ShowWordCount => {
+                let Some(TabKind::Editor { code_editor, .. }) = self
+                    .active_tab
+                    .and_then(|idx| self.tabs.get(idx))
+                    .map(|tab| &tab.kind)
+                else {
+                    return iced::Task::none();
+                };
+                let mut message = format!(
+                    "Document: {}",
+                    text_stats::count(&code_editor.content()).summary()
+                );
+                if let Some(selected) = self.selected_text() {
+                    message.push_str(&format!(
+                        "\nSelection: {}",
+                        text_stats::count(&selected).summary()
+                    ));
+                }
+                self.notifications.info(message);
+                iced::Task::none()
+            }
+            Message::GoToDefinition => self.go_to_definition(None),
+            Message::StartRename => {
+                let Some(word) = self.word_at_cursor() else {
+                    self.notifications.info("No symbol at the cursor to rename");
+                    return iced::Task::none();
+                };
+                self.pending_block_edit = None;
+                self.command_input.open();
+                self.command_input.input = format!("rename {word}");
+                iced::widget::operation::focus(self.command_input_id.clone())
+            }
+            Message::RenameConfirm(confirmed) => {
+                let Some(rename) = self.pending_rename.take() else {
+                    return iced::Task::none();
+                };
+                let Some(idx) = self.active_tab.filter(|_| confirmed) else {
+                    return iced::Task::none();
+                };
+                let Some(TabKind::Editor { code_editor, .. }) =
+                    self.tabs.get(idx).map(|tab| &tab.kind)
+                else {
+                    return iced::Task::none();
+                };
+                let text = rename::apply(&code_editor.content(), &rename.from, &rename.to);
+                self.notifications.success(format!(
+                    "Renamed {} to {} in {} places",
+                    rename.from, rename.to, rename.count
+                ));
+                self.replace_tab_text(idx, &text)
+            }
+            Message::DefinitionsFound(name, locations) => {
+                let Some(first) = locations.first().cloned() else {
+                    self.notifications
+                        .info(format!("No definition found for {name}"));
+                    return iced::Task::none();
+                };
+                if locations.len() > 1 {
+                    self.notifications.info(format!(
+                        "{} definitions of {name}, showing the first",
+                        locations.len()
+                    ));
+                }
+                self.open_at(first.path, first.line + 1, 1)
+            }
+            Message::ToggleSpellCheck => {
+                let enabled = !self.editor_preferences.spell_check;
+                self.editor_preferences.spell_check = enabled;
+                if enabled && self.spelling.is_empty() {
+                    self.notifications.warning(format!(
+                        "No dictionary found. Put a hunspell .dic file in {}",
+                        spelling::dictionaries_dir().display()
+                    ));
+                }
+                iced::Task::none()
+            }
+            Message::NextMisspelling => {
+                let line_count = match self.active_tab.and_then(|idx| self.tabs.get(idx)) {
+                    Some(Tab {
+                        kind: TabKind::Editor { buffer, .. },
+                        ..
+                    }) => buffer.line_count(),
+                    _ => return iced::Task::none(),
+                };
+                let (line, col) = (self.cursor_line - 1, self.cursor_col - 1);
+                // Wrap around, ending with the start of the cursor line
+                let found = (0..=line_count).find_map(|step| {
+                    let idx = (line + step) % line_count;
+                    self.misspellings_on(idx)
+                        .into_iter()
+                        .find(|misspelling| match step {
+                            0 => misspelling.start > col,
+                            _ if step == line_count => misspelling.start <= col,
+                            _ => true,
+                        })
+                        .map(|misspelling| (idx, misspelling))
+                });
+                match found {
+                    Some((line, misspelling)) => {
+                        self.go_to_position(line + 1, misspelling.start + 1)
+                    }
+                    None => {
+                        self.notifications.info("No misspelled words");
+                        iced::Task::none()
+                    }
+                }
+            }
+            Message::ShowSpellingSuggestions => {
+                let (line, col) = (self.cursor_line - 1, self.cursor_col - 1);
+                let Some(misspelling) = self
+                    .misspellings_on(line)
+                    .into_iter()
+                    .find(|misspelling| (misspelling.start..=misspelling.end).contains(&col))
+                else {
+                    self.notifications.info("No misspelled word at the cursor");
+                    return iced::Task::none();
+                };
+                self.pending_spelling = Some(spelling::Suggestions {
+                    line,
+                    words: self.spelling.suggest(&misspelling.word),
+                    misspelling,
+                });
+                iced::Task::none()
+            }
+            Message::FixSpelling(replacement) => {
+                let Some(suggestions) = self.pending_spelling.take() else {
+                    return iced::Task::none();
+                };
+                let Some(replacement) = replacement else {
+                    return iced::Task::none();
+                };
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+                    return iced::Task::none();
+                };
+                let TabKind::Editor {
+                    ref mut code_editor,
+                    ref mut buffer,
+                } = tab.kind
+                else {
+                    return iced::Task::none();
+                };
+                let content = code_editor.content();
+                let Misspelling { start, end, .. } = suggestions.misspelling;
+                let task = super::editing::replace_range(
+                    code_editor,
+                    &content,
+                    (suggestions.line, start),
+                    (suggestions.line, end),
+                    &replacement,
+                );
+                buffer.set_text(&code_editor.content());
+                code_editor.request_focus();
+                self.selection_anchor = None;
+                let (line, col) = code_editor.cursor_position();
+                self.cursor_line = line + 1;
+                self.cursor_col = col + 1;
+                task.map(Message::CodeEditorEvent)
+            }
+            Message::AddWordToDictionary => {
+                let Some(suggestions) = self.pending_spelling.take() else {
+                    return iced::Task::none();
+                };
+                let word = suggestions.misspelling.word;
+                let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+                match self.spelling.add(&word, root) {
+                    Ok(()) => self
+                        .notifications
+                        .success(format!("Added \"{word}\" to the dictionary")),
+                    Err(err) => self
+                        .notifications
+                        .error(format!("Could not add \"{word}\" to the dictionary: {err}")),
+                }
                 iced::Task::none()
             }
-            Message::FileTreeRefresh => {
-                if let Some(ref mut tree) = self.file_tree {
-                    tree.refresh();
+            Message::ToggleActivity => {
+                self.activity_visible = !self.activity_visible;
+                self.activity.save_if_dirty();
+                iced::Task::none()
+            }
+            Message::ConvertToUtf8 => {
+                if let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) {
+                    let path = tab.path.clone();
+                    self.set_encoding(path, Encoding::Utf8);
+                    self.notifications.info("File will be saved as UTF-8");
                 }
                 iced::Task::none()
             }
-            Message::OpenFolderDialog => iced::Task::perform(
-                async {
-                    rfd::AsyncFileDialog::new()
-                        .set_title("Open Folder")
-                        .pick_folder()
-                        .await
-                        .map(|handle| handle.path().to_path_buf())
-                },
-                |result| match result {
-                    Some(path) => Message::FolderOpened(path),
-                    None => Message::FileTreeRefresh,
-                },
-            ),
+            Message::SetLineEnding(ending) => {
+                if let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) {
+                    let path = tab.path.clone();
+                    self.set_line_ending(path, ending);
+                    self.notifications
+                        .info(format!("Line endings will be saved as {}", ending.label()));
+                }
+                iced::Task::none()
+            }
+            Message::PathDropped(path) => {
+                if path.is_dir() {
+                    self.update(Message::FolderOpened(path))
+                } else {
+                    self.update(Message::FileClicked(path))
+                }
+            }
             Message::FolderOpened(path) => {
-                self.file_tree = Some(FileTree::new(path.clone()));
-                self.all_workspace_files = crate::features::search::collect_all_files(&path);
+                crate::config::recent::push_recent(&mut self.recent_folders, path.clone());
+                let _ =
+                    crate::config::recent::save_recent(&self.recent_folders, &self.recent_files);
+                self.load_project_settings(&path);
+                let exclusions = self.exclusions_for(&path);
+                self.file_tree = Some(FileTree::new(path.clone(), exclusions.clone()));
+                self.git_branch = crate::features::status_line::git_branch(&path);
+                self.all_workspace_files.clear();
                 self.fuzzy_finder.set_folder(path.clone());
+                self.terminal.set_directory(path.clone());
                 self.lsp.set_workspace_root(path.clone());
                 self.lsp_enabled = true;
+                self.spelling.load_added(Some(&path));
+                let event_task = self.emit(EditorEvent::FolderOpened, &path);
+                iced::Task::batch([event_task, index_workspace(path, exclusions)])
+            }
+            Message::RevealInTree(path) => {
+                let revealed = self
+                    .file_tree
+                    .as_mut()
+                    .is_some_and(|tree| tree.reveal(&path));
+                if revealed {
+                    self.sidebar_visible = true;
+                } else {
+                    self.notifications
+                        .info(format!("{} is not in the open folder", path.display()));
+                }
+                iced::Task::none()
+            }
+            Message::CopyPath { path, relative } => {
+                let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+                let path = match root.and_then(|root| path.strip_prefix(root).ok()) {
+                    Some(inner) if relative => inner,
+                    _ => path.as_path(),
+                };
+                let text = path.to_string_lossy().into_owned();
+                self.notifications.info(format!("Copied {text}"));
+                iced::clipboard::write(text)
+            }
+            Message::ToggleHiddenFiles => {
+                self.editor_preferences.show_hidden_files =
+                    !self.editor_preferences.show_hidden_files;
+                let _ = self.save_preferences();
+                let Some(root) = self.file_tree.as_ref().map(|tree| tree.root.clone()) else {
+                    return iced::Task::none();
+                };
+                let exclusions = self.exclusions_for(&root);
+                if let Some(tree) = self.file_tree.as_mut() {
+                    tree.exclusions = exclusions.clone();
+                    tree.refresh();
+                }
+                index_workspace(root, exclusions)
+            }
+            Message::WorkspaceIndexed(root, files, finder_files) => {
+                // Another folder may have been opened in the meantime
+                if self
+                    .file_tree
+                    .as_ref()
+                    .is_some_and(|tree| tree.root == root)
+                {
+                    self.all_workspace_files = files;
+                    self.fuzzy_finder.set_files(finder_files);
+                }
+                iced::Task::none()
+            }
+            Message::WorkerFailed => {
+                self.notifications.error("A background job failed");
                 iced::Task::none()
             }
             Message::SaveFile => {
                 if let Some(idx) = self.active_tab {
                     if let Some(tab) = self.tabs.get(idx) {
-                        if let TabKind::Editor {
-                            ref code_editor, ..
-                        } = tab.kind
-                        {
-                            let entity = tab.path.to_string_lossy().to_string();
-                            let _ = wakatime::client::send_heartbeat(&entity, true, &self.wakatime);
-                            self.last_wakatime_entity = Some(entity);
-                            self.last_wakatime_sent_at = Some(Instant::now());
-
-                            let path = tab.path.clone();
-                            let content = code_editor.content();
-                            if path == PathBuf::from("untitled") {
+                        if let TabKind::Editor { .. } = tab.kind {
+                            if tab.path == PathBuf::from("untitled") {
                                 return iced::Task::perform(async {}, |_| Message::SaveAs);
                             }
-                            return iced::Task::perform(
-                                async move { std::fs::write(&path, content).map_err(|e| e.to_string()) },
-                                Message::FileSaved,
-                            );
+                            let path = tab.path.clone();
+                            let pre_task = self.emit(EditorEvent::BufWritePre, &path);
+                            // The formatter runs in the background and writes
+                            // the file once done, so it can't be a pre-write
+                            // reaction that the write waits on
+                            if self.editor_preferences.format_on_save {
+                                if let Some(format_task) = self.format_tab(idx, true) {
+                                    return iced::Task::batch([pre_task, format_task]);
+                                }
+                            }
+                            return iced::Task::batch([pre_task, self.write_tab(idx)]);
                         }
                     }
                 }
                 self.lsp_overlay = iced_code_editor::LspOverlayState::new();
                 iced::Task::none()
             }
+            Message::FormatDocument => {
+                let Some(idx) = self.active_tab else {
+                    return iced::Task::none();
+                };
+                if let Some(task) = self.format_tab(idx, false) {
+                    return task;
+                }
+                let Some(tab) = self.tabs.get(idx) else {
+                    return iced::Task::none();
+                };
+                if let TabKind::Editor {
+                    ref code_editor, ..
+                } = tab.kind
+                {
+                    let content = code_editor.content();
+                    let language =
+                        crate::language::detect(&tab.path, content.lines().next().unwrap_or(""));
+                    self.notifications
+                        .warning(format!("No formatter configured for {}", language.name()));
+                }
+                iced::Task::none()
+            }
+            Message::CloseOutputPanel => {
+                self.output_panel = None;
+                iced::Task::none()
+            }
+            Message::DocumentFormatted { path, result, save } => {
+                let Some(idx) = self.open_tab_index(&path) else {
+                    return iced::Task::none();
+                };
+                let format_task = match result {
+                    Ok(text) => self.replace_tab_text(idx, &text),
+                    Err(err) => {
+                        let first_line = err.lines().next().unwrap_or_default();
+                        self.notifications
+                            .error(format!("Formatting failed: {first_line}"));
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        let mut panel = OutputPanel::new(format!("Formatter output: {name}"));
+                        for line in err.lines() {
+                            panel.push_line(line);
+                        }
+                        self.output_panel = Some(panel);
+                        iced::Task::none()
+                    }
+                };
+                if save {
+                    return iced::Task::batch([format_task, self.write_tab(idx)]);
+                }
+                format_task
+            }
+            Message::RunTask(name) => {
+                let Some(root) = self.file_tree.as_ref().map(|tree| tree.root.clone()) else {
+                    return iced::Task::none();
+                };
+                let Some(spec) = self.project_tasks.iter().find(|t| t.name == name).cloned() else {
+                    return iced::Task::none();
+                };
+                if let Some(handle) = self.running_task.take() {
+                    handle.abort();
+                }
+                let mut panel = OutputPanel::new(format!("Task: {}", spec.name));
+                panel.push_line(&format!("$ {}", spec.command));
+                self.output_panel = Some(panel);
+
+                let (task, handle) =
+                    iced::Task::run(tasks::run(spec.command, root), Message::TaskEvent).abortable();
+                self.running_task = Some(handle);
+                task
+            }
+            Message::StopTask => {
+                if let Some(handle) = self.running_task.take() {
+                    handle.abort();
+                    if let Some(panel) = &mut self.output_panel {
+                        panel.push_line("Task stopped");
+                    }
+                }
+                iced::Task::none()
+            }
+            Message::TaskEvent(event) => {
+                let (severity, finished) = match event {
+                    TaskEvent::Line(line) => {
+                        if let Some(panel) = &mut self.output_panel {
+                            panel.push_line(&line);
+                        }
+                        return iced::Task::none();
+                    }
+                    TaskEvent::Finished(Ok(0)) => (Severity::Success, "Task finished".to_string()),
+                    TaskEvent::Finished(Ok(code)) => (
+                        Severity::Error,
+                        format!("Task failed with exit code {code}"),
+                    ),
+                    TaskEvent::Finished(Err(err)) => {
+                        (Severity::Error, format!("Could not run task: {err}"))
+                    }
+                };
+                self.running_task = None;
+                if let Some(panel) = &mut self.output_panel {
+                    panel.push_line(&finished);
+                }
+                self.notifications.push(severity, finished);
+                iced::Task::none()
+            }
+            Message::OutputLinkClicked(link) => match link {
+                Link::File { path, line, column } => {
+                    let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+                    let path = crate::features::paths::resolve(&path, root);
+                    if !path.is_file() {
+                        self.notifications
+                            .error(format!("File not found: {}", path.display()));
+                        return iced::Task::none();
+                    }
+                    self.open_at(path, line, column)
+                }
+                Link::Url(url) => {
+                    if let Err(err) = output::open_url(&url) {
+                        self.notifications
+                            .error(format!("Could not open {url}: {err}"));
+                    }
+                    iced::Task::none()
+                }
+            },
             Message::SaveCurrentFileAs(path) => {
                 if let Some(idx) = self.active_tab {
                     if let Some(tab) = self.tabs.get(idx) {
@@ -912,16 +3679,17 @@ impl App {
                             ref code_editor, ..
                         } = tab.kind
                         {
-                            let content = code_editor.content();
-                            return iced::Task::perform(
-                                async move {
-                                    std::fs::write(&path, content)
-                                        .map(|_| path)
-                                        .map_err(|e| e.to_string())
+                            let bytes = self.disk_bytes(&tab.path, &code_editor.content());
+                            let backup = self.editor_preferences.backups;
+                            return workers::perform(
+                                move || {
+                                    let result = bytes
+                                        .and_then(|bytes| file_io::write(&path, &bytes, backup));
+                                    (path, result)
                                 },
-                                |result| match result {
-                                    Ok(path) => Message::CurrentFileSavedAs(path),
-                                    Err(err) => Message::FileSaved(Err(err)),
+                                |(path, result)| match result {
+                                    Ok(()) => Message::CurrentFileSavedAs(path),
+                                    Err(err) => Message::FileSaved(path, Err(err)),
                                 },
                             );
                         }
@@ -937,7 +3705,13 @@ impl App {
                             .unwrap_or_default()
                             .to_string_lossy()
                             .to_string();
-                        tab.path = path.clone();
+                        let old_path = std::mem::replace(&mut tab.path, path.clone());
+                        if let Some(encoding) = self.file_encodings.remove(&old_path) {
+                            self.file_encodings.insert(path.clone(), encoding);
+                        }
+                        if let Some(ending) = self.file_line_endings.remove(&old_path) {
+                            self.file_line_endings.insert(path.clone(), ending);
+                        }
 
                         if let TabKind::Editor {
                             ref mut code_editor,
@@ -950,28 +3724,34 @@ impl App {
                     }
                 }
 
-                self.recent_files.retain(|p| p != &path);
-                self.recent_files.insert(0, path.clone());
-                if self.recent_files.len() > 20 {
-                    self.recent_files.truncate(20);
-                }
-
-                let entity = path.to_string_lossy().to_string();
-                let _ = wakatime::client::send_heartbeat(&entity, true, &self.wakatime);
-                self.last_wakatime_entity = Some(entity);
-                self.last_wakatime_sent_at = Some(Instant::now());
+                self.remember_recent_file(path.clone());
 
-                iced::Task::none()
+                self.emit(EditorEvent::BufWritePost, &path)
             }
             Message::InputLog(line) => {
                 eprintln!("{line}");
                 self.dev_log(line);
                 iced::Task::none()
             }
-            Message::FileSaved(result) => {
-                if let Err(e) = result {
-                    eprintln!("Failed to save file: {}", e);
-                } else if let Some(idx) = self.active_tab {
+            Message::FileSaved(path, Err(err)) => {
+                // Save As failures have no tab at the path to retry with
+                let actions = if self.open_tab_index(&path).is_some() {
+                    vec![
+                        ("Retry", Message::RetrySave(path.clone())),
+                        ("Save As…", Message::SaveTabAs(path)),
+                    ]
+                } else {
+                    vec![("Save As…", Message::SaveAs)]
+                };
+                self.notifications.push_with_actions(
+                    Severity::Error,
+                    format!("Could not save: {err}"),
+                    actions,
+                );
+                iced::Task::none()
+            }
+            Message::FileSaved(path, Ok(())) => {
+                if let Some(idx) = self.open_tab_index(&path) {
                     if let Some(tab) = self.tabs.get_mut(idx) {
                         if let TabKind::Editor {
                             ref mut code_editor,
@@ -981,6 +3761,8 @@ impl App {
                             code_editor.mark_saved();
                             code_editor.lsp_did_save();
                         }
+                        self.notifications.success(format!("Saved {}", tab.name));
+                        return self.emit(EditorEvent::BufWritePost, &path);
                     }
                 }
                 iced::Task::none()
@@ -1049,7 +3831,93 @@ impl App {
 
                 iced::Task::none()
             }
+            Message::TogglePreviewScrollLock => {
+                self.preview_scroll_locked = !self.preview_scroll_locked;
+                self.notifications.info(if self.preview_scroll_locked {
+                    "Preview scrolls with the editor"
+                } else {
+                    "Preview scrolls on its own"
+                });
+                iced::Task::none()
+            }
             Message::MarkdownLinkClicked(_uri) => iced::Task::none(),
+            Message::ToggleGitPanel => {
+                self.git_panel_visible = !self.git_panel_visible;
+                self.git_error = None;
+                self.vim_refresh_cursor_style();
+                if self.git_panel_visible {
+                    if self.file_tree.is_none() {
+                        self.git_error = Some("Open a folder to use source control".to_string());
+                        return iced::Task::none();
+                    }
+                    return self.git_status_task();
+                }
+                iced::Task::none()
+            }
+            Message::GitStatusLoaded(result) => {
+                match result {
+                    Ok(changes) => {
+                        self.git_changes = changes;
+                        self.git_error = None;
+                    }
+                    Err(err) => {
+                        self.git_changes.clear();
+                        self.git_error = Some(err);
+                    }
+                }
+                iced::Task::none()
+            }
+            Message::GitStage(path) => {
+                let root = self.file_tree.as_ref().map(|tree| tree.root.clone());
+                workers::perform(
+                    move || {
+                        let root = root.ok_or_else(|| "No folder open".to_string())?;
+                        crate::features::git::stage(&root, &path)
+                    },
+                    Message::GitOperationFinished,
+                )
+            }
+            Message::GitUnstage(path) => {
+                let root = self.file_tree.as_ref().map(|tree| tree.root.clone());
+                workers::perform(
+                    move || {
+                        let root = root.ok_or_else(|| "No folder open".to_string())?;
+                        crate::features::git::unstage(&root, &path)
+                    },
+                    Message::GitOperationFinished,
+                )
+            }
+            Message::GitCommitMessageChanged(message) => {
+                self.git_commit_message = message;
+                iced::Task::none()
+            }
+            Message::GitCommit => {
+                let root = self.file_tree.as_ref().map(|tree| tree.root.clone());
+                let message = self.git_commit_message.clone();
+                workers::perform(
+                    move || {
+                        let root = root.ok_or_else(|| "No folder open".to_string())?;
+                        crate::features::git::commit(&root, &message)
+                    },
+                    Message::GitCommitted,
+                )
+            }
+            Message::GitCommitted(result) => {
+                if result.is_ok() {
+                    self.git_commit_message.clear();
+                }
+                self.update(Message::GitOperationFinished(result))
+            }
+            Message::GitOperationFinished(result) => {
+                if let Err(err) = result {
+                    self.git_error = Some(err);
+                    return iced::Task::none();
+                }
+                if let Some(root) = self.file_tree.as_ref().map(|tree| tree.root.clone()) {
+                    self.git_branch = crate::features::status_line::git_branch(&root);
+                }
+                self.git_status_task()
+            }
             Message::ToggleSearch => {
                 if self.search_visible {
                     self.search_visible = false;
@@ -1071,8 +3939,11 @@ impl App {
                 }
                 if let Some(ref tree) = self.file_tree {
                     let root = tree.root.clone();
-                    iced::Task::perform(
-                        async move { crate::features::search::search_workspace(&root, &query) },
+                    let exclusions = tree.exclusions.clone();
+                    workers::perform(
+                        move || {
+                            crate::features::search::search_workspace(&root, &query, &exclusions)
+                        },
                         Message::SearchCompleted,
                     )
                 } else {
@@ -1083,23 +3954,73 @@ impl App {
                 self.search_results = results;
                 iced::Task::none()
             }
-            Message::SearchResultClicked(path, _line_number) => {
+            Message::SearchResultClicked(path, line_number) => {
+                // The search is case-insensitive, find the match the same way
+                let query = self.search_query.to_lowercase();
+                let columns = self
+                    .search_results
+                    .iter()
+                    .find(|result| result.path == path)
+                    .and_then(|result| {
+                        result
+                            .matches
+                            .iter()
+                            .find(|found| found.line_number == line_number)
+                    })
+                    .and_then(|found| {
+                        let lower = found.line_content.to_lowercase();
+                        let start = lower.find(&query)?;
+                        // Lowercasing can change lengths outside ASCII
+                        (lower.len() == found.line_content.len()).then(|| {
+                            let start = lower[..start].chars().count();
+                            start..start + query.chars().count()
+                        })
+                    })
+                    .unwrap_or(0..0);
                 self.search_visible = false;
                 self.search_query.clear();
                 self.search_results.clear();
                 if let Some(ref mut tree) = self.file_tree {
                     tree.select(path.clone());
                 }
-                if let Some(idx) = self.tabs.iter().position(|t| t.path == path) {
-                    self.active_tab = Some(idx);
-                    self.vim_refresh_cursor_style();
+                if Self::should_confirm_sensitive_open(&path)
+                    && self.open_tab_index(&path).is_none()
+                {
+                    self.pending_sensitive_open = Some(path);
                     return iced::Task::none();
                 }
-                if Self::should_confirm_sensitive_open(&path) {
-                    self.pending_sensitive_open = Some(path);
+                if let Some(from) = self.current_location() {
+                    self.jumps.push(from);
+                }
+                self.jump_flash = Some(JumpFlash {
+                    path: path.clone(),
+                    line: line_number.saturating_sub(1),
+                    columns: columns.clone(),
+                });
+                iced::Task::batch([
+                    self.open_at(path, line_number, columns.start + 1),
+                    iced::Task::perform(tokio::time::sleep(jumps::FLASH_DURATION), |_| {
+                        Message::JumpFlashEnded
+                    }),
+                ])
+            }
+            Message::JumpBack | Message::JumpForward => {
+                let Some(current) = self.current_location() else {
                     return iced::Task::none();
+                };
+                let to = if matches!(message, Message::JumpBack) {
+                    self.jumps.back(current)
+                } else {
+                    self.jumps.forward(current)
+                };
+                match to {
+                    Some(to) => self.open_at(to.path, to.line, to.column),
+                    None => iced::Task::none(),
                 }
-                Self::open_path_task(path)
+            }
+            Message::JumpFlashEnded => {
+                self.jump_flash = None;
+                iced::Task::none()
             }
             Message::ToggleFileFinder => {
                 self.file_finder_visible = !self.file_finder_visible;
@@ -1154,6 +4075,13 @@ impl App {
                     self.file_finder_results
                         .get(self.file_finder_selected)
                         .map(|(_, _, p)| p.clone())
+                        .or_else(|| {
+                            // Nothing matched, so treat the query as a typed path
+                            let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+                            let typed =
+                                crate::features::paths::resolve(&self.file_finder_query, root);
+                            typed.is_file().then_some(typed)
+                        })
                 };
                 self.file_finder_visible = false;
                 self.file_finder_query.clear();
@@ -1171,6 +4099,7 @@ impl App {
                     iced::Task::none()
                 } else {
                     self.fuzzy_finder.toggle();
+                    self.index_symbols();
                     self.fuzzy_finder.update_preview();
                     self.vim_refresh_cursor_style();
                     iced::widget::operation::focus(self.fuzzy_finder.input_id.clone())
@@ -1181,20 +4110,31 @@ impl App {
                     return iced::Task::none();
                 }
                 self.fuzzy_finder.input = query;
-                self.fuzzy_finder.filter();
-                self.fuzzy_finder.update_preview();
-                iced::widget::operation::focus(self.fuzzy_finder.input_id.clone())
+                iced::Task::batch([
+                    self.debounce(Debounce::FuzzyFinder),
+                    iced::widget::operation::focus(self.fuzzy_finder.input_id.clone()),
+                ])
             }
             Message::FuzzyFinderNavigate(delta) => {
-               if self.command_palette.open {
-                   let count = self.command_palette.filtered_commands.len();
-                   if count == 0 {
-                       return iced::Task::none();
-                   }
-                   let current = self.command_palette_selected as i32;
-                   let next = (current + delta).rem_euclid(count as i32) as usize;
-                   self.command_palette_selected = next;
-                   return iced::Task::none();
+                if self.buffer_switcher.is_some() {
+                    return self.update(Message::BufferSwitcherNext(delta));
+                }
+                if self.command_palette.open {
+                    let count = self.command_palette.filtered_commands.len();
+                    if count == 0 {
+                        return iced::Task::none();
+                    }
+                    let current = self.command_palette_selected as i32;
+                    let next = (current + delta).rem_euclid(count as i32) as usize;
+                    self.command_palette_selected = next;
+                    return iced::Task::none();
+                }
+
+               if self.command_input.open && self.pending_block_edit.is_none() {
+                   self.command_input.recall(delta);
+                   return iced::widget::operation::move_cursor_to_end(
+                       self.command_input_id.clone(),
+                   );
                }
 
                if !self.fuzzy_finder.open {
@@ -1204,6 +4144,9 @@ impl App {
                iced::Task::none()
             }
             Message::FuzzyFinderSelect => {
+                if self.buffer_switcher.is_some() {
+                    return self.update(Message::BufferSwitcherSelect);
+                }
                 if self.command_palette.open {
                     if let Some(cmd) = self
                         .command_palette
@@ -1212,6 +4155,7 @@ impl App {
                     {
                         let command_name = cmd.name.clone();
                         self.command_palette.close();
+                        self.record_palette_command(&command_name);
                         return self.execute_palette_command(&command_name);
                     }
                     return iced::Task::none();
@@ -1220,23 +4164,103 @@ impl App {
                 if !self.fuzzy_finder.open {
                     return iced::Task::none();
                 }
+                // Act on the query as typed, not the one last filtered
+                let _ = self.flush_debounced(Debounce::FuzzyFinder);
+                if self.fuzzy_finder.symbol_mode() {
+                    return match self.fuzzy_finder.select_symbol() {
+                        Some(line) => self.update(Message::FuzzyFinderSymbolClicked(line)),
+                        None => iced::Task::none(),
+                    };
+                }
+                if let Some(path) = self.fuzzy_finder.creatable_path() {
+                    return self.update(Message::FuzzyFinderCreateFile(path));
+                }
+                let position = self.fuzzy_finder.query_location().1;
                 if let Some(path) = self.fuzzy_finder.select() {
-                    return self.update(Message::FileClicked(path));
+                    return match position {
+                        Some((line, column)) => {
+                            self.update(Message::OpenLocation { path, line, column })
+                        }
+                        None => self.update(Message::FileClicked(path)),
+                    };
                 }
                 iced::Task::none()
             }
+            Message::GoToSymbol => {
+                if !self.fuzzy_finder.open {
+                    self.fuzzy_finder.toggle();
+                    self.index_symbols();
+                }
+                self.fuzzy_finder.input = fuzzy_finder::SYMBOL_PREFIX.to_string();
+                self.fuzzy_finder.filter();
+                self.fuzzy_finder.update_preview();
+                self.vim_refresh_cursor_style();
+                iced::widget::operation::focus(self.fuzzy_finder.input_id.clone())
+            }
+            Message::FuzzyFinderSymbolClicked(line) => {
+                self.fuzzy_finder.close();
+                self.vim_refresh_cursor_style();
+                self.go_to_line(line + 1)
+            }
+            Message::FuzzyFinderCreateFile(path) => {
+                let created = path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| {
+                        let mut file = std::fs::OpenOptions::new()
+                            .write(true)
+                            .create_new(true)
+                            .open(&path)?;
+                        match crate::config::templates::template_for(&path) {
+                            Some(template) => {
+                                std::io::Write::write_all(&mut file, template.as_bytes())
+                            }
+                            None => Ok(()),
+                        }
+                    });
+                if let Err(e) = created {
+                    self.notifications
+                        .error(format!("Could not create {}: {e}", path.display()));
+                    return iced::Task::none();
+                }
+                self.fuzzy_finder.close();
+                self.fuzzy_finder.add_file(path.clone());
+                if let Some(ref mut tree) = self.file_tree {
+                    tree.refresh();
+                }
+                self.update(Message::FileClicked(path))
+            }
             Message::EscapePressed => {
                 if self.autocomplete.active {
-                    self.autocomplete.cancel();
+                    self.cancel_autocomplete();
                 } else if self.lsp_overlay.completion_visible || self.lsp_overlay.hover_visible {
                     self.lsp_overlay = iced_code_editor::LspOverlayState::new();
                     self.pending_hover_request = None;
                 } else if self.command_palette.open {
                     self.command_palette.close();
+                } else if self.buffer_switcher.is_some() {
+                    self.buffer_switcher = None;
+                } else if self.activity_visible {
+                    self.activity_visible = false;
+                } else if self.local_history.is_some() {
+                    self.local_history = None;
+                } else if self.color_picking.is_some() {
+                    self.color_picking = None;
+                } else if self.notifications.history_open {
+                    self.notifications.history_open = false;
                 } else if self.pending_sensitive_open.is_some() {
                     self.pending_sensitive_open = None;
+                } else if self.pending_large_open.is_some() {
+                    self.pending_large_open = None;
+                } else if self.pending_large_paste.is_some() {
+                    self.pending_large_paste = None;
+                } else if self.pending_quit.is_some() {
+                    self.pending_quit = None;
+                } else if self.pending_replace_all.is_some() {
+                    self.pending_replace_all = None;
                 } else if self.command_input.open {
                     self.command_input.close();
+                    self.pending_block_edit = None;
                 } else if self.find_replace.open {
                     self.find_replace.close();
                 } else if self.fuzzy_finder.open {
@@ -1250,6 +4274,8 @@ impl App {
                     self.search_visible = false;
                     self.search_query.clear();
                     self.search_results.clear();
+                } else if self.git_panel_visible {
+                    self.git_panel_visible = false;
                 } else if self.theme_dropdown_open {
                     self.theme_dropdown_open = false;
                 } else if self.settings_open {
@@ -1258,16 +4284,90 @@ impl App {
                 self.vim_refresh_cursor_style();
                 iced::Task::none()
             }
+            Message::LargeFileOpen(action) => {
+                let Some((path, _)) = self.pending_large_open.take() else {
+                    return iced::Task::none();
+                };
+                match action {
+                    LargeFileAction::Open => Self::open_path_task(path),
+                    LargeFileAction::LargeFileMode => {
+                        self.large_file_mode_paths.insert(path.clone());
+                        Self::open_path_task(path)
+                    }
+                    LargeFileAction::Preview => {
+                        self.open_preview_tab(&path);
+                        iced::Task::none()
+                    }
+                    LargeFileAction::Cancel => iced::Task::none(),
+                }
+            }
+            Message::QuitRequested => {
+                self.activity.save_if_dirty();
+                self.remember_positions(0..self.tabs.len());
+                let unsaved = self.unsaved_tab_indices().len();
+                if unsaved == 0 {
+                    self.recovery.clear();
+                    return iced::exit();
+                }
+                self.pending_quit = Some(unsaved);
+                iced::Task::none()
+            }
+            Message::QuitConfirm(action) => {
+                self.pending_quit = None;
+                match action {
+                    QuitAction::SaveAndQuit => {
+                        if self.save_unsaved_tabs() {
+                            self.recovery.clear();
+                            iced::exit()
+                        } else {
+                            iced::Task::none()
+                        }
+                    }
+                    QuitAction::QuitWithoutSaving => {
+                        self.recovery.clear();
+                        iced::exit()
+                    }
+                    QuitAction::Cancel => iced::Task::none(),
+                }
+            }
+            Message::LargePasteConfirm(confirmed) => {
+                let Some(text) = self.pending_large_paste.take() else {
+                    return iced::Task::none();
+                };
+                if !confirmed {
+                    return iced::Task::none();
+                }
+                if let Some(idx) = self.active_tab {
+                    if let Some(tab) = self.tabs.get_mut(idx) {
+                        if let TabKind::Editor {
+                            ref mut code_editor,
+                            ref mut buffer,
+                        } = tab.kind
+                        {
+                            let task = code_editor.update(&EditorMessage::Paste(text));
+                            buffer.set_text(&code_editor.content());
+                            let (line, col) = code_editor.cursor_position();
+                            self.cursor_line = line + 1;
+                            self.cursor_col = col + 1;
+                            return task.map(Message::CodeEditorEvent);
+                        }
+                    }
+                }
+                iced::Task::none()
+            }
             Message::SensitiveFileOpenConfirm(confirmed) => {
                 let path = self.pending_sensitive_open.take();
                 if confirmed {
                     if let Some(path) = path {
-                        return Self::open_path_task(path);
+                        return self.open_path_checked(path);
                     }
                 }
                 iced::Task::none()
             }
             Message::ToggleCommandPalette => {
+                if !self.command_palette.open {
+                    self.refresh_project_tasks();
+                }
                 let include_markdown_render = self.active_tab_supports_markdown_preview();
                 self.command_palette.toggle(include_markdown_render);
                 self.command_palette_selected = 0;
@@ -1287,6 +4387,7 @@ impl App {
             }
             Message::CommandPaletteSelect(command_name) => {
                 self.command_palette.close();
+                self.record_palette_command(&command_name);
                 self.execute_palette_command(&command_name)
             }
             Message::CommandPaletteNavigate(delta) => {
@@ -1329,18 +4430,7 @@ impl App {
             }
             Message::FindQueryChanged(query) => {
                 self.find_replace.find_text = query;
-                if let Some(idx) = self.active_tab {
-                    if let Some(tab) = self.tabs.get(idx) {
-                        if let TabKind::Editor {
-                            ref code_editor, ..
-                        } = tab.kind
-                        {
-                            let text = code_editor.content();
-                            self.find_replace.find_matches(&text);
-                        }
-                    }
-                }
-                iced::Task::none()
+                self.debounce(Debounce::Find)
             }
             Message::ReplaceQueryChanged(query) => {
                 self.find_replace.replace_text = query;
@@ -1363,15 +4453,64 @@ impl App {
                             ..
                         } = tab.kind
                         {
-                            let mut text = code_editor.content();
+                            let content = code_editor.content();
+                            let mut text = content.clone();
+                            if self.find_replace.scope.is_multi_file() {
+                                self.find_replace.find_matches(&text);
+                            }
                             self.find_replace.replace_next(&mut text);
-                            let _ = code_editor.reset(&text);
+                            let _ =
+                                super::editing::replace_changed_lines(code_editor, &content, &text);
                             buffer.set_text(&text);
                         }
                     }
                 }
                 self.vim_refresh_cursor_style();
-                iced::Task::none()
+                if self.find_replace.scope.is_multi_file() {
+                    return self.refresh_find_matches();
+                }
+                iced::Task::none()
+            }
+            Message::ReplaceAll if self.find_replace.scope == Scope::Workspace => {
+                self.pending_replace_all = Some((
+                    self.find_replace.match_count,
+                    self.find_replace.files_matched,
+                ));
+                iced::Task::none()
+            }
+            Message::ReplaceAll if self.find_replace.scope.is_multi_file() => {
+                let (matches, files) = self.replace_in_open_editors(false);
+                self.vim_refresh_cursor_style();
+                self.update(Message::FilesReplaced(find_replace::Replaced {
+                    matches,
+                    files,
+                    errors: Vec::new(),
+                }))
+            }
+            Message::ReplaceAllConfirm(confirmed) => {
+                if self.pending_replace_all.take().is_none() || !confirmed {
+                    return iced::Task::none();
+                }
+                let (matches, files) = self.replace_in_open_editors(true);
+                self.vim_refresh_cursor_style();
+                let open: HashSet<PathBuf> = self.tabs.iter().map(|tab| tab.path.clone()).collect();
+                let on_disk: Vec<PathBuf> = self
+                    .all_workspace_files
+                    .iter()
+                    .map(|(_, path)| path.clone())
+                    .filter(|path| !open.contains(path))
+                    .collect();
+                let find = self.find_replace.clone();
+                let backup = self.editor_preferences.backups;
+                workers::perform(
+                    move || {
+                        let mut replaced = find.replace_in_files(&on_disk, backup);
+                        replaced.matches += matches;
+                        replaced.files += files;
+                        replaced
+                    },
+                    Message::FilesReplaced,
+                )
             }
             Message::ReplaceAll => {
                 if let Some(idx) = self.active_tab {
@@ -1382,9 +4521,11 @@ impl App {
                             ..
                         } = tab.kind
                         {
-                            let mut text = code_editor.content();
+                            let content = code_editor.content();
+                            let mut text = content.clone();
                             self.find_replace.replace_all(&mut text);
-                            let _ = code_editor.reset(&text);
+                            let _ =
+                                super::editing::replace_changed_lines(code_editor, &content, &text);
                             buffer.set_text(&text);
                         }
                     }
@@ -1394,22 +4535,67 @@ impl App {
             }
             Message::ToggleCaseSensitive => {
                 self.find_replace.case_sensitive = !self.find_replace.case_sensitive;
-                if let Some(idx) = self.active_tab {
-                    if let Some(tab) = self.tabs.get(idx) {
-                        if let TabKind::Editor {
-                            ref code_editor, ..
-                        } = tab.kind
-                        {
-                            let text = code_editor.content();
-                            self.find_replace.find_matches(&text);
-                        }
-                    }
+                self.refresh_find_matches()
+            }
+            Message::FindScopeSelected(scope) => {
+                if scope == Scope::Selection {
+                    let content = match self.active_tab.and_then(|idx| self.tabs.get(idx)) {
+                        Some(Tab {
+                            kind: TabKind::Editor { code_editor, .. },
+                            ..
+                        }) => code_editor.content(),
+                        _ => String::new(),
+                    };
+                    let Some(range) = self
+                        .selection_offsets(&content)
+                        .filter(|(start, end)| start < end)
+                    else {
+                        self.notifications
+                            .info("Select the text to search in first");
+                        return iced::Task::none();
+                    };
+                    self.find_replace.selection = Some(range);
+                }
+                self.find_replace.scope = scope;
+                self.find_replace.current_match = 0;
+                self.refresh_find_matches()
+            }
+            Message::FindCounted {
+                query,
+                matches,
+                files,
+            } => {
+                // Drop counts for a query or scope that has since changed
+                if query == self.find_replace.find_text
+                    && self.find_replace.scope == Scope::Workspace
+                {
+                    self.find_replace.match_count = matches;
+                    self.find_replace.files_matched = files;
                 }
                 iced::Task::none()
             }
+            Message::FilesReplaced(replaced) => {
+                for err in &replaced.errors {
+                    self.notifications.error(err.to_string());
+                }
+                let files = if replaced.files == 1 { "file" } else { "files" };
+                self.notifications.success(format!(
+                    "Replaced {} matches in {} {files}",
+                    replaced.matches, replaced.files
+                ));
+                self.refresh_find_matches()
+            }
+            Message::TogglePreserveCase => {
+                self.find_replace.preserve_case = !self.find_replace.preserve_case;
+                iced::Task::none()
+            }
             Message::ToggleSettings => {
                 self.settings_open = !self.settings_open;
                 self.theme_dropdown_open = false;
+                if self.settings_open {
+                    // Fonts from the fonts folder are loaded after startup
+                    self.font_families = fonts::monospace_families();
+                }
                 self.vim_refresh_cursor_style();
                 iced::Task::none()
             }
@@ -1425,19 +4611,273 @@ impl App {
             Message::SettingsTabSizeChanged(val) => {
                 if let Ok(size) = val.parse::<usize>() {
                     self.editor_preferences.tab_size = size.max(1).min(16);
+                    self.user_preferences.tab_size = self.editor_preferences.tab_size;
                 }
                 iced::Task::none()
             }
             Message::SettingsToggleUseSpaces => {
                 self.editor_preferences.use_spaces = !self.editor_preferences.use_spaces;
+                self.user_preferences.use_spaces = self.editor_preferences.use_spaces;
+                iced::Task::none()
+            }
+            Message::EditLines(edit) => self.rewrite_selected_lines(edit),
+            Message::MoveLines { up, duplicate } => self.move_selected_lines(up, duplicate),
+            Message::DuplicateSelection => self.duplicate_selection(),
+            Message::JoinLines => self.join_selected_lines(),
+            Message::TransformSelection(transform) => self.transform_selection(transform),
+            Message::StepNumbers { delta, sequential } => self.step_numbers(delta, sequential),
+            Message::ExpandSelection => self.expand_selection(),
+            Message::DeleteSurroundingPair => self.delete_surrounding_pair(),
+            Message::PromptBlockEdit(edit) => {
+                if self.active_selection().is_none() {
+                    return iced::Task::none();
+                }
+                self.command_input.open();
+                self.pending_block_edit = Some(edit);
+                iced::widget::operation::focus(self.command_input_id.clone())
+            }
+            Message::ShrinkSelection => self.shrink_selection(),
+            Message::ModifiersChanged(modifiers) => {
+                self.keyboard_modifiers = modifiers;
+                // Releasing Ctrl picks the highlighted buffer, like Alt+Tab
+                if !modifiers.control() && self.buffer_switcher.is_some() {
+                    return self.update(Message::BufferSwitcherSelect);
+                }
+                iced::Task::none()
+            }
+            Message::BufferSwitcherNext(delta) => {
+                let count = self.tabs.len();
+                if count < 2 {
+                    return iced::Task::none();
+                }
+                // Opening starts on the previous buffer
+                let current = self.buffer_switcher.unwrap_or(0) as i32;
+                let next = (current + delta).rem_euclid(count as i32) as usize;
+                self.buffer_switcher = Some(next);
+                iced::Task::none()
+            }
+            Message::BufferSwitcherSelect => {
+                let Some(selected) = self.buffer_switcher.take() else {
+                    return iced::Task::none();
+                };
+                match self.tabs_by_recency().get(selected) {
+                    Some(&idx) => self.update(Message::TabSelected(idx)),
+                    None => iced::Task::none(),
+                }
+            }
+            Message::BufferSwitcherClicked(idx) => {
+                self.buffer_switcher = None;
+                self.update(Message::TabSelected(idx))
+            }
+            Message::ReopenClosedTab => match self.closed_tabs.pop() {
+                Some(path) => self.update(Message::FileClicked(path)),
+                None => iced::Task::none(),
+            },
+            Message::SettingsCycleBackups => {
+                self.editor_preferences.backups = self.editor_preferences.backups.next();
+                iced::Task::none()
+            }
+            Message::PickColor(line, col) => {
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+                    return iced::Task::none();
+                };
+                let TabKind::Editor { buffer, .. } = &tab.kind else {
+                    return iced::Task::none();
+                };
+                let text = buffer.line(line);
+                match colors::at(text.trim_end_matches(['\n', '\r']), col) {
+                    Some(literal) => {
+                        self.color_picking = Some(colors::Picking {
+                            path: tab.path.clone(),
+                            line,
+                            literal,
+                        });
+                    }
+                    None => self.notifications.info("No color literal at the cursor"),
+                }
+                iced::Task::none()
+            }
+            Message::ResolveConflict { line, resolution } => {
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+                    return iced::Task::none();
+                };
+                let TabKind::Editor {
+                    ref mut code_editor,
+                    ref mut buffer,
+                } = tab.kind
+                else {
+                    return iced::Task::none();
+                };
+                let content = code_editor.content();
+                let lines: Vec<&str> = content.split('\n').collect();
+                let Some(conflict) = conflicts::find(lines.iter().copied().enumerate())
+                    .into_iter()
+                    .find(|conflict| conflict.start == line)
+                else {
+                    return iced::Task::none();
+                };
+                let kept = conflicts::resolve(&lines, &conflict, resolution);
+                // The conflict goes with the line break after it, unless it
+                // ends the file
+                let (end, text) = if conflict.end + 1 < lines.len() {
+                    let text = kept.iter().map(|line| format!("{line}\n")).collect();
+                    ((conflict.end + 1, 0), text)
+                } else {
+                    let end = (conflict.end, lines[conflict.end].chars().count());
+                    (end, kept.join("\n"))
+                };
+                let task = super::editing::replace_range(
+                    code_editor,
+                    &content,
+                    (conflict.start, 0),
+                    end,
+                    &text,
+                );
+                buffer.set_text(&code_editor.content());
+                self.selection_anchor = None;
+                self.expanded_selections.clear();
+                task.map(Message::CodeEditorEvent)
+            }
+            Message::ColorPickCancelled => {
+                self.color_picking = None;
+                iced::Task::none()
+            }
+            Message::ColorPicked(color) => {
+                let Some(picking) = self.color_picking.take() else {
+                    return iced::Task::none();
+                };
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+                    return iced::Task::none();
+                };
+                let TabKind::Editor {
+                    ref mut code_editor,
+                    ref mut buffer,
+                } = tab.kind
+                else {
+                    return iced::Task::none();
+                };
+                if tab.path != picking.path {
+                    return iced::Task::none();
+                }
+                let content = code_editor.content();
+                let literal = picking.literal;
+                let task = super::editing::replace_range(
+                    code_editor,
+                    &content,
+                    (picking.line, literal.start),
+                    (picking.line, literal.end),
+                    &colors::write(color, literal.format),
+                );
+                buffer.set_text(&code_editor.content());
+                code_editor.request_focus();
+                self.selection_anchor = None;
+                let (line, col) = code_editor.cursor_position();
+                self.cursor_line = line + 1;
+                self.cursor_col = col + 1;
+                task.map(Message::CodeEditorEvent)
+            }
+            Message::ToggleSmoothScroll => {
+                let prefs = &mut self.editor_preferences;
+                prefs.smooth_scroll_ms = if prefs.smooth_scroll_ms == 0 {
+                    smooth_scroll::DEFAULT_DURATION_MS
+                } else {
+                    0
+                };
+                let _ = self.save_preferences();
+                iced::Task::none()
+            }
+            Message::ScrollAnimationTick => {
+                let Some(animation) = self.scroll_animation.clone() else {
+                    return iced::Task::none();
+                };
+                let Some(tab) = self
+                    .active_tab
+                    .and_then(|idx| self.tabs.get_mut(idx))
+                    .filter(|tab| tab.path == animation.path)
+                else {
+                    self.scroll_animation = None;
+                    return iced::Task::none();
+                };
+                let TabKind::Editor {
+                    ref mut code_editor,
+                    ..
+                } = tab.kind
+                else {
+                    self.scroll_animation = None;
+                    return iced::Task::none();
+                };
+                // The cursor moved meanwhile, leave the view to it
+                if code_editor.cursor_position() != (animation.line, animation.column) {
+                    self.scroll_animation = None;
+                    return iced::Task::none();
+                }
+                let target = EditorMessage::GotoPosition(animation.line, animation.column);
+                let task = match animation.top_at(Instant::now()) {
+                    Some(top) => {
+                        // Same trick as `scrolloff_task`: keep the scroll of
+                        // a cursor on the anchor line, then move it back
+                        let anchor = animation.anchor_line(
+                            top,
+                            code_editor.line_height(),
+                            code_editor.viewport_height(),
+                        );
+                        let task = code_editor
+                            .update(&EditorMessage::GotoPosition(anchor, animation.column));
+                        let _ = code_editor.update(&target);
+                        task
+                    }
+                    None => {
+                        self.scroll_animation = None;
+                        code_editor.update(&target)
+                    }
+                };
+                task.map(Message::CodeEditorEvent)
+            }
+            Message::ToggleStickyScroll => {
+                self.editor_preferences.sticky_scroll = !self.editor_preferences.sticky_scroll;
+                let _ = self.save_preferences();
+                iced::Task::none()
+            }
+            Message::StickyHeaderClicked(line) => self.go_to_line(line + 1),
+            Message::CycleRenderWhitespace => {
+                let mode = self.editor_preferences.render_whitespace.next();
+                self.editor_preferences.render_whitespace = mode;
+                if mode != RenderWhitespace::Off && self.editor_preferences.word_wrap {
+                    self.notifications
+                        .info("Whitespace is not drawn while word wrap is on");
+                }
+                let _ = self.save_preferences();
+                iced::Task::none()
+            }
+            Message::SettingsToggleAutoClose => {
+                self.editor_preferences.auto_close_brackets =
+                    !self.editor_preferences.auto_close_brackets;
+                iced::Task::none()
+            }
+            Message::ToggleWordWrap => {
+                let enabled = !self.editor_preferences.word_wrap;
+                self.editor_preferences.word_wrap = enabled;
+                for tab in &mut self.tabs {
+                    if let TabKind::Editor { code_editor, .. } = &mut tab.kind {
+                        code_editor.set_wrap_enabled(enabled);
+                    }
+                }
+                // Changing the wrap scrolls editors back to the left
+                self.editor_scroll_x.clear();
+                iced::Task::none()
+            }
+            Message::SettingsToggleDetectIndentation => {
+                self.editor_preferences.detect_indentation =
+                    !self.editor_preferences.detect_indentation;
                 iced::Task::none()
             }
             Message::SettingsSavePreferences => {
-                let _ = prefs::save_preferences(&self.editor_preferences);
-                self.notification = Some(Notification {
-                    message: "Preferences saved".to_string(),
-                    shown_at: Instant::now(),
-                });
+                match self.save_preferences() {
+                    Ok(()) => self.notifications.success("Preferences saved"),
+                    Err(err) => self
+                        .notifications
+                        .error(format!("Could not save preferences: {err}")),
+                }
                 iced::Task::none()
             }
             Message::SettingsSelectTheme(name) => {
@@ -1447,7 +4887,7 @@ impl App {
                 self.active_theme_name = name.clone();
                 self.editor_preferences.theme_name = name;
                 self.theme_dropdown_open = false;
-                let _ = prefs::save_preferences(&self.editor_preferences);
+                let _ = self.save_preferences();
                 iced::Task::none()
             }
             Message::SettingsReloadTheme => {
@@ -1459,7 +4899,8 @@ impl App {
                 self.active_theme_name = "Custom (theme.lua)".to_string();
                 self.editor_preferences.theme_name = "Custom (theme.lua)".to_string();
                 self.theme_dropdown_open = false;
-                let _ = prefs::save_preferences(&self.editor_preferences);
+                let _ = self.save_preferences();
+                self.notifications.success("Theme reloaded from theme.lua");
                 iced::Task::none()
             }
             Message::SettingsLineNumberWidthChanged(val) => {
@@ -1468,6 +4909,49 @@ impl App {
                 }
                 iced::Task::none()
             }
+            Message::SettingsScrolloffChanged(val) => {
+                if let Ok(lines) = val.parse::<usize>() {
+                    self.editor_preferences.scrolloff = lines.min(999);
+                }
+                iced::Task::none()
+            }
+            Message::SettingsFontFamilySelected(family) => {
+                self.editor_preferences.font_family = family;
+                self.apply_editor_font_to_tabs();
+                let _ = self.save_preferences();
+                iced::Task::none()
+            }
+            // The terminal takes its own clipboard keys
+            Message::Clipboard(_) if self.focused_pane == FocusPane::Terminal => iced::Task::none(),
+            Message::Clipboard(Clipboard::Cut) => self.copy_to_clipboard(true),
+            Message::Clipboard(Clipboard::Copy) => self.copy_to_clipboard(false),
+            Message::Clipboard(Clipboard::Paste) => {
+                iced::clipboard::read().map(Message::ClipboardRead)
+            }
+            Message::ClipboardRead(Some(text)) if !text.is_empty() => {
+                self.update(Message::CodeEditorEvent(EditorMessage::Paste(text)))
+            }
+            Message::ClipboardRead(_) => iced::Task::none(),
+            Message::Zoom(zoom) => {
+                let size = self.editor_preferences.font_size;
+                self.editor_preferences.font_size = match zoom {
+                    Zoom::In => fonts::zoomed(size, 1),
+                    Zoom::Out => fonts::zoomed(size, -1),
+                    Zoom::Reset => fonts::DEFAULT_SIZE,
+                };
+                self.apply_editor_font_to_tabs();
+                if let Err(err) = self.save_preferences() {
+                    self.notifications
+                        .error(format!("Could not save preferences: {err}"));
+                }
+                iced::Task::none()
+            }
+            Message::SettingsToggleUpdateCheck => {
+                self.editor_preferences.check_for_updates =
+                    !self.editor_preferences.check_for_updates;
+                let _ = self.save_preferences();
+                iced::Task::none()
+            }
             Message::SettingsToggleDeveloperMode => {
                 self.editor_preferences.developer_mode = !self.editor_preferences.developer_mode;
                 self.dev_log(format!(
@@ -1478,7 +4962,7 @@ impl App {
                         "disabled"
                     }
                 ));
-                let _ = prefs::save_preferences(&self.editor_preferences);
+                let _ = self.save_preferences();
                 iced::Task::none()
             }
             Message::ToggleLsp => {
@@ -1507,6 +4991,7 @@ impl App {
                 iced::Task::none()
             }
             Message::ToggleCommandInput => {
+                self.pending_block_edit = None;
                 if self.command_input.open {
                     self.command_input.close();
                 } else {
@@ -1517,22 +5002,46 @@ impl App {
                 self.vim_refresh_cursor_style();
                 iced::Task::none()
             }
+            Message::UserKeyPressed(chord) => match self.user_keymaps.get(&chord).cloned() {
+                Some(action) => self.run_action(&action, true),
+                None => iced::Task::none(),
+            },
+            Message::CommandInputComplete => {
+                if !self.command_input.open || self.pending_block_edit.is_some() {
+                    return iced::Task::none();
+                }
+                let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+                self.command_input.complete(root);
+                iced::Task::batch([
+                    iced::widget::operation::focus(self.command_input_id.clone()),
+                    iced::widget::operation::move_cursor_to_end(self.command_input_id.clone()),
+                ])
+            }
             Message::CommandInputChanged(input) => {
                 self.command_input.input = input;
                 iced::Task::none()
             }
             Message::CommandInputSubmit => {
+                if let Some(edit) = self.pending_block_edit.take() {
+                    let text = self.command_input.input.clone();
+                    self.command_input.close();
+                    return self.apply_block_edit(edit, &text);
+                }
+                if self.command_input.remember() {
+                    let _ = crate::config::history::save_history(&self.command_input.history);
+                }
                 if let Some(cmd) = self.command_input.process_command() {
                     self.command_input.close();
-                    return self.execute_palette_command(&cmd);
+                    return self.run_ex_command(cmd);
                 }
                 self.command_input.close();
                 iced::Task::none()
             }
+            Message::WindowFocused => self.check_disk_changes(),
             Message::WindowResized(width, height) => {
                 self.editor_preferences.window_width = (width as f32).max(640.0);
                 self.editor_preferences.window_height = (height as f32).max(480.0);
-                let _ = prefs::save_preferences(&self.editor_preferences);
+                let _ = self.save_preferences();
                 iced::Task::none()
             }
             Message::NewFile => {
@@ -1545,14 +5054,35 @@ impl App {
                         code_editor: editor,
                         buffer: crate::features::editor_buffer::EditorBuffer::from_text(""),
                     },
+                    pinned: false,
                 });
                 self.active_tab = Some(self.tabs.len() - 1);
+                self.remember_visited_tab(self.tabs[self.tabs.len() - 1].path.clone());
                 self.cursor_line = 1;
                 self.cursor_col = 1;
-                self.autocomplete.cancel();
+                self.cancel_autocomplete();
                 self.vim_refresh_cursor_style();
                 iced::Task::none()
             }
+            Message::FileOpenFailed(path, err) => {
+                self.notifications.push_with_actions(
+                    Severity::Error,
+                    format!("Could not open: {err}"),
+                    vec![("Retry", Message::FileClicked(path))],
+                );
+                iced::Task::none()
+            }
+            Message::RetrySave(path) => match self.open_tab_index(&path) {
+                Some(idx) => self.write_tab(idx),
+                None => iced::Task::none(),
+            },
+            Message::SaveTabAs(path) => match self.open_tab_index(&path) {
+                Some(idx) => {
+                    let select = self.update(Message::TabSelected(idx));
+                    select.chain(iced::Task::done(Message::SaveAs))
+                }
+                None => iced::Task::none(),
+            },
             Message::SaveAs => iced::Task::perform(
                 async {
                     rfd::AsyncFileDialog::new()
@@ -1566,6 +5096,61 @@ impl App {
                     None => Message::FileTreeRefresh,
                 },
             ),
+            Message::ExportHtml => {
+                let selection = self.active_selection();
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+                    return iced::Task::none();
+                };
+                let TabKind::Editor { code_editor, .. } = &tab.kind else {
+                    return iced::Task::none();
+                };
+                let content = code_editor.content();
+                let (text, first_line) = match selection {
+                    Some((start, end)) => {
+                        let start_offset = super::editing::position_to_offset(&content, start);
+                        let end_offset = super::editing::position_to_offset(&content, end);
+                        // Whole lines, so highlighting starts at a line start
+                        let start_offset = content[..start_offset].rfind('\n').map_or(0, |i| i + 1);
+                        (content[start_offset..end_offset].to_string(), start.0 + 1)
+                    }
+                    None => (content, 1),
+                };
+                let first = text.lines().next().unwrap_or("");
+                let extension = crate::language::detect(&tab.path, first).syntax();
+                let html = crate::features::export::to_html(
+                    &tab.name,
+                    &text,
+                    extension,
+                    &self.editor_preferences.font_family,
+                    first_line,
+                );
+                let file_name = format!("{}.html", tab.name);
+                iced::Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Export as HTML")
+                            .set_file_name(file_name)
+                            .save_file()
+                            .await
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    move |result| match result {
+                        Some(path) => Message::ExportHtmlWrite(path, html),
+                        None => Message::FileTreeRefresh,
+                    },
+                )
+            }
+            Message::ExportHtmlWrite(path, html) => {
+                match std::fs::write(&path, html) {
+                    Ok(()) => self
+                        .notifications
+                        .success(format!("Exported to {}", path.display())),
+                    Err(err) => self
+                        .notifications
+                        .error(format!("Could not export to {}: {err}", path.display())),
+                }
+                iced::Task::none()
+            }
             Message::WakaTimeApiKeyChanged(key) => {
                 self.wakatime.api_key = key;
                 iced::Task::none()
@@ -1583,11 +5168,85 @@ impl App {
                 iced::Task::none()
             }
             Message::SaveWakaTimeSettings => {
-                let _ = wakatime::save(&self.wakatime);
+                match wakatime::save(&self.wakatime) {
+                    Ok(()) => self.notifications.success("WakaTime settings saved"),
+                    Err(err) => self
+                        .notifications
+                        .error(format!("Could not save WakaTime settings: {err}")),
+                }
+                iced::Task::none()
+            }
+            Message::SnippetEndpointChanged(endpoint) => {
+                self.snippets.endpoint = endpoint;
+                iced::Task::none()
+            }
+            Message::SnippetTokenChanged(token) => {
+                self.snippets.token = token;
+                iced::Task::none()
+            }
+            Message::SaveSnippetSettings => {
+                match snippets::save(&self.snippets) {
+                    Ok(()) => self.notifications.success("Snippet settings saved"),
+                    Err(err) => self
+                        .notifications
+                        .error(format!("Could not save snippet settings: {err}")),
+                }
+                iced::Task::none()
+            }
+            Message::ShareSnippet => {
+                let selection = self.active_selection();
+                let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+                    return iced::Task::none();
+                };
+                let TabKind::Editor { code_editor, .. } = &tab.kind else {
+                    return iced::Task::none();
+                };
+                let content = code_editor.content();
+                let text = match selection {
+                    Some((start, end)) => {
+                        let from = super::editing::position_to_offset(&content, start);
+                        let to = super::editing::position_to_offset(&content, end);
+                        content[from..to].to_string()
+                    }
+                    None => content,
+                };
+                if text.trim().is_empty() {
+                    self.notifications.warning("Nothing to share");
+                    return iced::Task::none();
+                }
+                self.notifications
+                    .info(format!("Uploading {}...", tab.name));
+                iced::Task::perform(
+                    snippets::upload(self.snippets.clone(), tab.name.clone(), text),
+                    Message::SnippetShared,
+                )
+            }
+            Message::SnippetShared(result) => match result {
+                Ok(url) => {
+                    self.notifications
+                        .success(format!("Snippet link copied: {url}"));
+                    iced::clipboard::write(url)
+                }
+                Err(err) => {
+                    self.notifications
+                        .error(format!("Could not share snippet: {err}"));
+                    iced::Task::none()
+                }
+            },
+            Message::DismissNotification(index) => {
+                self.notifications.dismiss(index);
                 iced::Task::none()
             }
-            Message::DismissNotification => {
-                self.notification = None;
+            Message::NotificationTick => {
+                self.notifications.expire(Instant::now());
+                iced::Task::none()
+            }
+            Message::ToggleNotificationHistory => {
+                self.notifications.history_open = !self.notifications.history_open;
+                iced::Task::none()
+            }
+            Message::ClearNotifications => {
+                self.notifications.clear_history();
                 iced::Task::none()
             }
             Message::LspTick => {
@@ -1633,6 +5292,7 @@ impl App {
                 }
 
                 // Drain LSP events from the shared channel
+                let mut definition = None;
                 for event in self.lsp.drain_events() {
                     match event {
                         iced_code_editor::LspEvent::Hover { text } => {
@@ -1666,18 +5326,11 @@ impl App {
                                                 .lines()
                                                 .nth(self.cursor_line.saturating_sub(1))
                                                 .unwrap_or("");
-                                            let before = &line[..self
-                                                .cursor_col
-                                                .saturating_sub(1)
-                                                .min(line.len())];
-                                            before
-                                                .chars()
-                                                .rev()
-                                                .take_while(|c| c.is_alphanumeric() || *c == '_')
-                                                .collect::<String>()
-                                                .chars()
-                                                .rev()
-                                                .collect::<String>()
+                                            super::editing::word_before(
+                                                line,
+                                                self.cursor_col.saturating_sub(1),
+                                            )
+                                            .to_string()
                                         } else {
                                             String::new()
                                         }
@@ -1711,7 +5364,16 @@ impl App {
                         }
                         iced_code_editor::LspEvent::Definition { uri, range } => {
                             self.dev_log(format!("LSP: Definition at {} {:?}", uri, range));
-                            eprintln!("Definition: {} at {:?}", uri, range);
+                            let path = url::Url::parse(&uri)
+                                .ok()
+                                .and_then(|url| url.to_file_path().ok());
+                            if let Some(path) = path {
+                                definition = Some((
+                                    path,
+                                    range.start.line as usize + 1,
+                                    range.start.character as usize + 1,
+                                ));
+                            }
                         }
                         iced_code_editor::LspEvent::Progress { .. } => {}
                         iced_code_editor::LspEvent::Log {
@@ -1723,6 +5385,9 @@ impl App {
                         }
                     }
                 }
+                if let Some((path, line, column)) = definition {
+                    return self.open_at(path, line, column);
+                }
                 iced::Task::none()
             }
             Message::CheckForUpdate => {
@@ -1744,15 +5409,10 @@ impl App {
         }
     }
 
-    fn refresh_autocomplete_for_event(
-        &mut self,
-        event: &EditorMessage,
-        content: &str,
-        path: &std::path::Path,
-    ) {
+    fn refresh_autocomplete_for_event(&mut self, event: &EditorMessage) -> iced::Task<Message> {
         if self.lsp_enabled {
-            self.autocomplete.cancel();
-            return;
+            self.cancel_autocomplete();
+            return iced::Task::none();
         }
 
         let should_trigger = matches!(
@@ -1774,39 +5434,100 @@ impl App {
         );
 
         if should_cancel {
-            self.autocomplete.cancel();
-            return;
+            self.cancel_autocomplete();
+            return iced::Task::none();
         }
 
         if should_trigger {
-            let cursor_idx = Self::position_to_index(content, self.cursor_line, self.cursor_col);
-            let lang = path
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .and_then(Autocomplete::detect_language);
-            self.autocomplete
-                .trigger(content, cursor_idx, lang.as_deref());
-            // Only keep suggestions when prefix is at least 2 characters
-            if self.autocomplete.prefix.len() <= 1 {
-                self.autocomplete.cancel();
-            }
+            return self.debounce(Debounce::Autocomplete);
         }
+        iced::Task::none()
     }
 
-    fn smart_indent_for_enter(&self, content: &str) -> String {
-        let line = content
-            .lines()
-            .nth(self.cursor_line.saturating_sub(1))
-            .unwrap_or("");
+    /// Suggests completions for the word before the cursor of the active
+    /// editor.
+    fn trigger_autocomplete(&mut self) {
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get(idx)) else {
+            return;
+        };
+        let TabKind::Editor {
+            ref code_editor, ..
+        } = tab.kind
+        else {
+            return;
+        };
+        let content = code_editor.content();
+        let path = tab.path.clone();
+        let cursor_idx = super::editing::position_to_offset(
+            &content,
+            (
+                self.cursor_line.saturating_sub(1),
+                self.cursor_col.saturating_sub(1),
+            ),
+        );
+        let first_line = content.lines().next().unwrap_or("");
+        let lang = crate::language::detect(&path, first_line).completion_id();
+        self.autocomplete
+            .trigger(&content, cursor_idx, lang, Some(&path));
+        // Only keep suggestions when prefix is at least 2 characters
+        if self.autocomplete.prefix.chars().count() <= 1 {
+            self.autocomplete.cancel();
+        }
+    }
+
+    /// Counts a command picked from the palette, for ranking it next time.
+    fn record_palette_command(&mut self, name: &str) {
+        let usage = self.command_palette.record_use(name);
+        let _ = crate::config::command_usage::save_command_usage(usage);
+    }
+
+    /// Hides completions, including ones still waiting for typing to pause.
+    fn cancel_autocomplete(&mut self) {
+        self.debounced.remove(&Debounce::Autocomplete);
+        self.autocomplete.cancel();
+    }
 
-        let base = leading_whitespace(line);
-        let mut indent = base.clone();
+    /// Sends `Message::Debounced(what)` once typing pauses, replacing the
+    /// one still waiting for `what`.
+    fn debounce(&mut self, what: Debounce) -> iced::Task<Message> {
+        let delay = self.editor_preferences.typing_debounce_ms;
+        if delay == 0 {
+            return self.run_debounced(what);
+        }
+        let (task, handle) = iced::Task::perform(
+            tokio::time::sleep(Duration::from_millis(delay)),
+            move |_| Message::Debounced(what),
+        )
+        .abortable();
+        self.debounced.insert(what, handle.abort_on_drop());
+        task
+    }
 
-        if should_increase_indent(line) {
-            indent.push_str(&self.editor_preferences.indent_unit());
+    /// Runs the query for `what` still waiting on `debounce` right away.
+    fn flush_debounced(&mut self, what: Debounce) -> iced::Task<Message> {
+        match self.debounced.remove(&what) {
+            Some(_) => self.run_debounced(what),
+            None => iced::Task::none(),
         }
+    }
 
-        indent
+    fn run_debounced(&mut self, what: Debounce) -> iced::Task<Message> {
+        match what {
+            Debounce::Autocomplete => {
+                if !self.lsp_enabled {
+                    self.trigger_autocomplete();
+                }
+                iced::Task::none()
+            }
+            Debounce::Find => self.refresh_find_matches(),
+            Debounce::FuzzyFinder => {
+                if self.fuzzy_finder.open {
+                    self.fuzzy_finder.filter();
+                    self.fuzzy_finder.update_preview();
+                }
+                iced::Task::none()
+            }
+        }
     }
 
     fn sync_cursor_from_editor_event(&mut self, event: &EditorMessage, _before: &str, after: &str) {
@@ -1909,65 +5630,32 @@ impl App {
             .unwrap_or(0);
         self.cursor_col = self.cursor_col.clamp(1, current_len + 1);
     }
-
-    fn position_to_index(text: &str, line: usize, col: usize) -> usize {
-        let mut current_line = 1usize;
-        let mut current_col = 1usize;
-        let mut idx = 0usize;
-        for ch in text.chars() {
-            if current_line == line && current_col == col {
-                break;
-            }
-            idx += ch.len_utf8();
-            if ch == '\n' {
-                current_line += 1;
-                current_col = 1;
-            } else {
-                current_col += 1;
-            }
-        }
-        idx
-    }
-
-    fn leading_whitespace(line: &str) -> String {
-        line.chars()
-            .take_while(|ch| *ch == ' ' || *ch == '\t')
-            .collect()
-    }
-
-    fn should_increase_indent(line: &str) -> bool {
-        let trimmed = line.trim_end();
-        trimmed.ends_with('{')
-            || trimmed.ends_with('[')
-            || trimmed.ends_with('(')
-            || trimmed.ends_with(':')
-            || trimmed.ends_with('\\')
-    }
-
-    fn should_decrease_indent(line: &str) -> bool {
-        let trimmed = line.trim();
-        trimmed == "}"
-            || trimmed == "]"
-            || trimmed == ")"
-            || trimmed == "else"
-            || trimmed == "elif"
-            || trimmed == "finally"
-            || trimmed == "except"
-    }
 }
 
-fn smart_indent_for_enter(content: &str, cursor_line: usize, indent_unit: &str) -> String {
+/// Indentation for the line opened by Enter at `cursor` (0-based line and
+/// column), based on the text before the cursor.
+fn smart_indent_for_enter(
+    content: &str,
+    cursor: (usize, usize),
+    indent_unit: &str,
+    language: crate::language::Language,
+) -> String {
     let lines: Vec<&str> = content.lines().collect();
     if lines.is_empty() {
         return String::new();
     }
 
-    let mut i = cursor_line.saturating_sub(1);
-    if i >= lines.len() {
-        i = lines.len() - 1;
-    }
-
-    let mut line = lines[i];
+    let mut i = cursor.0.min(lines.len() - 1);
+    let mut line = if i == cursor.0 {
+        let end = lines[i]
+            .char_indices()
+            .nth(cursor.1)
+            .map(|(idx, _)| idx)
+            .unwrap_or(lines[i].len());
+        &lines[i][..end]
+    } else {
+        lines[i]
+    };
     if line.trim().is_empty() {
         while i > 0 {
             i -= 1;
@@ -1978,26 +5666,158 @@ fn smart_indent_for_enter(content: &str, cursor_line: usize, indent_unit: &str)
         }
     }
 
-    let base = leading_whitespace(line);
+    let base = indentation::leading_whitespace(line);
     let mut indent = base.clone();
-    if should_increase_indent(line) {
+    if indentation::opens_block(line, language) {
         indent.push_str(indent_unit);
     }
     indent
 }
 
-fn leading_whitespace(line: &str) -> String {
-    line.chars()
-        .take_while(|ch| *ch == ' ' || *ch == '\t')
-        .collect()
+/// When the cursor sits between a bracket pair like `{|}`, returns the
+/// indentation the closing bracket should keep on its own line.
+fn split_bracket_indent(content: &str, cursor: (usize, usize)) -> Option<String> {
+    let line = content.lines().nth(cursor.0)?;
+    let split = line
+        .char_indices()
+        .nth(cursor.1)
+        .map(|(idx, _)| idx)
+        .unwrap_or(line.len());
+    let (head, tail) = line.split_at(split);
+    let closer = match head.trim_end().chars().last()? {
+        '{' => '}',
+        '[' => ']',
+        '(' => ')',
+        _ => return None,
+    };
+    if tail.trim_start().starts_with(closer) {
+        Some(indentation::leading_whitespace(line))
+    } else {
+        None
+    }
+}
+
+/// Whether `event` neither edits the text nor moves the cursor. These
+/// come in all the time, with the cursor blinking and the mouse moving.
+fn is_passive(event: &EditorMessage) -> bool {
+    matches!(
+        event,
+        EditorMessage::Tick
+            | EditorMessage::MouseHover(_)
+            | EditorMessage::Scrolled(_)
+            | EditorMessage::HorizontalScrolled(_)
+            | EditorMessage::CanvasFocusGained
+    )
+}
+
+/// Whether `event` leaves the goal column of Up and Down alone: vertical
+/// moves, End, and events that do not move the cursor.
+fn keeps_goal_column(event: &EditorMessage) -> bool {
+    is_passive(event)
+        || matches!(
+            event,
+            EditorMessage::ArrowKey(
+                iced_code_editor::ArrowDirection::Up | iced_code_editor::ArrowDirection::Down,
+                false
+            ) | EditorMessage::End(false)
+                | EditorMessage::Copy
+        )
+}
+
+/// Puts the cursor back on its goal column after Up or Down.
+///
+/// The editor widget clamps the column to each line it passes, so going
+/// through a short line would pull the cursor left for good. After End the
+/// goal is past every line end, so the cursor sticks to them.
+fn goal_column_task(
+    code_editor: &mut iced_code_editor::CodeEditor,
+    buffer: &crate::features::editor_buffer::EditorBuffer,
+    event: &EditorMessage,
+    before: (usize, usize),
+    goal_column: &mut Option<usize>,
+) -> Option<iced::Task<EditorMessage>> {
+    match event {
+        EditorMessage::End(false) => {
+            *goal_column = Some(usize::MAX);
+            return None;
+        }
+        EditorMessage::ArrowKey(
+            iced_code_editor::ArrowDirection::Up | iced_code_editor::ArrowDirection::Down,
+            false,
+        ) => {}
+        _ => return None,
+    }
+    // Wrapped lines are stepped through a row at a time by the widget
+    if code_editor.wrap_enabled() {
+        *goal_column = None;
+        return None;
+    }
+    let goal = *goal_column.get_or_insert(before.1);
+    let (line, _) = code_editor.cursor_position();
+    if line == before.0 {
+        return None;
+    }
+    let text = buffer.line(line);
+    let len = text.trim_end_matches(['\n', '\r']).chars().count();
+    Some(code_editor.update(&EditorMessage::GotoPosition(line, goal.min(len))))
 }
 
-fn should_increase_indent(line: &str) -> bool {
-    let trimmed = line.trim_end();
-    trimmed.ends_with('{')
-        || trimmed.ends_with('[')
-        || trimmed.ends_with('(')
-        || trimmed.ends_with(':')
+/// Scrolls the editor so the cursor keeps `scrolloff` lines of context.
+///
+/// The editor widget only keeps a fixed two-line margin when following the
+/// cursor, so this briefly moves the cursor to the line that must stay
+/// visible, keeps that scroll task, and moves the cursor back.
+fn scrolloff_task(
+    code_editor: &mut iced_code_editor::CodeEditor,
+    event: &EditorMessage,
+    scrolloff: usize,
+) -> Option<iced::Task<EditorMessage>> {
+    const BUILTIN_MARGIN: usize = 2;
+
+    if !matches!(
+        event,
+        EditorMessage::ArrowKey(
+            iced_code_editor::ArrowDirection::Up | iced_code_editor::ArrowDirection::Down,
+            false
+        ) | EditorMessage::PageUp
+            | EditorMessage::PageDown
+    ) {
+        return None;
+    }
+
+    let line_height = code_editor.line_height();
+    let viewport_height = code_editor.viewport_height();
+    if line_height <= 0.0 || viewport_height <= 0.0 {
+        return None;
+    }
+
+    let visible_lines = (viewport_height / line_height) as usize;
+    let scrolloff = scrolloff.min(visible_lines.saturating_sub(1) / 2);
+    if scrolloff <= BUILTIN_MARGIN {
+        return None;
+    }
+
+    let (line, col) = code_editor.cursor_position();
+    let cursor_y = line as f32 * line_height;
+    let margin = scrolloff as f32 * line_height;
+    let viewport_top = code_editor.viewport_scroll();
+    let viewport_bottom = viewport_top + viewport_height;
+    let extra = scrolloff - BUILTIN_MARGIN;
+
+    let target = if cursor_y < viewport_top + margin {
+        line.saturating_sub(extra)
+    } else if cursor_y + line_height > viewport_bottom - margin {
+        line + extra
+    } else {
+        return None;
+    };
+    if target == line {
+        return None;
+    }
+
+    let task = code_editor.update(&EditorMessage::GotoPosition(target, col));
+    let _ = code_editor.update(&EditorMessage::GotoPosition(line, col));
+    Some(task)
 }
 
 fn indent_visual_width(indent: &str, tab_size: usize) -> usize {
@@ -1,6 +1,260 @@
 use super::*;
 use frostmark::MarkWidget;
-use iced::widget::column;
+use iced::widget::{column, scrollable::Direction, scrollable::Scrollbar, Space};
+
+use crate::features::colors;
+use crate::features::conflicts;
+use crate::features::file_viewer::{self, Viewer};
+use crate::features::jumps;
+use crate::features::sticky_scroll;
+use crate::features::whitespace::{self, RenderWhitespace};
+use crate::message::{CloseTabs, LargeFileAction, QuitAction};
+use crate::ui::tree_selected_button_style;
+use iced_aw::drop_down::{self, DropDown};
+use iced_aw::ContextMenu;
+
+/// Menu of the tab at `idx`, showing the file at `path`.
+fn tab_menu<'a>(idx: usize, path: &std::path::Path, pinned: bool) -> Element<'a, Message> {
+    let item = |label: &'a str, message: Message| {
+        button(text(label).size(12))
+            .style(tree_button_style)
+            .on_press(message)
+            .padding([5, 12])
+            .width(Length::Fill)
+    };
+    let separator = container(Space::new().width(Length::Fill).height(Length::Fixed(1.0)))
+        .style(sidebar_editor_separator_style);
+    container(
+        column![
+            item("Close", Message::TabClosed(idx)),
+            item("Close Others", Message::CloseTabs(CloseTabs::Others(idx))),
+            item(
+                "Close to the Right",
+                Message::CloseTabs(CloseTabs::ToTheRight(idx))
+            ),
+            item("Close Saved", Message::CloseTabs(CloseTabs::Saved)),
+            item(
+                if pinned { "Unpin" } else { "Pin" },
+                Message::TogglePinTab(idx)
+            ),
+            separator,
+            item(
+                "Copy Path",
+                Message::CopyPath {
+                    path: path.to_path_buf(),
+                    relative: false,
+                }
+            ),
+            item(
+                "Copy Relative Path",
+                Message::CopyPath {
+                    path: path.to_path_buf(),
+                    relative: true,
+                }
+            ),
+            item(
+                "Reveal in File Tree",
+                Message::RevealInTree(path.to_path_buf())
+            ),
+        ]
+        .spacing(2)
+        .width(Length::Fixed(200.0)),
+    )
+    .padding(4)
+    .style(context_menu_style)
+    .into()
+}
+
+/// `line` as spans, with the `changed` bytes on a stronger background.
+fn diff_spans<'a>(
+    line: &'a str,
+    changed: Option<std::ops::Range<usize>>,
+    color: Color,
+) -> Vec<iced::widget::text::Span<'a, Message>> {
+    let Some(changed) = changed.filter(|changed| !changed.is_empty()) else {
+        return vec![iced::widget::text::Span::new(line)];
+    };
+    vec![
+        iced::widget::text::Span::new(&line[..changed.start]),
+        iced::widget::text::Span::new(&line[changed.clone()])
+            .background(Color { a: 0.35, ..color }),
+        iced::widget::text::Span::new(&line[changed.end..]),
+    ]
+}
+
+/// One side of a diff row: the line number and the line.
+fn diff_cell<'a>(
+    number: Option<usize>,
+    spans: Vec<iced::widget::text::Span<'a, Message>>,
+    background: Option<Color>,
+) -> Element<'a, Message> {
+    container(
+        row![
+            text(number.map(|n| (n + 1).to_string()).unwrap_or_default())
+                .size(12)
+                .font(iced::Font::MONOSPACE)
+                .color(theme().text_dim)
+                .width(Length::Fixed(44.0)),
+            iced::widget::rich_text(spans)
+                .size(12)
+                .font(iced::Font::MONOSPACE)
+                .color(theme().text_secondary),
+        ]
+        .spacing(8),
+    )
+    .padding([1, 8])
+    .width(Length::Fill)
+    .style(move |_theme| container::Style {
+        background: background.map(iced::Background::Color),
+        ..Default::default()
+    })
+    .into()
+}
+
+/// The buffer and the other side of `diff` next to each other, with a
+/// choice between them above every hunk.
+fn view_diff(diff: &crate::features::diff::DiffView) -> Element<'_, Message> {
+    use crate::features::diff::{self, Row};
+
+    let removed = Color {
+        a: 0.14,
+        ..ACCENT_RED
+    };
+    let added = Color {
+        a: 0.14,
+        ..ACCENT_GREEN
+    };
+    let name = diff
+        .path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let changes = if diff.hunks.len() == 1 {
+        "change"
+    } else {
+        "changes"
+    };
+    let header = row![
+        text(format!("{name}  ↔  {}", diff.theirs_label))
+            .size(13)
+            .color(theme().text_primary),
+        text(format!("{} {changes}", diff.hunks.len()))
+            .size(12)
+            .color(theme().text_dim),
+        Space::new().width(Length::Fill),
+        button(text("Apply").size(12))
+            .style(tree_button_style)
+            .on_press(Message::DiffApply)
+            .padding([5, 12]),
+        button(text("Cancel").size(12))
+            .style(tree_button_style)
+            .on_press(Message::DiffClose)
+            .padding([5, 12]),
+    ]
+    .spacing(12)
+    .align_y(iced::Alignment::Center);
+
+    let mut lines: Vec<Element<'_, Message>> = Vec::new();
+    let mut shown_hunk = None;
+    for row in diff.rows() {
+        match row {
+            Row::Same { mine, theirs } => lines.push(
+                row![
+                    diff_cell(
+                        Some(mine),
+                        vec![iced::widget::text::Span::new(diff.mine_line(mine))],
+                        None
+                    ),
+                    diff_cell(
+                        Some(theirs),
+                        vec![iced::widget::text::Span::new(diff.theirs_line(theirs))],
+                        None
+                    ),
+                ]
+                .into(),
+            ),
+            Row::Folded(count) => lines.push(
+                container(
+                    text(format!("⋯ {count} unchanged lines"))
+                        .size(11)
+                        .color(theme().text_dim),
+                )
+                .padding([4, 60])
+                .into(),
+            ),
+            Row::Changed { hunk, mine, theirs } => {
+                let take_theirs = diff.take_theirs[hunk];
+                if shown_hunk != Some(hunk) {
+                    shown_hunk = Some(hunk);
+                    let choice = |label, take| {
+                        let style = if take == take_theirs {
+                            tree_selected_button_style
+                        } else {
+                            tree_button_style
+                        };
+                        button(text(label).size(11))
+                            .style(style)
+                            .on_press(Message::DiffHunkChoice {
+                                hunk,
+                                take_theirs: take,
+                            })
+                            .padding([3, 10])
+                    };
+                    lines.push(
+                        row![choice("Keep Mine", false), choice("Take Theirs", true)]
+                            .spacing(6)
+                            .padding([4, 60])
+                            .into(),
+                    );
+                }
+                let (mine_changed, theirs_changed) = match (mine, theirs) {
+                    (Some(mine), Some(theirs)) => {
+                        let (a, b) =
+                            diff::inline_changes(diff.mine_line(mine), diff.theirs_line(theirs));
+                        (Some(a), Some(b))
+                    }
+                    _ => (None, None),
+                };
+                // The side not taken is dimmed
+                let (mine_bg, theirs_bg) = if take_theirs {
+                    (Color { a: 0.06, ..removed }, added)
+                } else {
+                    (removed, Color { a: 0.06, ..added })
+                };
+                let mine_cell = match mine {
+                    Some(line) => diff_cell(
+                        Some(line),
+                        diff_spans(diff.mine_line(line), mine_changed, ACCENT_RED),
+                        Some(mine_bg),
+                    ),
+                    None => diff_cell(None, Vec::new(), None),
+                };
+                let theirs_cell = match theirs {
+                    Some(line) => diff_cell(
+                        Some(line),
+                        diff_spans(diff.theirs_line(line), theirs_changed, ACCENT_GREEN),
+                        Some(theirs_bg),
+                    ),
+                    None => diff_cell(None, Vec::new(), None),
+                };
+                lines.push(row![mine_cell, theirs_cell].into());
+            }
+        }
+    }
+
+    container(column![
+        container(header).padding([8, 12]),
+        scrollable(column(lines)).height(Length::Fill),
+    ])
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(|_theme| container::Style {
+        background: Some(iced::Background::Color(theme().bg_editor)),
+        ..Default::default()
+    })
+    .into()
+}
 
 impl App {
     pub(super) fn view_tab_bar(&self) -> Element<'_, Message> {
@@ -21,16 +275,19 @@ impl App {
                     text("x").size(10).color(theme().text_dim)
                 };
 
-                button(
-                    row![
-                        text(&tab.name).size(12).color(theme().text_muted),
-                        button(close_icon)
-                            .style(tab_close_button_style)
-                            .on_press(Message::TabClosed(idx))
-                            .padding(2),
-                    ]
-                    .spacing(8)
-                    .align_y(iced::Alignment::Center),
+                let mut label = row![].spacing(8).align_y(iced::Alignment::Center);
+                if tab.pinned {
+                    label = label.push(text("◆").size(8).color(theme().text_dim));
+                }
+                let tab_button = button(
+                    label
+                        .push(text(&tab.name).size(12).color(theme().text_muted))
+                        .push(
+                            button(close_icon)
+                                .style(tab_close_button_style)
+                                .on_press(Message::TabClosed(idx))
+                                .padding(2),
+                        ),
                 )
                 .style(tab_button_style(is_active))
                 .on_press(Message::TabSelected(idx))
@@ -39,20 +296,90 @@ impl App {
                     right: 16.0,
                     bottom: 8.0,
                     left: 16.0,
-                })
+                });
+                let path = tab.path.clone();
+                let pinned = tab.pinned;
+                ContextMenu::new(
+                    mouse_area(tab_button).on_middle_press(Message::TabClosed(idx)),
+                    move || tab_menu(idx, &path, pinned),
+                )
                 .into()
             })
             .collect();
 
-        container(row(tabs).spacing(6))
-            .padding(iced::Padding {
-                top: 8.0,
-                right: 12.0,
-                bottom: 8.0,
-                left: 12.0,
+        // Tabs that do not fit scroll sideways, the list reaches all of them
+        let tab_row = scrollable(row(tabs).spacing(6).padding(iced::Padding {
+            bottom: 4.0,
+            ..iced::Padding::ZERO
+        }))
+        .direction(Direction::Horizontal(
+            Scrollbar::new().width(3).scroller_width(3),
+        ))
+        .width(Length::Fill);
+        let list_button = button(text("▾").size(12).color(theme().text_muted))
+            .style(tab_close_button_style)
+            .on_press(Message::ToggleTabList)
+            .padding([6, 8]);
+        let tab_list = DropDown::new(list_button, self.view_tab_list(), self.tab_list_open)
+            .alignment(drop_down::Alignment::BottomStart)
+            .on_dismiss(Message::ToggleTabList);
+
+        container(
+            row![tab_row, tab_list]
+                .spacing(6)
+                .align_y(iced::Alignment::Start),
+        )
+        .padding(iced::Padding {
+            top: 8.0,
+            right: 12.0,
+            bottom: 8.0,
+            left: 12.0,
+        })
+        .width(Length::Fill)
+        .style(tab_bar_style)
+        .into()
+    }
+
+    /// Every open tab in order, opened from the end of the tab bar.
+    fn view_tab_list(&self) -> Element<'_, Message> {
+        let items: Vec<Element<'_, Message>> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(idx, tab)| {
+                let is_active = self.active_tab == Some(idx);
+                let is_modified = matches!(&tab.kind, TabKind::Editor { code_editor, .. } if code_editor.is_modified());
+                button(
+                    row![
+                        text(&tab.name).size(12).color(if is_active {
+                            theme().text_primary
+                        } else {
+                            theme().text_muted
+                        }),
+                        Space::new().width(Length::Fill),
+                        text(match (is_modified, tab.pinned) {
+                            (true, _) => "●",
+                            (false, true) => "◆",
+                            (false, false) => "",
+                        })
+                        .size(9)
+                        .color(theme().text_dim),
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                )
+                .style(tree_button_style)
+                .on_press(Message::TabListClicked(idx))
+                .padding([5, 12])
+                .width(Length::Fill)
+                .into()
             })
-            .width(Length::Fill)
-            .style(tab_bar_style)
+            .collect();
+        container(scrollable(column(items).spacing(2)).height(Length::Shrink))
+            .padding(4)
+            .max_height(360)
+            .width(Length::Fixed(240.0))
+            .style(context_menu_style)
             .into()
     }
 
@@ -136,49 +463,229 @@ impl App {
             .into()
     }
 
+    pub(super) fn view_git_panel(&self) -> Element<'_, Message> {
+        let branch = self.git_branch.as_deref().unwrap_or("no branch");
+        let header = row![
+            text("Source Control").size(13).color(theme().text_primary),
+            iced::widget::Space::new().width(Length::Fill),
+            text(branch).size(11).color(theme().text_dim),
+        ]
+        .align_y(iced::Alignment::Center);
+
+        let message_input = text_input("Commit message", &self.git_commit_message)
+            .on_input(Message::GitCommitMessageChanged)
+            .on_submit(Message::GitCommit)
+            .style(search_input_style)
+            .size(13)
+            .padding(10)
+            .width(Length::Fill);
+
+        let commit_btn = button(text("Commit").size(12).color(theme().text_primary))
+            .on_press(Message::GitCommit)
+            .style(tree_button_style)
+            .padding(iced::Padding {
+                top: 6.0,
+                right: 16.0,
+                bottom: 6.0,
+                left: 16.0,
+            });
+
+        let mut content_col = column![header, message_input, commit_btn].spacing(6);
+
+        if let Some(err) = &self.git_error {
+            content_col = content_col.push(text(err).size(11).color(theme().text_secondary));
+        }
+
+        let mut items: Vec<Element<'_, Message>> = Vec::new();
+        let staged: Vec<_> = self.git_changes.iter().filter(|c| c.is_staged()).collect();
+        let unstaged: Vec<_> = self
+            .git_changes
+            .iter()
+            .filter(|c| c.has_unstaged())
+            .collect();
+
+        if !staged.is_empty() {
+            items.push(
+                text("Staged Changes")
+                    .size(11)
+                    .color(theme().text_secondary)
+                    .into(),
+            );
+            for change in staged {
+                items.push(git_change_row(change, true));
+            }
+        }
+        if !unstaged.is_empty() {
+            items.push(
+                text("Changes")
+                    .size(11)
+                    .color(theme().text_secondary)
+                    .into(),
+            );
+            for change in unstaged {
+                items.push(git_change_row(change, false));
+            }
+        }
+        if items.is_empty() && self.git_error.is_none() {
+            items.push(text("No changes").size(11).color(theme().text_dim).into());
+        }
+
+        let changes_scroll = scrollable(column(items).spacing(4)).height(Length::Shrink);
+        content_col = content_col.push(container(changes_scroll).max_height(400.0));
+
+        container(content_col)
+            .width(Length::Fixed(320.0))
+            .padding(10)
+            .style(search_panel_style)
+            .into()
+    }
+
     pub(super) fn view_editor(&self) -> Element<'_, Message> {
+        if !self.pending_recovery.is_empty() {
+            let names: Vec<String> = self.pending_recovery.iter().map(|b| b.name()).collect();
+            return confirm_prompt(
+                format!(
+                    "Pinel did not exit cleanly. Restore unsaved changes to {}?",
+                    names.join(", ")
+                ),
+                vec![
+                    ("Restore", Message::RestoreRecovery(true)),
+                    ("Discard", Message::RestoreRecovery(false)),
+                ],
+            );
+        }
+
+        if let Some(suggestions) = &self.pending_spelling {
+            let word = &suggestions.misspelling.word;
+            let question = if suggestions.words.is_empty() {
+                format!("No suggestions for \"{word}\"")
+            } else {
+                format!("Replace \"{word}\" with")
+            };
+            let mut answers: Vec<(&str, Message)> = suggestions
+                .words
+                .iter()
+                .map(|word| (word.as_str(), Message::FixSpelling(Some(word.clone()))))
+                .collect();
+            answers.push(("Add to Dictionary", Message::AddWordToDictionary));
+            answers.push(("Cancel", Message::FixSpelling(None)));
+            return confirm_prompt(question, answers);
+        }
+
+        if let Some(rename) = &self.pending_rename {
+            let places = if rename.count == 1 { "place" } else { "places" };
+            let preview =
+                rename
+                    .preview
+                    .iter()
+                    .fold(column![].spacing(2), |lines, (number, line)| {
+                        lines.push(
+                            text(format!("{number:>5}  {}", line.trim_end()))
+                                .size(12)
+                                .font(iced::Font::MONOSPACE)
+                                .color(theme().text_secondary),
+                        )
+                    });
+            return confirm_prompt_with_details(
+                format!(
+                    "Rename {} to {} in {} {places}?",
+                    rename.from, rename.to, rename.count
+                ),
+                Some(scrollable(preview).height(Length::Shrink).into()),
+                vec![
+                    ("Rename", Message::RenameConfirm(true)),
+                    ("Cancel", Message::RenameConfirm(false)),
+                ],
+            );
+        }
+
+        if let Some(diff) = &self.diff_view {
+            return view_diff(diff);
+        }
+
         if self.pending_sensitive_open.is_some() {
-            return container(
-                column![
-                    text("You are opening a sensitive file, continue?")
-                        .size(18)
-                        .color(theme().text_muted),
-                    row![
-                        button(text("Yes").size(13))
-                            .on_press(Message::SensitiveFileOpenConfirm(true))
-                            .padding(iced::Padding {
-                                top: 8.0,
-                                right: 16.0,
-                                bottom: 8.0,
-                                left: 16.0,
-                            }),
-                        button(text("No").size(13))
-                            .on_press(Message::SensitiveFileOpenConfirm(false))
-                            .padding(iced::Padding {
-                                top: 8.0,
-                                right: 16.0,
-                                bottom: 8.0,
-                                left: 16.0,
-                            }),
-                    ]
-                    .spacing(12)
-                    .align_y(iced::Alignment::Center),
-                ]
-                .spacing(16)
-                .align_x(iced::Alignment::Center),
-            )
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .into();
+            return confirm_prompt(
+                "You are opening a sensitive file, continue?".to_string(),
+                vec![
+                    ("Yes", Message::SensitiveFileOpenConfirm(true)),
+                    ("No", Message::SensitiveFileOpenConfirm(false)),
+                ],
+            );
+        }
+
+        if let Some((_, size)) = &self.pending_large_open {
+            return confirm_prompt(
+                format!(
+                    "This file is {}, which may make the editor slow. Open it anyway?",
+                    crate::features::status_line::format_size(*size as usize)
+                ),
+                vec![
+                    ("Open Anyway", Message::LargeFileOpen(LargeFileAction::Open)),
+                    (
+                        "Large File Mode",
+                        Message::LargeFileOpen(LargeFileAction::LargeFileMode),
+                    ),
+                    ("Preview", Message::LargeFileOpen(LargeFileAction::Preview)),
+                    ("Cancel", Message::LargeFileOpen(LargeFileAction::Cancel)),
+                ],
+            );
+        }
+
+        if let Some(count) = self.pending_quit {
+            let files = if count == 1 { "file has" } else { "files have" };
+            return confirm_prompt(
+                format!("{count} {files} unsaved changes. Save before quitting?"),
+                vec![
+                    (
+                        "Save and Quit",
+                        Message::QuitConfirm(QuitAction::SaveAndQuit),
+                    ),
+                    (
+                        "Quit Without Saving",
+                        Message::QuitConfirm(QuitAction::QuitWithoutSaving),
+                    ),
+                    ("Cancel", Message::QuitConfirm(QuitAction::Cancel)),
+                ],
+            );
+        }
+
+        if let Some((matches, files)) = self.pending_replace_all {
+            let files = if files == 1 {
+                "1 file".to_string()
+            } else {
+                format!("{files} files")
+            };
+            return confirm_prompt(
+                format!(
+                    "Replace {matches} matches in {files}? Files that aren't open are \
+                     written right away and can't be undone."
+                ),
+                vec![
+                    ("Replace All", Message::ReplaceAllConfirm(true)),
+                    ("Cancel", Message::ReplaceAllConfirm(false)),
+                ],
+            );
+        }
+
+        if let Some(text) = &self.pending_large_paste {
+            return confirm_prompt(
+                format!("Paste {} lines?", text.lines().count()),
+                vec![
+                    ("Paste", Message::LargePasteConfirm(true)),
+                    ("Cancel", Message::LargePasteConfirm(false)),
+                ],
+            );
         }
 
         if let Some(idx) = self.active_tab {
             if let Some(tab) = self.tabs.get(idx) {
                 match &tab.kind {
-                    TabKind::Editor { code_editor, .. } => {
+                    TabKind::Editor {
+                        code_editor,
+                        buffer,
+                    } => {
                         let editor = container(code_editor.view().map(Message::CodeEditorEvent))
+                            .id(self.editor_container_id.clone())
                             .width(Length::Fill)
                             .height(Length::Fill)
                             .style(|_theme| container::Style {
@@ -186,6 +693,121 @@ impl App {
                                 ..Default::default()
                             });
                         let editor = mouse_area(editor).on_press(Message::FocusEditor);
+                        let scroll_x = self.editor_scroll_x.get(&tab.path).copied();
+                        let layout =
+                            crate::ui::TextLayout::new(code_editor, scroll_x.unwrap_or(0.0));
+                        let mut layers: Vec<Element<'_, Message>> = vec![editor.into()];
+                        let mode = self.editor_preferences.render_whitespace;
+                        if mode != RenderWhitespace::Off {
+                            let markers = whitespace::Overlay::new(
+                                layout.as_ref(),
+                                buffer,
+                                mode,
+                                fonts::editor_font(&self.editor_preferences.font_family),
+                                code_editor.font_size(),
+                                Color {
+                                    a: 0.45,
+                                    ..theme().text_dim
+                                },
+                            );
+                            layers.push(
+                                iced::widget::canvas(markers)
+                                    .width(Length::Fill)
+                                    .height(Length::Fill)
+                                    .into(),
+                            );
+                        }
+                        layers.push(
+                            iced::widget::canvas(colors::Swatches::new(layout.as_ref(), buffer))
+                                .width(Length::Fill)
+                                .height(Length::Fill)
+                                .into(),
+                        );
+                        layers.push(
+                            iced::widget::canvas(conflicts::Lenses::new(
+                                layout.as_ref(),
+                                buffer,
+                                fonts::editor_font(&self.editor_preferences.font_family),
+                                code_editor.font_size(),
+                                theme().text_muted,
+                            ))
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .into(),
+                        );
+                        if let Some(flash) = self
+                            .jump_flash
+                            .as_ref()
+                            .filter(|flash| flash.path == tab.path)
+                            .filter(|flash| flash.line < buffer.line_count())
+                        {
+                            let line = buffer.line(flash.line);
+                            let text = line.trim_end_matches(['\n', '\r']);
+                            layers.push(
+                                iced::widget::canvas(jumps::Flash::new(
+                                    layout.as_ref(),
+                                    flash,
+                                    text,
+                                ))
+                                .width(Length::Fill)
+                                .height(Length::Fill)
+                                .into(),
+                            );
+                        }
+                        if let Some(layout) = layout
+                            .as_ref()
+                            .filter(|_| self.editor_preferences.sticky_scroll)
+                        {
+                            let first = layout.visible_lines(buffer.line_count()).start;
+                            let pinned = sticky_scroll::pinned(buffer, first);
+                            if !pinned.is_empty() {
+                                layers.push(view_sticky_headers(
+                                    buffer,
+                                    &pinned,
+                                    layout,
+                                    fonts::editor_font(&self.editor_preferences.font_family),
+                                    code_editor.font_size(),
+                                ));
+                            }
+                        }
+                        if let Some(picking) = self
+                            .color_picking
+                            .as_ref()
+                            .filter(|picking| picking.path == tab.path)
+                        {
+                            let line = buffer.line(picking.line);
+                            let anchor = layout
+                                .as_ref()
+                                .and_then(|layout| {
+                                    layout.point(picking.line, &line, picking.literal.start)
+                                })
+                                .or_else(|| code_editor.cursor_screen_position())
+                                .unwrap_or(iced::Point::new(48.0, 20.0));
+                            let picker = iced_aw::ColorPicker::new(
+                                true,
+                                picking.literal.color,
+                                iced::widget::Space::new()
+                                    .width(Length::Fixed(1.0))
+                                    .height(Length::Fixed(code_editor.line_height())),
+                                Message::ColorPickCancelled,
+                                Message::ColorPicked,
+                            );
+                            layers.push(
+                                container(picker)
+                                    .padding(iced::Padding {
+                                        top: anchor.y.max(0.0),
+                                        left: anchor.x.max(0.0),
+                                        bottom: 0.0,
+                                        right: 0.0,
+                                    })
+                                    .width(Length::Fill)
+                                    .height(Length::Fill)
+                                    .into(),
+                            );
+                        }
+                        let editor = iced::widget::Stack::with_children(layers)
+                            .width(Length::Fill)
+                            .height(Length::Fill);
 
                         let lsp_overlay = if self.lsp_enabled {
                             iced_code_editor::view_lsp_overlay(
@@ -404,6 +1026,7 @@ impl App {
                                         .padding(16)
                                         .width(Length::Fill),
                                 )
+                                .id(self.markdown_preview_scroll_id.clone())
                                 .height(Length::Fill),
                             )
                             .width(Length::FillPortion(1))
@@ -439,6 +1062,7 @@ impl App {
                         .height(Length::Fill)
                         .into();
                     }
+                    TabKind::Viewer(viewer) => return view_file_viewer(viewer),
                 }
             }
         }
@@ -504,6 +1128,69 @@ impl App {
         .into()
     }
 
+    pub(super) fn view_output_panel(&self) -> Element<'_, Message> {
+        let Some(panel) = &self.output_panel else {
+            return iced::widget::Space::new().into();
+        };
+
+        let header = container(
+            row![
+                text(&panel.title).size(12).color(theme().text_muted),
+                iced::widget::Space::new().width(Length::Fill),
+                button(text("x").size(12).color(theme().text_dim))
+                    .style(tab_close_button_style)
+                    .on_press(Message::CloseOutputPanel),
+            ]
+            .align_y(iced::Alignment::Center),
+        )
+        .padding(iced::Padding {
+            top: 6.0,
+            right: 8.0,
+            bottom: 6.0,
+            left: 10.0,
+        })
+        .style(|_theme| container::Style {
+            background: Some(Background::Color(theme().bg_secondary)),
+            border: iced::Border {
+                color: theme().border_subtle,
+                width: 1.0,
+                radius: 0.0.into(),
+            },
+            ..Default::default()
+        });
+
+        let lines = panel.lines.iter().map(|line| {
+            let spans: Vec<_> = line
+                .iter()
+                .map(|segment| {
+                    let span = iced::widget::text::Span::new(segment.text.as_str())
+                        .color(segment.color.unwrap_or(theme().text_primary));
+                    match &segment.link {
+                        Some(link) => span.link(link.clone()).underline(true),
+                        None => span,
+                    }
+                })
+                .collect();
+            iced::widget::rich_text(spans)
+                .size(12)
+                .font(iced::Font::MONOSPACE)
+                .on_link_click(Message::OutputLinkClicked)
+                .into()
+        });
+        let body = container(scrollable(column(lines).padding(10)).height(Length::Fill))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(|_theme| container::Style {
+                background: Some(Background::Color(theme().bg_editor)),
+                ..Default::default()
+            });
+
+        container(column![header, body].spacing(0))
+            .width(Length::Fill)
+            .height(Length::Fixed(self.terminal_panel_height))
+            .into()
+    }
+
     pub(super) fn view_status_bar(&self) -> Element<'_, Message> {
         let file_info = self
             .active_tab
@@ -511,9 +1198,12 @@ impl App {
             .map(|tab| tab.name.clone())
             .unwrap_or_default();
 
-        let left = row![text(file_info).size(10).color(theme().text_dim),]
+        let mut left = row![text(file_info).size(10).color(theme().text_dim),]
             .spacing(8)
             .align_y(iced::Alignment::Center);
+        for segment in self.status_segment_labels() {
+            left = left.push(text(segment).size(10).color(theme().text_placeholder));
+        }
 
         let current_line_diag = self
             .active_tab
@@ -522,6 +1212,8 @@ impl App {
             .and_then(|path| self.lsp_diagnostics.get(&path))
             .and_then(|items| items.iter().find(|d| d.line == self.cursor_line))
             .map(|d| d.message.clone())
+            .or_else(|| self.spelling_hint())
+            .or_else(|| self.line_length_hint())
             .unwrap_or_default();
 
         let right = row![
@@ -550,7 +1242,93 @@ impl App {
         .into()
     }
 
+    /// Hint for the cursor line when it is over the soft line length limit.
+    fn line_length_hint(&self) -> Option<String> {
+        use crate::features::line_length;
+
+        let tab = self.tabs.get(self.active_tab?)?;
+        let TabKind::Editor { buffer, .. } = &tab.kind else {
+            return None;
+        };
+        let language = crate::language::detect(&tab.path, &buffer.line(0));
+        let limit = line_length::limit_for(&self.editor_preferences.line_length_limits, language)?;
+        let line = buffer.line(self.cursor_line.saturating_sub(1));
+        let line = line.trim_end_matches(['\n', '\r']);
+        line_length::hint(line, limit, self.editor_preferences.tab_size)
+    }
+
+    /// Misspelled words on the cursor line, while spell checking is on.
+    fn spelling_hint(&self) -> Option<String> {
+        let words: Vec<String> = self
+            .misspellings_on(self.cursor_line.saturating_sub(1))
+            .into_iter()
+            .map(|misspelling| misspelling.word)
+            .collect();
+        match words.len() {
+            0 => None,
+            1 => Some(format!("Unknown word: {}", words[0])),
+            _ => Some(format!("Unknown words: {}", words.join(", "))),
+        }
+    }
+
+    /// Text for each status line segment enabled in the preferences.
+    fn status_segment_labels(&self) -> Vec<String> {
+        use crate::features::status_line::{self, StatusSegment};
+        use crate::features::text_stats;
+
+        let active_editor = self
+            .active_tab
+            .and_then(|idx| self.tabs.get(idx))
+            .and_then(|tab| match &tab.kind {
+                TabKind::Editor { buffer, .. } => Some((tab, buffer)),
+                TabKind::Preview { .. } | TabKind::Viewer(_) => None,
+            });
+
+        let mut labels = Vec::new();
+        for segment in status_line::parse_segments(&self.editor_preferences.status_segments) {
+            let label = match (segment, active_editor) {
+                (StatusSegment::Branch, _) => self.git_branch.clone(),
+                (StatusSegment::Language, Some((tab, buffer))) => {
+                    let language = crate::language::detect(&tab.path, &buffer.line(0));
+                    Some(language.name().to_string())
+                }
+                (StatusSegment::Indent, Some((_, buffer))) => {
+                    let lines = (0..buffer.line_count()).map(|idx| buffer.line(idx));
+                    Some(self.editor_preferences.indent_style_for(lines).label())
+                }
+                (StatusSegment::LineEnding, Some((tab, _))) => {
+                    Some(self.line_ending_of(&tab.path).label().to_string())
+                }
+                (StatusSegment::Encoding, Some((tab, _))) => {
+                    Some(self.encoding_of(&tab.path).label().to_string())
+                }
+                (StatusSegment::Size, Some((_, buffer))) => {
+                    Some(status_line::format_size(buffer.len_bytes()))
+                }
+                (StatusSegment::Words, Some((_, buffer))) => {
+                    let words = text_stats::count(&buffer.text()).words;
+                    Some(match self.selected_text() {
+                        Some(selected) => {
+                            format!("{} of {words} words", text_stats::count(&selected).words)
+                        }
+                        None => format!("{words} words"),
+                    })
+                }
+                _ => None,
+            };
+            labels.extend(label);
+        }
+        labels
+    }
+
     pub(super) fn view_welcome_screen(&self) -> iced::widget::Container<'_, Message> {
+        const RECENT_SHOWN: usize = 5;
+        let primary = if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Ctrl"
+        };
+
         let folder_name = self
             .file_tree
             .as_ref()
@@ -563,12 +1341,78 @@ impl App {
             })
             .unwrap_or_else(|| String::from("No folder open"));
 
+        let actions = row![
+            welcome_action("Open Folder", Message::OpenFolderDialog),
+            welcome_action("Open File", Message::OpenFileDialog),
+            welcome_action("New File", Message::NewFile),
+        ]
+        .spacing(8);
+
+        let mut recent = column![].spacing(2).width(Length::Fixed(420.0));
+        if !self.recent_folders.is_empty() {
+            recent = recent.push(welcome_heading("Recent Folders"));
+            for path in self.recent_folders.iter().take(RECENT_SHOWN) {
+                recent = recent.push(welcome_recent_row(
+                    path,
+                    Message::FolderOpened(path.clone()),
+                ));
+            }
+        }
+        if !self.recent_files.is_empty() {
+            recent = recent.push(welcome_heading("Recent Files"));
+            for path in self.recent_files.iter().take(RECENT_SHOWN) {
+                recent = recent.push(welcome_recent_row(path, Message::FileClicked(path.clone())));
+            }
+        }
+
+        let shortcuts = [
+            (format!("{primary}+Shift+P"), "Command palette"),
+            (format!("{primary}+T"), "Go to file"),
+            (format!("{primary}+G"), "Go to line"),
+            (format!("{primary}+P"), "Fuzzy finder with preview"),
+            (format!("{primary}+J"), "Toggle terminal"),
+            (format!("{primary}+Shift+S"), "Settings"),
+        ];
+        let mut cheat_sheet = column![welcome_heading("Shortcuts")]
+            .spacing(2)
+            .width(Length::Fixed(420.0));
+        for (keys, action) in shortcuts {
+            cheat_sheet = cheat_sheet.push(
+                row![
+                    text(keys)
+                        .size(12)
+                        .color(theme().text_muted)
+                        .width(Length::Fixed(140.0)),
+                    text(action).size(12).color(theme().text_dim),
+                ]
+                .padding(iced::Padding {
+                    top: 2.0,
+                    right: 8.0,
+                    bottom: 2.0,
+                    left: 8.0,
+                }),
+            );
+        }
+
+        let update_label = if self.editor_preferences.check_for_updates {
+            "Check for updates on startup: On"
+        } else {
+            "Check for updates on startup: Off"
+        };
+        let update_toggle = button(text(update_label).size(11).color(theme().text_dim))
+            .on_press(Message::SettingsToggleUpdateCheck)
+            .style(tree_button_style);
+
         container(
             column![
                 text(folder_name).size(24).color(theme().text_muted),
                 text("Select a file from the sidebar to begin editing")
                     .size(13)
                     .color(theme().text_placeholder),
+                actions,
+                recent,
+                cheat_sheet,
+                update_toggle,
             ]
             .spacing(12)
             .align_x(iced::Alignment::Center),
@@ -577,3 +1421,219 @@ impl App {
         .center_y(Length::Fill)
     }
 }
+
+fn welcome_action(label: &str, message: Message) -> Element<'_, Message> {
+    button(text(label).size(13))
+        .on_press(message)
+        .padding(iced::Padding {
+            top: 6.0,
+            right: 14.0,
+            bottom: 6.0,
+            left: 14.0,
+        })
+        .into()
+}
+
+fn welcome_heading(label: &str) -> Element<'_, Message> {
+    container(text(label).size(10).color(theme().text_dim))
+        .padding(iced::Padding {
+            top: 8.0,
+            right: 8.0,
+            bottom: 4.0,
+            left: 8.0,
+        })
+        .into()
+}
+
+/// A recent folder or file: its name, then the parent directory dimmed.
+fn welcome_recent_row(path: &std::path::Path, message: Message) -> Element<'_, Message> {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let parent = path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    button(
+        row![
+            text(name).size(12).color(theme().text_primary),
+            text(parent).size(11).color(theme().text_dim),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center),
+    )
+    .on_press(message)
+    .style(tree_button_style)
+    .width(Length::Fill)
+    .into()
+}
+
+fn git_change_row(change: &crate::features::git::GitChange, staged: bool) -> Element<'_, Message> {
+    let (label, action) = if staged {
+        ("-", Message::GitUnstage(change.path.clone()))
+    } else {
+        ("+", Message::GitStage(change.path.clone()))
+    };
+    row![
+        text(change.code.trim().to_string())
+            .size(11)
+            .color(theme().text_dim)
+            .width(Length::Fixed(20.0)),
+        text(&change.path)
+            .size(11)
+            .color(theme().text_muted)
+            .width(Length::Fill),
+        button(text(label).size(12).color(theme().text_primary))
+            .on_press(action)
+            .style(tree_button_style)
+            .padding(iced::Padding {
+                top: 0.0,
+                right: 6.0,
+                bottom: 0.0,
+                left: 6.0,
+            }),
+    ]
+    .spacing(6)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// A centered question with a row of answer buttons, shown in place of the
+/// editor.
+fn confirm_prompt(question: String, answers: Vec<(&str, Message)>) -> Element<'_, Message> {
+    confirm_prompt_with_details(question, None, answers)
+}
+
+/// A confirm prompt with more information, such as a preview, between the
+/// question and the answers.
+fn confirm_prompt_with_details<'a>(
+    question: String,
+    details: Option<Element<'a, Message>>,
+    answers: Vec<(&'a str, Message)>,
+) -> Element<'a, Message> {
+    let buttons = answers
+        .into_iter()
+        .fold(row![].spacing(12), |row, (label, msg)| {
+            row.push(
+                button(text(label).size(13))
+                    .on_press(msg)
+                    .padding(iced::Padding {
+                        top: 8.0,
+                        right: 16.0,
+                        bottom: 8.0,
+                        left: 16.0,
+                    }),
+            )
+        });
+
+    container(
+        column![text(question).size(18).color(theme().text_muted)]
+            .push(details.map(|details| container(details).max_height(320.0)))
+            .push(buttons.align_y(iced::Alignment::Center))
+            .spacing(16)
+            .align_x(iced::Alignment::Center),
+    )
+    .center_x(Length::Fill)
+    .center_y(Length::Fill)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
+
+/// Block headers pinned over the top of an editor, with their line
+/// numbers in the gutter. Clicking one goes to its line.
+fn view_sticky_headers<'a>(
+    buffer: &crate::features::editor_buffer::EditorBuffer,
+    lines: &[usize],
+    layout: &crate::ui::TextLayout,
+    font: iced::Font,
+    size: f32,
+) -> Element<'a, Message> {
+    let headers = lines.iter().map(|&line| {
+        let header = buffer.line(line).trim_end().replace('\t', "    ");
+        button(
+            row![
+                container(
+                    text((line + 1).to_string())
+                        .size(size)
+                        .color(theme().text_dim)
+                )
+                .width(Length::Fixed((layout.text_left() - 8.0).max(0.0)))
+                .align_x(iced::alignment::Horizontal::Right),
+                text(header)
+                    .font(font)
+                    .size(size)
+                    .color(theme().text_primary)
+                    .wrapping(iced::widget::text::Wrapping::None),
+            ]
+            .spacing(8)
+            .height(Length::Fixed(layout.line_height()))
+            .align_y(iced::Alignment::Center),
+        )
+        .on_press(Message::StickyHeaderClicked(line))
+        .padding(0)
+        .width(Length::Fill)
+        .style(|_theme, status| button::Style {
+            background: Some(iced::Background::Color(match status {
+                button::Status::Hovered => theme().bg_hover,
+                _ => theme().bg_editor,
+            })),
+            ..Default::default()
+        })
+        .into()
+    });
+    column(headers).width(Length::Fill).clip(true).into()
+}
+
+/// Read-only view of an image or binary file tab.
+fn view_file_viewer(viewer: &Viewer) -> Element<'_, Message> {
+    let body: Element<'_, Message> = match viewer {
+        Viewer::Image(handle) => {
+            container(iced::widget::image(handle.clone()).content_fit(iced::ContentFit::ScaleDown))
+                .center(Length::Fill)
+                .padding(16)
+                .into()
+        }
+        Viewer::Svg(handle) => {
+            container(iced::widget::svg(handle.clone()).content_fit(iced::ContentFit::ScaleDown))
+                .center(Length::Fill)
+                .padding(16)
+                .into()
+        }
+        Viewer::Hex { dump, size } => {
+            let note = if *size > file_viewer::HEX_BYTES {
+                format!(
+                    "Binary file, {size} bytes. Showing the first {} bytes.",
+                    file_viewer::HEX_BYTES
+                )
+            } else {
+                format!("Binary file, {size} bytes.")
+            };
+            column![
+                text(note).size(12).color(theme().text_muted),
+                scrollable(
+                    text(dump.as_str())
+                        .size(13)
+                        .font(iced::Font::MONOSPACE)
+                        .color(theme().text_primary)
+                )
+                .width(Length::Fill)
+                .height(Length::Fill),
+            ]
+            .spacing(8)
+            .padding(12)
+            .into()
+        }
+    };
+    container(body)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|_theme| container::Style {
+            background: Some(iced::Background::Color(theme().bg_editor)),
+            ..Default::default()
+        })
+        .into()
+}
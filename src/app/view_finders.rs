@@ -10,18 +10,21 @@ impl App {
         };
         use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
 
-        let input = text_input("Search files...", &self.fuzzy_finder.input)
-            .id(self.fuzzy_finder.input_id.clone())
-            .on_input(Message::FuzzyFinderQueryChanged)
-            .size(15)
-            .padding(iced::Padding {
-                top: 16.0,
-                right: 18.0,
-                bottom: 16.0,
-                left: 18.0,
-            })
-            .style(search_input_style)
-            .width(Length::Fill);
+        let input = text_input(
+            "Search files, or @ for symbols...",
+            &self.fuzzy_finder.input,
+        )
+        .id(self.fuzzy_finder.input_id.clone())
+        .on_input(Message::FuzzyFinderQueryChanged)
+        .size(15)
+        .padding(iced::Padding {
+            top: 16.0,
+            right: 18.0,
+            bottom: 16.0,
+            left: 18.0,
+        })
+        .style(search_input_style)
+        .width(Length::Fill);
 
         let folder_label: Element<'_, Message> =
             if let Some(folder) = &self.fuzzy_finder.current_folder {
@@ -43,7 +46,70 @@ impl App {
 
         let mut items: Vec<Element<'_, Message>> = Vec::new();
 
-        if self.fuzzy_finder.filtered_files.is_empty() {
+        if self.fuzzy_finder.symbol_mode() {
+            if self.fuzzy_finder.filtered_symbols.is_empty() {
+                items.push(
+                    container(text("No symbols found").size(13).color(theme().text_dim))
+                        .padding(20)
+                        .width(Length::Fill)
+                        .center_x(Length::Fill)
+                        .into(),
+                );
+            }
+            for (idx, symbol) in self.fuzzy_finder.filtered_symbols.iter().enumerate() {
+                let is_selected = idx == self.fuzzy_finder.selected_index;
+                items.push(
+                    button(
+                        row![
+                            text(symbol.kind)
+                                .size(11)
+                                .font(iced::Font::MONOSPACE)
+                                .color(theme().text_dim)
+                                .width(Length::Fixed(64.0)),
+                            text(&symbol.name).size(13).color(if is_selected {
+                                theme().text_primary
+                            } else {
+                                theme().text_muted
+                            }),
+                            Space::new().width(Length::Fill),
+                            text(format!("{}", symbol.line + 1))
+                                .size(11)
+                                .color(theme().text_dim),
+                        ]
+                        .spacing(8)
+                        .align_y(iced::Alignment::Center),
+                    )
+                    .style(file_finder_item_style(is_selected))
+                    .on_press(Message::FuzzyFinderSymbolClicked(symbol.line))
+                    .padding(iced::Padding {
+                        top: 6.0,
+                        right: 10.0,
+                        bottom: 6.0,
+                        left: 10.0,
+                    })
+                    .width(Length::Fill)
+                    .into(),
+                );
+            }
+        } else if let Some(path) = self.fuzzy_finder.creatable_path() {
+            items.push(
+                button(
+                    text(format!("Create file '{}'", self.fuzzy_finder.input.trim()))
+                        .size(13)
+                        .color(theme().text_primary),
+                )
+                .style(file_finder_item_style(true))
+                .on_press(Message::FuzzyFinderCreateFile(path))
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 10.0,
+                    bottom: 6.0,
+                    left: 10.0,
+                })
+                .width(Length::Fill)
+                .into(),
+            );
+        } else if self.fuzzy_finder.filtered_files.is_empty() {
             items.push(
                 container(text("No files found").size(13).color(theme().text_dim))
                     .padding(20)
@@ -52,6 +118,7 @@ impl App {
                     .into(),
             );
         } else {
+            let position = self.fuzzy_finder.query_location().1;
             for (idx, file) in self.fuzzy_finder.filtered_files.iter().enumerate() {
                 let is_selected = idx == self.fuzzy_finder.selected_index;
                 let path = file.path.clone();
@@ -83,7 +150,10 @@ impl App {
                         .align_y(iced::Alignment::Center),
                     )
                     .style(file_finder_item_style(is_selected))
-                    .on_press(Message::FileClicked(path))
+                    .on_press(match position {
+                        Some((line, column)) => Message::OpenLocation { path, line, column },
+                        None => Message::FileClicked(path),
+                    })
                     .padding(iced::Padding {
                         top: 6.0,
                         right: 10.0,
@@ -137,6 +207,7 @@ impl App {
                 let mut highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
 
                 let mut line_elements: Vec<Element<'_, Message>> = Vec::new();
+                let first_line = self.fuzzy_finder.preview_first_line;
 
                 for (line_idx, line) in content.lines().enumerate().take(100) {
                     let line_with_newline = format!("{}\n", line);
@@ -151,11 +222,14 @@ impl App {
                     )
                     .collect();
 
-                    let line_num: Element<'_, Message> =
-                        container(text(format!("{}", line_idx + 1)).size(11).color(OVERLAY_2))
-                            .width(Length::Fixed(36.0))
-                            .align_right(Length::Fixed(36.0))
-                            .into();
+                    let line_num: Element<'_, Message> = container(
+                        text(format!("{}", first_line + line_idx + 1))
+                            .size(11)
+                            .color(OVERLAY_2),
+                    )
+                    .width(Length::Fixed(36.0))
+                    .align_right(Length::Fixed(36.0))
+                    .into();
 
                     let mut spans: Vec<iced::widget::text::Span<'_, iced::Font>> = Vec::new();
                     for (style, fragment) in &ranges {
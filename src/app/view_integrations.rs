@@ -281,4 +281,103 @@ impl App {
         .width(Length::Fill)
         .into()
     }
+
+    pub(super) fn view_settings_snippets(&self) -> Element<'_, Message> {
+        use iced::widget::Space;
+
+        let heading = text("Snippets").size(18).color(theme().text_primary);
+        let desc = text("Share the selection or a file with the Share Snippet command.")
+            .size(12)
+            .color(theme().text_dim);
+
+        let separator = || {
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(|_theme| {
+                container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.06))),
+                    ..Default::default()
+                }
+            })
+        };
+        let padding = iced::Padding {
+            top: 8.0,
+            right: 12.0,
+            bottom: 8.0,
+            left: 12.0,
+        };
+
+        let endpoint_row = row![
+            column![
+                text("Endpoint").size(13).color(theme().text_muted),
+                text("Where snippets are posted, GitHub Gists by default")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            text_input("https://api.github.com/gists", &self.snippets.endpoint)
+                .on_input(Message::SnippetEndpointChanged)
+                .size(13)
+                .padding(padding)
+                .style(search_input_style)
+                .width(Length::FillPortion(3)),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let token_row = row![
+            column![
+                text("Token").size(13).color(theme().text_muted),
+                text("Sent as a bearer token, needs the gist scope for GitHub")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            text_input("ghp_xxxxx", &self.snippets.token)
+                .on_input(Message::SnippetTokenChanged)
+                .secure(true)
+                .size(13)
+                .padding(padding)
+                .style(search_input_style)
+                .width(Length::FillPortion(3)),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let save_btn = button(
+            text("Save Snippet Settings")
+                .size(12)
+                .color(theme().text_primary),
+        )
+        .on_press(Message::SaveSnippetSettings)
+        .style(|_theme, _status| button::Style {
+            background: Some(Background::Color(ACCENT_PURPLE.scale_alpha(0.2))),
+            border: iced::Border {
+                color: ACCENT_PURPLE.scale_alpha(0.4),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            text_color: theme().text_primary,
+            ..Default::default()
+        })
+        .padding(iced::Padding {
+            top: 8.0,
+            right: 20.0,
+            bottom: 8.0,
+            left: 20.0,
+        });
+
+        column![
+            heading,
+            desc,
+            separator(),
+            endpoint_row,
+            token_row,
+            Space::new().height(Length::Fixed(8.0)),
+            save_btn,
+        ]
+        .spacing(12)
+        .width(Length::Fill)
+        .into()
+    }
 }
@@ -3,78 +3,153 @@ use iced::widget::column;
 
 impl App {
     pub(super) fn view_notification_toast(&self) -> Element<'_, Message> {
-        let check_circle = container(text("✓").size(14).color(Color::from_rgb(0.40, 0.90, 0.55)))
-            .width(Length::Fixed(26.0))
-            .height(Length::Fixed(26.0))
-            .center_x(Length::Fixed(26.0))
-            .center_y(Length::Fixed(26.0))
-            .style(|_theme| container::Style {
-                background: Some(Background::Color(Color::from_rgba(0.30, 0.85, 0.50, 0.15))),
-                border: iced::Border {
-                    color: Color::from_rgba(0.35, 0.88, 0.52, 0.35),
-                    width: 1.0,
-                    radius: 13.0.into(),
-                },
-                ..Default::default()
-            });
-
-        let label = text("Preferences saved")
-            .size(13)
-            .color(Color::from_rgb(0.85, 0.97, 0.88));
-
-        let dismiss_btn = button(
-            text("×")
-                .size(14)
-                .color(Color::from_rgba(0.65, 0.90, 0.70, 0.7)),
-        )
-        .on_press(Message::DismissNotification)
-        .style(|_theme, _status| button::Style {
-            background: None,
-            border: iced::Border::default(),
-            text_color: Color::from_rgba(0.65, 0.90, 0.70, 0.7),
-            ..Default::default()
-        })
-        .padding(iced::Padding {
-            top: 0.0,
-            right: 4.0,
-            bottom: 0.0,
-            left: 8.0,
-        });
-
-        let toast_inner = row![check_circle, label, dismiss_btn]
+        use crate::features::notifications::age_label;
+        use iced::widget::Space;
+
+        let mut cards: Vec<Element<'_, Message>> = Vec::new();
+        for (idx, toast) in self.notifications.toasts().iter().enumerate() {
+            let (icon, accent) = severity_style(toast.severity);
+            let icon_circle = container(text(icon).size(13).color(accent))
+                .width(Length::Fixed(24.0))
+                .height(Length::Fixed(24.0))
+                .center_x(Length::Fixed(24.0))
+                .center_y(Length::Fixed(24.0))
+                .style(move |_theme| container::Style {
+                    background: Some(Background::Color(Color { a: 0.15, ..accent })),
+                    border: iced::Border {
+                        color: Color { a: 0.35, ..accent },
+                        width: 1.0,
+                        radius: 12.0.into(),
+                    },
+                    ..Default::default()
+                });
+
+            let dismiss_btn = button(text("×").size(14).color(theme().text_dim))
+                .on_press(Message::DismissNotification(idx))
+                .style(|_theme, _status| button::Style {
+                    background: None,
+                    border: iced::Border::default(),
+                    text_color: theme().text_dim,
+                    ..Default::default()
+                })
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 4.0,
+                    bottom: 0.0,
+                    left: 8.0,
+                });
+
+            let mut body = row![
+                icon_circle,
+                text(&toast.message)
+                    .size(13)
+                    .color(theme().text_primary)
+                    .width(Length::Fill),
+            ]
             .spacing(10)
             .align_y(iced::Alignment::Center);
+            for (label, message) in &toast.actions {
+                body = body.push(
+                    button(text(*label).size(12).color(accent))
+                        .on_press(message.clone())
+                        .style(tab_close_button_style)
+                        .padding([2, 6]),
+                );
+            }
+
+            let card = container(body.push(dismiss_btn))
+                .width(Length::Fixed(340.0))
+                .padding(iced::Padding {
+                    top: 10.0,
+                    right: 12.0,
+                    bottom: 10.0,
+                    left: 12.0,
+                })
+                .style(move |_theme| container::Style {
+                    background: Some(Background::Color(theme().bg_secondary)),
+                    border: iced::Border {
+                        color: Color { a: 0.45, ..accent },
+                        width: 1.0,
+                        radius: 10.0.into(),
+                    },
+                    shadow: iced::Shadow {
+                        color: Color::from_rgba(0.0, 0.0, 0.0, 0.45),
+                        offset: iced::Vector::new(0.0, 6.0),
+                        blur_radius: 24.0,
+                    },
+                    ..Default::default()
+                });
+            cards.push(card.into());
+        }
 
-        let toast = container(toast_inner)
-            .padding(iced::Padding {
-                top: 10.0,
-                right: 16.0,
-                bottom: 10.0,
-                left: 12.0,
-            })
-            .style(|_theme| container::Style {
-                background: Some(Background::Color(Color::from_rgba(0.07, 0.20, 0.10, 0.96))),
-                border: iced::Border {
-                    color: Color::from_rgba(0.30, 0.78, 0.45, 0.40),
-                    width: 1.0,
-                    radius: 12.0.into(),
-                },
-                shadow: iced::Shadow {
-                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.55),
-                    offset: iced::Vector::new(0.0, 8.0),
-                    blur_radius: 32.0,
-                },
-                ..Default::default()
-            });
+        if self.notifications.history_open {
+            let now = Instant::now();
+            let mut entries: Vec<Element<'_, Message>> = Vec::new();
+            for entry in self.notifications.history() {
+                let (icon, accent) = severity_style(entry.severity);
+                entries.push(
+                    row![
+                        text(icon).size(12).color(accent),
+                        text(&entry.message)
+                            .size(12)
+                            .color(theme().text_primary)
+                            .width(Length::Fill),
+                        text(age_label(entry.shown_at, now))
+                            .size(11)
+                            .color(theme().text_dim),
+                    ]
+                    .spacing(8)
+                    .into(),
+                );
+            }
+            if entries.is_empty() {
+                entries.push(
+                    text("No notifications")
+                        .size(12)
+                        .color(theme().text_muted)
+                        .into(),
+                );
+            }
+
+            let header = row![
+                text("Notifications").size(13).color(theme().text_primary),
+                Space::new().width(Length::Fill),
+                button(text("Clear").size(12).color(theme().text_muted))
+                    .on_press(Message::ClearNotifications)
+                    .style(tab_close_button_style),
+                button(text("×").size(14).color(theme().text_dim))
+                    .on_press(Message::ToggleNotificationHistory)
+                    .style(tab_close_button_style),
+            ]
+            .spacing(6)
+            .align_y(iced::Alignment::Center);
 
-        container(column![
-            container(toast).center_x(Length::Fill),
-            iced::widget::Space::new().height(Length::Fill),
-        ])
+            let panel = container(
+                column![
+                    header,
+                    scrollable(column(entries).spacing(6)).height(Length::Shrink)
+                ]
+                .spacing(8),
+            )
+            .width(Length::Fixed(340.0))
+            .max_height(320.0)
+            .padding(12)
+            .style(file_finder_panel_style);
+            cards.insert(0, panel.into());
+        }
+
+        container(
+            column![
+                Space::new().height(Length::Fill),
+                column(cards).spacing(8).align_x(iced::Alignment::End),
+            ]
+            .width(Length::Fill)
+            .align_x(iced::Alignment::End),
+        )
         .padding(iced::Padding {
             top: 20.0,
-            right: 0.0,
-            bottom: 0.0,
+            right: 20.0,
+            bottom: 40.0,
             left: 0.0,
         })
         .width(Length::Fill)
@@ -195,6 +270,7 @@ impl App {
             let is_selected = idx == self.command_palette_selected;
             let cmd_name = cmd.name.clone();
             let shortcut_text = cmd.description.clone();
+            let keys = crate::features::command_palette::shortcut(&cmd.name).unwrap_or_default();
 
             items.push(
                 button(
@@ -206,7 +282,9 @@ impl App {
                         }),
                         iced::widget::Space::new().width(Length::Fill),
                         text(shortcut_text).size(11).color(theme().text_dim),
+                        text(keys).size(11).color(theme().text_muted),
                     ]
+                    .spacing(12)
                     .align_y(iced::Alignment::Center),
                 )
                 .style(file_finder_item_style(is_selected))
@@ -263,6 +341,241 @@ impl App {
         stack![backdrop, center(opaque(overlay_box))].into()
     }
 
+    pub(super) fn view_buffer_switcher_overlay(&self) -> Element<'_, Message> {
+        use iced::widget::{center, opaque, stack, Space};
+
+        let selected = self.buffer_switcher.unwrap_or(0);
+        let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+        let mut items: Vec<Element<'_, Message>> = Vec::new();
+        for (row_idx, idx) in self.tabs_by_recency().into_iter().enumerate() {
+            let tab = &self.tabs[idx];
+            let is_selected = row_idx == selected;
+            let is_modified = matches!(&tab.kind, TabKind::Editor { code_editor, .. } if code_editor.is_modified());
+            let folder = tab
+                .path
+                .parent()
+                .map(|parent| {
+                    root.and_then(|root| parent.strip_prefix(root).ok())
+                        .unwrap_or(parent)
+                })
+                .map(|parent| parent.display().to_string())
+                .unwrap_or_default();
+
+            items.push(
+                button(
+                    row![
+                        text(&tab.name).size(13).color(if is_selected {
+                            theme().text_primary
+                        } else {
+                            theme().text_muted
+                        }),
+                        text(if is_modified { "●" } else { "" })
+                            .size(10)
+                            .color(theme().text_muted),
+                        Space::new().width(Length::Fill),
+                        text(folder).size(11).color(theme().text_dim),
+                    ]
+                    .spacing(8)
+                    .align_y(iced::Alignment::Center),
+                )
+                .style(file_finder_item_style(is_selected))
+                .on_press(Message::BufferSwitcherClicked(idx))
+                .padding(iced::Padding {
+                    top: 7.0,
+                    right: 10.0,
+                    bottom: 7.0,
+                    left: 10.0,
+                })
+                .width(Length::Fill)
+                .into(),
+            );
+        }
+
+        let overlay_box = container(scrollable(column(items).spacing(2).padding(6)))
+            .width(Length::Fixed(520.0))
+            .max_height(440.0)
+            .style(file_finder_panel_style);
+
+        let backdrop = mouse_area(
+            container(Space::new())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.45))),
+                    ..Default::default()
+                }),
+        )
+        .on_press(Message::EscapePressed);
+
+        stack![backdrop, center(opaque(overlay_box))].into()
+    }
+
+    pub(super) fn view_local_history_overlay(&self) -> Element<'_, Message> {
+        use crate::features::local_history::age_label;
+        use iced::widget::{center, opaque, stack, Space};
+
+        let Some((path, versions)) = &self.local_history else {
+            return Space::new().into();
+        };
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let now = std::time::SystemTime::now();
+        let action = |label: &'static str, message: Message| {
+            button(text(label).size(12))
+                .style(tree_button_style)
+                .on_press(message)
+                .padding([4, 10])
+        };
+        let mut rows: Vec<Element<'_, Message>> = Vec::new();
+        if versions.is_empty() {
+            rows.push(
+                text("No saved versions yet")
+                    .size(13)
+                    .color(theme().text_muted)
+                    .into(),
+            );
+        }
+        for (idx, version) in versions.iter().enumerate() {
+            rows.push(
+                row![
+                    text(age_label(version.saved_at, now))
+                        .size(13)
+                        .color(theme().text_primary),
+                    Space::new().width(Length::Fill),
+                    action("Compare", Message::CompareWithVersion(idx)),
+                    action("Restore", Message::RestoreVersion(idx)),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            );
+        }
+
+        let content = column![
+            text(format!("Local History of {name}"))
+                .size(16)
+                .color(theme().text_primary),
+            column(rows).spacing(4),
+        ]
+        .spacing(14)
+        .padding(16);
+
+        let overlay_box = container(scrollable(content))
+            .width(Length::Fixed(420.0))
+            .max_height(480.0)
+            .style(file_finder_panel_style);
+
+        let backdrop = mouse_area(
+            container(Space::new())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.45))),
+                    ..Default::default()
+                }),
+        )
+        .on_press(Message::ToggleLocalHistory);
+
+        stack![backdrop, center(opaque(overlay_box))].into()
+    }
+
+    pub(super) fn view_activity_overlay(&self) -> Element<'_, Message> {
+        use crate::features::activity::{self, format_duration};
+        use iced::widget::{center, opaque, stack, Space};
+
+        let today = activity::day_of(activity::now());
+        let day = self.activity.day(today).cloned().unwrap_or_default();
+        let streak = self.activity.streak(today);
+        let root = self.file_tree.as_ref().map(|tree| tree.root.as_path());
+
+        // Largest totals first, at most five of them
+        let top = |totals: &std::collections::BTreeMap<String, u64>| {
+            let mut entries: Vec<(String, u64)> = totals
+                .iter()
+                .map(|(name, secs)| (name.clone(), *secs))
+                .collect();
+            entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            entries.truncate(5);
+            entries
+        };
+        let list = |title: &'static str, entries: Vec<(String, u64)>| {
+            let mut rows: Vec<Element<'_, Message>> =
+                vec![text(title).size(12).color(theme().text_dim).into()];
+            if entries.is_empty() {
+                rows.push(
+                    text("Nothing yet")
+                        .size(13)
+                        .color(theme().text_muted)
+                        .into(),
+                );
+            }
+            for (name, secs) in entries {
+                rows.push(
+                    row![
+                        text(name).size(13).color(theme().text_primary),
+                        Space::new().width(Length::Fill),
+                        text(format_duration(secs))
+                            .size(13)
+                            .color(theme().text_muted),
+                    ]
+                    .spacing(8)
+                    .into(),
+                );
+            }
+            column(rows).spacing(6).width(Length::Fill)
+        };
+        let files = top(&day.files)
+            .into_iter()
+            .map(|(path, secs)| {
+                let path = std::path::PathBuf::from(path);
+                let shown = root
+                    .and_then(|root| path.strip_prefix(root).ok())
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string();
+                (shown, secs)
+            })
+            .collect();
+
+        let content = column![
+            text("Activity").size(16).color(theme().text_primary),
+            row![
+                text(format!("Today {}", format_duration(day.total())))
+                    .size(14)
+                    .color(theme().text_primary),
+                Space::new().width(Length::Fill),
+                text(format!("{streak} day streak"))
+                    .size(14)
+                    .color(theme().text_muted),
+            ],
+            list("LANGUAGES", top(&day.languages)),
+            list("FILES", files),
+        ]
+        .spacing(14)
+        .padding(16);
+
+        let overlay_box = container(scrollable(content))
+            .width(Length::Fixed(480.0))
+            .max_height(480.0)
+            .style(file_finder_panel_style);
+
+        let backdrop = mouse_area(
+            container(Space::new())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(|_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.45))),
+                    ..Default::default()
+                }),
+        )
+        .on_press(Message::ToggleActivity);
+
+        stack![backdrop, center(opaque(overlay_box))].into()
+    }
+
     pub(super) fn view_find_replace_panel(&self) -> Element<'_, Message> {
         let find_input = text_input("Find...", &self.find_replace.find_text)
             .id(self.find_input_id.clone())
@@ -294,6 +607,42 @@ impl App {
             .size(11)
             .color(theme().text_dim);
 
+        let scope_list = iced::widget::pick_list(
+            crate::features::find_replace::Scope::ALL,
+            Some(self.find_replace.scope),
+            Message::FindScopeSelected,
+        )
+        .text_size(11)
+        .padding(iced::Padding {
+            top: 4.0,
+            right: 8.0,
+            bottom: 4.0,
+            left: 8.0,
+        })
+        .style(|_theme, _status| iced::widget::pick_list::Style {
+            text_color: theme().text_muted,
+            placeholder_color: theme().text_placeholder,
+            handle_color: theme().text_dim,
+            background: Background::Color(theme().bg_editor),
+            border: iced::Border {
+                color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+        })
+        .menu_style(|_theme| iced::overlay::menu::Style {
+            background: Background::Color(theme().bg_secondary),
+            border: iced::Border {
+                color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            text_color: theme().text_muted,
+            selected_text_color: theme().text_primary,
+            selected_background: Background::Color(theme().bg_hover),
+            shadow: Default::default(),
+        });
+
         let case_btn = button(
             text(if self.find_replace.case_sensitive {
                 "Aa"
@@ -331,6 +680,23 @@ impl App {
                 left: 6.0,
             });
 
+        let preserve_case_btn = button(
+            text(if self.find_replace.preserve_case {
+                "AB"
+            } else {
+                "ab"
+            })
+            .size(11),
+        )
+        .on_press(Message::TogglePreserveCase)
+        .style(tab_close_button_style)
+        .padding(iced::Padding {
+            top: 3.0,
+            right: 6.0,
+            bottom: 3.0,
+            left: 6.0,
+        });
+
         let replace_btn = button(text("Replace").size(11).color(theme().text_muted))
             .on_press(Message::ReplaceOne)
             .style(tab_close_button_style)
@@ -361,13 +727,19 @@ impl App {
                 left: 6.0,
             });
 
-        let find_row = row![find_input, match_info, case_btn, prev_btn, next_btn, close_btn]
-            .spacing(6)
-            .align_y(iced::Alignment::Center);
+        let find_row =
+            row![find_input, match_info, scope_list, case_btn, prev_btn, next_btn, close_btn]
+                .spacing(6)
+                .align_y(iced::Alignment::Center);
 
-        let replace_row = row![replace_input, replace_btn, replace_all_btn]
-            .spacing(6)
-            .align_y(iced::Alignment::Center);
+        let replace_row = row![
+            replace_input,
+            preserve_case_btn,
+            replace_btn,
+            replace_all_btn
+        ]
+        .spacing(6)
+        .align_y(iced::Alignment::Center);
 
         container(column![find_row, replace_row].spacing(6))
             .padding(iced::Padding {
@@ -389,3 +761,14 @@ impl App {
             .into()
     }
 }
+
+/// Icon and accent color of a notification.
+fn severity_style(severity: crate::features::notifications::Severity) -> (&'static str, Color) {
+    use crate::features::notifications::Severity;
+    match severity {
+        Severity::Info => ("i", Color::from_rgb(0.45, 0.70, 1.0)),
+        Severity::Success => ("✓", Color::from_rgb(0.40, 0.90, 0.55)),
+        Severity::Warning => ("!", Color::from_rgb(0.98, 0.78, 0.35)),
+        Severity::Error => ("×", Color::from_rgb(0.96, 0.45, 0.45)),
+    }
+}
@@ -22,20 +22,31 @@ impl App {
             if self.terminal_open {
                 editor_col_items.push(self.view_terminal_panel());
             }
+            if self.output_panel.is_some() {
+                editor_col_items.push(self.view_output_panel());
+            }
             if self.command_input.open {
                 editor_col_items.push(self.view_command_input_bar());
             }
             editor_col_items.push(status_bar);
 
-            let editor_container =
-                if self.active_tab.is_some() || self.pending_sensitive_open.is_some() {
-                    container(column(editor_col_items))
-                } else {
-                    self.view_welcome_screen()
-                }
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .style(editor_container_style);
+            let editor_container = if self.active_tab.is_some()
+                || self.pending_sensitive_open.is_some()
+                || self.pending_large_open.is_some()
+                || self.pending_large_paste.is_some()
+                || self.pending_quit.is_some()
+                || self.pending_replace_all.is_some()
+                || !self.pending_recovery.is_empty()
+                || self.pending_spelling.is_some()
+                || self.pending_rename.is_some()
+            {
+                container(column(editor_col_items))
+            } else {
+                self.view_welcome_screen()
+            }
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(editor_container_style);
 
             container(editor_container)
                 .padding(0)
@@ -74,6 +85,12 @@ impl App {
 
         let base_view: Element<'_, Message> = if self.command_palette.open {
             stack![wrapped, self.view_command_palette_overlay()].into()
+        } else if self.buffer_switcher.is_some() {
+            stack![wrapped, self.view_buffer_switcher_overlay()].into()
+        } else if self.activity_visible {
+            stack![wrapped, self.view_activity_overlay()].into()
+        } else if self.local_history.is_some() {
+            stack![wrapped, self.view_local_history_overlay()].into()
         } else if self.fuzzy_finder.open {
             stack![wrapped, self.view_fuzzy_finder_overlay()].into()
         } else if self.file_finder_visible {
@@ -89,15 +106,27 @@ impl App {
                 .width(Length::Fill)
                 .height(Length::Fill);
             stack![wrapped, search_panel].into()
+        } else if self.git_panel_visible {
+            let git_panel = container(self.view_git_panel())
+                .padding(iced::Padding {
+                    top: 20.0,
+                    right: 0.0,
+                    bottom: 0.0,
+                    left: 20.0,
+                })
+                .width(Length::Fill)
+                .height(Length::Fill);
+            stack![wrapped, git_panel].into()
         } else {
             wrapped.into()
         };
 
-        let with_notification: Element<'_, Message> = if self.notification.is_some() {
-            stack![base_view, self.view_notification_toast()].into()
-        } else {
-            base_view
-        };
+        let with_notification: Element<'_, Message> =
+            if !self.notifications.toasts().is_empty() || self.notifications.history_open {
+                stack![base_view, self.view_notification_toast()].into()
+            } else {
+                base_view
+            };
 
         if self.update_banner.is_some() {
             stack![with_notification, self.view_update_banner()].into()
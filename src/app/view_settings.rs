@@ -1,9 +1,15 @@
 use super::*;
+use crate::message::{BlockEdit, Zoom};
 use iced::widget::column;
 
 impl App {
     pub(super) fn view_command_input_bar(&self) -> Element<'_, Message> {
-        let input = text_input(":", &self.command_input.input)
+        let placeholder = match self.pending_block_edit {
+            Some(BlockEdit::Insert) => "Text to insert at the start of each line in the block",
+            Some(BlockEdit::Append) => "Text to append to each line in the block",
+            None => "Line number or command",
+        };
+        let input = text_input(placeholder, &self.command_input.input)
             .id(self.command_input_id.clone())
             .on_input(Message::CommandInputChanged)
             .on_submit(Message::CommandInputSubmit)
@@ -38,6 +44,7 @@ impl App {
             ("general", "General"),
             ("preferences", "Preferences"),
             ("wakatime", "WakaTime"),
+            ("snippets", "Snippets"),
             ("developer", "Developer"),
         ];
 
@@ -129,6 +136,7 @@ impl App {
             "general" => self.view_settings_general(),
             "preferences" => self.view_settings_preferences(),
             "wakatime" => self.view_settings_wakatime(),
+            "snippets" => self.view_settings_snippets(),
             "developer" => self.view_settings_developer(),
             _ => self.view_settings_general(),
         };
@@ -294,6 +302,350 @@ impl App {
         .spacing(16)
         .align_y(iced::Alignment::Center);
 
+        let detect_label = if self.editor_preferences.detect_indentation {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+        let detect_indentation_row = row![
+            column![
+                text("Detect Indentation")
+                    .size(13)
+                    .color(theme().text_muted),
+                text("Follow the tabs or spaces already used by each file")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(text(detect_label).size(12).color(theme().text_primary))
+                .on_press(Message::SettingsToggleDetectIndentation)
+                .style(|_theme, _status| button::Style {
+                    background: Some(Background::Color(theme().bg_secondary)),
+                    border: iced::Border {
+                        color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: theme().text_primary,
+                    ..Default::default()
+                })
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 16.0,
+                    bottom: 6.0,
+                    left: 16.0
+                }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let auto_close_label = if self.editor_preferences.auto_close_brackets {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+        let auto_close_row = row![
+            column![
+                text("Auto-close Brackets")
+                    .size(13)
+                    .color(theme().text_muted),
+                text("Insert the closing bracket or quote while typing")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(text(auto_close_label).size(12).color(theme().text_primary))
+                .on_press(Message::SettingsToggleAutoClose)
+                .style(|_theme, _status| button::Style {
+                    background: Some(Background::Color(theme().bg_secondary)),
+                    border: iced::Border {
+                        color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: theme().text_primary,
+                    ..Default::default()
+                })
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 16.0,
+                    bottom: 6.0,
+                    left: 16.0
+                }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let word_wrap_label = if self.editor_preferences.word_wrap {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+        let word_wrap_row = row![
+            column![
+                text("Word Wrap").size(13).color(theme().text_muted),
+                text("Wrap long lines at the edge of the editor")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(text(word_wrap_label).size(12).color(theme().text_primary))
+                .on_press(Message::ToggleWordWrap)
+                .style(|_theme, _status| button::Style {
+                    background: Some(Background::Color(theme().bg_secondary)),
+                    border: iced::Border {
+                        color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: theme().text_primary,
+                    ..Default::default()
+                })
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 16.0,
+                    bottom: 6.0,
+                    left: 16.0
+                }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let spell_check_label = if self.editor_preferences.spell_check {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+        let spell_check_row = row![
+            column![
+                text("Spell Check").size(13).color(theme().text_muted),
+                text("Check the spelling of comments, strings and prose")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(text(spell_check_label).size(12).color(theme().text_primary))
+                .on_press(Message::ToggleSpellCheck)
+                .style(|_theme, _status| button::Style {
+                    background: Some(Background::Color(theme().bg_secondary)),
+                    border: iced::Border {
+                        color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: theme().text_primary,
+                    ..Default::default()
+                })
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 16.0,
+                    bottom: 6.0,
+                    left: 16.0
+                }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let backups_row = row![
+            column![
+                text("Backups").size(13).color(theme().text_muted),
+                text("Keep the previous contents of a file when saving it")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(
+                text(self.editor_preferences.backups.label())
+                    .size(12)
+                    .color(theme().text_primary)
+            )
+            .on_press(Message::SettingsCycleBackups)
+            .style(|_theme, _status| button::Style {
+                background: Some(Background::Color(theme().bg_secondary)),
+                border: iced::Border {
+                    color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                text_color: theme().text_primary,
+                ..Default::default()
+            })
+            .padding(iced::Padding {
+                top: 6.0,
+                right: 16.0,
+                bottom: 6.0,
+                left: 16.0
+            }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let whitespace_row = row![
+            column![
+                text("Render Whitespace").size(13).color(theme().text_muted),
+                text("Draw spaces, tabs and line ends, or only trailing whitespace")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(
+                text(self.editor_preferences.render_whitespace.label())
+                    .size(12)
+                    .color(theme().text_primary)
+            )
+            .on_press(Message::CycleRenderWhitespace)
+            .style(|_theme, _status| button::Style {
+                background: Some(Background::Color(theme().bg_secondary)),
+                border: iced::Border {
+                    color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                text_color: theme().text_primary,
+                ..Default::default()
+            })
+            .padding(iced::Padding {
+                top: 6.0,
+                right: 16.0,
+                bottom: 6.0,
+                left: 16.0
+            }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let sticky_scroll_label = if self.editor_preferences.sticky_scroll {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+        let sticky_scroll_row = row![
+            column![
+                text("Sticky Scroll").size(13).color(theme().text_muted),
+                text("Pin the headers of enclosing blocks at the top of the editor")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            button(
+                text(sticky_scroll_label)
+                    .size(12)
+                    .color(theme().text_primary)
+            )
+            .on_press(Message::ToggleStickyScroll)
+            .style(|_theme, _status| button::Style {
+                background: Some(Background::Color(theme().bg_secondary)),
+                border: iced::Border {
+                    color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                text_color: theme().text_primary,
+                ..Default::default()
+            })
+            .padding(iced::Padding {
+                top: 6.0,
+                right: 16.0,
+                bottom: 6.0,
+                left: 16.0
+            }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let font_family_row = row![
+            column![
+                text("Font").size(13).color(theme().text_muted),
+                text("Monospaced font of the editor text")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            iced::widget::pick_list(
+                self.font_families.as_slice(),
+                Some(&self.editor_preferences.font_family),
+                Message::SettingsFontFamilySelected,
+            )
+            .text_size(12)
+            .padding(iced::Padding {
+                top: 6.0,
+                right: 12.0,
+                bottom: 6.0,
+                left: 12.0,
+            })
+            .style(|_theme, _status| iced::widget::pick_list::Style {
+                text_color: theme().text_primary,
+                placeholder_color: theme().text_placeholder,
+                handle_color: theme().text_dim,
+                background: Background::Color(theme().bg_secondary),
+                border: iced::Border {
+                    color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+            })
+            .menu_style(|_theme| iced::overlay::menu::Style {
+                background: Background::Color(theme().bg_secondary),
+                border: iced::Border {
+                    color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                text_color: theme().text_muted,
+                selected_text_color: theme().text_primary,
+                selected_background: Background::Color(theme().bg_hover),
+                shadow: Default::default(),
+            }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
+        let zoom_button = |label: &'static str, zoom: Zoom| {
+            button(text(label).size(12).color(theme().text_primary))
+                .on_press(Message::Zoom(zoom))
+                .style(|_theme, _status| button::Style {
+                    background: Some(Background::Color(theme().bg_secondary)),
+                    border: iced::Border {
+                        color: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    text_color: theme().text_primary,
+                    ..Default::default()
+                })
+                .padding(iced::Padding {
+                    top: 6.0,
+                    right: 12.0,
+                    bottom: 6.0,
+                    left: 12.0,
+                })
+        };
+        let font_size_row = row![
+            column![
+                text("Font Size").size(13).color(theme().text_muted),
+                text("Editor text size in points, also Ctrl+= and Ctrl+-")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            zoom_button("\u{2212}", Zoom::Out),
+            text(self.editor_preferences.font_size.to_string())
+                .size(13)
+                .color(theme().text_primary),
+            zoom_button("+", Zoom::In),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
         let line_number_width_row = row![
             column![
                 text("Line Number Width").size(13).color(theme().text_muted),
@@ -318,6 +670,30 @@ impl App {
         .spacing(16)
         .align_y(iced::Alignment::Center);
 
+        let scrolloff_row = row![
+            column![
+                text("Scroll Off").size(13).color(theme().text_muted),
+                text("Lines kept visible above and below the cursor")
+                    .size(11)
+                    .color(theme().text_dim),
+            ]
+            .spacing(2)
+            .width(Length::FillPortion(2)),
+            text_input("3", &self.editor_preferences.scrolloff.to_string())
+                .on_input(Message::SettingsScrolloffChanged)
+                .size(13)
+                .padding(iced::Padding {
+                    top: 8.0,
+                    right: 12.0,
+                    bottom: 8.0,
+                    left: 12.0
+                })
+                .style(search_input_style)
+                .width(Length::Fixed(80.0)),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center);
+
         let all_themes: Vec<&str> = {
             let mut v: Vec<&str> = BUILTIN_THEMES.to_vec();
             v.push("Custom (theme.lua)");
@@ -469,6 +845,69 @@ impl App {
                     ..Default::default()
                 }
             ),
+            detect_indentation_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            auto_close_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            word_wrap_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            spell_check_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            whitespace_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            sticky_scroll_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            font_family_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            font_size_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
+            backups_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
             line_number_width_row,
             container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
                 |_theme| container::Style {
@@ -476,6 +915,13 @@ impl App {
                     ..Default::default()
                 }
             ),
+            scrolloff_row,
+            container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
+                |_theme| container::Style {
+                    background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.03))),
+                    ..Default::default()
+                }
+            ),
             theme_row,
             container(Space::new().width(Length::Fill).height(Length::Fixed(1.0))).style(
                 |_theme| container::Style {
@@ -0,0 +1,121 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Lines looked at on each side of the cursor when ranking by distance.
+/// Further away the proximity boost is too small to change the order.
+const WINDOW_LINES: usize = 200;
+
+/// Identifiers harvested from every open buffer, so completion can offer
+/// names defined in other files of the project.
+#[derive(Default)]
+pub struct DocumentWords {
+    buffers: HashMap<PathBuf, Vec<String>>,
+}
+
+impl DocumentWords {
+    pub fn set_buffer(&mut self, path: PathBuf, words: Vec<String>) {
+        self.buffers.insert(path, words);
+    }
+
+    pub fn remove_buffer(&mut self, path: &Path) {
+        self.buffers.remove(path);
+    }
+
+    /// Words from all buffers other than `current`, without duplicates.
+    pub fn words_except(&self, current: Option<&Path>) -> HashSet<&str> {
+        self.buffers
+            .iter()
+            .filter(|(path, _)| Some(path.as_path()) != current)
+            .flat_map(|(_, words)| words.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Splits `text` into identifiers with their byte offsets. Words shorter
+/// than two characters or starting with a digit are skipped.
+fn words_with_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|ch: char| !is_word_char(ch))
+        .filter(|word| word.len() > 1 && !word.starts_with(|c: char| c.is_numeric()))
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Unique identifiers in `text`. Cheap enough to run per buffer, but meant to
+/// be called off the UI thread for large files.
+pub fn harvest(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words_with_offsets(text)
+        .filter(|(_, word)| seen.insert(*word))
+        .map(|(_, word)| word.to_string())
+        .collect()
+}
+
+/// Distance in lines from `cursor_pos` to the closest occurrence of each
+/// identifier within [`WINDOW_LINES`] of it. The word being typed at the
+/// cursor is excluded.
+pub fn line_distances(text: &str, cursor_pos: usize) -> HashMap<String, usize> {
+    let cursor_pos = cursor_pos.min(text.len());
+    let start = text[..cursor_pos]
+        .rmatch_indices('\n')
+        .nth(WINDOW_LINES)
+        .map_or(0, |(idx, _)| idx + 1);
+    let end = text[cursor_pos..]
+        .match_indices('\n')
+        .nth(WINDOW_LINES)
+        .map_or(text.len(), |(idx, _)| cursor_pos + idx);
+    let text = &text[start..end];
+    let cursor_pos = cursor_pos - start;
+    let cursor_line = text[..cursor_pos].matches('\n').count();
+
+    let mut distances: HashMap<String, usize> = HashMap::new();
+    let mut line = 0;
+    let mut scanned = 0;
+    for (offset, word) in words_with_offsets(text) {
+        if offset <= cursor_pos && cursor_pos <= offset + word.len() {
+            continue;
+        }
+        line += text[scanned..offset].matches('\n').count();
+        scanned = offset;
+
+        let distance = line.abs_diff(cursor_line);
+        distances
+            .entry(word.to_string())
+            .and_modify(|d| *d = (*d).min(distance))
+            .or_insert(distance);
+    }
+    distances
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harvests_unique_identifiers() {
+        let words = harvest("let café_1 = x + 2nd;\nlet value = café_1 * 10;");
+        assert_eq!(words, ["let", "café_1", "value"]);
+    }
+
+    #[test]
+    fn ranks_by_closest_line_near_the_cursor() {
+        let text = "alpha\nbeta\nalpha gamma\nbet";
+        let distances = line_distances(text, text.len());
+        assert_eq!(distances.get("gamma"), Some(&1));
+        assert_eq!(distances.get("alpha"), Some(&1));
+        assert_eq!(distances.get("beta"), Some(&2));
+        // The word being typed
+        assert_eq!(distances.get("bet"), None);
+
+        let far = format!("far\n{}near cursor", "\n".repeat(WINDOW_LINES * 2));
+        let distances = line_distances(&far, far.len());
+        assert_eq!(distances.get("near"), Some(&0));
+        assert_eq!(distances.get("far"), None);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::autocomplete::{
+    buffer_words::{self, DocumentWords},
     context::CompletionContext,
     language::LanguageDefinitions,
     scoring::FuzzyScorer,
     types::{Suggestion, SuggestionKind},
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 /// Main autocomplete engine with fuzzy matching, context awareness,
 /// and language-specific suggestions.
@@ -14,6 +16,8 @@ pub struct Autocomplete {
     pub selected_index: usize,
     pub trigger_position: usize,
     pub prefix: String,
+    /// Identifiers from the other open buffers.
+    pub document_words: DocumentWords,
 
     language_defs: LanguageDefinitions,
     recent_identifiers: Vec<String>,
@@ -34,6 +38,7 @@ impl Autocomplete {
             selected_index: 0,
             trigger_position: 0,
             prefix: String::new(),
+            document_words: DocumentWords::default(),
             language_defs: LanguageDefinitions::new(),
             recent_identifiers: Vec::new(),
             max_recent: 100,
@@ -126,18 +131,13 @@ impl Autocomplete {
     }
 
     /// Detect the language from a file extension string (e.g. "rs" -> "rust")
-    pub fn detect_language(ext: &str) -> Option<String> {
-        match ext {
-            "rs" => Some("rust".to_string()),
-            "js" | "mjs" | "cjs" => Some("javascript".to_string()),
-            "ts" => Some("typescript".to_string()),
-            "tsx" | "jsx" => Some("typescript".to_string()),
-            "py" => Some("python".to_string()),
-            _ => None,
-        }
-    }
-
-    pub fn trigger(&mut self, text: &str, cursor_pos: usize, language: Option<&str>) {
+    pub fn trigger(
+        &mut self,
+        text: &str,
+        cursor_pos: usize,
+        language: Option<&str>,
+        current_path: Option<&Path>,
+    ) {
         let (prefix, start_pos) = Self::get_current_word(text, cursor_pos);
 
         if prefix.is_empty() {
@@ -156,7 +156,16 @@ impl Autocomplete {
         }
 
         self.add_type_suggestions(&prefix, language, &context, &mut all_suggestions);
-        self.add_identifier_suggestions(text, &prefix, &context, &mut all_suggestions);
+        let distances = buffer_words::line_distances(text, cursor_pos);
+        self.add_identifier_suggestions(text, &prefix, &context, &distances, &mut all_suggestions);
+        self.add_document_word_suggestions(
+            text,
+            &prefix,
+            &context,
+            &distances,
+            current_path,
+            &mut all_suggestions,
+        );
 
         all_suggestions.sort_by(|a, b| {
             b.score
@@ -230,6 +239,7 @@ impl Autocomplete {
         text: &str,
         prefix: &str,
         context: &CompletionContext,
+        distances: &HashMap<String, usize>,
         suggestions: &mut Vec<Suggestion>,
     ) {
         for identifier in &self.recent_identifiers.clone() {
@@ -250,12 +260,44 @@ impl Autocomplete {
             if score > 0.0 && identifier != prefix {
                 let kind = self.infer_identifier_kind(text, &identifier, context);
                 let adjusted_score = FuzzyScorer::apply_context_boost(score, &kind, context);
+                let adjusted_score = FuzzyScorer::apply_proximity_boost(
+                    adjusted_score,
+                    distances.get(&identifier).copied(),
+                );
 
                 suggestions.push(Suggestion::with_score(identifier, kind, adjusted_score));
             }
         }
     }
 
+    /// Offers identifiers found in other open buffers, ranked below words
+    /// from the current buffer.
+    fn add_document_word_suggestions(
+        &self,
+        text: &str,
+        prefix: &str,
+        context: &CompletionContext,
+        distances: &HashMap<String, usize>,
+        current_path: Option<&Path>,
+        suggestions: &mut Vec<Suggestion>,
+    ) {
+        for word in self.document_words.words_except(current_path) {
+            if word == prefix || distances.contains_key(word) {
+                continue;
+            }
+            let score = FuzzyScorer::score(word, prefix);
+            if score > 0.0 {
+                let kind = self.infer_identifier_kind(text, word, context);
+                let adjusted_score = FuzzyScorer::apply_context_boost(score, &kind, context);
+                suggestions.push(Suggestion::with_score(
+                    word.to_string(),
+                    kind,
+                    adjusted_score - 50.0,
+                ));
+            }
+        }
+    }
+
     pub fn select_next(&mut self) {
         if !self.suggestions.is_empty() {
             self.selected_index = (self.selected_index + 1) % self.suggestions.len();
@@ -1,3 +1,4 @@
+pub mod buffer_words;
 pub mod context;
 pub mod engine;
 pub mod language;
@@ -92,6 +92,14 @@ impl FuzzyScorer {
         adjusted_score
     }
 
+    /// Favors identifiers that appear close to the cursor.
+    pub fn apply_proximity_boost(score: f32, line_distance: Option<usize>) -> f32 {
+        match line_distance {
+            Some(distance) => score + 100.0 / (1.0 + distance as f32),
+            None => score,
+        }
+    }
+
     pub fn apply_recency_boost(score: f32, is_recent: bool) -> f32 {
         if is_recent {
             score + 100.0
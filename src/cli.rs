@@ -0,0 +1,93 @@
+//! Command line arguments: files and folders to open at startup.
+//!
+//! `pinel .` opens the current folder as the workspace, `pinel a.rs b.rs`
+//! opens both files and `pinel src/main.rs:12:5` jumps to line 12,
+//! column 5. Files that don't exist yet open as new buffers.
+
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub path: PathBuf,
+    /// 1-based line and column to move to.
+    pub position: Option<(usize, usize)>,
+}
+
+/// Targets given on the command line of this process.
+pub fn targets() -> Vec<Target> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| parse_target(&arg, &cwd))
+        .collect()
+}
+
+/// Reads an argument, splitting off a `:line` or `:line:col` suffix unless
+/// a file with the full name exists.
+fn parse_target(arg: &str, cwd: &Path) -> Target {
+    let resolve = |path: &str| {
+        let path = crate::features::paths::resolve(path, Some(cwd));
+        // Tidy `.` and `..` so `pinel .` shows the folder's real name
+        std::fs::canonicalize(&path).unwrap_or(path)
+    };
+    let whole = resolve(arg);
+    if whole.exists() {
+        return Target {
+            path: whole,
+            position: None,
+        };
+    }
+
+    let mut parts = arg.rsplitn(3, ':');
+    let last = parts.next().and_then(|n| n.parse::<usize>().ok());
+    let middle = parts.next();
+    let target = match (last, middle.map(|n| n.parse::<usize>())) {
+        (Some(col), Some(Ok(line))) => parts.next().map(|path| (path, line, col)),
+        (Some(line), Some(Err(_))) => {
+            let path = arg.rsplit_once(':').map(|(path, _)| path);
+            path.map(|path| (path, line, 1))
+        }
+        _ => None,
+    };
+    match target {
+        Some((path, line, col)) if !path.is_empty() => Target {
+            path: resolve(path),
+            position: Some((line.max(1), col.max(1))),
+        },
+        _ => Target {
+            path: whole,
+            position: None,
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_line_and_column() {
+        let cwd = Path::new("/nonexistent");
+        let target = |path: &str, position| Target {
+            path: PathBuf::from(path),
+            position,
+        };
+        assert_eq!(
+            parse_target("src/main.rs:12:5", cwd),
+            target("/nonexistent/src/main.rs", Some((12, 5)))
+        );
+        assert_eq!(
+            parse_target("main.rs:7", cwd),
+            target("/nonexistent/main.rs", Some((7, 1)))
+        );
+        assert_eq!(
+            parse_target("notes:todo", cwd),
+            target("/nonexistent/notes:todo", None)
+        );
+    }
+}
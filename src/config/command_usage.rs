@@ -0,0 +1,122 @@
+//! How often and how lately each palette command was run, kept between
+//! sessions to put the commands used most at the top of the palette.
+
+use super::theme_manager::get_config_dir;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many commands are remembered, the least recently used are dropped.
+pub const MAX_COMMANDS: usize = 200;
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Usage {
+    count: u32,
+    /// Unix time of the last run, in seconds.
+    last_used: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandUsage {
+    commands: HashMap<String, Usage>,
+}
+
+impl CommandUsage {
+    /// Counts a run of `name` at `now`, in Unix seconds.
+    pub fn record(&mut self, name: &str, now: u64) {
+        let usage = self.commands.entry(name.to_string()).or_insert(Usage {
+            count: 0,
+            last_used: now,
+        });
+        usage.count = usage.count.saturating_add(1);
+        usage.last_used = now;
+
+        if self.commands.len() > MAX_COMMANDS {
+            if let Some(oldest) = self
+                .commands
+                .iter()
+                .min_by_key(|(_, usage)| usage.last_used)
+                .map(|(name, _)| name.clone())
+            {
+                self.commands.remove(&oldest);
+            }
+        }
+    }
+
+    /// Ranking of `name` at `now`: its run count, weighed down the longer
+    /// ago it last ran. Commands never run score 0.
+    pub fn score(&self, name: &str, now: u64) -> f64 {
+        self.commands.get(name).map_or(0.0, |usage| {
+            let days = now.saturating_sub(usage.last_used) as f64 / SECS_PER_DAY;
+            f64::from(usage.count) / (1.0 + days)
+        })
+    }
+}
+
+pub fn get_command_usage_path() -> PathBuf {
+    get_config_dir().join("command_usage.txt")
+}
+
+/// Loads the saved usage, one `count last_used name` line per command.
+pub fn load_command_usage() -> CommandUsage {
+    let mut usage = CommandUsage::default();
+    let Ok(content) = fs::read_to_string(get_command_usage_path()) else {
+        return usage;
+    };
+    for line in content.lines() {
+        let mut parts = line.splitn(3, ' ');
+        let (Some(count), Some(last_used), Some(name)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let (Ok(count), Ok(last_used)) = (count.parse(), last_used.parse()) else {
+            continue;
+        };
+        usage
+            .commands
+            .insert(name.to_string(), Usage { count, last_used });
+    }
+    usage
+}
+
+pub fn save_command_usage(usage: &CommandUsage) -> Result<(), std::io::Error> {
+    let path = get_command_usage_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for (name, usage) in &usage.commands {
+        content.push_str(&format!("{} {} {name}\n", usage.count, usage.last_used));
+    }
+    fs::write(path, content)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_frequent_and_recent_commands_first() {
+        let day = SECS_PER_DAY as u64;
+        let now = 100 * day;
+        let mut usage = CommandUsage::default();
+        for _ in 0..5 {
+            usage.record("Save File", now - day);
+        }
+        usage.record("Word Count", now);
+        for _ in 0..5 {
+            usage.record("Toggle Minimap", now - 60 * day);
+        }
+
+        let score = |name| usage.score(name, now);
+        assert!(score("Save File") > score("Word Count"));
+        assert!(score("Word Count") > score("Toggle Minimap"));
+        assert_eq!(score("Quit"), 0.0);
+    }
+}
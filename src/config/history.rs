@@ -0,0 +1,40 @@
+//! Command bar history, kept between sessions.
+
+use super::theme_manager::get_config_dir;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many commands are remembered.
+pub const MAX_HISTORY: usize = 100;
+
+pub fn get_history_path() -> PathBuf {
+    get_config_dir().join("command_history.txt")
+}
+
+/// Loads the saved commands, oldest first.
+pub fn load_history() -> Vec<String> {
+    let Ok(content) = fs::read_to_string(get_history_path()) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<String> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+    let excess = entries.len().saturating_sub(MAX_HISTORY);
+    entries.drain(..excess);
+    entries
+}
+
+pub fn save_history(entries: &[String]) -> Result<(), std::io::Error> {
+    let path = get_history_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    fs::write(path, content)
+}
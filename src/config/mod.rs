@@ -1,2 +1,8 @@
+pub mod command_usage;
+pub mod history;
+pub mod positions;
 pub mod preferences;
+pub mod project;
+pub mod recent;
+pub mod templates;
 pub mod theme_manager;
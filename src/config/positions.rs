@@ -0,0 +1,100 @@
+//! Where the cursor was when each file was last closed, kept between
+//! sessions so reopening a file puts the cursor back, like Vim's `'"` mark.
+
+use super::theme_manager::get_config_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How many files are remembered, the ones closed longest ago are dropped.
+pub const MAX_FILES: usize = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct LastPositions {
+    /// 1-based line and column per file, the one closed last at the end.
+    files: Vec<(PathBuf, (usize, usize))>,
+}
+
+impl LastPositions {
+    pub fn remember(&mut self, path: &Path, position: (usize, usize)) {
+        self.files.retain(|(file, _)| file != path);
+        self.files.push((path.to_path_buf(), position));
+        let excess = self.files.len().saturating_sub(MAX_FILES);
+        self.files.drain(..excess);
+    }
+
+    pub fn get(&self, path: &Path) -> Option<(usize, usize)> {
+        self.files
+            .iter()
+            .find(|(file, _)| file == path)
+            .map(|(_, position)| *position)
+    }
+}
+
+pub fn get_positions_path() -> PathBuf {
+    get_config_dir().join("positions.txt")
+}
+
+/// Loads the saved positions, one `line column path` line per file.
+pub fn load_positions() -> LastPositions {
+    fs::read_to_string(get_positions_path())
+        .map(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+fn parse(content: &str) -> LastPositions {
+    let mut positions = LastPositions::default();
+    for line in content.lines() {
+        let mut parts = line.splitn(3, ' ');
+        let (Some(row), Some(column), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let (Ok(row), Ok(column)) = (row.parse(), column.parse()) else {
+            continue;
+        };
+        positions.remember(Path::new(path), (row, column));
+    }
+    positions
+}
+
+pub fn save_positions(positions: &LastPositions) -> Result<(), std::io::Error> {
+    let path = get_positions_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for (file, (line, column)) in &positions.files {
+        content.push_str(&format!("{line} {column} {}\n", file.display()));
+    }
+    fs::write(path, content)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_latest_position_per_file() {
+        let mut positions = parse("12 5 /src/main.rs\nbad line\n3 1 /notes with spaces.md\n");
+        assert_eq!(positions.get(Path::new("/src/main.rs")), Some((12, 5)));
+        assert_eq!(
+            positions.get(Path::new("/notes with spaces.md")),
+            Some((3, 1))
+        );
+
+        positions.remember(Path::new("/src/main.rs"), (40, 2));
+        assert_eq!(positions.get(Path::new("/src/main.rs")), Some((40, 2)));
+        assert_eq!(positions.files.len(), 2);
+        assert_eq!(positions.files[1].0, Path::new("/src/main.rs"));
+
+        for idx in 0..MAX_FILES {
+            positions.remember(Path::new(&format!("/{idx}")), (1, 1));
+        }
+        assert_eq!(positions.files.len(), MAX_FILES);
+        assert_eq!(positions.get(Path::new("/src/main.rs")), None);
+    }
+}
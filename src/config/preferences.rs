@@ -1,4 +1,8 @@
 use super::theme_manager::{get_config_dir, load_theme, ThemeColors};
+use crate::features::file_io::Backup;
+use crate::features::indentation::IndentStyle;
+use crate::features::smooth_scroll::Easing;
+use crate::features::whitespace::RenderWhitespace;
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
@@ -7,6 +11,8 @@ use std::path::PathBuf;
 pub struct EditorPreferences {
     pub tab_size: usize,
     pub use_spaces: bool,
+    /// Follow the indentation already used by a file instead of the settings above.
+    pub detect_indentation: bool,
     pub theme_name: String,
     pub window_width: f32,
     pub window_height: f32,
@@ -14,6 +20,56 @@ pub struct EditorPreferences {
     pub line_number_width: f32,
     /// Enable developer mode with debug logging
     pub developer_mode: bool,
+    /// Minimum number of lines kept visible above and below the cursor.
+    pub scrolloff: usize,
+    /// Comma separated status line segments, see `features::status_line`.
+    pub status_segments: String,
+    /// Files larger than this many megabytes ask before opening (0 disables).
+    pub large_file_threshold_mb: u64,
+    /// Pastes with more lines than this ask for confirmation (0 disables).
+    pub large_paste_lines: usize,
+    /// Check for a newer release when the editor starts.
+    pub check_for_updates: bool,
+    /// Insert the closing bracket or quote when an opening one is typed.
+    pub auto_close_brackets: bool,
+    /// Comma separated language names that never auto-close pairs.
+    pub auto_close_exclude: String,
+    /// Soft line length limits, see `features::line_length` (empty disables).
+    pub line_length_limits: String,
+    /// Wrap long lines at the edge of the editor instead of scrolling.
+    pub word_wrap: bool,
+    /// Remove trailing spaces and tabs when saving.
+    pub trim_trailing_whitespace: bool,
+    /// End files with a newline when saving.
+    pub insert_final_newline: bool,
+    /// Run the language's formatter when saving.
+    pub format_on_save: bool,
+    /// Formatter commands per language, see `features::formatter`.
+    pub formatters: String,
+    /// Copies of the previous contents kept when saving.
+    pub backups: Backup,
+    /// Check the spelling of comments, strings and prose.
+    pub spell_check: bool,
+    /// Font family of the editor text.
+    pub font_family: String,
+    /// Font size of the editor text in points.
+    pub font_size: f32,
+    /// Spaces, tabs and line ends drawn in the editor.
+    pub render_whitespace: RenderWhitespace,
+    /// Pin the headers of the blocks enclosing the top line while scrolling.
+    pub sticky_scroll: bool,
+    /// Milliseconds jumps out of view take to scroll there (0 jumps at once).
+    pub smooth_scroll_ms: u64,
+    /// How the speed of a smooth scroll changes along the way.
+    pub scroll_easing: Easing,
+    /// Milliseconds typing must pause before completions and find-as-you-type
+    /// queries run (0 runs them on every keystroke).
+    pub typing_debounce_ms: u64,
+    /// Comma separated globs left out of the file tree, finder and search,
+    /// see `features::exclusions`.
+    pub exclude: String,
+    /// List dotfiles and excluded or git ignored files too.
+    pub show_hidden_files: bool,
 }
 
 impl Default for EditorPreferences {
@@ -21,22 +77,62 @@ impl Default for EditorPreferences {
         Self {
             tab_size: 4,
             use_spaces: true,
+            detect_indentation: true,
             theme_name: "Pinel Blueberry Dark".to_string(),
             window_width: 1200.0,
             window_height: 800.0,
             line_number_width: 40.0,
             developer_mode: false,
+            scrolloff: 3,
+            status_segments: crate::features::status_line::DEFAULT_SEGMENTS.to_string(),
+            large_file_threshold_mb: 10,
+            large_paste_lines: 5000,
+            check_for_updates: true,
+            auto_close_brackets: true,
+            auto_close_exclude: "plain text".to_string(),
+            line_length_limits: String::new(),
+            word_wrap: false,
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
+            format_on_save: false,
+            formatters: crate::features::formatter::DEFAULT_FORMATTERS.to_string(),
+            backups: Backup::Off,
+            spell_check: false,
+            font_family: crate::features::fonts::DEFAULT_FAMILY.to_string(),
+            font_size: crate::features::fonts::DEFAULT_SIZE,
+            render_whitespace: RenderWhitespace::Off,
+            sticky_scroll: true,
+            smooth_scroll_ms: 0,
+            scroll_easing: Easing::EaseOut,
+            typing_debounce_ms: 120,
+            exclude: crate::features::exclusions::DEFAULT_EXCLUDE.to_string(),
+            show_hidden_files: false,
         }
     }
 }
 
 impl EditorPreferences {
-    pub fn indent_unit(&self) -> String {
-        if self.use_spaces {
-            " ".repeat(self.tab_size)
+    /// Indentation style for a buffer, following the buffer's own style when
+    /// `detect_indentation` is on.
+    pub fn indent_style_for<I, S>(&self, lines: I) -> IndentStyle
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let detected = if self.detect_indentation {
+            crate::features::indentation::detect(lines)
         } else {
-            "\t".to_string()
-        }
+            None
+        };
+        detected.unwrap_or(if self.use_spaces {
+            IndentStyle::Spaces(self.tab_size)
+        } else {
+            IndentStyle::Tabs
+        })
+    }
+
+    pub fn indent_unit_for(&self, content: &str) -> String {
+        self.indent_style_for(content.lines()).unit()
     }
 }
 
@@ -78,13 +174,8 @@ pub fn load_preferences() -> EditorPreferences {
 }
 
 fn legacy_preferences_path() -> Option<PathBuf> {
-    let home = std::env::var("HOME").ok()?;
-    Some(
-        PathBuf::from(home)
-            .join(".config")
-            .join("pinel")
-            .join("preferences.lua"),
-    )
+    let home = crate::features::paths::home_dir()?;
+    Some(home.join(".config").join("pinel").join("preferences.lua"))
 }
 
 fn parse_preferences(content: &str) -> EditorPreferences {
@@ -110,6 +201,9 @@ fn parse_preferences(content: &str) -> EditorPreferences {
                 "use_spaces" => {
                     prefs.use_spaces = value == "true";
                 }
+                "detect_indentation" => {
+                    prefs.detect_indentation = value == "true";
+                }
                 "theme_name" => {
                     prefs.theme_name = value.to_string();
                 }
@@ -131,6 +225,90 @@ fn parse_preferences(content: &str) -> EditorPreferences {
                 "developer_mode" => {
                     prefs.developer_mode = value == "true";
                 }
+                "scrolloff" => {
+                    if let Ok(lines) = value.parse::<usize>() {
+                        prefs.scrolloff = lines.min(999);
+                    }
+                }
+                "status_segments" => {
+                    prefs.status_segments = value.to_string();
+                }
+                "large_file_threshold_mb" => {
+                    if let Ok(mb) = value.parse::<u64>() {
+                        prefs.large_file_threshold_mb = mb;
+                    }
+                }
+                "typing_debounce_ms" => {
+                    if let Ok(ms) = value.parse::<u64>() {
+                        prefs.typing_debounce_ms = ms.min(2000);
+                    }
+                }
+                "large_paste_lines" => {
+                    if let Ok(lines) = value.parse::<usize>() {
+                        prefs.large_paste_lines = lines;
+                    }
+                }
+                "check_for_updates" => {
+                    prefs.check_for_updates = value == "true";
+                }
+                "auto_close_brackets" => {
+                    prefs.auto_close_brackets = value == "true";
+                }
+                "auto_close_exclude" => {
+                    prefs.auto_close_exclude = value.to_string();
+                }
+                "line_length_limits" => {
+                    prefs.line_length_limits = value.to_string();
+                }
+                "word_wrap" => {
+                    prefs.word_wrap = value == "true";
+                }
+                "trim_trailing_whitespace" => {
+                    prefs.trim_trailing_whitespace = value == "true";
+                }
+                "insert_final_newline" => {
+                    prefs.insert_final_newline = value == "true";
+                }
+                "format_on_save" => {
+                    prefs.format_on_save = value == "true";
+                }
+                "formatters" => {
+                    prefs.formatters = value.to_string();
+                }
+                "backups" => {
+                    prefs.backups = Backup::parse(value);
+                }
+                "spell_check" => {
+                    prefs.spell_check = value == "true";
+                }
+                "font_family" if !value.is_empty() => {
+                    prefs.font_family = value.to_string();
+                }
+                "font_size" => {
+                    if let Ok(size) = value.parse::<f32>() {
+                        prefs.font_size = crate::features::fonts::clamp_size(size);
+                    }
+                }
+                "render_whitespace" => {
+                    prefs.render_whitespace = RenderWhitespace::parse(value);
+                }
+                "smooth_scroll_ms" => {
+                    if let Ok(ms) = value.parse::<u64>() {
+                        prefs.smooth_scroll_ms = ms.min(1000);
+                    }
+                }
+                "scroll_easing" => {
+                    prefs.scroll_easing = Easing::parse(value);
+                }
+                "sticky_scroll" => {
+                    prefs.sticky_scroll = value == "true";
+                }
+                "exclude" => {
+                    prefs.exclude = value.to_string();
+                }
+                "show_hidden_files" => {
+                    prefs.show_hidden_files = value == "true";
+                }
                 _ => {}
             }
         }
@@ -207,6 +385,8 @@ fn save_preferences_to_path(
 return {{
     tab_size = {},
     use_spaces = {},
+    -- Follow the indentation already used by each file
+    detect_indentation = {},
     theme_name = "{}",
     window_width = {},
     window_height = {},
@@ -214,15 +394,88 @@ return {{
     line_number_width = {},
     -- Enable developer mode with debug logging (WARNING: Logs may contain sensitive data)
     developer_mode = {},
+    -- Lines of context kept above and below the cursor while scrolling
+    scrolloff = {},
+    -- Status line segments: branch, language, indent, line_ending, encoding, size, words
+    status_segments = "{}",
+    -- Ask before opening files over this size in MB (0 disables)
+    large_file_threshold_mb = {},
+    -- Ask before pasting more than this many lines (0 disables)
+    large_paste_lines = {},
+    -- Check for a newer release on startup
+    check_for_updates = {},
+    -- Insert closing brackets and quotes while typing
+    auto_close_brackets = {},
+    -- Languages that never auto-close, e.g. "plain text, markdown"
+    auto_close_exclude = "{}",
+    -- Flag lines longer than this, e.g. "100, python:88" (empty disables)
+    line_length_limits = "{}",
+    -- Wrap long lines at the edge of the editor
+    word_wrap = {},
+    -- Remove trailing spaces and tabs when saving
+    trim_trailing_whitespace = {},
+    -- End files with a newline when saving
+    insert_final_newline = {},
+    -- Run the formatter below when saving
+    format_on_save = {},
+    -- Formatter per language, the buffer is piped through it
+    formatters = "{}",
+    -- Keep the previous contents when saving: "off", "tilde" (file~) or "numbered" (file.~1~)
+    backups = "{}",
+    -- Check the spelling of comments, strings and prose
+    spell_check = {},
+    -- Editor font, any monospaced family installed or in the fonts folder
+    font_family = "{}",
+    -- Editor font size in points (6–48), changed by zooming too
+    font_size = {},
+    -- Draw whitespace: "off", "all" or "trailing" (spaces and tabs at line ends)
+    render_whitespace = "{}",
+    -- Pin the headers of enclosing functions and blocks at the top of the editor
+    sticky_scroll = {},
+    -- Scroll to jumps out of view over this many milliseconds (0–1000, 0 jumps at once)
+    smooth_scroll_ms = {},
+    -- Speed along a smooth scroll: "linear", "ease_out" or "ease_in_out"
+    scroll_easing = "{}",
+    -- Wait this many milliseconds after typing before completing and searching (0–2000)
+    typing_debounce_ms = {},
+    -- Left out of the file tree, finder and search, gitignore globs like "vendor/, *.min.js"
+    exclude = "{}",
+    -- List dotfiles and excluded or git ignored files in the file tree, finder and search
+    show_hidden_files = {},
 }}
 "#,
         prefs.tab_size,
         prefs.use_spaces,
+        prefs.detect_indentation,
         prefs.theme_name,
         prefs.window_width,
         prefs.window_height,
         prefs.line_number_width,
         prefs.developer_mode,
+        prefs.scrolloff,
+        prefs.status_segments,
+        prefs.large_file_threshold_mb,
+        prefs.large_paste_lines,
+        prefs.check_for_updates,
+        prefs.auto_close_brackets,
+        prefs.auto_close_exclude,
+        prefs.line_length_limits,
+        prefs.word_wrap,
+        prefs.trim_trailing_whitespace,
+        prefs.insert_final_newline,
+        prefs.format_on_save,
+        prefs.formatters,
+        prefs.backups.key(),
+        prefs.spell_check,
+        prefs.font_family,
+        prefs.font_size,
+        prefs.render_whitespace.key(),
+        prefs.sticky_scroll,
+        prefs.smooth_scroll_ms,
+        prefs.scroll_easing.key(),
+        prefs.typing_debounce_ms,
+        prefs.exclude,
+        prefs.show_hidden_files,
     );
     let mut file = fs::File::create(path)?;
     file.write_all(content.as_bytes())?;
@@ -0,0 +1,115 @@
+//! Settings of the open folder, read from `.pinel/config.toml`. They
+//! win over the user's own settings while that folder is open:
+//!
+//! ```toml
+//! tab_size = 2
+//! use_spaces = true
+//! exclude = ["vendor/", "*.snap"]
+//!
+//! [formatters]
+//! rust = "rustfmt --edition 2024"
+//! ```
+
+use super::preferences::EditorPreferences;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectSettings {
+    pub tab_size: Option<usize>,
+    pub use_spaces: Option<bool>,
+    /// Formatter commands by language name, used before the user's.
+    pub formatters: BTreeMap<String, String>,
+    /// Globs left out of the file tree, finder and search, added to the
+    /// `exclude` preference.
+    pub exclude: Vec<String>,
+}
+
+impl ProjectSettings {
+    /// Overrides `prefs` with the settings the project sets.
+    pub fn apply(&self, prefs: &mut EditorPreferences) {
+        if let Some(tab_size) = self.tab_size {
+            prefs.tab_size = tab_size.clamp(1, 16);
+        }
+        if let Some(use_spaces) = self.use_spaces {
+            prefs.use_spaces = use_spaces;
+        }
+        if !self.formatters.is_empty() {
+            // The first entry for a language is the one used
+            let project: Vec<String> = self
+                .formatters
+                .iter()
+                .map(|(language, command)| format!("{language}: {command}"))
+                .collect();
+            prefs.formatters = format!("{}; {}", project.join("; "), prefs.formatters);
+        }
+    }
+
+    /// Puts back the `user` values of the settings the project overrides,
+    /// so saving `prefs` does not store the project's ones.
+    pub fn restore(&self, prefs: &mut EditorPreferences, user: &EditorPreferences) {
+        if self.tab_size.is_some() {
+            prefs.tab_size = user.tab_size;
+        }
+        if self.use_spaces.is_some() {
+            prefs.use_spaces = user.use_spaces;
+        }
+        if !self.formatters.is_empty() {
+            prefs.formatters = user.formatters.clone();
+        }
+    }
+}
+
+pub fn get_project_settings_path(root: &Path) -> PathBuf {
+    root.join(".pinel").join("config.toml")
+}
+
+/// Loads the settings of the folder at `root`. A folder without the file
+/// has no settings, a file that does not parse is an error.
+pub fn load_project_settings(root: &Path) -> Result<ProjectSettings, String> {
+    let Ok(content) = fs::read_to_string(get_project_settings_path(root)) else {
+        return Ok(ProjectSettings::default());
+    };
+    toml::from_str(&content).map_err(|err| err.message().to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_and_restores_user_settings() {
+        let project: ProjectSettings = toml::from_str(
+            "tab_size = 2\nexclude = [\"vendor\"]\n[formatters]\nrust = \"rustfmt --edition 2024\"\n",
+        )
+        .unwrap();
+        assert_eq!(project.exclude, ["vendor"]);
+
+        let user = EditorPreferences::default();
+        let mut prefs = user.clone();
+        project.apply(&mut prefs);
+        assert_eq!(prefs.tab_size, 2);
+        assert_eq!(prefs.use_spaces, user.use_spaces);
+        assert_eq!(
+            crate::features::formatter::command_for(
+                &prefs.formatters,
+                crate::language::Language::Rust
+            )
+            .as_deref(),
+            Some("rustfmt --edition 2024")
+        );
+
+        project.restore(&mut prefs, &user);
+        assert_eq!(prefs.tab_size, user.tab_size);
+        assert_eq!(prefs.formatters, user.formatters);
+
+        assert!(toml::from_str::<ProjectSettings>("tab_width = 2").is_err());
+    }
+}
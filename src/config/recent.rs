@@ -0,0 +1,64 @@
+//! Recently opened folders and files, kept between sessions for the
+//! welcome screen and the file finder.
+
+use super::theme_manager::get_config_dir;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many folders and files are remembered.
+pub const MAX_RECENT: usize = 20;
+
+#[derive(Debug, Clone, Default)]
+pub struct RecentItems {
+    pub folders: Vec<PathBuf>,
+    pub files: Vec<PathBuf>,
+}
+
+pub fn get_recent_path() -> PathBuf {
+    get_config_dir().join("recent.txt")
+}
+
+/// Loads the saved lists, dropping entries that no longer exist.
+pub fn load_recent() -> RecentItems {
+    let mut items = RecentItems::default();
+    let Ok(content) = fs::read_to_string(get_recent_path()) else {
+        return items;
+    };
+
+    for line in content.lines() {
+        let (list, path) = if let Some(path) = line.strip_prefix("folder ") {
+            (&mut items.folders, PathBuf::from(path))
+        } else if let Some(path) = line.strip_prefix("file ") {
+            (&mut items.files, PathBuf::from(path))
+        } else {
+            continue;
+        };
+        if path.exists() && !list.contains(&path) && list.len() < MAX_RECENT {
+            list.push(path);
+        }
+    }
+    items
+}
+
+pub fn save_recent(folders: &[PathBuf], files: &[PathBuf]) -> Result<(), std::io::Error> {
+    let path = get_recent_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut content = String::new();
+    for folder in folders {
+        content.push_str(&format!("folder {}\n", folder.display()));
+    }
+    for file in files {
+        content.push_str(&format!("file {}\n", file.display()));
+    }
+    fs::write(path, content)
+}
+
+/// Moves `path` to the front of `list`, keeping at most [`MAX_RECENT`] entries.
+pub fn push_recent(list: &mut Vec<PathBuf>, path: PathBuf) {
+    list.retain(|p| p != &path);
+    list.insert(0, path);
+    list.truncate(MAX_RECENT);
+}
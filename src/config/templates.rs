@@ -0,0 +1,83 @@
+//! Starting content for newly created files.
+//!
+//! A file named `template.<ext>` in the templates directory is copied into
+//! every new file with that extension. Templates may use these variables:
+//!
+//! - `{{file}}`: file name, e.g. `main.rs`
+//! - `{{name}}`: file name without its extension, e.g. `main`
+//! - `{{date}}`: today's date as `YYYY-MM-DD`
+//! - `{{year}}`: the current year
+
+use super::theme_manager::get_config_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn get_templates_dir() -> PathBuf {
+    get_config_dir().join("templates")
+}
+
+/// Rendered template for a new file at `path`, if one exists for its
+/// extension.
+pub fn template_for(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?;
+    let template = fs::read_to_string(get_templates_dir().join(format!("template.{ext}"))).ok()?;
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400);
+    Some(render(&template, path, civil_date(days)))
+}
+
+fn render(template: &str, path: &Path, (year, month, day): (u64, u64, u64)) -> String {
+    let file = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    template
+        .replace("{{file}}", &file)
+        .replace("{{name}}", &name)
+        .replace("{{date}}", &format!("{year:04}-{month:02}-{day:02}"))
+        .replace("{{year}}", &year.to_string())
+}
+
+/// Converts days since 1970-01-01 into a (year, month, day) date in UTC.
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    // Howard Hinnant's days-to-civil algorithm, with eras of 400 years
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(19_782), (2024, 2, 29));
+        assert_eq!(civil_date(20_742), (2026, 10, 16));
+    }
+
+    #[test]
+    fn substitutes_variables() {
+        let template = "// {{file}} ({{name}}), {{date}}\n// (c) {{year}}\n";
+        let out = render(template, Path::new("src/parser.rs"), (2026, 3, 7));
+        assert_eq!(out, "// parser.rs (parser), 2026-03-07\n// (c) 2026\n");
+    }
+}
@@ -184,8 +184,8 @@ return {{
 }
 
 pub fn get_config_dir() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home).join(".config").join("pinel")
+    let home = crate::features::paths::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home.join(".config").join("pinel")
 }
 
 pub fn get_theme_path() -> PathBuf {
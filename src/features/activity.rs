@@ -0,0 +1,192 @@
+//! Local time tracking, independent of WakaTime.
+//!
+//! Time between two edits counts towards the file and language being
+//! edited, unless the gap is long enough to mean the user stepped away.
+//! Totals are kept per day, counted in UTC, in `activity.txt` in the
+//! config folder.
+
+use crate::config::theme_manager::get_config_dir;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Gaps between edits longer than this are not counted.
+pub const IDLE_SECS: u64 = 120;
+/// How many days of totals are kept.
+const MAX_DAYS: usize = 366;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Seconds spent on each file and language in one day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Day {
+    pub files: BTreeMap<String, u64>,
+    pub languages: BTreeMap<String, u64>,
+}
+
+impl Day {
+    pub fn total(&self) -> u64 {
+        self.languages.values().sum()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Activity {
+    /// Totals keyed by days since the Unix epoch.
+    days: BTreeMap<u64, Day>,
+    /// Unix time of the last recorded edit.
+    last_edit: Option<u64>,
+    dirty: bool,
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn day_of(secs: u64) -> u64 {
+    secs / SECS_PER_DAY
+}
+
+impl Activity {
+    /// Records an edit to `file` at Unix time `at`, crediting the time
+    /// since the previous edit.
+    pub fn record(&mut self, file: &str, language: &str, at: u64) {
+        if let Some(last) = self.last_edit {
+            let elapsed = at.saturating_sub(last);
+            if elapsed > 0 && elapsed <= IDLE_SECS {
+                let day = self.days.entry(day_of(at)).or_default();
+                *day.files.entry(file.to_string()).or_default() += elapsed;
+                *day.languages.entry(language.to_string()).or_default() += elapsed;
+                self.dirty = true;
+            }
+        }
+        self.last_edit = Some(at);
+    }
+
+    pub fn day(&self, day: u64) -> Option<&Day> {
+        self.days.get(&day)
+    }
+
+    /// Number of consecutive active days ending with `today`, or with
+    /// yesterday while nothing was done today yet.
+    pub fn streak(&self, today: u64) -> usize {
+        let mut day = if self.days.contains_key(&today) {
+            today
+        } else {
+            today.saturating_sub(1)
+        };
+        let mut streak = 0;
+        while self.days.contains_key(&day) {
+            streak += 1;
+            if day == 0 {
+                break;
+            }
+            day -= 1;
+        }
+        streak
+    }
+
+    /// Writes the totals if anything was recorded since the last save.
+    pub fn save_if_dirty(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        if save(&self.days).is_ok() {
+            self.dirty = false;
+        }
+    }
+}
+
+pub fn get_activity_path() -> PathBuf {
+    get_config_dir().join("activity.txt")
+}
+
+/// Loads saved totals, one `day<TAB>file|language<TAB>name<TAB>secs`
+/// entry per line.
+pub fn load() -> Activity {
+    let mut activity = Activity::default();
+    let Ok(content) = fs::read_to_string(get_activity_path()) else {
+        return activity;
+    };
+    for line in content.lines() {
+        let mut fields = line.splitn(4, '\t');
+        let (Some(day), Some(kind), Some(name), Some(secs)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let (Ok(day), Ok(secs)) = (day.parse::<u64>(), secs.parse::<u64>()) else {
+            continue;
+        };
+        let entry = activity.days.entry(day).or_default();
+        let totals = match kind {
+            "file" => &mut entry.files,
+            "language" => &mut entry.languages,
+            _ => continue,
+        };
+        totals.insert(name.to_string(), secs);
+    }
+    activity
+}
+
+fn save(days: &BTreeMap<u64, Day>) -> Result<(), std::io::Error> {
+    let path = get_activity_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = String::new();
+    for (day, totals) in days.iter().rev().take(MAX_DAYS) {
+        for (kind, map) in [("file", &totals.files), ("language", &totals.languages)] {
+            for (name, secs) in map {
+                content.push_str(&format!("{day}\t{kind}\t{name}\t{secs}\n"));
+            }
+        }
+    }
+    fs::write(path, content)
+}
+
+/// Short duration such as `1h 05m` or `12m`.
+pub fn format_duration(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credits_short_gaps_and_counts_streaks() {
+        let start = 10 * SECS_PER_DAY;
+        let mut activity = Activity::default();
+        activity.record("a.rs", "Rust", start);
+        activity.record("a.rs", "Rust", start + 30);
+        activity.record("b.py", "Python", start + 40);
+        // Away for an hour
+        activity.record("b.py", "Python", start + 3640);
+
+        let day = activity.day(10).unwrap();
+        assert_eq!(day.files["a.rs"], 30);
+        assert_eq!(day.files["b.py"], 10);
+        assert_eq!(day.total(), 40);
+
+        activity.record("a.rs", "Rust", start - SECS_PER_DAY);
+        activity.record("a.rs", "Rust", start - SECS_PER_DAY + 5);
+        assert_eq!(activity.streak(10), 2);
+        assert_eq!(activity.streak(11), 2);
+        assert_eq!(activity.streak(12), 0);
+        assert_eq!(format_duration(3900), "1h 05m");
+    }
+}
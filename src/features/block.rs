@@ -0,0 +1,47 @@
+//! Rectangular (column) edits over a range of lines.
+
+/// Inserts `text` at character column `col` of every line. Lines shorter
+/// than `col` are padded with spaces when `pad` is set and skipped
+/// otherwise.
+pub fn insert_column(lines: &[&str], col: usize, text: &str, pad: bool) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            let len = line.chars().count();
+            if len < col {
+                return if pad {
+                    format!("{line}{}{text}", " ".repeat(col - len))
+                } else {
+                    line.to_string()
+                };
+            }
+            let split = line
+                .char_indices()
+                .nth(col)
+                .map_or(line.len(), |(idx, _)| idx);
+            format!("{}{text}{}", &line[..split], &line[split..])
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_at_column_and_pads_only_when_asked() {
+        let lines = ["let a = 1;", "b", "let cc = 2;"];
+        assert_eq!(
+            insert_column(&lines, 4, "mut ", false),
+            ["let mut a = 1;", "b", "let mut cc = 2;"]
+        );
+        assert_eq!(
+            insert_column(&lines, 3, "|", true),
+            ["let| a = 1;", "b  |", "let| cc = 2;"]
+        );
+    }
+}
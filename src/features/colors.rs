@@ -0,0 +1,286 @@
+//! Color literals like `#1e1e2e` and `rgb(30, 30, 46)`, shown with a
+//! swatch under them that opens a color picker.
+
+use crate::features::editor_buffer::EditorBuffer;
+use crate::message::Message;
+use crate::ui::TextLayout;
+use iced::widget::canvas;
+use iced::{mouse, Color, Point, Rectangle, Renderer, Size, Theme};
+
+/// Height of the swatch drawn under a literal.
+const SWATCH_HEIGHT: f32 = 3.0;
+/// Extra height above the swatch that still counts as clicking it.
+const SWATCH_HIT_SLOP: f32 = 4.0;
+
+/// How a color is written, kept when the literal is rewritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `#rgb`, `#rrggbb` or `#rrggbbaa`.
+    Hex {
+        digits: usize,
+        upper: bool,
+    },
+    Rgb,
+    Rgba,
+}
+
+/// A color literal in a line, by character column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Literal {
+    pub start: usize,
+    pub end: usize,
+    pub color: Color,
+    pub format: Format,
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn hex_at(line: &str, at: usize) -> Option<(usize, Color, Format)> {
+    let bytes = line.as_bytes();
+    if at > 0 && (is_word_byte(bytes[at - 1]) || bytes[at - 1] == b'&') {
+        return None;
+    }
+    let digits = bytes[at + 1..]
+        .iter()
+        .take_while(|b| b.is_ascii_hexdigit())
+        .count();
+    if !matches!(digits, 3 | 6 | 8) || bytes.get(at + 1 + digits).is_some_and(|b| is_word_byte(*b))
+    {
+        return None;
+    }
+    let hex = &line[at + 1..at + 1 + digits];
+    let channel = |idx: usize| -> f32 {
+        let value = if digits == 3 {
+            let digit = u8::from_str_radix(&hex[idx..idx + 1], 16).unwrap_or(0);
+            digit * 17
+        } else {
+            u8::from_str_radix(&hex[idx * 2..idx * 2 + 2], 16).unwrap_or(0)
+        };
+        f32::from(value) / 255.0
+    };
+    let alpha = if digits == 8 { channel(3) } else { 1.0 };
+    let upper = hex.chars().any(|c| c.is_ascii_uppercase());
+    Some((
+        at + 1 + digits,
+        Color::from_rgba(channel(0), channel(1), channel(2), alpha),
+        Format::Hex { digits, upper },
+    ))
+}
+
+fn rgb_at(line: &str, at: usize) -> Option<(usize, Color, Format)> {
+    let rest = &line[at..];
+    let (format, args) = if let Some(args) = rest.strip_prefix("rgba(") {
+        (Format::Rgba, args)
+    } else {
+        (Format::Rgb, rest.strip_prefix("rgb(")?)
+    };
+    if at > 0 && is_word_byte(line.as_bytes()[at - 1]) {
+        return None;
+    }
+    let close = args.find(')')?;
+    let values: Vec<f32> = args[..close]
+        .split([',', ' ', '/'])
+        .filter(|part| !part.is_empty())
+        .map(|part| part.trim().parse::<f32>().ok())
+        .collect::<Option<_>>()?;
+    let color = match values[..] {
+        [r, g, b] => Color::from_rgb8(r as u8, g as u8, b as u8),
+        [r, g, b, a] => Color::from_rgba8(r as u8, g as u8, b as u8, a.clamp(0.0, 1.0)),
+        _ => return None,
+    };
+    let end = line.len() - args.len() + close + 1;
+    Some((end, color, format))
+}
+
+/// Color literals in `line`.
+pub fn find(line: &str) -> Vec<Literal> {
+    let mut literals = Vec::new();
+    let mut idx = 0;
+    while idx < line.len() {
+        let found = match line.as_bytes()[idx] {
+            b'#' => hex_at(line, idx),
+            b'r' => rgb_at(line, idx),
+            _ => None,
+        };
+        if let Some((end, color, format)) = found {
+            literals.push(Literal {
+                start: line[..idx].chars().count(),
+                end: line[..end].chars().count(),
+                color,
+                format,
+            });
+            idx = end;
+        } else {
+            idx += 1;
+            while !line.is_char_boundary(idx) {
+                idx += 1;
+            }
+        }
+    }
+    literals
+}
+
+/// The literal at column `col` of `line`, including its end.
+pub fn at(line: &str, col: usize) -> Option<Literal> {
+    find(line)
+        .into_iter()
+        .find(|literal| (literal.start..=literal.end).contains(&col))
+}
+
+/// `color` written as `format`. Hex colors get an alpha pair and `rgb()`
+/// becomes `rgba()` when the color is translucent.
+pub fn write(color: Color, format: Format) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    let translucent = a < 255;
+    match format {
+        Format::Hex { digits, upper } => {
+            let hex = if digits == 8 || translucent {
+                format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+            } else {
+                format!("#{r:02x}{g:02x}{b:02x}")
+            };
+            if upper {
+                hex.to_uppercase()
+            } else {
+                hex
+            }
+        }
+        Format::Rgb if !translucent => format!("rgb({r}, {g}, {b})"),
+        Format::Rgb | Format::Rgba => {
+            let alpha = format!("{:.2}", color.a);
+            let alpha = alpha.trim_end_matches('0').trim_end_matches('.');
+            format!("rgba({r}, {g}, {b}, {alpha})")
+        }
+    }
+}
+
+/// A literal whose color is being picked.
+#[derive(Debug, Clone)]
+pub struct Picking {
+    pub path: std::path::PathBuf,
+    /// 0-based line of the literal.
+    pub line: usize,
+    pub literal: Literal,
+}
+
+/// Swatches under the color literals on screen. Clicking one sends
+/// `Message::PickColor` with its line and column.
+pub struct Swatches {
+    swatches: Vec<(Rectangle, usize, usize, Color)>,
+}
+
+impl Swatches {
+    pub fn new(layout: Option<&TextLayout>, buffer: &EditorBuffer) -> Self {
+        let mut swatches = Vec::new();
+        if let Some(layout) = layout {
+            for idx in layout.visible_lines(buffer.line_count()) {
+                let line = buffer.line(idx);
+                let text = line.trim_end_matches(['\n', '\r']);
+                for literal in find(text) {
+                    let Some(point) = layout.point(idx, text, literal.start) else {
+                        continue;
+                    };
+                    let written: String = text
+                        .chars()
+                        .skip(literal.start)
+                        .take(literal.end - literal.start)
+                        .collect();
+                    let rect = Rectangle::new(
+                        Point::new(point.x, point.y + layout.line_height() - SWATCH_HEIGHT),
+                        Size::new(layout.width(&written), SWATCH_HEIGHT),
+                    );
+                    swatches.push((rect, idx, literal.start, literal.color));
+                }
+            }
+        }
+        Self { swatches }
+    }
+
+    fn hit(&self, point: Point) -> Option<&(Rectangle, usize, usize, Color)> {
+        self.swatches.iter().find(|(rect, ..)| {
+            Rectangle {
+                y: rect.y - SWATCH_HIT_SLOP,
+                height: rect.height + SWATCH_HIT_SLOP,
+                ..*rect
+            }
+            .contains(point)
+        })
+    }
+}
+
+impl canvas::Program<Message> for Swatches {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return None;
+        };
+        let (_, line, col, _) = self.hit(cursor.position_in(bounds)?)?;
+        Some(canvas::Action::publish(Message::PickColor(*line, *col)).and_capture())
+    }
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        for (rect, _, _, color) in &self.swatches {
+            frame.fill_rectangle(rect.position(), rect.size(), *color);
+        }
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &(),
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match cursor.position_in(bounds) {
+            Some(point) if self.hit(point).is_some() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_and_rewrites_color_literals() {
+        let line = "bg = \"#1E1E2E\", fg = rgb(205, 214, 244), id = #12345, a#fff";
+        let literals = find(line);
+        assert_eq!(
+            literals
+                .iter()
+                .map(|l| (l.start, l.end))
+                .collect::<Vec<_>>(),
+            [(6, 13), (21, 39)]
+        );
+        assert_eq!(literals[0].color, Color::from_rgb8(0x1e, 0x1e, 0x2e));
+        assert_eq!(at(line, 13).map(|l| l.start), Some(6));
+
+        let translucent = Color::from_rgba8(255, 0, 0, 0.5);
+        assert_eq!(write(literals[0].color, literals[0].format), "#1E1E2E");
+        assert_eq!(write(translucent, literals[0].format), "#FF000080");
+        assert_eq!(write(translucent, Format::Rgb), "rgba(255, 0, 0, 0.5)");
+        assert_eq!(find("#abc")[0].color, Color::from_rgb8(0xaa, 0xbb, 0xcc));
+    }
+}
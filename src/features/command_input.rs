@@ -1,9 +1,33 @@
+use crate::config::history::MAX_HISTORY;
+use regex::{Regex, RegexBuilder};
+use std::path::Path;
+
+/// Command names offered by tab completion.
+const COMMAND_NAMES: &[&str] = &[
+    "buffer",
+    "buffers",
+    "delete",
+    "edit",
+    "ls",
+    "new",
+    "quit",
+    "rename",
+    "substitute",
+    "wq",
+    "write",
+];
+
 /// Vim-style `:` command input bar
 /// Ported from pinel's hotkey/command_input.rs, adapted for iced.
-
 pub struct CommandInput {
     pub open: bool,
     pub input: String,
+    /// Submitted commands, oldest first.
+    pub history: Vec<String>,
+    /// Entry shown while browsing the history, `None` when editing.
+    history_index: Option<usize>,
+    /// What was typed before browsing the history started.
+    draft: String,
 }
 
 impl Default for CommandInput {
@@ -11,34 +35,481 @@ impl Default for CommandInput {
         Self {
             open: false,
             input: String::new(),
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
         }
     }
 }
 
 impl CommandInput {
+    pub fn with_history(history: Vec<String>) -> Self {
+        Self {
+            history,
+            ..Self::default()
+        }
+    }
+
     pub fn open(&mut self) {
         self.open = true;
         self.input.clear();
+        self.history_index = None;
+    }
+
+    /// Adds the current input to the history, moving a repeated command to
+    /// the end. Returns whether the history changed.
+    pub fn remember(&mut self) -> bool {
+        let command = self.input.trim();
+        if command.is_empty() || self.history.last().is_some_and(|last| last == command) {
+            return false;
+        }
+        let command = command.to_string();
+        self.history.retain(|entry| entry != &command);
+        self.history.push(command);
+        let excess = self.history.len().saturating_sub(MAX_HISTORY);
+        self.history.drain(..excess);
+        true
+    }
+
+    /// Steps through history entries starting with what was typed, `-1`
+    /// going back in time. Stepping past the newest entry restores the draft.
+    pub fn recall(&mut self, delta: i32) {
+        if self.history_index.is_none() {
+            if delta > 0 {
+                return;
+            }
+            self.draft = self.input.clone();
+        }
+        let matching = |idx: &usize| self.history[*idx].starts_with(&self.draft);
+        let next = match (self.history_index, delta < 0) {
+            (None, _) => (0..self.history.len()).rev().find(matching),
+            (Some(idx), true) => (0..idx).rev().find(matching).or(Some(idx)),
+            (Some(idx), false) => (idx + 1..self.history.len()).find(matching),
+        };
+        self.history_index = next;
+        self.input = match next {
+            Some(idx) => self.history[idx].clone(),
+            None => self.draft.clone(),
+        };
+    }
+
+    /// Completes a command name, or a file path after `:e`, as far as it is
+    /// unambiguous. Relative paths are taken from `base`.
+    pub fn complete(&mut self, base: Option<&Path>) {
+        let colon = if self.input.starts_with(':') { ":" } else { "" };
+        let cmd = self.input[colon.len()..].trim_start();
+
+        if let Some((name, arg)) = cmd.split_once(' ') {
+            if matches!(name, "e" | "edit") {
+                if let Some(path) = complete_path(arg.trim_start(), base) {
+                    self.input = format!("{colon}{name} {path}");
+                }
+            }
+            return;
+        }
+        if cmd.is_empty() {
+            return;
+        }
+        let names: Vec<&str> = COMMAND_NAMES
+            .iter()
+            .copied()
+            .filter(|name| name.starts_with(cmd))
+            .collect();
+        match names.as_slice() {
+            [] => {}
+            ["edit"] => self.input = format!("{colon}edit "),
+            [name] => self.input = format!("{colon}{name}"),
+            _ => self.input = format!("{colon}{}", common_prefix(&names)),
+        }
+        self.history_index = None;
     }
 
     pub fn close(&mut self) {
         self.open = false;
     }
 
-    /// Process a vim-style command string and return the command name
-    pub fn process_command(&self) -> Option<String> {
-        let cmd = self.input.trim();
-        if cmd.is_empty() {
-            return None;
+    /// Parses the vim-style command line, see [`parse`].
+    pub fn process_command(&self) -> Option<ExCommand> {
+        parse(&self.input)
+    }
+}
+
+/// One end of a line range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Address {
+    /// A 1-based line number.
+    Line(usize),
+    /// `.`, the cursor line.
+    Current,
+    /// `$`, the last line.
+    Last,
+}
+
+/// Inclusive line range such as `1,10`, `.,$` or `%`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: Address,
+    pub end: Address,
+}
+
+impl LineRange {
+    pub const CURRENT: Self = Self {
+        start: Address::Current,
+        end: Address::Current,
+    };
+
+    /// 0-based `(first, last)` lines, ordered and clamped to the buffer.
+    pub fn resolve(self, cursor_line: usize, line_count: usize) -> (usize, usize) {
+        let last_line = line_count.saturating_sub(1);
+        let resolve = |address| match address {
+            Address::Line(line) => line.saturating_sub(1).min(last_line),
+            Address::Current => cursor_line.min(last_line),
+            Address::Last => last_line,
+        };
+        let (start, end) = (resolve(self.start), resolve(self.end));
+        (start.min(end), start.max(end))
+    }
+}
+
+/// A parsed `:s/pattern/replacement/flags`.
+///
+/// The pattern uses regex crate syntax. In the replacement `&` is the whole
+/// match and `\1`..`\9` are capture groups, as in vim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitute {
+    pub pattern: String,
+    pub replacement: String,
+    /// `g`: replace every match on a line, not just the first.
+    pub global: bool,
+    /// `i`: match case-insensitively.
+    pub ignore_case: bool,
+}
+
+impl Substitute {
+    pub fn regex(&self) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(&self.pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+    }
+
+    /// Applies the substitution to each line, returning the new lines and the
+    /// number of replacements made.
+    pub fn apply(&self, regex: &Regex, lines: &[&str]) -> (Vec<String>, usize) {
+        let replacement = regex_replacement(&self.replacement);
+        let limit = if self.global { 0 } else { 1 };
+        let mut count = 0;
+        let out = lines
+            .iter()
+            .map(|line| {
+                let matches = regex.find_iter(line).count();
+                count += if self.global { matches } else { matches.min(1) };
+                regex
+                    .replacen(line, limit, replacement.as_str())
+                    .into_owned()
+            })
+            .collect();
+        (out, count)
+    }
+}
+
+/// What a submitted command line asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    /// Run the command palette entry with this name.
+    Palette(String),
+    /// Move the cursor to a 1-based line.
+    GoToLine(usize),
+    /// `:[range]s/pattern/replacement/flags`
+    Substitute(LineRange, Substitute),
+    /// `:[range]d`
+    Delete(LineRange),
+    /// `:e path`, opening the file or a new buffer for it.
+    Edit(String),
+    /// `:ls`, listing the open buffers.
+    ListBuffers,
+    /// `:b n`, switching to the 1-based buffer `n`.
+    Buffer(usize),
+    /// `:rename name`, renaming the symbol at the cursor.
+    Rename(String),
+}
+
+/// Parses a vim-style command line. A bare number jumps to that line.
+pub fn parse(input: &str) -> Option<ExCommand> {
+    let cmd = input.trim();
+    let cmd = cmd.strip_prefix(':').unwrap_or(cmd).trim();
+    if cmd.is_empty() {
+        return None;
+    }
+
+    let (range, rest) = parse_range(cmd);
+    if let Some(range) = range {
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            return match range.end {
+                Address::Line(line) => Some(ExCommand::GoToLine(line)),
+                Address::Last => Some(ExCommand::GoToLine(usize::MAX)),
+                Address::Current => None,
+            };
+        }
+        return parse_ranged(range, rest);
+    }
+    if let Some(command) = parse_ranged(LineRange::CURRENT, cmd) {
+        return Some(command);
+    }
+    if let Some((name, path)) = cmd.split_once(char::is_whitespace) {
+        if matches!(name, "e" | "edit") {
+            return Some(ExCommand::Edit(path.trim().to_string()));
         }
+        if matches!(name, "b" | "buffer") {
+            return path.trim().parse().ok().map(ExCommand::Buffer);
+        }
+        if name == "rename" {
+            return Some(ExCommand::Rename(path.trim().to_string()));
+        }
+    }
+
+    if matches!(cmd, "ls" | "buffers") {
+        return Some(ExCommand::ListBuffers);
+    }
+    let name = match cmd {
+        "w" | "write" => "Save File",
+        "q" | "quit" => "Quit",
+        "wq" => "Save and Quit",
+        "e" | "edit" => "Open File",
+        "new" => "New File",
+        _ => return None,
+    };
+    Some(ExCommand::Palette(name.to_string()))
+}
+
+/// Longest prefix shared by all `words`.
+fn common_prefix(words: &[&str]) -> String {
+    let Some((first, rest)) = words.split_first() else {
+        return String::new();
+    };
+    let mut prefix = first.to_string();
+    for word in rest {
+        let shared = prefix
+            .char_indices()
+            .zip(word.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(prefix.len().min(word.len()), |((idx, _), _)| idx);
+        prefix.truncate(shared);
+    }
+    prefix
+}
+
+/// Completes the last component of `partial` against the files on disk,
+/// adding a `/` after a single matching directory.
+fn complete_path(partial: &str, base: Option<&Path>) -> Option<String> {
+    let split = partial.rfind(['/', '\\']).map_or(0, |idx| idx + 1);
+    let (dir, file) = partial.split_at(split);
+    let dir_path = crate::features::paths::resolve(if dir.is_empty() { "." } else { dir }, base);
+
+    let mut entries: Vec<(String, bool)> = std::fs::read_dir(dir_path)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let hidden = name.starts_with('.') && !file.starts_with('.');
+            (name.starts_with(file) && !hidden)
+                .then(|| (name, entry.file_type().is_ok_and(|kind| kind.is_dir())))
+        })
+        .collect();
+    entries.sort();
+
+    if let [(name, is_dir)] = entries.as_slice() {
+        let slash = if *is_dir { "/" } else { "" };
+        return Some(format!("{dir}{name}{slash}"));
+    }
+    let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+    let prefix = common_prefix(&names);
+    (prefix.len() > file.len()).then(|| format!("{dir}{prefix}"))
+}
+
+fn parse_address(input: &str) -> Option<(Address, &str)> {
+    if let Some(rest) = input.strip_prefix('.') {
+        return Some((Address::Current, rest));
+    }
+    if let Some(rest) = input.strip_prefix('$') {
+        return Some((Address::Last, rest));
+    }
+    let digits = input.len() - input.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let line = input[..digits].parse().ok()?;
+    Some((Address::Line(line), &input[digits..]))
+}
+
+/// Splits a leading `%`, `N`, `N,M`, `.` or `$` range off `input`.
+fn parse_range(input: &str) -> (Option<LineRange>, &str) {
+    if let Some(rest) = input.strip_prefix('%') {
+        let range = LineRange {
+            start: Address::Line(1),
+            end: Address::Last,
+        };
+        return (Some(range), rest);
+    }
+    let Some((start, rest)) = parse_address(input) else {
+        return (None, input);
+    };
+    if let Some((end, rest)) = rest.strip_prefix(',').and_then(parse_address) {
+        return (Some(LineRange { start, end }), rest);
+    }
+    (Some(LineRange { start, end: start }), rest)
+}
+
+/// Parses the commands that take a range.
+fn parse_ranged(range: LineRange, input: &str) -> Option<ExCommand> {
+    if matches!(input, "d" | "delete") {
+        return Some(ExCommand::Delete(range));
+    }
+    let args = input
+        .strip_prefix("substitute")
+        .or_else(|| input.strip_prefix('s'))?;
+    let delimiter = args.chars().next()?;
+    if delimiter.is_alphanumeric() || delimiter.is_whitespace() || delimiter == '\\' {
+        return None;
+    }
+
+    let mut fields = split_unescaped(&args[delimiter.len_utf8()..], delimiter).into_iter();
+    let pattern = fields.next().filter(|pattern| !pattern.is_empty())?;
+    let replacement = fields.next().unwrap_or_default();
+    let flags = fields.next().unwrap_or_default();
+    if fields.next().is_some() || !flags.chars().all(|c| matches!(c, 'g' | 'i' | 'I')) {
+        return None;
+    }
+    let substitute = Substitute {
+        pattern,
+        replacement,
+        global: flags.contains('g'),
+        ignore_case: flags.contains('i'),
+    };
+    Some(ExCommand::Substitute(range, substitute))
+}
+
+/// Splits on `delimiter`, turning escaped delimiters into plain ones.
+fn split_unescaped(input: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = input.chars();
+    while let Some(ch) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        if ch == '\\' {
+            match chars.next() {
+                Some(next) if next == delimiter => field.push(next),
+                Some(next) => {
+                    field.push(ch);
+                    field.push(next);
+                }
+                None => field.push(ch),
+            }
+        } else if ch == delimiter {
+            fields.push(String::new());
+        } else {
+            field.push(ch);
+        }
+    }
+    fields
+}
 
-        match cmd {
-            "w" | "write" => Some("Save File".to_string()),
-            "q" | "quit" => Some("Quit".to_string()),
-            "wq" => Some("Save and Quit".to_string()),
-            "e" | "edit" => Some("Open File".to_string()),
-            "new" => Some("New File".to_string()),
-            _ => None,
+/// Converts a vim replacement string to the regex crate's syntax.
+fn regex_replacement(replacement: &str) -> String {
+    let mut out = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '&' => out.push_str("${0}"),
+            '$' => out.push_str("$$"),
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => out.push_str(&format!("${{{digit}}}")),
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            },
+            _ => out.push(ch),
         }
     }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Option<ExCommand> {
+        let command_input = CommandInput {
+            input: input.to_string(),
+            ..Default::default()
+        };
+        command_input.process_command()
+    }
+
+    #[test]
+    fn parses_ranges_and_substitute() {
+        assert_eq!(parse(":42"), Some(ExCommand::GoToLine(42)));
+        assert_eq!(parse("$"), Some(ExCommand::GoToLine(usize::MAX)));
+        assert_eq!(
+            parse("w"),
+            Some(ExCommand::Palette("Save File".to_string()))
+        );
+        assert_eq!(
+            parse("e  ~/a b.txt"),
+            Some(ExCommand::Edit("~/a b.txt".to_string()))
+        );
+        assert_eq!(
+            parse("rename total"),
+            Some(ExCommand::Rename("total".to_string()))
+        );
+
+        let Some(ExCommand::Substitute(range, sub)) = parse("%s/a\\/b/x/gi") else {
+            panic!("expected a substitute");
+        };
+        assert_eq!(range.resolve(3, 10), (0, 9));
+        assert_eq!(sub.pattern, "a/b");
+        assert!(sub.global && sub.ignore_case);
+
+        let Some(ExCommand::Delete(range)) = parse("5,.d") else {
+            panic!("expected a delete");
+        };
+        assert_eq!(range.resolve(1, 10), (1, 4));
+        assert_eq!(parse("s/x/y/q"), None);
+    }
+
+    #[test]
+    fn recalls_history_by_prefix() {
+        let mut command_input = CommandInput::with_history(vec![
+            "s/a/b/".to_string(),
+            "w".to_string(),
+            "s/c/d/".to_string(),
+        ]);
+        command_input.input = "s".to_string();
+        command_input.recall(-1);
+        assert_eq!(command_input.input, "s/c/d/");
+        command_input.recall(-1);
+        assert_eq!(command_input.input, "s/a/b/");
+        command_input.recall(1);
+        command_input.recall(1);
+        assert_eq!(command_input.input, "s");
+
+        command_input.input = "subst".to_string();
+        command_input.complete(None);
+        assert_eq!(command_input.input, "substitute");
+    }
+
+    #[test]
+    fn substitutes_with_vim_replacements() {
+        let sub = Substitute {
+            pattern: "(\\w+)=(\\w+)".to_string(),
+            replacement: "\\2=\\1 [&] $".to_string(),
+            global: false,
+            ignore_case: false,
+        };
+        let regex = sub.regex().unwrap();
+        let (lines, count) = sub.apply(&regex, &["a=b c=d", "none"]);
+        assert_eq!(lines, ["b=a [a=b] $ c=d", "none"]);
+        assert_eq!(count, 1);
+    }
 }
@@ -1,5 +1,7 @@
 /// Command Palette - VS Code-style overlay command palette (Cmd+Shift+P)
 /// Ported from pinel's command_palette.rs, adapted for iced.
+use crate::config::command_usage::CommandUsage;
+use crate::features::activity;
 
 #[derive(Clone, Debug)]
 pub struct Command {
@@ -7,11 +9,70 @@ pub struct Command {
     pub description: String,
 }
 
+/// Name of the Cmd key on macOS and Ctrl elsewhere, which both work for
+/// every shortcut.
+const PRIMARY: &str = if cfg!(target_os = "macos") {
+    "Cmd"
+} else {
+    "Ctrl"
+};
+
+/// The keyboard shortcut of a built-in command, as shown in the palette.
+pub fn shortcut(name: &str) -> Option<String> {
+    let (primary, keys) = match name {
+        "Settings" => (true, "Shift+S"),
+        "Open File" => (true, "O"),
+        "Open Folder" => (true, "Shift+O"),
+        "Save File" => (true, "S"),
+        "New File" => (true, "N"),
+        "Cut" => (true, "X"),
+        "Copy" => (true, "C"),
+        "Paste" => (true, "V"),
+        "Toggle Terminal" => (true, "J"),
+        "Toggle File Tree" => (true, "B"),
+        "Find and Replace" => (true, "F"),
+        "Source Control" => (true, "Shift+G"),
+        "Toggle Comment" => (true, "/"),
+        "Go to Line" => (true, "G"),
+        "Switch Buffer" => (false, "Ctrl+Tab"),
+        "Reopen Closed Tab" => (true, "Shift+T"),
+        "Rename Symbol" => (false, "F2"),
+        "Go to Definition" => (false, "F12"),
+        "Zoom In" => (true, "="),
+        "Zoom Out" => (true, "-"),
+        "Reset Zoom" => (true, "0"),
+        "Format Document" => (false, "Alt+Shift+F"),
+        "Toggle Word Wrap" => (false, "Alt+Z"),
+        "Move Line Up" => (false, "Alt+Up"),
+        "Move Line Down" => (false, "Alt+Down"),
+        "Duplicate Line" => (false, "Alt+Shift+Down"),
+        "Duplicate Selection" => (true, "Shift+D"),
+        "Expand Selection" => (false, "Alt+Shift+Right"),
+        "Shrink Selection" => (false, "Alt+Shift+Left"),
+        "Render Markdown" => (true, "Shift+V"),
+        _ => return None,
+    };
+    Some(if primary {
+        format!("{PRIMARY}+{keys}")
+    } else {
+        keys.to_string()
+    })
+}
+
+/// Prefix of the palette entries that run a project task.
+pub const TASK_PREFIX: &str = "Run Task: ";
+
 pub struct CommandPalette {
     pub open: bool,
     pub input: String,
     commands: Vec<Command>,
     pub filtered_commands: Vec<Command>,
+    /// Commands defined in init.lua, listed after the built-in ones.
+    user_commands: Vec<Command>,
+    /// `Run Task: <name>` entries for the open folder's tasks.
+    task_commands: Vec<Command>,
+    /// Runs of each command, to list the ones used most first.
+    usage: CommandUsage,
 }
 
 impl Default for CommandPalette {
@@ -24,6 +85,9 @@ impl Default for CommandPalette {
             input: String::new(),
             commands,
             filtered_commands: filtered,
+            user_commands: Vec::new(),
+            task_commands: Vec::new(),
+            usage: CommandUsage::default(),
         }
     }
 }
@@ -63,14 +127,316 @@ impl CommandPalette {
                 name: "Save As".to_string(),
                 description: "Save the current file with a new name".to_string(),
             },
+            Command {
+                name: "Go Back".to_string(),
+                description: "Return to where the last jump left".to_string(),
+            },
+            Command {
+                name: "Go Forward".to_string(),
+                description: "Go to where the last Go Back left".to_string(),
+            },
+            Command {
+                name: "Share Snippet".to_string(),
+                description: "Upload the selection or file and copy its link".to_string(),
+            },
+            Command {
+                name: "Export as HTML".to_string(),
+                description: "Save the selection or file as a highlighted web page".to_string(),
+            },
+            Command {
+                name: "Cut".to_string(),
+                description: "Cut the selection or the current line".to_string(),
+            },
+            Command {
+                name: "Copy".to_string(),
+                description: "Copy the selection or the current line".to_string(),
+            },
+            Command {
+                name: "Paste".to_string(),
+                description: "Paste from the clipboard".to_string(),
+            },
             Command {
                 name: "Toggle Terminal".to_string(),
                 description: "Toggle embedded terminal panel".to_string(),
             },
+            Command {
+                name: "Toggle File Tree".to_string(),
+                description: "Show or hide the file tree".to_string(),
+            },
+            Command {
+                name: "Reveal Active File in File Tree".to_string(),
+                description: "Open the folders down to the active file and select it".to_string(),
+            },
+            Command {
+                name: "Copy Path".to_string(),
+                description: "Copy the full path of the active file".to_string(),
+            },
+            Command {
+                name: "Copy Relative Path".to_string(),
+                description: "Copy the path of the active file in the open folder".to_string(),
+            },
+            Command {
+                name: "Compare Active File With...".to_string(),
+                description: "Show the active file side by side with another file".to_string(),
+            },
+            Command {
+                name: "Compare Active File With Saved".to_string(),
+                description: "Show unsaved changes against the file on disk".to_string(),
+            },
+            Command {
+                name: "Toggle Hidden Files".to_string(),
+                description: "Show dotfiles and excluded folders in the tree and finders"
+                    .to_string(),
+            },
+            Command {
+                name: "Find in Files".to_string(),
+                description: "Search the files of the open folder".to_string(),
+            },
+            Command {
+                name: "Reload Theme".to_string(),
+                description: "Load the colors in theme.lua again".to_string(),
+            },
             Command {
                 name: "Find and Replace".to_string(),
                 description: "Search and replace text in editor".to_string(),
             },
+            Command {
+                name: "Source Control".to_string(),
+                description: "Stage changes and commit with git".to_string(),
+            },
+            Command {
+                name: "Indent Lines".to_string(),
+                description: "Indent the selected lines one level".to_string(),
+            },
+            Command {
+                name: "Outdent Lines".to_string(),
+                description: "Outdent the selected lines one level".to_string(),
+            },
+            Command {
+                name: "Reindent Lines".to_string(),
+                description: "Fix the indentation of the selected lines".to_string(),
+            },
+            Command {
+                name: "Toggle Comment".to_string(),
+                description: "Comment or uncomment the selected lines".to_string(),
+            },
+            Command {
+                name: "Go to Line".to_string(),
+                description: "Jump to a line number".to_string(),
+            },
+            Command {
+                name: "Switch Buffer".to_string(),
+                description: "Jump between open files, most recent first".to_string(),
+            },
+            Command {
+                name: "Pin Tab".to_string(),
+                description: "Pin or unpin the active tab, keeping it leftmost".to_string(),
+            },
+            Command {
+                name: "Reopen Closed Tab".to_string(),
+                description: "Open the most recently closed file again".to_string(),
+            },
+            Command {
+                name: "Notification History".to_string(),
+                description: "Show recent notifications".to_string(),
+            },
+            Command {
+                name: "Clear Notifications".to_string(),
+                description: "Forget past notifications".to_string(),
+            },
+            Command {
+                name: "Rename Symbol".to_string(),
+                description: "Rename every occurrence of the symbol at the cursor in this file"
+                    .to_string(),
+            },
+            Command {
+                name: "Go to Definition".to_string(),
+                description: "Jump to where the symbol at the cursor is defined".to_string(),
+            },
+            Command {
+                name: "Toggle Spell Check".to_string(),
+                description: "Check the spelling of comments, strings and prose".to_string(),
+            },
+            Command {
+                name: "Next Misspelling".to_string(),
+                description: "Go to the next misspelled word".to_string(),
+            },
+            Command {
+                name: "Spelling Suggestions".to_string(),
+                description:
+                    "Replace the misspelled word at the cursor or add it to the dictionary"
+                        .to_string(),
+            },
+            Command {
+                name: "Pick Color".to_string(),
+                description: "Edit the color literal at the cursor with a color picker".to_string(),
+            },
+            Command {
+                name: "Toggle Sticky Scroll".to_string(),
+                description: "Pin the headers of enclosing blocks at the top of the editor"
+                    .to_string(),
+            },
+            Command {
+                name: "Toggle Smooth Scrolling".to_string(),
+                description: "Animate the scroll to jumps that land out of view".to_string(),
+            },
+            Command {
+                name: "Render Whitespace".to_string(),
+                description: "Draw all whitespace, only trailing whitespace, or none".to_string(),
+            },
+            Command {
+                name: "Zoom In".to_string(),
+                description: "Make the editor text larger".to_string(),
+            },
+            Command {
+                name: "Zoom Out".to_string(),
+                description: "Make the editor text smaller".to_string(),
+            },
+            Command {
+                name: "Reset Zoom".to_string(),
+                description: "Go back to the default editor font size".to_string(),
+            },
+            Command {
+                name: "Word Count".to_string(),
+                description: "Count lines, words and characters in the file and selection"
+                    .to_string(),
+            },
+            Command {
+                name: "Local History".to_string(),
+                description: "Browse, compare and restore saved versions of the active file"
+                    .to_string(),
+            },
+            Command {
+                name: "Show Activity".to_string(),
+                description: "Show today's editing time and your streak".to_string(),
+            },
+            Command {
+                name: "Convert Line Endings to LF".to_string(),
+                description: "Save the current file with Unix line endings".to_string(),
+            },
+            Command {
+                name: "Convert Line Endings to CRLF".to_string(),
+                description: "Save the current file with Windows line endings".to_string(),
+            },
+            Command {
+                name: "Convert to UTF-8".to_string(),
+                description: "Save the current file as UTF-8 from now on".to_string(),
+            },
+            Command {
+                name: "Go to Symbol".to_string(),
+                description: "List the functions and types of the current file".to_string(),
+            },
+            Command {
+                name: "Format Document".to_string(),
+                description: "Run the formatter configured for this language".to_string(),
+            },
+            Command {
+                name: "Stop Task".to_string(),
+                description: "Stop the running task".to_string(),
+            },
+            Command {
+                name: "Toggle Word Wrap".to_string(),
+                description: "Wrap long lines at the edge of the editor".to_string(),
+            },
+            Command {
+                name: "Reflow Long Lines".to_string(),
+                description: "Wrap selected lines that are over the line length limit".to_string(),
+            },
+            Command {
+                name: "Move Line Up".to_string(),
+                description: "Move the selected lines above the previous line".to_string(),
+            },
+            Command {
+                name: "Move Line Down".to_string(),
+                description: "Move the selected lines below the next line".to_string(),
+            },
+            Command {
+                name: "Duplicate Line".to_string(),
+                description: "Copy the selected lines below themselves".to_string(),
+            },
+            Command {
+                name: "Duplicate Selection".to_string(),
+                description: "Copy the selection after itself, or the line below it".to_string(),
+            },
+            Command {
+                name: "Join Lines".to_string(),
+                description: "Join the selected lines, or the next line onto this one".to_string(),
+            },
+            Command {
+                name: "Sort Lines Ascending".to_string(),
+                description: "Sort the selected lines, or every line, A to Z".to_string(),
+            },
+            Command {
+                name: "Sort Lines Descending".to_string(),
+                description: "Sort the selected lines, or every line, Z to A".to_string(),
+            },
+            Command {
+                name: "Sort Lines Unique".to_string(),
+                description: "Sort the selected lines and drop repeated ones".to_string(),
+            },
+            Command {
+                name: "Reverse Lines".to_string(),
+                description: "Reverse the order of the selected lines".to_string(),
+            },
+            Command {
+                name: "Transform to Uppercase".to_string(),
+                description: "Make the selection uppercase".to_string(),
+            },
+            Command {
+                name: "Transform to Lowercase".to_string(),
+                description: "Make the selection lowercase".to_string(),
+            },
+            Command {
+                name: "Transform to Title Case".to_string(),
+                description: "Capitalize every word of the selection".to_string(),
+            },
+            Command {
+                name: "Base64 Encode".to_string(),
+                description: "Encode the selection as base64".to_string(),
+            },
+            Command {
+                name: "Base64 Decode".to_string(),
+                description: "Decode the base64 selection back to text".to_string(),
+            },
+            Command {
+                name: "Increment Number".to_string(),
+                description:
+                    "Add one to the number at or after the cursor, or on each selected line"
+                        .to_string(),
+            },
+            Command {
+                name: "Decrement Number".to_string(),
+                description:
+                    "Subtract one from the number at or after the cursor, or on each selected line"
+                        .to_string(),
+            },
+            Command {
+                name: "Increment Numbers Sequentially".to_string(),
+                description: "Add 1, 2, 3 and so on to the numbers of the selected lines"
+                    .to_string(),
+            },
+            Command {
+                name: "Expand Selection".to_string(),
+                description: "Grow the selection to the enclosing word, string or brackets"
+                    .to_string(),
+            },
+            Command {
+                name: "Shrink Selection".to_string(),
+                description: "Undo the last Expand Selection".to_string(),
+            },
+            Command {
+                name: "Delete Surrounding Pair".to_string(),
+                description: "Remove the brackets or quotes around the cursor".to_string(),
+            },
+            Command {
+                name: "Insert Before Block".to_string(),
+                description: "Type text into every line at the selection's left column".to_string(),
+            },
+            Command {
+                name: "Append After Block".to_string(),
+                description: "Type text into every line at the selection's right column"
+                    .to_string(),
+            },
         ];
 
         if include_markdown_render {
@@ -78,6 +444,10 @@ impl CommandPalette {
                 name: "Render Markdown".to_string(),
                 description: "Open a live markdown preview beside the editor".to_string(),
             });
+            commands.push(Command {
+                name: "Toggle Preview Scroll Lock".to_string(),
+                description: "Scroll the markdown preview together with the editor".to_string(),
+            });
         }
 
         commands
@@ -88,10 +458,54 @@ impl CommandPalette {
         if self.open {
             self.input.clear();
             self.commands = Self::commands_for(include_markdown_render);
+            self.commands.extend(self.task_commands.iter().cloned());
+            self.commands.extend(self.user_commands.iter().cloned());
             self.filtered_commands = self.commands.clone();
+            self.rank();
         }
     }
 
+    pub fn set_usage(&mut self, usage: CommandUsage) {
+        self.usage = usage;
+    }
+
+    /// Counts a run of the command `name`, returning the updated usage to
+    /// be saved.
+    pub fn record_use(&mut self, name: &str) -> &CommandUsage {
+        self.usage.record(name, activity::now());
+        &self.usage
+    }
+
+    /// Moves the commands used most, and most lately, to the top. Others
+    /// keep their order.
+    fn rank(&mut self) {
+        let now = activity::now();
+        let usage = &self.usage;
+        self.filtered_commands.sort_by(|a, b| {
+            usage
+                .score(&b.name, now)
+                .total_cmp(&usage.score(&a.name, now))
+        });
+    }
+
+    /// Adds a user command, replacing one with the same name.
+    pub fn add_user_command(&mut self, command: Command) {
+        self.user_commands
+            .retain(|existing| existing.name != command.name);
+        self.user_commands.push(command);
+    }
+
+    /// Replaces the task entries with one per `(name, command)` pair.
+    pub fn set_tasks<'a>(&mut self, tasks: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        self.task_commands = tasks
+            .into_iter()
+            .map(|(name, command)| Command {
+                name: format!("{TASK_PREFIX}{name}"),
+                description: command.to_string(),
+            })
+            .collect();
+    }
+
     pub fn close(&mut self) {
         self.open = false;
         self.input.clear();
@@ -100,6 +514,8 @@ impl CommandPalette {
 
     pub fn filter_commands(&mut self, include_markdown_render: bool) {
         self.commands = Self::commands_for(include_markdown_render);
+        self.commands.extend(self.task_commands.iter().cloned());
+        self.commands.extend(self.user_commands.iter().cloned());
         let input_lower = self.input.to_lowercase();
 
         if input_lower.is_empty() {
@@ -112,5 +528,6 @@ impl CommandPalette {
                 .cloned()
                 .collect();
         }
+        self.rank();
     }
 }
@@ -0,0 +1,131 @@
+//! Toggling line comments using the comment syntax of each language.
+
+use crate::language::Language;
+
+/// Comment markers placed around a single line. Languages with only block
+/// comments use an opening and closing pair.
+pub fn markers(language: Language) -> Option<(&'static str, &'static str)> {
+    let markers = match language {
+        Language::Rust
+        | Language::JavaScript
+        | Language::JavaScriptJsx
+        | Language::TypeScript
+        | Language::TypeScriptJsx
+        | Language::Go
+        | Language::C
+        | Language::Cpp
+        | Language::Java => ("//", ""),
+        Language::Python
+        | Language::Ruby
+        | Language::Perl
+        | Language::Toml
+        | Language::Yaml
+        | Language::Shell
+        | Language::Makefile => ("#", ""),
+        Language::Lua => ("--", ""),
+        Language::Html | Language::Markdown => ("<!--", "-->"),
+        Language::Css => ("/*", "*/"),
+        Language::Json | Language::PlainText => return None,
+    };
+    Some(markers)
+}
+
+fn is_commented(line: &str, (open, close): (&str, &str)) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with(open) && trimmed.ends_with(close)
+}
+
+/// Comments `lines`, or uncomments them when every non-blank line is
+/// already commented. Comments are placed at the smallest indentation so a
+/// commented block stays aligned.
+pub fn toggle(lines: &[&str], language: Language) -> Vec<String> {
+    let Some(markers) = markers(language) else {
+        return lines.iter().map(|line| line.to_string()).collect();
+    };
+    let (open, close) = markers;
+    let filled = || lines.iter().filter(|line| !line.trim().is_empty());
+
+    if filled().all(|line| is_commented(line, markers)) {
+        return lines
+            .iter()
+            .map(|line| {
+                if line.trim().is_empty() {
+                    return line.to_string();
+                }
+                let indent = &line[..line.len() - line.trim_start().len()];
+                // Markers may overlap, as in `/*/`
+                let body = &line.trim()[open.len()..];
+                let body = body.strip_suffix(close).unwrap_or(body);
+                let body = body.strip_prefix(' ').unwrap_or(body);
+                let body = if close.is_empty() {
+                    body
+                } else {
+                    body.strip_suffix(' ').unwrap_or(body)
+                };
+                format!("{indent}{body}")
+            })
+            .collect();
+    }
+
+    // Only spaces and tabs are shared, other whitespace may be several bytes
+    let column = filled()
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                return line.to_string();
+            }
+            let (indent, body) = line.split_at(column);
+            let body = body.trim_end();
+            if close.is_empty() {
+                format!("{indent}{open} {body}")
+            } else {
+                format!("{indent}{open} {body} {close}")
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_line_comments_at_shared_indent() {
+        let lines = ["    if x {", "", "        y();", "    }"];
+        let commented = toggle(&lines, Language::Rust);
+        assert_eq!(
+            commented,
+            ["    // if x {", "", "    //     y();", "    // }"]
+        );
+
+        let commented: Vec<&str> = commented.iter().map(String::as_str).collect();
+        assert_eq!(toggle(&commented, Language::Rust), lines);
+    }
+
+    #[test]
+    fn mixed_lines_are_commented_and_block_markers_wrap() {
+        let lines = ["# done", "todo()"];
+        assert_eq!(toggle(&lines, Language::Python), ["# # done", "# todo()"]);
+
+        let lines = ["<p>hi</p>"];
+        assert_eq!(toggle(&lines, Language::Html), ["<!-- <p>hi</p> -->"]);
+        assert_eq!(toggle(&["<!-- <p>hi</p> -->"], Language::Html), lines);
+    }
+
+    #[test]
+    fn odd_whitespace_and_overlapping_markers() {
+        assert_eq!(
+            toggle(&["\u{3000}x", "  y"], Language::Rust),
+            ["// \u{3000}x", "//   y"]
+        );
+        assert_eq!(toggle(&["/*/"], Language::Css), ["/"]);
+    }
+}
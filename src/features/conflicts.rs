@@ -0,0 +1,313 @@
+//! Merge conflicts left in a file by git: the `<<<<<<<`, `=======` and
+//! `>>>>>>>` marker lines and, in diff3 style, the `|||||||` base section.
+//!
+//! Each section is tinted in the editor and the first marker line gets
+//! buttons that replace the whole conflict with one side or both.
+
+use crate::features::editor_buffer::EditorBuffer;
+use crate::message::{Message, Resolution};
+use crate::ui::TextLayout;
+use iced::widget::canvas;
+use iced::{mouse, Color, Point, Rectangle, Renderer, Size, Theme};
+
+/// Starts of the marker lines, also used to find them in a buffer.
+pub const MARKERS: [&str; 4] = ["<<<<<<<", "|||||||", "=======", ">>>>>>>"];
+
+/// Space between the marker text and the first button, and between buttons.
+const LENS_GAP: f32 = 18.0;
+
+/// Lines of one conflict, 0-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    /// The `<<<<<<<` line, current changes follow it.
+    pub start: usize,
+    /// The `|||||||` line of diff3 style conflicts.
+    pub base: Option<usize>,
+    /// The `=======` line, incoming changes follow it.
+    pub separator: usize,
+    /// The `>>>>>>>` line.
+    pub end: usize,
+}
+
+impl Conflict {
+    /// Lines of our side, the branch checked out.
+    pub fn current(&self) -> std::ops::Range<usize> {
+        self.start + 1..self.base.unwrap_or(self.separator)
+    }
+
+    /// Lines of the side being merged in.
+    pub fn incoming(&self) -> std::ops::Range<usize> {
+        self.separator + 1..self.end
+    }
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\r']))
+}
+
+/// Conflicts in `lines`, given by line index. Only marker lines matter, so
+/// other lines may be left out. Unfinished conflicts are skipped.
+pub fn find<'a>(lines: impl IntoIterator<Item = (usize, &'a str)>) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut start = None;
+    let mut base = None;
+    let mut separator = None;
+    for (idx, line) in lines {
+        if is_marker(line, MARKERS[0]) {
+            (start, base, separator) = (Some(idx), None, None);
+        } else if is_marker(line, MARKERS[1]) && start.is_some() && separator.is_none() {
+            base = Some(idx);
+        } else if is_marker(line, MARKERS[2]) && start.is_some() && separator.is_none() {
+            separator = Some(idx);
+        } else if is_marker(line, MARKERS[3]) {
+            if let (Some(start), Some(separator)) = (start, separator) {
+                conflicts.push(Conflict {
+                    start,
+                    base,
+                    separator,
+                    end: idx,
+                });
+            }
+            (start, base, separator) = (None, None, None);
+        }
+    }
+    conflicts
+}
+
+/// Conflicts in the whole of `buffer`.
+pub fn find_in_buffer(buffer: &EditorBuffer) -> Vec<Conflict> {
+    let lines = buffer.lines_starting_with(&MARKERS);
+    find(lines.iter().map(|(idx, line)| (*idx, line.as_str())))
+}
+
+/// The lines replacing `conflict` in `lines`.
+pub fn resolve<'a>(lines: &[&'a str], conflict: &Conflict, resolution: Resolution) -> Vec<&'a str> {
+    let current = &lines[conflict.current()];
+    let incoming = &lines[conflict.incoming()];
+    match resolution {
+        Resolution::Current => current.to_vec(),
+        Resolution::Incoming => incoming.to_vec(),
+        Resolution::Both => [current, incoming].concat(),
+    }
+}
+
+impl Resolution {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Current => "Accept Current",
+            Self::Incoming => "Accept Incoming",
+            Self::Both => "Accept Both",
+        }
+    }
+}
+
+/// Tinted conflict sections on screen and the buttons on their first
+/// line. Clicking one sends `Message::ResolveConflict`.
+pub struct Lenses {
+    /// Top and color of each tinted line.
+    tints: Vec<(f32, Color)>,
+    line_height: f32,
+    buttons: Vec<(Rectangle, usize, Resolution)>,
+    font: iced::Font,
+    size: f32,
+    color: Color,
+}
+
+impl Lenses {
+    /// Nothing is drawn without a `layout`, while word wrap is on.
+    pub fn new(
+        layout: Option<&TextLayout>,
+        buffer: &EditorBuffer,
+        font: iced::Font,
+        size: f32,
+        color: Color,
+    ) -> Self {
+        let mut tints = Vec::new();
+        let mut buttons = Vec::new();
+        let mut line_height = 0.0;
+        if let Some(layout) = layout {
+            let visible = layout.visible_lines(buffer.line_count());
+            line_height = layout.line_height();
+            let current = Color {
+                a: 0.10,
+                ..crate::theme::ACCENT_GREEN
+            };
+            let incoming = Color {
+                a: 0.10,
+                ..crate::theme::ACCENT_BLUE
+            };
+            let marker = |color: Color| Color { a: 0.22, ..color };
+            for conflict in find_in_buffer(buffer) {
+                if conflict.end < visible.start || conflict.start >= visible.end {
+                    continue;
+                }
+                let sections = [
+                    (conflict.start..conflict.start + 1, marker(current)),
+                    (conflict.current(), current),
+                    (
+                        conflict.base.map_or(0..0, |base| base..conflict.separator),
+                        Color { a: 0.06, ..color },
+                    ),
+                    (conflict.separator..conflict.separator + 1, marker(color)),
+                    (conflict.incoming(), incoming),
+                    (conflict.end..conflict.end + 1, marker(incoming)),
+                ];
+                for (lines, tint) in sections {
+                    for line in lines.filter(|line| visible.contains(line)) {
+                        tints.push((layout.line_top(line), tint));
+                    }
+                }
+
+                let text = buffer.line(conflict.start);
+                let text = text.trim_end_matches(['\n', '\r']);
+                let Some(point) = layout.point(conflict.start, text, text.chars().count()) else {
+                    continue;
+                };
+                let mut x = point.x + LENS_GAP;
+                for resolution in [Resolution::Current, Resolution::Incoming, Resolution::Both] {
+                    let label_width = layout.width(resolution.label());
+                    buttons.push((
+                        Rectangle::new(Point::new(x, point.y), Size::new(label_width, line_height)),
+                        conflict.start,
+                        resolution,
+                    ));
+                    x += label_width + LENS_GAP;
+                }
+            }
+        }
+        Self {
+            tints,
+            line_height,
+            buttons,
+            font,
+            size,
+            color,
+        }
+    }
+
+    fn hit(&self, point: Point) -> Option<&(Rectangle, usize, Resolution)> {
+        self.buttons.iter().find(|(rect, ..)| rect.contains(point))
+    }
+}
+
+impl canvas::Program<Message> for Lenses {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return None;
+        };
+        let (_, line, resolution) = self.hit(cursor.position_in(bounds)?)?;
+        Some(
+            canvas::Action::publish(Message::ResolveConflict {
+                line: *line,
+                resolution: *resolution,
+            })
+            .and_capture(),
+        )
+    }
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        for (top, tint) in &self.tints {
+            frame.fill_rectangle(
+                Point::new(0.0, *top),
+                Size::new(bounds.width, self.line_height),
+                *tint,
+            );
+        }
+        let hovered = cursor
+            .position_in(bounds)
+            .and_then(|point| self.hit(point))
+            .map(|(rect, ..)| *rect);
+        for (rect, _, resolution) in &self.buttons {
+            let alpha = if hovered == Some(*rect) { 1.0 } else { 0.6 };
+            frame.fill_text(canvas::Text {
+                content: resolution.label().to_string(),
+                position: rect.position(),
+                color: Color {
+                    a: alpha,
+                    ..self.color
+                },
+                size: self.size.into(),
+                font: self.font,
+                ..canvas::Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &(),
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match cursor.position_in(bounds) {
+            Some(point) if self.hit(point).is_some() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_and_resolves_conflicts() {
+        let text = "a\n<<<<<<< HEAD\nmine\n||||||| base\nold\n=======\ntheirs\nmore\n>>>>>>> topic\nb\n<<<<<<< HEAD\n=======\nx\n>>>>>>>\n=======\n";
+        let lines: Vec<&str> = text.split('\n').collect();
+        let conflicts = find(lines.iter().copied().enumerate());
+        assert_eq!(
+            conflicts,
+            [
+                Conflict {
+                    start: 1,
+                    base: Some(3),
+                    separator: 5,
+                    end: 8
+                },
+                Conflict {
+                    start: 10,
+                    base: None,
+                    separator: 11,
+                    end: 13
+                },
+            ]
+        );
+        assert_eq!(
+            resolve(&lines, &conflicts[0], Resolution::Current),
+            ["mine"]
+        );
+        assert_eq!(
+            resolve(&lines, &conflicts[0], Resolution::Incoming),
+            ["theirs", "more"]
+        );
+        assert_eq!(
+            resolve(&lines, &conflicts[0], Resolution::Both),
+            ["mine", "theirs", "more"]
+        );
+        assert!(resolve(&lines, &conflicts[1], Resolution::Current).is_empty());
+
+        assert!(find([(0, "<<<<<<<< not a marker"), (1, "======="), (2, ">>>>>>>")]).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Finding definitions without a language server.
+//!
+//! A `tags` file in the open folder, as written by Universal Ctags
+//! (`ctags -R`), is used when there is one. Otherwise the files of the
+//! folder are scanned with the symbol patterns of `features::symbols`.
+
+use crate::features::symbols;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Names of tags files looked for in the folder root.
+const TAGS_FILES: &[&str] = &["tags", ".tags", "TAGS"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    /// 0-based line of the definition.
+    pub line: usize,
+}
+
+/// Where the tag in a tags file line points: a line number or a search
+/// pattern for the line's text.
+#[derive(Debug, PartialEq, Eq)]
+enum Address {
+    Line(usize),
+    Text(String),
+}
+
+/// Splits a tags file line into name, file and address.
+fn parse_line(line: &str) -> Option<(&str, &str, Address)> {
+    if line.starts_with("!_TAG_") {
+        return None;
+    }
+    let mut fields = line.splitn(3, '\t');
+    let name = fields.next()?;
+    let file = fields.next()?;
+    let rest = fields.next()?;
+    let address = rest.split(";\"").next().unwrap_or(rest).trim_end();
+    let address = if let Ok(line) = address.parse::<usize>() {
+        Address::Line(line.saturating_sub(1))
+    } else {
+        let pattern = address
+            .strip_prefix('/')
+            .and_then(|p| p.strip_suffix('/'))
+            .or_else(|| address.strip_prefix('?').and_then(|p| p.strip_suffix('?')))?;
+        let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+        let pattern = pattern.strip_suffix('$').unwrap_or(pattern);
+        Address::Text(pattern.replace("\\/", "/").replace("\\\\", "\\"))
+    };
+    Some((name, file, address))
+}
+
+/// Definitions of `name` listed in the tags file of `root`, or `None` when
+/// the folder has no tags file.
+fn from_tags_file(root: &Path, name: &str) -> Option<Vec<Location>> {
+    let content = TAGS_FILES
+        .iter()
+        .find_map(|file| fs::read_to_string(root.join(file)).ok())?;
+    let mut locations = Vec::new();
+    for (tag, file, address) in content.lines().filter_map(parse_line) {
+        if tag != name {
+            continue;
+        }
+        let path = root.join(file);
+        let line = match address {
+            Address::Line(line) => line,
+            Address::Text(text) => fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| content.lines().position(|line| line == text))
+                .unwrap_or(0),
+        };
+        locations.push(Location { path, line });
+    }
+    Some(locations)
+}
+
+/// Definitions of `name` among the symbols of `text`.
+pub fn in_text(path: &Path, text: &str, name: &str) -> Vec<Location> {
+    let language = crate::language::detect(path, text.lines().next().unwrap_or(""));
+    symbols::extract(text, language)
+        .into_iter()
+        .filter(|symbol| symbol.kind != "impl" && symbol.kind != "heading")
+        .filter(|symbol| {
+            // Lua and Ruby symbols can be qualified, like `M.setup`
+            symbol.name == name
+                || symbol
+                    .name
+                    .rsplit(['.', ':'])
+                    .next()
+                    .is_some_and(|last| last == name)
+        })
+        .map(|symbol| Location {
+            path: path.to_path_buf(),
+            line: symbol.line,
+        })
+        .collect()
+}
+
+/// Definitions of `name` in the folder at `root`, from its tags file or by
+/// scanning `files`.
+pub fn find(root: Option<&Path>, files: &[PathBuf], name: &str) -> Vec<Location> {
+    if let Some(locations) = root.and_then(|root| from_tags_file(root, name)) {
+        return locations;
+    }
+    files
+        .iter()
+        .filter_map(|path| Some((path, fs::read_to_string(path).ok()?)))
+        .flat_map(|(path, text)| in_text(path, &text, name))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tags_lines() {
+        assert_eq!(
+            parse_line("App\tsrc/app.rs\t/^pub struct App {$/;\"\ts"),
+            Some((
+                "App",
+                "src/app.rs",
+                Address::Text("pub struct App {".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_line("main\tsrc/main.rs\t12;\"\tf"),
+            Some(("main", "src/main.rs", Address::Line(11)))
+        );
+        assert_eq!(parse_line("!_TAG_FILE_FORMAT\t2\t/extended format/"), None);
+
+        let found = in_text(Path::new("a.rs"), "fn helper() {}\nfn main() {}\n", "main");
+        assert_eq!(found.iter().map(|l| l.line).collect::<Vec<_>>(), [1]);
+    }
+}
@@ -0,0 +1,348 @@
+//! Line diff of a buffer against another file or the file on disk, shown
+//! side by side. Each hunk keeps the buffer's lines or takes the other
+//! side's, and applying writes the choices back into the buffer.
+//!
+//! Lines are compared whole. A changed line pair also gets the part that
+//! differs, found by trimming what both lines start and end with.
+
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Unchanged lines shown around each hunk, the rest are folded.
+const CONTEXT: usize = 3;
+
+/// Largest table the line matching builds. Bigger changes become one hunk.
+const MAX_TABLE: usize = 4_000_000;
+
+/// Lines that differ between the two sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub mine: Range<usize>,
+    pub theirs: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Row {
+    /// A line both sides share.
+    Same { mine: usize, theirs: usize },
+    /// Lines of the hunk at `hunk`. One side runs out first when the hunk
+    /// changes more lines on the other.
+    Changed {
+        hunk: usize,
+        mine: Option<usize>,
+        theirs: Option<usize>,
+    },
+    /// This many unchanged lines left out.
+    Folded(usize),
+}
+
+/// The hunks turning `mine` into `theirs`, in order.
+pub fn hunks(mine: &[&str], theirs: &[&str]) -> Vec<Hunk> {
+    let prefix = mine.iter().zip(theirs).take_while(|(a, b)| a == b).count();
+    let suffix = mine[prefix..]
+        .iter()
+        .rev()
+        .zip(theirs[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &mine[prefix..mine.len() - suffix];
+    let b = &theirs[prefix..theirs.len() - suffix];
+    if a.is_empty() && b.is_empty() {
+        return Vec::new();
+    }
+    let hunk = |i: Range<usize>, j: Range<usize>| Hunk {
+        mine: prefix + i.start..prefix + i.end,
+        theirs: prefix + j.start..prefix + j.end,
+    };
+    if (a.len() + 1) * (b.len() + 1) > MAX_TABLE {
+        return vec![hunk(0..a.len(), 0..b.len())];
+    }
+
+    // Longest common subsequence of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let mut open: Option<(usize, usize)> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            if let Some((start_i, start_j)) = open.take() {
+                hunks.push(hunk(start_i..i, start_j..j));
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+        open.get_or_insert((i, j));
+        if j == b.len()
+            || (i < a.len() && lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+        {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if let Some((start_i, start_j)) = open {
+        hunks.push(hunk(start_i..a.len(), start_j..b.len()));
+    }
+    hunks
+}
+
+/// Byte ranges of `mine` and `theirs` left after removing what both start
+/// and end with, the part of a changed line worth highlighting.
+pub fn inline_changes(mine: &str, theirs: &str) -> (Range<usize>, Range<usize>) {
+    let prefix: usize = mine
+        .chars()
+        .zip(theirs.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    let suffix: usize = mine[prefix..]
+        .chars()
+        .rev()
+        .zip(theirs[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    (prefix..mine.len() - suffix, prefix..theirs.len() - suffix)
+}
+
+/// An open comparison of the buffer at `path` with `theirs_label`.
+#[derive(Debug, Clone)]
+pub struct DiffView {
+    pub path: PathBuf,
+    pub theirs_label: String,
+    /// Whether the other side is the file on disk, so taking all of it
+    /// leaves the buffer saved.
+    pub against_disk: bool,
+    mine: Vec<String>,
+    theirs: Vec<String>,
+    pub hunks: Vec<Hunk>,
+    /// Per hunk, whether to take the other side's lines.
+    pub take_theirs: Vec<bool>,
+}
+
+impl DiffView {
+    pub fn new(
+        path: PathBuf,
+        theirs_label: String,
+        against_disk: bool,
+        mine: &str,
+        theirs: &str,
+    ) -> Self {
+        let mine: Vec<String> = mine.split('\n').map(str::to_string).collect();
+        let theirs: Vec<String> = theirs.split('\n').map(str::to_string).collect();
+        let hunks = hunks(
+            &mine.iter().map(String::as_str).collect::<Vec<_>>(),
+            &theirs.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+        let take_theirs = vec![false; hunks.len()];
+        Self {
+            path,
+            theirs_label,
+            against_disk,
+            mine,
+            theirs,
+            hunks,
+            take_theirs,
+        }
+    }
+
+    pub fn mine_line(&self, line: usize) -> &str {
+        &self.mine[line]
+    }
+
+    pub fn theirs_line(&self, line: usize) -> &str {
+        &self.theirs[line]
+    }
+
+    /// Whether `text` is still the buffer's text the diff was made from.
+    pub fn is_mine(&self, text: &str) -> bool {
+        text.split('\n').eq(self.mine.iter().map(String::as_str))
+    }
+
+    /// The rows to show, with unchanged runs away from hunks folded.
+    pub fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        let (mut mine, mut theirs) = (0, 0);
+        for (idx, hunk) in self.hunks.iter().enumerate() {
+            self.push_same(&mut rows, mine, theirs, hunk.mine.start, idx == 0, false);
+            for offset in 0..hunk.mine.len().max(hunk.theirs.len()) {
+                rows.push(Row::Changed {
+                    hunk: idx,
+                    mine: Some(hunk.mine.start + offset).filter(|l| hunk.mine.contains(l)),
+                    theirs: Some(hunk.theirs.start + offset).filter(|l| hunk.theirs.contains(l)),
+                });
+            }
+            mine = hunk.mine.end;
+            theirs = hunk.theirs.end;
+        }
+        self.push_same(
+            &mut rows,
+            mine,
+            theirs,
+            self.mine.len(),
+            self.hunks.is_empty(),
+            true,
+        );
+        rows
+    }
+
+    /// Rows of the unchanged lines from `mine` up to `end`, keeping the
+    /// context next to the hunks before and after them.
+    fn push_same(
+        &self,
+        rows: &mut Vec<Row>,
+        mine: usize,
+        theirs: usize,
+        end: usize,
+        first: bool,
+        last: bool,
+    ) {
+        let len = end - mine;
+        let head = if first { 0 } else { CONTEXT.min(len) };
+        let tail = if last { 0 } else { CONTEXT.min(len - head) };
+        let same = |offset: usize| Row::Same {
+            mine: mine + offset,
+            theirs: theirs + offset,
+        };
+        rows.extend((0..head).map(same));
+        if len > head + tail {
+            rows.push(Row::Folded(len - head - tail));
+        }
+        rows.extend((len - tail..len).map(same));
+    }
+
+    /// The buffer's text with the chosen hunks taken from the other side.
+    pub fn merged(&self) -> String {
+        let mut lines: Vec<&str> = Vec::with_capacity(self.mine.len());
+        let mut mine = 0;
+        for (hunk, take) in self.hunks.iter().zip(&self.take_theirs) {
+            lines.extend(self.mine[mine..hunk.mine.start].iter().map(String::as_str));
+            let side = if *take {
+                &self.theirs[hunk.theirs.clone()]
+            } else {
+                &self.mine[hunk.mine.clone()]
+            };
+            lines.extend(side.iter().map(String::as_str));
+            mine = hunk.mine.end;
+        }
+        lines.extend(self.mine[mine..].iter().map(String::as_str));
+        lines.join("\n")
+    }
+
+    /// Edits turning the buffer into [`Self::merged`], as byte ranges of
+    /// the buffer's text and their replacements. They come last first, so
+    /// applying them in order leaves the earlier ranges in place.
+    pub fn edits(&self) -> Vec<(Range<usize>, String)> {
+        let mut starts = Vec::with_capacity(self.mine.len() + 1);
+        let mut offset = 0;
+        for line in &self.mine {
+            starts.push(offset);
+            offset += line.len() + 1;
+        }
+        // Past the end, as if the text ended with a line break
+        starts.push(offset);
+        let end_of_text = offset - 1;
+
+        let mut edits = Vec::new();
+        for (hunk, _) in self
+            .hunks
+            .iter()
+            .zip(&self.take_theirs)
+            .filter(|(_, take)| **take)
+            .rev()
+        {
+            let lines = self.theirs[hunk.theirs.clone()].join("\n");
+            let edit = if hunk.mine.end < self.mine.len() {
+                let text = if hunk.theirs.is_empty() {
+                    String::new()
+                } else {
+                    format!("{lines}\n")
+                };
+                (starts[hunk.mine.start]..starts[hunk.mine.end], text)
+            } else if hunk.mine.start == 0 {
+                (0..end_of_text, lines)
+            } else {
+                // At the end there is no line break after the hunk, so the
+                // one before it goes instead
+                let text = if hunk.theirs.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n{lines}")
+                };
+                (starts[hunk.mine.start] - 1..end_of_text, text)
+            };
+            edits.push(edit);
+        }
+        edits
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_chosen_hunks() {
+        let mine = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn";
+        let theirs = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\no";
+        let mut diff = DiffView::new(PathBuf::from("x"), String::new(), false, mine, theirs);
+        assert_eq!(
+            diff.hunks,
+            [
+                Hunk {
+                    mine: 1..2,
+                    theirs: 1..2
+                },
+                Hunk {
+                    mine: 14..14,
+                    theirs: 14..15
+                },
+            ]
+        );
+        assert_eq!(diff.rows()[5], Row::Folded(6));
+        assert_eq!(diff.merged(), mine);
+
+        let apply = |diff: &DiffView| {
+            let mut text = mine.to_string();
+            for (range, replacement) in diff.edits() {
+                text.replace_range(range, &replacement);
+            }
+            text
+        };
+        diff.take_theirs = vec![true, true];
+        assert_eq!(diff.merged(), theirs);
+        assert_eq!(apply(&diff), theirs);
+        diff.take_theirs = vec![false, true];
+        assert_eq!(apply(&diff), diff.merged());
+
+        // Deleting the last lines and replacing everything
+        for (mine, theirs) in [("a\nb\nc", "a"), ("x\ny", "z\n"), ("", "a\nb")] {
+            let mut diff = DiffView::new(PathBuf::from("x"), String::new(), true, mine, theirs);
+            diff.take_theirs.fill(true);
+            let mut text = mine.to_string();
+            for (range, replacement) in diff.edits() {
+                text.replace_range(range, &replacement);
+            }
+            assert_eq!(text, theirs);
+        }
+
+        assert_eq!(inline_changes("let x = 1;", "let y = 1;"), (4..5, 4..5));
+        assert_eq!(inline_changes("ab", "aXb"), (1..1, 1..2));
+    }
+}
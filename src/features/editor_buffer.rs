@@ -20,6 +20,10 @@ impl EditorBuffer {
         self.rope = Rope::from_str(text);
     }
 
+    pub fn len_bytes(&self) -> usize {
+        self.rope.len_bytes()
+    }
+
     pub fn line_count(&self) -> usize {
         self.rope.len_lines().max(1)
     }
@@ -27,4 +31,20 @@ impl EditorBuffer {
     pub fn line(&self, line_idx: usize) -> String {
         self.rope.line(line_idx).to_string()
     }
+
+    /// Lines starting with any of `prefixes`, by index. Other lines are
+    /// skipped without being copied.
+    pub fn lines_starting_with(&self, prefixes: &[&str]) -> Vec<(usize, String)> {
+        self.rope
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                prefixes.iter().any(|prefix| {
+                    line.len_chars() >= prefix.chars().count()
+                        && line.chars().zip(prefix.chars()).all(|(a, b)| a == b)
+                })
+            })
+            .map(|(idx, line)| (idx, line.to_string()))
+            .collect()
+    }
 }
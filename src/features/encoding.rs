@@ -0,0 +1,115 @@
+//! Text encodings of files on disk.
+//!
+//! Files are read as UTF-8 when they are valid UTF-8 and as UTF-16 when
+//! they start with its byte order mark. Anything else is read as Latin-1,
+//! which maps every byte to a character and so saves back unchanged.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// UTF-8 starting with a byte order mark, kept when saving.
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    /// Name shown in the status bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf8Bom => "UTF-8 with BOM",
+            Self::Utf16Le => "UTF-16 LE",
+            Self::Utf16Be => "UTF-16 BE",
+            Self::Latin1 => "Latin-1",
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Decodes file contents, returning the text and the encoding it was
+/// read with.
+pub fn decode(bytes: &[u8]) -> (String, Encoding) {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        if let Ok(text) = std::str::from_utf8(rest) {
+            return (text.to_string(), Encoding::Utf8Bom);
+        }
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (text.to_string(), Encoding::Utf8);
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        if let Some(text) = decode_utf16(rest, u16::from_le_bytes) {
+            return (text, Encoding::Utf16Le);
+        }
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        if let Some(text) = decode_utf16(rest, u16::from_be_bytes) {
+            return (text, Encoding::Utf16Be);
+        }
+    }
+    let text = bytes.iter().map(|&byte| byte as char).collect();
+    (text, Encoding::Latin1)
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units).collect::<Result<_, _>>().ok()
+}
+
+/// Encodes `text` for writing. Fails when a character has no Latin-1 code.
+pub fn encode(text: &str, encoding: Encoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        Encoding::Utf8 => Ok(text.as_bytes().to_vec()),
+        Encoding::Utf8Bom => Ok([UTF8_BOM, text.as_bytes()].concat()),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let (bom, unit): (_, fn(u16) -> [u8; 2]) = if encoding == Encoding::Utf16Le {
+                (UTF16_LE_BOM, u16::to_le_bytes)
+            } else {
+                (UTF16_BE_BOM, u16::to_be_bytes)
+            };
+            let mut bytes = bom.to_vec();
+            bytes.extend(text.encode_utf16().flat_map(unit));
+            Ok(bytes)
+        }
+        Encoding::Latin1 => text
+            .chars()
+            .map(|ch| {
+                u8::try_from(u32::from(ch))
+                    .map_err(|_| format!("'{ch}' can't be saved as Latin-1, convert to UTF-8"))
+            })
+            .collect(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_each_encoding() {
+        let cases: [(&[u8], &str, Encoding); 4] = [
+            (b"caf\xc3\xa9", "café", Encoding::Utf8),
+            (b"\xef\xbb\xbfid", "id", Encoding::Utf8Bom),
+            (b"\xff\xfeh\x00i\x00", "hi", Encoding::Utf16Le),
+            (b"caf\xe9", "café", Encoding::Latin1),
+        ];
+        for (bytes, text, encoding) in cases {
+            assert_eq!(decode(bytes), (text.to_string(), encoding));
+            assert_eq!(encode(text, encoding).as_deref(), Ok(bytes));
+        }
+        assert!(encode("€", Encoding::Latin1).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Files and directories left out of the file tree, the fuzzy finder and
+//! the workspace index and search.
+//!
+//! Patterns are gitignore globs: a bare name matches at any depth, a
+//! trailing `/` only matches directories and a leading `/` anchors to the
+//! workspace root. The built-in patterns always apply, the `exclude`
+//! preference and the project's `exclude` setting add to them.
+//!
+//! Showing hidden files turns all of it off, along with skipping dotfiles
+//! and git ignored files where the listing does so.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+
+/// Tool state and dependency folders no workspace wants listed.
+const BUILTIN: &[&str] = &[
+    ".git/",
+    "node_modules/",
+    "target/",
+    "__pycache__/",
+    ".claude/",
+    ".DS_Store",
+];
+
+/// Version control folders, never written to even while hidden files show.
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// Default of the `exclude` preference, build output users may want back.
+pub const DEFAULT_EXCLUDE: &str = "dist/, build/, .next/";
+
+/// Whether `path` is inside a version control folder.
+pub fn in_vcs_dir(path: &Path) -> bool {
+    path.components()
+        .any(|part| VCS_DIRS.iter().any(|dir| part.as_os_str() == *dir))
+}
+
+/// Patterns of a comma separated list, like the `exclude` preference.
+pub fn parse(spec: &str) -> impl Iterator<Item = String> + '_ {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+}
+
+#[derive(Debug, Clone)]
+pub struct Exclusions {
+    globs: Gitignore,
+    show_hidden: bool,
+}
+
+impl Exclusions {
+    /// The built-in patterns and `patterns` for the workspace at `root`.
+    /// Patterns that are not valid globs are skipped.
+    pub fn new(root: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in BUILTIN
+            .iter()
+            .copied()
+            .chain(patterns.iter().map(String::as_str))
+        {
+            let _ = builder.add_line(None, pattern);
+        }
+        Self {
+            globs: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            show_hidden: false,
+        }
+    }
+
+    /// The same exclusions, turned off while `show_hidden` is set.
+    pub fn with_hidden(self, show_hidden: bool) -> Self {
+        Self {
+            show_hidden,
+            ..self
+        }
+    }
+
+    pub fn shows_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    /// Whether `path`, under the workspace root, is left out. Only the path
+    /// itself is matched, so walks should not enter excluded directories.
+    pub fn excludes(&self, path: &Path, is_dir: bool) -> bool {
+        !self.show_hidden && self.globs.matched(path, is_dir).is_ignore()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_builtin_and_user_patterns() {
+        let root = Path::new("/work");
+        let patterns: Vec<String> = parse(DEFAULT_EXCLUDE)
+            .chain(parse("vendor, *.min.js, /docs/out/"))
+            .collect();
+        let exclusions = Exclusions::new(root, &patterns);
+
+        assert!(exclusions.excludes(&root.join("node_modules"), true));
+        assert!(exclusions.excludes(&root.join("web/dist"), true));
+        assert!(!exclusions.excludes(&root.join("src/dist.rs"), false));
+        assert!(exclusions.excludes(&root.join("pkg/vendor"), true));
+        assert!(exclusions.excludes(&root.join("static/app.min.js"), false));
+        assert!(exclusions.excludes(&root.join("docs/out"), true));
+        assert!(!exclusions.excludes(&root.join("api/docs/out"), true));
+        assert!(!exclusions.excludes(&root.join("src/main.rs"), false));
+
+        let exclusions = exclusions.with_hidden(true);
+        assert!(!exclusions.excludes(&root.join("node_modules"), true));
+        assert!(in_vcs_dir(&root.join(".git/config")));
+        assert!(!in_vcs_dir(&root.join("src/.github.rs")));
+    }
+}
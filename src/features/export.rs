@@ -0,0 +1,127 @@
+//! Exporting a buffer, or part of it, as a standalone HTML page that
+//! looks like the editor: the same syntax highlighting, the active theme's
+//! colors and the editor font when the reader has it installed.
+
+use crate::theme::theme;
+use iced::Color;
+use syntect::highlighting::{FontStyle, HighlightIterator, HighlightState, Highlighter};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
+
+fn css_color(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str, html: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(c),
+        }
+    }
+}
+
+/// `text` as an HTML page titled `title`, highlighted as the syntax of
+/// files ending in `extension` and set in `font_family`. Lines are
+/// numbered from `first_line`, 1-based, and the numbers are left out when
+/// the code is copied.
+pub fn to_html(
+    title: &str,
+    text: &str,
+    extension: &str,
+    font_family: &str,
+    first_line: usize,
+) -> String {
+    let colors = theme();
+    let syntax_set = SyntaxSet::load_defaults_newlines();
+    let syntax = syntax_set
+        .find_syntax_by_extension(extension)
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+    let highlighter = Highlighter::new(&colors.syntax_theme);
+    let mut parse_state = ParseState::new(syntax);
+    let mut highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
+
+    let mut body = String::new();
+    let lines: Vec<&str> = text.split('\n').collect();
+    let width = (first_line + lines.len()).to_string().len();
+    for (idx, line) in lines.iter().enumerate() {
+        body.push_str(&format!(
+            "<span class=\"ln\">{:>width$}</span>",
+            first_line + idx
+        ));
+        let line = format!("{}\n", line.trim_end_matches('\r'));
+        let ops = parse_state
+            .parse_line(&line, &syntax_set)
+            .unwrap_or_default();
+        for (style, fragment) in
+            HighlightIterator::new(&mut highlight_state, &ops, &line, &highlighter)
+        {
+            let fragment = fragment.trim_end_matches('\n');
+            if fragment.is_empty() {
+                continue;
+            }
+            let fg = style.foreground;
+            let mut css = format!("color:#{:02x}{:02x}{:02x}", fg.r, fg.g, fg.b);
+            if style.font_style.contains(FontStyle::BOLD) {
+                css.push_str(";font-weight:bold");
+            }
+            if style.font_style.contains(FontStyle::ITALIC) {
+                css.push_str(";font-style:italic");
+            }
+            body.push_str(&format!("<span style=\"{css}\">"));
+            escape(fragment, &mut body);
+            body.push_str("</span>");
+        }
+        body.push('\n');
+    }
+
+    let mut page =
+        String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>");
+    escape(title, &mut page);
+    page.push_str("</title>\n<style>\n");
+    page.push_str(&format!(
+        "body {{ margin: 0; background: {bg}; color: {fg}; }}\n\
+         pre {{ margin: 0; padding: 16px; font-family: \"{font}\", ui-monospace, monospace; \
+         font-size: 13px; line-height: 1.5; tab-size: 4; }}\n\
+         .ln {{ color: {dim}; margin-right: 16px; user-select: none; }}\n",
+        bg = css_color(colors.bg_editor),
+        fg = css_color(colors.text_primary),
+        dim = css_color(colors.text_dim),
+        font = font_family.replace('"', ""),
+    ));
+    page.push_str("</style>\n</head>\n<body>\n<pre><code>");
+    page.push_str(body.trim_end_matches('\n'));
+    page.push_str("</code></pre>\n</body>\n</html>\n");
+    page
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_highlighted_escaped_html() {
+        let html = to_html(
+            "main.rs",
+            "fn main() {\n    let a = 1 < 2 && true;\n}",
+            "rs",
+            "Fira Code",
+            9,
+        );
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>main.rs</title>"));
+        assert!(html.contains("&lt;"));
+        assert!(html.contains("&amp;"));
+        assert!(!html.contains("1 < 2") && !html.contains("&&"));
+        assert!(html.contains("<span class=\"ln\"> 9</span>"));
+        assert!(html.contains("<span class=\"ln\">11</span>"));
+        assert!(html.contains("<span style=\"color:#"));
+    }
+}
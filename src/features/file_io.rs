@@ -0,0 +1,271 @@
+//! Reading and writing files on disk, with errors that say what went
+//! wrong in words a user can act on.
+//!
+//! Saves write a temporary file next to the target, flush it to disk and
+//! rename it over the target, so a crash mid-save leaves either the old or
+//! the new contents and never a truncated file.
+
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Temp files created by this process, numbering the next one.
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Copies kept of a file's previous contents when it is saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backup {
+    #[default]
+    Off,
+    /// A single `name~` copy, replaced on every save.
+    Tilde,
+    /// `name.~1~`, `name.~2~` and so on, one per save.
+    Numbered,
+}
+
+impl Backup {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "tilde" => Self::Tilde,
+            "numbered" => Self::Numbered,
+            _ => Self::Off,
+        }
+    }
+
+    /// Name used in the preferences file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Tilde => "tilde",
+            Self::Numbered => "numbered",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Tilde => "file~",
+            Self::Numbered => "Numbered",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Tilde,
+            Self::Tilde => Self::Numbered,
+            Self::Numbered => Self::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileError {
+    NotFound(PathBuf),
+    PermissionDenied(PathBuf),
+    /// A folder on the way to the file could not be created.
+    MissingDirectory(PathBuf, String),
+    IsDirectory(PathBuf),
+    ReadOnly(PathBuf),
+    /// The text can't be written in the file's encoding.
+    Encoding(String),
+    Other(PathBuf, String),
+}
+
+impl FileError {
+    fn from_io(path: &Path, err: &std::io::Error) -> Self {
+        let path = path.to_path_buf();
+        match err.kind() {
+            ErrorKind::NotFound => Self::NotFound(path),
+            ErrorKind::PermissionDenied => Self::PermissionDenied(path),
+            ErrorKind::IsADirectory => Self::IsDirectory(path),
+            ErrorKind::ReadOnlyFilesystem => Self::ReadOnly(path),
+            _ => Self::Other(path, err.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "{} does not exist", path.display()),
+            Self::PermissionDenied(path) => write!(f, "Permission denied: {}", path.display()),
+            Self::MissingDirectory(path, err) => {
+                write!(f, "Could not create folder {}: {err}", path.display())
+            }
+            Self::IsDirectory(path) => write!(f, "{} is a folder", path.display()),
+            Self::ReadOnly(path) => {
+                write!(f, "{} is on a read-only file system", path.display())
+            }
+            Self::Encoding(err) => write!(f, "{err}"),
+            Self::Other(path, err) => write!(f, "{}: {err}", path.display()),
+        }
+    }
+}
+
+pub fn read(path: &Path) -> Result<Vec<u8>, FileError> {
+    fs::read(path).map_err(|err| FileError::from_io(path, &err))
+}
+
+/// FNV-1a hash of `bytes`, for naming files kept in the config folder.
+/// Unlike `DefaultHasher` it gives the same value across Rust releases.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Reads at most `limit` bytes from the start of `path`.
+pub fn read_prefix(path: &Path, limit: u64) -> Result<Vec<u8>, FileError> {
+    use std::io::Read;
+
+    let mut bytes = Vec::new();
+    fs::File::open(path)
+        .and_then(|file| file.take(limit).read_to_end(&mut bytes))
+        .map_err(|err| FileError::from_io(path, &err))?;
+    Ok(bytes)
+}
+
+/// Writes `bytes` to `path`, creating missing parent folders first and
+/// keeping a backup of the old contents when asked to.
+pub fn write(path: &Path, bytes: &[u8], backup: Backup) -> Result<(), FileError> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty());
+    if let Some(parent) = parent {
+        fs::create_dir_all(parent)
+            .map_err(|err| FileError::MissingDirectory(parent.to_path_buf(), err.to_string()))?;
+    }
+    // Replace the file a symlink points to, not the link
+    let target = if path.is_symlink() {
+        fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    } else {
+        path.to_path_buf()
+    };
+    if target.is_file() {
+        if let Some(backup_path) = backup_path(&target, backup) {
+            fs::copy(&target, &backup_path)
+                .map_err(|err| FileError::from_io(&backup_path, &err))?;
+        }
+    }
+    write_atomic(&target, bytes).map_err(|err| FileError::from_io(path, &err))
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    // Renaming would replace a read-only file that writing to it can't
+    if fs::metadata(path).is_ok_and(|metadata| metadata.permissions().readonly()) {
+        return Err(ErrorKind::PermissionDenied.into());
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    // Saves of the same file can run at once, each gets its own temp file
+    let (tmp, mut file) = loop {
+        let count = TEMP_FILES.fetch_add(1, Ordering::Relaxed);
+        let tmp = dir.join(format!(".{name}.{}.{count}.tmp", std::process::id()));
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)
+        {
+            Ok(file) => break (tmp, file),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            // A folder we can't create files in may still hold a writable file
+            Err(err) if err.kind() == ErrorKind::PermissionDenied => return fs::write(path, bytes),
+            Err(err) => return Err(err),
+        }
+    };
+
+    let written = (|| {
+        file.write_all(bytes)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&tmp, metadata.permissions())?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return written;
+    }
+    // Make the rename itself durable; not every platform can open folders
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// Where the backup of `path` goes, `None` when backups are off.
+fn backup_path(path: &Path, backup: Backup) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    match backup {
+        Backup::Off => None,
+        Backup::Tilde => Some(path.with_file_name(format!("{name}~"))),
+        Backup::Numbered => {
+            let prefix = format!("{name}.~");
+            let last = fs::read_dir(path.parent()?)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|entry| {
+                    let entry_name = entry.file_name().to_string_lossy().to_string();
+                    entry_name
+                        .strip_prefix(&prefix)?
+                        .strip_suffix('~')?
+                        .parse::<u32>()
+                        .ok()
+                })
+                .max()
+                .unwrap_or(0);
+            Some(path.with_file_name(format!("{name}.~{}~", last + 1)))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hash_is_fnv1a() {
+        assert_eq!(stable_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(stable_hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn describes_common_failures() {
+        let dir = std::env::temp_dir().join(format!("pinel-file-io-{}", std::process::id()));
+        let missing = dir.join("missing.txt");
+        assert_eq!(read(&missing), Err(FileError::NotFound(missing.clone())));
+
+        let nested = dir.join("a/b/c.txt");
+        assert_eq!(write(&nested, b"hi", Backup::Numbered), Ok(()));
+        assert_eq!(write(&nested, b"hi again", Backup::Numbered), Ok(()));
+        assert_eq!(write(&nested, b"bye", Backup::Numbered), Ok(()));
+        assert_eq!(read(&nested).as_deref(), Ok(&b"bye"[..]));
+        assert_eq!(
+            read(&dir.join("a/b/c.txt.~2~")).as_deref(),
+            Ok(&b"hi again"[..])
+        );
+        assert!(matches!(read(&dir), Err(FileError::IsDirectory(_))));
+
+        let mut permissions = fs::metadata(&nested).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&nested, permissions).unwrap();
+        assert_eq!(
+            write(&nested, b"no", Backup::Off),
+            Err(FileError::PermissionDenied(nested.clone()))
+        );
+        assert_eq!(read(&nested).as_deref(), Ok(&b"bye"[..]));
+        assert_eq!(
+            FileError::PermissionDenied(PathBuf::from("/etc/x")).to_string(),
+            "Permission denied: /etc/x"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::features::exclusions::Exclusions;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -26,18 +27,20 @@ pub struct FileTree {
     // Storing only expanded ones, not collapsed ones to save memory
     // Collapsed ones are simply all of those that are not expanded
     pub selected: Option<PathBuf>, // The currently selected FileEntry
+    pub exclusions: Exclusions,    // Files and folders left out of the tree
 }
 
 impl FileTree {
-    pub fn new(root: PathBuf) -> Self {
+    pub fn new(root: PathBuf, exclusions: Exclusions) -> Self {
         // This creates a new file tree that is rooted at a given path
-        let entries = scan_directory(&root); // Scans the directory and builds a FileEntry vector for it
+        let entries = scan_directory(&root, &exclusions); // Scans the directory and builds a FileEntry vector for it
         Self {
             // Creates and stores a new FileTree instance
             root,
             entries,
             expanded: HashSet::new(),
             selected: None,
+            exclusions,
         }
     }
 
@@ -48,7 +51,7 @@ impl FileTree {
             self.expanded.remove(path); // If it finds it, it removes the path from the HashSet, marking the FileEntry as collapsed
         } else {
             self.expanded.insert(path.to_path_buf()); // If not found, adds it to the HashSet
-            populate_children(&mut self.entries, path); // Lazily load this folder's contents
+            populate_children(&mut self.entries, path, &self.exclusions); // Lazily load this folder's contents
         }
     }
 
@@ -57,6 +60,24 @@ impl FileTree {
         self.expanded.contains(path)
     }
 
+    pub fn reveal(&mut self, path: &Path) -> bool {
+        // Expands every folder between the root and `path`, then selects it
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false; // Files outside the open folder have no entry
+        };
+        let mut folder = self.root.clone();
+        if let Some(parent) = relative.parent() {
+            for component in parent.components() {
+                folder.push(component);
+                self.expanded.insert(folder.clone());
+                populate_children(&mut self.entries, &folder, &self.exclusions);
+                // Loads the folder like opening it by hand
+            }
+        }
+        self.selected = Some(path.to_path_buf());
+        true
+    }
+
     pub fn select(&mut self, path: PathBuf) {
         // Selecting a folder/file
         self.selected = Some(path);
@@ -64,27 +85,17 @@ impl FileTree {
 
     pub fn refresh(&mut self) {
         // Refresh the directory to see if a new file is created
-        self.entries = scan_directory(&self.root);
+        self.entries = scan_directory(&self.root, &self.exclusions);
         let mut expanded: Vec<PathBuf> = self.expanded.iter().cloned().collect();
         expanded.sort_by_key(|p| p.components().count());
         for path in expanded {
-            populate_children(&mut self.entries, &path);
+            populate_children(&mut self.entries, &path, &self.exclusions);
         }
     }
 }
 
-/// List of directories to ignore when scanning, since they are hidden or just bloat
-const IGNORED_DIRS: &[&str] = &[
-    ".git",
-    "node_modules",
-    "target",
-    ".DS_Store",
-    "__pycache__",
-    ".claude",
-];
-
 /// Scan a directory and return a list of FileEntry
-fn scan_directory(path: &Path) -> Vec<FileEntry> {
+fn scan_directory(path: &Path, exclusions: &Exclusions) -> Vec<FileEntry> {
     let mut entries = Vec::new(); // An empty vector of entires
 
     let Ok(read_dir) = fs::read_dir(path) else {
@@ -97,8 +108,8 @@ fn scan_directory(path: &Path) -> Vec<FileEntry> {
         let entry_path = entry.path();
         let name = entry.file_name().to_string_lossy().to_string();
 
-        if IGNORED_DIRS.contains(&name.as_str()) {
-            // Checks if the entry is part of the ignored
+        if exclusions.excludes(&entry_path, entry_path.is_dir()) {
+            // Checks if the entry is left out by the exclusion patterns
             continue;
         }
 
@@ -139,16 +150,16 @@ fn scan_directory(path: &Path) -> Vec<FileEntry> {
     return entries;
 }
 
-fn populate_children(entries: &mut Vec<FileEntry>, target: &Path) {
+fn populate_children(entries: &mut Vec<FileEntry>, target: &Path, exclusions: &Exclusions) {
     for entry in entries.iter_mut() {
         if let FileEntry::Directory { path, children, .. } = entry {
             if path == target {
                 if children.is_empty() {
-                    *children = scan_directory(path);
+                    *children = scan_directory(path, exclusions);
                 }
                 return;
             }
-            populate_children(children, target);
+            populate_children(children, target, exclusions);
         }
     }
 }
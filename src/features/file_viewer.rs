@@ -0,0 +1,96 @@
+//! Read-only views of files that aren't text. Images are drawn as they
+//! are and anything else shows a hex dump of its first bytes, rather than
+//! loading the bytes into an editor.
+
+use iced::widget::{image, svg};
+use std::path::Path;
+
+/// Bytes looked at when deciding whether a file is binary.
+const SNIFF_BYTES: usize = 8000;
+/// Bytes shown in a hex dump.
+pub const HEX_BYTES: usize = 16 * 1024;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico"];
+
+#[derive(Debug, Clone)]
+pub enum Viewer {
+    Image(image::Handle),
+    Svg(svg::Handle),
+    Hex { dump: String, size: usize },
+}
+
+/// The viewer for a file, or `None` when it should open as text.
+pub fn for_file(path: &Path, bytes: &[u8]) -> Option<Viewer> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if extension == "svg" {
+        return Some(Viewer::Svg(svg::Handle::from_memory(bytes.to_vec())));
+    }
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(Viewer::Image(image::Handle::from_bytes(bytes.to_vec())));
+    }
+    is_binary(bytes).then(|| Viewer::Hex {
+        dump: hex_dump(&bytes[..bytes.len().min(HEX_BYTES)]),
+        size: bytes.len(),
+    })
+}
+
+/// Whether `bytes` look like binary data: a NUL byte near the start, the
+/// same test git uses. UTF-16 text is left to the encoding detection.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.starts_with(&[0xFF, 0xFE]) || bytes.starts_with(&[0xFE, 0xFF]) {
+        return false;
+    }
+    bytes[..bytes.len().min(SNIFF_BYTES)].contains(&0)
+}
+
+/// Offset, hex bytes and printable characters, 16 bytes per line.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        out.push_str(&format!("{:08x}  ", row * 16));
+        for col in 0..16 {
+            match chunk.get(col) {
+                Some(byte) => out.push_str(&format!("{byte:02x} ")),
+                None => out.push_str("   "),
+            }
+            if col == 7 {
+                out.push(' ');
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_binary_and_dumps_hex() {
+        assert!(is_binary(b"ELF\0\x01"));
+        assert!(!is_binary(b"plain text"));
+        assert!(!is_binary(b"\xff\xfeh\0i\0"));
+        assert_eq!(
+            hex_dump(b"Hi\0"),
+            "00000000  48 69 00                                          |Hi.|\n"
+        );
+        assert!(for_file(Path::new("notes.txt"), b"text").is_none());
+    }
+}
@@ -1,12 +1,71 @@
 /// Find and Replace - In-editor find and replace with case-sensitive toggle,
 /// match navigation, replace-one, and replace-all.
 /// Ported from pinel's hotkey/find_replace.rs, adapted for iced.
+use crate::features::exclusions;
+use crate::features::file_io::{self, Backup, FileError};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+/// Where find and replace looks for matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+    /// The text that was selected when the scope was picked.
+    Selection,
+    #[default]
+    File,
+    OpenFiles,
+    /// Every file in the open folder, including ones that aren't open.
+    Workspace,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 4] = [
+        Scope::Selection,
+        Scope::File,
+        Scope::OpenFiles,
+        Scope::Workspace,
+    ];
+
+    /// Whether matches are counted over several files.
+    pub fn is_multi_file(self) -> bool {
+        matches!(self, Scope::OpenFiles | Scope::Workspace)
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scope::Selection => "Selection",
+            Scope::File => "File",
+            Scope::OpenFiles => "Open Files",
+            Scope::Workspace => "Workspace",
+        })
+    }
+}
+
+/// Outcome of replacing in several files.
+#[derive(Debug, Clone, Default)]
+pub struct Replaced {
+    pub matches: usize,
+    pub files: usize,
+    pub errors: Vec<FileError>,
+}
+
+#[derive(Debug, Clone)]
 pub struct FindReplace {
     pub open: bool,
     pub find_text: String,
     pub replace_text: String,
     pub case_sensitive: bool,
+    /// Replace `Color` and `COLOR` with `Colour` and `COLOUR`.
+    pub preserve_case: bool,
+    pub scope: Scope,
+    /// Byte range searched in the selection scope.
+    pub selection: Option<(usize, usize)>,
+    /// Files with matches in the multi-file scopes.
+    pub files_matched: usize,
     pub match_count: usize,
     pub current_match: usize,
     pub matches: Vec<usize>,
@@ -19,6 +78,10 @@ impl Default for FindReplace {
             find_text: String::new(),
             replace_text: String::new(),
             case_sensitive: false,
+            preserve_case: false,
+            scope: Scope::File,
+            selection: None,
+            files_matched: 0,
             match_count: 0,
             current_match: 0,
             matches: Vec::new(),
@@ -41,9 +104,20 @@ impl FindReplace {
     }
 
     pub fn find_matches(&mut self, text: &str) -> Vec<usize> {
+        let mut found_matches = self.positions(text);
+        if let (Scope::Selection, Some((start, end))) = (self.scope, self.selection) {
+            let len = self.find_text.len();
+            found_matches.retain(|&pos| pos >= start && pos + len <= end);
+        }
+
+        self.matches = found_matches.clone();
+        self.match_count = found_matches.len();
+        found_matches
+    }
+
+    /// Byte offsets of every match in `text`, ignoring the scope.
+    fn positions(&self, text: &str) -> Vec<usize> {
         if self.find_text.is_empty() {
-            self.matches.clear();
-            self.match_count = 0;
             return Vec::new();
         }
 
@@ -64,12 +138,95 @@ impl FindReplace {
             found_matches.push(start + pos);
             start += pos + 1;
         }
-
-        self.matches = found_matches.clone();
-        self.match_count = found_matches.len();
         found_matches
     }
 
+    /// Number of matches in another file's `text`, for the multi-file scopes.
+    pub fn count_in(&self, text: &str) -> usize {
+        self.positions(text).len()
+    }
+
+    /// Replaces every match in another file's `text`, returning how many
+    /// were replaced.
+    pub fn replace_in(&self, text: &mut String) -> usize {
+        let positions = self.positions(text);
+        let mut last_end = usize::MAX;
+        let mut count = 0;
+        for &pos in positions.iter().rev() {
+            let end = pos + self.find_text.len();
+            // Overlapping matches were already replaced
+            if end > last_end {
+                continue;
+            }
+            let replacement = self.replacement_for(&text[pos..end]);
+            text.replace_range(pos..end, &replacement);
+            last_end = pos;
+            count += 1;
+        }
+        count
+    }
+
+    /// Matches and files with matches among `files`, reading the text of
+    /// open ones from `open` rather than from disk. Stops early, with the
+    /// counts so far, once `cancelled` is set.
+    pub fn count_in_files(
+        &self,
+        files: &[PathBuf],
+        open: &HashMap<PathBuf, String>,
+        cancelled: &AtomicBool,
+    ) -> (usize, usize) {
+        let mut matches = 0;
+        let mut matched_files = 0;
+        for path in files {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let count = match open.get(path) {
+                Some(text) => self.count_in(text),
+                None => std::fs::read_to_string(path).map_or(0, |text| self.count_in(&text)),
+            };
+            if count > 0 {
+                matches += count;
+                matched_files += 1;
+            }
+        }
+        (matches, matched_files)
+    }
+
+    /// Replaces every match in `files` on disk. Files that aren't UTF-8
+    /// text, and those in version control folders, are left alone.
+    pub fn replace_in_files(&self, files: &[PathBuf], backup: Backup) -> Replaced {
+        let mut replaced = Replaced::default();
+        for path in files {
+            if exclusions::in_vcs_dir(path) {
+                continue;
+            }
+            let Ok(mut text) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let count = self.replace_in(&mut text);
+            if count == 0 {
+                continue;
+            }
+            match file_io::write(path, text.as_bytes(), backup) {
+                Ok(()) => {
+                    replaced.matches += count;
+                    replaced.files += 1;
+                }
+                Err(err) => replaced.errors.push(err),
+            }
+        }
+        replaced
+    }
+
+    /// Keeps the selection scope covering the same text after a match in it
+    /// was replaced.
+    fn shift_selection(&mut self, matched_len: usize, replacement_len: usize) {
+        if let Some((_, end)) = self.selection.as_mut() {
+            *end = (*end + replacement_len).saturating_sub(matched_len);
+        }
+    }
+
     pub fn go_to_next_match(&mut self) {
         if !self.matches.is_empty() {
             self.current_match = (self.current_match + 1) % self.matches.len();
@@ -93,7 +250,9 @@ impl FindReplace {
 
         let pos = self.matches[self.current_match];
         let end = pos + self.find_text.len();
-        text.replace_range(pos..end, &self.replace_text);
+        let replacement = self.replacement_for(&text[pos..end]);
+        text.replace_range(pos..end, &replacement);
+        self.shift_selection(end - pos, replacement.len());
 
         self.find_matches(text);
 
@@ -111,9 +270,11 @@ impl FindReplace {
 
         let count = self.matches.len();
 
-        for &pos in self.matches.iter().rev() {
+        for pos in std::mem::take(&mut self.matches).into_iter().rev() {
             let end = pos + self.find_text.len();
-            text.replace_range(pos..end, &self.replace_text);
+            let replacement = self.replacement_for(&text[pos..end]);
+            text.replace_range(pos..end, &replacement);
+            self.shift_selection(end - pos, replacement.len());
         }
 
         self.matches.clear();
@@ -123,9 +284,24 @@ impl FindReplace {
         count
     }
 
+    fn replacement_for(&self, matched: &str) -> String {
+        if self.preserve_case {
+            preserve_case(matched, &self.replace_text)
+        } else {
+            self.replace_text.clone()
+        }
+    }
+
     pub fn match_status(&self) -> String {
         if self.find_text.is_empty() {
             String::new()
+        } else if self.scope.is_multi_file() && self.match_count > 0 {
+            let files = if self.files_matched == 1 {
+                "file"
+            } else {
+                "files"
+            };
+            format!("{} in {} {files}", self.match_count, self.files_matched)
         } else if self.match_count > 0 {
             format!("{} of {}", self.current_match + 1, self.match_count)
         } else {
@@ -133,3 +309,95 @@ impl FindReplace {
         }
     }
 }
+
+/// `replacement` in the case `matched` was written in: ALL CAPS, all lower
+/// case, Title Case per word, or with the first letter's case copied over
+/// for PascalCase and camelCase.
+pub fn preserve_case(matched: &str, replacement: &str) -> String {
+    let has_letters = matched.chars().any(char::is_alphabetic);
+    if has_letters && !matched.chars().any(char::is_lowercase) {
+        return replacement.to_uppercase();
+    }
+    if has_letters && !matched.chars().any(char::is_uppercase) {
+        return replacement.to_lowercase();
+    }
+    let words: Vec<&str> = matched.split_whitespace().collect();
+    if words.len() > 1 && words.iter().all(|word| starts_uppercase(word)) {
+        return replacement
+            .split(' ')
+            .map(|word| with_first(word, true))
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    with_first(replacement, starts_uppercase(matched))
+}
+
+fn starts_uppercase(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+}
+
+fn with_first(word: &str, upper: bool) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) if upper => first.to_uppercase().chain(chars).collect(),
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_every_case_variant() {
+        let mut find_replace = FindReplace {
+            find_text: "color".to_string(),
+            replace_text: "colour".to_string(),
+            preserve_case: true,
+            ..Default::default()
+        };
+        let mut text = "color Color COLOR textColor".to_string();
+        find_replace.find_matches(&text);
+        assert_eq!(find_replace.replace_all(&mut text), 4);
+        assert_eq!(text, "colour Colour COLOUR textColour");
+
+        let mut text = "color Color COLOR".to_string();
+        find_replace.scope = Scope::Selection;
+        find_replace.selection = Some((0, 11));
+        find_replace.find_matches(&text);
+        assert_eq!(find_replace.replace_all(&mut text), 2);
+        assert_eq!(text, "colour Colour COLOR");
+
+        assert_eq!(preserve_case("fooBar", "bazQux"), "bazQux");
+        assert_eq!(preserve_case("FooBar", "bazQux"), "BazQux");
+        assert_eq!(preserve_case("Light Gray", "dark grey"), "Dark Grey");
+    }
+
+    #[test]
+    fn counts_open_files_until_cancelled() {
+        let find_replace = FindReplace {
+            find_text: "todo".to_string(),
+            ..Default::default()
+        };
+        let files = vec![PathBuf::from("/a.rs"), PathBuf::from("/b.rs")];
+        let open = HashMap::from([
+            (files[0].clone(), "todo todo".to_string()),
+            (files[1].clone(), "done".to_string()),
+        ]);
+        let cancelled = AtomicBool::new(false);
+        assert_eq!(
+            find_replace.count_in_files(&files, &open, &cancelled),
+            (2, 1)
+        );
+        cancelled.store(true, Ordering::Relaxed);
+        assert_eq!(
+            find_replace.count_in_files(&files, &open, &cancelled),
+            (0, 0)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Editor font family and size, zooming the size, and font files added
+//! by the user.
+//!
+//! Font files in the `fonts` folder of the config directory are loaded at
+//! startup. Their families can be picked as the editor font, and glyphs
+//! missing from the other fonts, like Nerd Font icons, fall back to them.
+
+use crate::config::theme_manager::get_config_dir;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+pub const DEFAULT_FAMILY: &str = "Fira Code";
+pub const DEFAULT_SIZE: f32 = 13.0;
+pub const MIN_SIZE: f32 = 6.0;
+pub const MAX_SIZE: f32 = 48.0;
+/// Points added or removed by one zoom step.
+const ZOOM_STEP: f32 = 1.0;
+
+/// Extensions of the font files loaded from the fonts folder.
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+
+/// Family names handed to iced, which only takes `&'static str`. Each name
+/// is leaked once.
+static FAMILY_NAMES: Lazy<Mutex<HashMap<String, &'static str>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The editor font for a family name.
+pub fn editor_font(family: &str) -> iced::Font {
+    let name = match FAMILY_NAMES.lock() {
+        Ok(mut names) => *names
+            .entry(family.to_string())
+            .or_insert_with(|| Box::leak(family.to_string().into_boxed_str())),
+        Err(_) => DEFAULT_FAMILY,
+    };
+    iced::Font {
+        family: iced::font::Family::Name(name),
+        weight: iced::font::Weight::Normal,
+        ..iced::Font::DEFAULT
+    }
+}
+
+/// Monospaced font families installed on the system, sorted by name. The
+/// bundled Fira Code is always among them.
+pub fn monospace_families() -> Vec<String> {
+    let mut font_system = iced::advanced::graphics::text::font_system()
+        .write()
+        .expect("font system lock");
+    let mut families: Vec<String> = font_system
+        .raw()
+        .db()
+        .faces()
+        .filter(|face| face.monospaced)
+        .filter_map(|face| face.families.first().map(|(name, _)| name.clone()))
+        .collect();
+    families.push(DEFAULT_FAMILY.to_string());
+    families.sort_unstable_by_key(|name| name.to_lowercase());
+    families.dedup();
+    families
+}
+
+pub fn fonts_dir() -> PathBuf {
+    get_config_dir().join("fonts")
+}
+
+fn is_font_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Contents of the font files in the fonts folder, in name order. Files
+/// that cannot be read are skipped.
+pub fn user_fonts() -> Vec<Vec<u8>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(fonts_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_font_file(path))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| fs::read(path).ok())
+        .collect()
+}
+
+pub fn clamp_size(size: f32) -> f32 {
+    size.clamp(MIN_SIZE, MAX_SIZE)
+}
+
+/// `size` zoomed in (`steps` > 0) or out.
+pub fn zoomed(size: f32, steps: i32) -> f32 {
+    clamp_size(size + steps as f32 * ZOOM_STEP)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zooming_stays_in_range() {
+        assert_eq!(zoomed(DEFAULT_SIZE, 1), 14.0);
+        assert_eq!(zoomed(DEFAULT_SIZE, -2), 11.0);
+        assert_eq!(zoomed(MIN_SIZE, -1), MIN_SIZE);
+        assert_eq!(zoomed(MAX_SIZE, 3), MAX_SIZE);
+        assert!(is_font_file(std::path::Path::new("Hack Nerd Font.TTF")));
+        assert!(!is_font_file(std::path::Path::new("LICENSE.txt")));
+    }
+}
@@ -0,0 +1,112 @@
+//! Save actions and external formatters.
+//!
+//! Formatters are configured per language as `language: command` entries
+//! separated by `;`, e.g. `rust: rustfmt; python: black -q -`. The buffer
+//! is piped to the command and its output replaces the buffer. `{file}` in
+//! a command is replaced by the path of the file being formatted.
+
+use crate::language::Language;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub const DEFAULT_FORMATTERS: &str = "rust: rustfmt --edition 2021; python: black -q -; \
+     go: gofmt; javascript: prettier --stdin-filepath {file}; \
+     typescript: prettier --stdin-filepath {file}";
+
+/// Formatter command for `language`, if one is configured.
+pub fn command_for(spec: &str, language: Language) -> Option<String> {
+    let name = language.name().to_lowercase();
+    spec.split(';').find_map(|entry| {
+        let (key, command) = entry.split_once(':')?;
+        (key.trim().to_lowercase() == name && !command.trim().is_empty())
+            .then(|| command.trim().to_string())
+    })
+}
+
+/// Removes trailing spaces and tabs from every line and, when asked, makes
+/// sure a non-empty text ends with a newline.
+pub fn tidy(text: &str, trim_trailing: bool, final_newline: bool) -> String {
+    let mut out = if trim_trailing {
+        text.split('\n')
+            .map(|line| {
+                let (body, cr) = match line.strip_suffix('\r') {
+                    Some(body) => (body, "\r"),
+                    None => (line, ""),
+                };
+                format!("{}{cr}", body.trim_end_matches([' ', '\t']))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        text.to_string()
+    };
+    if final_newline && !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Pipes `input` through `command`, run from the file's folder. On failure
+/// the error holds the formatter's stderr.
+pub fn run(command: &str, file: &Path, input: &str) -> Result<String, String> {
+    let file_name = file.to_string_lossy();
+    let mut words = command
+        .split_whitespace()
+        .map(|word| word.replace("{file}", &file_name));
+    let program = words.next().ok_or("Formatter command is empty")?;
+
+    let mut process = Command::new(&program);
+    process
+        .args(words)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = file.parent().filter(|dir| dir.is_dir()) {
+        process.current_dir(dir);
+    }
+    let mut child = process
+        .spawn()
+        .map_err(|e| format!("Could not run {program}: {e}"))?;
+
+    // Write from another thread so a formatter that streams its output
+    // can't fill the pipe while we are still writing
+    let mut stdin = child.stdin.take().ok_or("Formatter has no stdin")?;
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("{program} failed ({})", output.status)
+        } else {
+            stderr
+        });
+    }
+    String::from_utf8(output.stdout).map_err(|_| format!("{program} printed invalid UTF-8"))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tidies_and_finds_commands() {
+        assert_eq!(tidy("a  \r\nb\t\nc ", true, true), "a\r\nb\nc\n");
+        assert_eq!(tidy("a  ", false, false), "a  ");
+        assert_eq!(tidy("", true, true), "");
+
+        let spec = "rust: rustfmt --edition 2021; python:black -q -";
+        assert_eq!(
+            command_for(spec, Language::Python).as_deref(),
+            Some("black -q -")
+        );
+        assert_eq!(command_for(spec, Language::Go), None);
+    }
+}
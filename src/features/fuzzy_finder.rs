@@ -1,5 +1,18 @@
+use crate::features::exclusions::Exclusions;
+use crate::features::file_viewer;
+use crate::features::symbols::Symbol;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+/// Queries starting with this list the current file's symbols instead of
+/// files.
+pub const SYMBOL_PREFIX: char = '@';
+
+/// Lines shown above a `path:line` query's line in the preview.
+const PREVIEW_CONTEXT: usize = 3;
+
+/// Bytes of a binary file shown in the preview.
+const PREVIEW_HEX_BYTES: usize = 1024;
 
 /// A single file entry produced by scanning a directory.
 #[derive(Debug, Clone)]
@@ -16,8 +29,16 @@ pub struct FuzzyFinder {
     all_files: Vec<FileEntry>,
     pub filtered_files: Vec<FileEntry>,
     pub selected_index: usize,
+    /// File the query would create, worked out when the query changes.
+    creatable: Option<PathBuf>,
     /// Cached preview: (path that was loaded, file content string)
     pub preview_cache: Option<(PathBuf, String)>,
+    /// 0-based line the preview starts at.
+    pub preview_first_line: usize,
+    /// The file symbols are listed for, with its text.
+    symbol_source: Option<(PathBuf, String)>,
+    symbols: Vec<Symbol>,
+    pub filtered_symbols: Vec<Symbol>,
     pub input_id: iced::widget::Id,
 }
 
@@ -30,26 +51,17 @@ impl Default for FuzzyFinder {
             all_files: Vec::new(),
             filtered_files: Vec::new(),
             selected_index: 0,
+            creatable: None,
             preview_cache: None,
+            preview_first_line: 0,
+            symbol_source: None,
+            symbols: Vec::new(),
+            filtered_symbols: Vec::new(),
             input_id: iced::widget::Id::unique(),
         }
     }
 }
 
-/// Directories to skip while scanning.
-const IGNORED_DIRS: &[&str] = &[
-    ".git",
-    "node_modules",
-    "target",
-    ".DS_Store",
-    "__pycache__",
-    ".claude",
-    ".vscode",
-    "dist",
-    "build",
-    ".next",
-];
-
 impl FuzzyFinder {
     /// Toggle open / closed.  Resets state on open.
     pub fn toggle(&mut self) {
@@ -58,6 +70,7 @@ impl FuzzyFinder {
             self.input.clear();
             self.filtered_files = self.all_files.clone();
             self.selected_index = 0;
+            self.creatable = None;
             self.preview_cache = None;
         }
     }
@@ -68,23 +81,72 @@ impl FuzzyFinder {
         self.input.clear();
         self.filtered_files = self.all_files.clone();
         self.selected_index = 0;
+        self.creatable = None;
         self.preview_cache = None;
     }
 
-    /// Point the finder at a workspace root and index all files.
+    /// Point the finder at a workspace root, listing no files until
+    /// [`Self::set_files`] gives the ones found by [`list_files`].
     pub fn set_folder(&mut self, folder_path: PathBuf) {
-        self.current_folder = Some(folder_path.clone());
-        self.all_files = scan_directory(&folder_path, &folder_path);
-        self.filtered_files = self.all_files.clone();
-        self.selected_index = 0;
+        self.current_folder = Some(folder_path);
+        self.set_files(Vec::new());
+    }
+
+    /// Lists `files`, filtered by the query typed so far.
+    pub fn set_files(&mut self, files: Vec<FileEntry>) {
+        self.all_files = files;
+        self.filter();
+    }
+
+    /// Sets the file whose symbols `@` queries list.
+    pub fn set_symbol_source(&mut self, source: Option<(PathBuf, String)>, symbols: Vec<Symbol>) {
+        self.symbol_source = source;
+        self.symbols = symbols;
+    }
+
+    /// The file part of the query, relative to the workspace root, and the
+    /// 1-based line and column of a `:line` or `:line:col` suffix.
+    pub fn query_location(&self) -> (&str, Option<(usize, usize)>) {
+        let (path, position) = split_position(self.input.trim());
+        // Component-wise, so `/work` doesn't strip `/workshop/a.rs`
+        let relative = self
+            .current_folder
+            .as_deref()
+            .and_then(|root| Path::new(path).strip_prefix(root).ok())
+            .and_then(Path::to_str);
+        (relative.unwrap_or(path), position)
+    }
+
+    /// Whether the query lists symbols rather than files.
+    pub fn symbol_mode(&self) -> bool {
+        self.input.starts_with(SYMBOL_PREFIX)
+    }
+
+    fn result_count(&self) -> usize {
+        if self.symbol_mode() {
+            self.filtered_symbols.len()
+        } else {
+            self.filtered_files.len()
+        }
     }
 
     /// Re-filter after the query changes.
     pub fn filter(&mut self) {
-        if self.input.is_empty() {
+        if let Some(query) = self.input.strip_prefix(SYMBOL_PREFIX) {
+            let query = query.trim().to_lowercase();
+            let mut scored: Vec<(&Symbol, i32)> = self
+                .symbols
+                .iter()
+                .map(|symbol| (symbol, fuzzy_match(&symbol.name.to_lowercase(), &query)))
+                .filter(|(_, score)| *score > 0)
+                .collect();
+            // Stable, so equal scores stay in document order
+            scored.sort_by(|(_, a), (_, b)| b.cmp(a));
+            self.filtered_symbols = scored.into_iter().map(|(s, _)| s.clone()).collect();
+        } else if self.query_location().0.is_empty() {
             self.filtered_files = self.all_files.clone();
         } else {
-            let input_lower = self.input.to_lowercase();
+            let input_lower = self.query_location().0.to_lowercase();
 
             let mut scored: Vec<(FileEntry, i32)> = self
                 .all_files
@@ -108,12 +170,13 @@ impl FuzzyFinder {
             self.filtered_files = scored.into_iter().map(|(file, _)| file).collect();
         }
         self.selected_index = 0;
+        self.creatable = self.find_creatable();
         self.preview_cache = None;
     }
 
     /// Navigate selection up or down.
     pub fn navigate(&mut self, delta: i32) {
-        let count = self.filtered_files.len();
+        let count = self.result_count();
         if count == 0 {
             return;
         }
@@ -132,21 +195,97 @@ impl FuzzyFinder {
         path
     }
 
+    /// Select the highlighted symbol; returns its 0-based line.
+    pub fn select_symbol(&mut self) -> Option<usize> {
+        let line = self
+            .filtered_symbols
+            .get(self.selected_index)
+            .map(|symbol| symbol.line);
+        self.close();
+        line
+    }
+
+    /// Path a query that matches nothing would create, relative to the
+    /// workspace root. `None` while something matches or the file exists.
+    pub fn creatable_path(&self) -> Option<PathBuf> {
+        self.creatable.clone()
+    }
+
+    /// Checks the disk for [`Self::creatable_path`], once per query rather
+    /// than every frame. Paths that lead out of the workspace are refused.
+    fn find_creatable(&self) -> Option<PathBuf> {
+        let root = self.current_folder.as_deref()?;
+        let query = self.input.trim();
+        if self.symbol_mode()
+            || !self.filtered_files.is_empty()
+            || self.query_location().1.is_some()
+            || query.is_empty()
+            || query.ends_with(['/', '\\'])
+        {
+            return None;
+        }
+        let path = normalize(&crate::features::paths::resolve(query, Some(root)));
+        (path.starts_with(root) && path != root && !path.exists()).then_some(path)
+    }
+
+    /// Add a file created outside of a folder scan to the index.
+    pub fn add_file(&mut self, path: PathBuf) {
+        let root = self.current_folder.as_deref().unwrap_or(Path::new(""));
+        let display_name = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        self.all_files.push(FileEntry { path, display_name });
+        self.all_files
+            .sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    }
+
     /// Ensure the preview cache matches the currently selected file.
     pub fn update_preview(&mut self) {
+        if self.symbol_mode() {
+            let symbol = self.filtered_symbols.get(self.selected_index);
+            self.preview_cache = self
+                .symbol_source
+                .as_ref()
+                .zip(symbol)
+                .map(|(source, symbol)| {
+                    // Start a few lines above the definition for context
+                    let first = symbol.line.saturating_sub(3);
+                    let lines = source.1.lines().skip(first).take(200);
+                    self.preview_first_line = first;
+                    (source.0.clone(), lines.collect::<Vec<_>>().join("\n"))
+                });
+            return;
+        }
         let Some(entry) = self.filtered_files.get(self.selected_index) else {
             self.preview_cache = None;
             return;
         };
+        let first = self
+            .query_location()
+            .1
+            .map_or(0, |(line, _)| line.saturating_sub(PREVIEW_CONTEXT + 1));
         if let Some((cached_path, _)) = &self.preview_cache {
-            if cached_path == &entry.path {
+            if cached_path == &entry.path && self.preview_first_line == first {
                 return; // already cached
             }
         }
-        // Read first ~200 lines for preview (no need to load huge files)
-        let content = fs::read_to_string(&entry.path)
-            .unwrap_or_else(|_| String::from("[binary or unreadable file]"));
-        let truncated: String = content.lines().take(200).collect::<Vec<_>>().join("\n");
+        self.preview_first_line = first;
+        // Read ~200 lines for preview (no need to load huge files)
+        let content = match fs::read(&entry.path) {
+            Ok(bytes) if file_viewer::is_binary(&bytes) => {
+                file_viewer::hex_dump(&bytes[..bytes.len().min(PREVIEW_HEX_BYTES)])
+            }
+            Ok(bytes) => crate::features::encoding::decode(&bytes).0,
+            Err(_) => String::from("[unreadable file]"),
+        };
+        let truncated: String = content
+            .lines()
+            .skip(first)
+            .take(200)
+            .collect::<Vec<_>>()
+            .join("\n");
         self.preview_cache = Some((entry.path.clone(), truncated));
     }
 
@@ -162,7 +301,13 @@ impl FuzzyFinder {
 
 // ── Directory scanner ───────────────────────────────────────────────────────
 
-fn scan_directory(dir: &Path, root: &Path) -> Vec<FileEntry> {
+/// Lists the files of `root` not left out by `exclusions`. This walks the
+/// whole folder, so it runs on the worker pool.
+pub fn list_files(root: &Path, exclusions: &Exclusions) -> Vec<FileEntry> {
+    scan_directory(root, root, exclusions)
+}
+
+fn scan_directory(dir: &Path, root: &Path, exclusions: &Exclusions) -> Vec<FileEntry> {
     let mut files = Vec::new();
 
     let Ok(entries) = fs::read_dir(dir) else {
@@ -177,11 +322,9 @@ fn scan_directory(dir: &Path, root: &Path) -> Vec<FileEntry> {
         };
         let name_str = name.to_string_lossy();
 
-        // Skip hidden files/dirs and ignored directories
-        if name_str.starts_with('.') {
-            continue;
-        }
-        if IGNORED_DIRS.contains(&name_str.as_ref()) {
+        // Skip hidden files/dirs and excluded ones
+        let hidden = name_str.starts_with('.') && !exclusions.shows_hidden();
+        if hidden || exclusions.excludes(&path, path.is_dir()) {
             continue;
         }
 
@@ -193,7 +336,7 @@ fn scan_directory(dir: &Path, root: &Path) -> Vec<FileEntry> {
                 .to_string();
             files.push(FileEntry { path, display_name });
         } else if path.is_dir() {
-            files.extend(scan_directory(&path, root));
+            files.extend(scan_directory(&path, root, exclusions));
         }
     }
 
@@ -201,6 +344,28 @@ fn scan_directory(dir: &Path, root: &Path) -> Vec<FileEntry> {
     files
 }
 
+// ── Query parsing ───────────────────────────────────────────────────────────
+
+/// Splits a `:line` or `:line:col` suffix, as printed by compilers, off
+/// `query`. A trailing `:` still being typed is dropped too.
+fn split_position(query: &str) -> (&str, Option<(usize, usize)>) {
+    let query = query.strip_suffix(':').unwrap_or(query);
+    let number = |part: &str| part.parse::<usize>().ok().map(|n| n.max(1));
+    let Some((rest, last)) = query.rsplit_once(':') else {
+        return (query, None);
+    };
+    let Some(last) = number(last) else {
+        return (query, None);
+    };
+    match rest.rsplit_once(':') {
+        Some((path, line)) => match number(line) {
+            Some(line) => (path, Some((line, last))),
+            None => (rest, Some((last, 1))),
+        },
+        None => (rest, Some((last, 1))),
+    }
+}
+
 // ── Fuzzy matching algorithm ────────────────────────────────────────────────
 
 fn fuzzy_match(text: &str, pattern: &str) -> i32 {
@@ -241,3 +406,76 @@ fn fuzzy_match(text: &str, pattern: &str) -> i32 {
         0
     }
 }
+
+/// `path` with `.` and `..` components worked out, without touching the
+/// disk.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_line_and_column_suffixes() {
+        assert_eq!(split_position("app.rs"), ("app.rs", None));
+        assert_eq!(split_position("app.rs:"), ("app.rs", None));
+        assert_eq!(split_position("app.rs:120"), ("app.rs", Some((120, 1))));
+        assert_eq!(split_position("app.rs:120:"), ("app.rs", Some((120, 1))));
+        assert_eq!(
+            split_position("src/app.rs:120:7"),
+            ("src/app.rs", Some((120, 7)))
+        );
+        assert_eq!(split_position("notes:todo"), ("notes:todo", None));
+
+        let mut finder = FuzzyFinder {
+            current_folder: Some(PathBuf::from("/work")),
+            ..FuzzyFinder::default()
+        };
+        finder.input = "/work/src/app.rs:3:0".to_string();
+        assert_eq!(finder.query_location(), ("src/app.rs", Some((3, 1))));
+        finder.input = "/workshop/app.rs".to_string();
+        assert_eq!(finder.query_location(), ("/workshop/app.rs", None));
+    }
+
+    #[test]
+    fn creates_files_only_inside_the_workspace() {
+        let root = std::env::temp_dir().join(format!("pinel-finder-{}", std::process::id()));
+        let mut finder = FuzzyFinder::default();
+        finder.set_folder(root.clone());
+        let creatable = |finder: &mut FuzzyFinder, query: &str| {
+            finder.input = query.to_string();
+            finder.filter();
+            finder.creatable_path()
+        };
+
+        assert_eq!(
+            creatable(&mut finder, "src/new.rs"),
+            Some(root.join("src/new.rs"))
+        );
+        assert_eq!(
+            creatable(&mut finder, "./src/../notes.md"),
+            Some(root.join("notes.md"))
+        );
+        assert_eq!(creatable(&mut finder, "../outside.rs"), None);
+        assert_eq!(creatable(&mut finder, "src/.."), None);
+        assert_eq!(creatable(&mut finder, "new.rs:3"), None);
+        finder.close();
+        assert_eq!(finder.creatable_path(), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Minimal git integration for the source control panel.
+//!
+//! Everything shells out to the `git` binary in the workspace root, so the
+//! panel behaves exactly like running the same commands in a terminal.
+
+use std::path::Path;
+use std::process::Command;
+
+/// A changed file reported by `git status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitChange {
+    /// Path relative to the repository root.
+    pub path: String,
+    /// Two letter porcelain status code, e.g. `"M "` or `"??"`.
+    pub code: String,
+}
+
+impl GitChange {
+    /// Whether the change has anything in the index.
+    pub fn is_staged(&self) -> bool {
+        let index = self.code.chars().next().unwrap_or(' ');
+        index != ' ' && index != '?'
+    }
+
+    /// Whether the working tree has changes not yet in the index.
+    pub fn has_unstaged(&self) -> bool {
+        let worktree = self.code.chars().nth(1).unwrap_or(' ');
+        worktree != ' '
+    }
+}
+
+fn run_git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() {
+            format!("git {} failed", args.first().copied().unwrap_or_default())
+        } else {
+            stderr
+        })
+    }
+}
+
+/// Lists changed files in the repository containing `root`.
+pub fn status(root: &Path) -> Result<Vec<GitChange>, String> {
+    let output = run_git(
+        root,
+        &["status", "--porcelain=v1", "-z", "--untracked-files=all"],
+    )?;
+    Ok(parse_status(&output))
+}
+
+/// Parses `git status --porcelain=v1 -z`: entries end with NUL and paths
+/// are not quoted. Renames and copies are followed by their old path.
+fn parse_status(output: &str) -> Vec<GitChange> {
+    let mut changes = Vec::new();
+    let mut entries = output.split('\0');
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 || !entry.is_char_boundary(2) {
+            continue;
+        }
+        let (code, path) = entry.split_at(2);
+        if code.contains(['R', 'C']) {
+            entries.next();
+        }
+        changes.push(GitChange {
+            path: path[1..].to_string(),
+            code: code.to_string(),
+        });
+    }
+    changes
+}
+
+/// Pathspec for a path relative to the repository root, matched as is
+/// from whichever folder of the repository git runs in.
+fn from_top(path: &str) -> String {
+    format!(":(top,literal){path}")
+}
+
+pub fn stage(root: &Path, path: &str) -> Result<(), String> {
+    run_git(root, &["add", "--", &from_top(path)]).map(|_| ())
+}
+
+pub fn unstage(root: &Path, path: &str) -> Result<(), String> {
+    run_git(root, &["reset", "-q", "HEAD", "--", &from_top(path)]).map(|_| ())
+}
+
+pub fn commit(root: &Path, message: &str) -> Result<(), String> {
+    if message.trim().is_empty() {
+        return Err("Commit message is empty".to_string());
+    }
+    run_git(root, &["commit", "-q", "-m", message]).map(|_| ())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nul_separated_status() {
+        let output = " M src/main.rs\0R  new name.rs\0old name.rs\0?? dir/\"quoted\".txt\0";
+        let changes = parse_status(output);
+        let paths: Vec<&str> = changes.iter().map(|change| change.path.as_str()).collect();
+        assert_eq!(paths, ["src/main.rs", "new name.rs", "dir/\"quoted\".txt"]);
+        assert!(changes[1].is_staged());
+        assert!(!changes[1].has_unstaged());
+        assert!(changes[0].has_unstaged());
+        assert!(!changes[2].is_staged());
+        assert!(parse_status("").is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+//! Indentation helpers: guessing whether a file is indented with tabs or
+//! spaces, and simple per-language rules for where blocks open and close.
+
+use crate::language::Language;
+
+/// Number of lines looked at when guessing.
+pub const SAMPLE_LINES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tabs,
+    Spaces(usize),
+}
+
+impl IndentStyle {
+    /// Text inserted for one indentation level.
+    pub fn unit(self) -> String {
+        match self {
+            Self::Tabs => "\t".to_string(),
+            Self::Spaces(width) => " ".repeat(width),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Tabs => "Tabs".to_string(),
+            Self::Spaces(width) => format!("Spaces: {width}"),
+        }
+    }
+}
+
+/// Detects the indentation used by the first [`SAMPLE_LINES`] lines.
+///
+/// Space widths are taken from the most common increase in indentation
+/// between consecutive indented lines. Returns `None` when nothing is
+/// indented.
+pub fn detect<I, S>(lines: I) -> Option<IndentStyle>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut tab_lines = 0usize;
+    let mut space_lines = 0usize;
+    let mut widths = [0usize; 9];
+    let mut previous = 0usize;
+
+    for line in lines.into_iter().take(SAMPLE_LINES) {
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('\t') {
+            tab_lines += 1;
+            previous = 0;
+            continue;
+        }
+
+        let spaces = line.chars().take_while(|c| *c == ' ').count();
+        // Skip the aligned `*` of block comments
+        if line[spaces..].starts_with('*') {
+            continue;
+        }
+        if spaces > 0 {
+            space_lines += 1;
+        }
+        if spaces > previous && spaces - previous < widths.len() {
+            widths[spaces - previous] += 1;
+        }
+        previous = spaces;
+    }
+
+    if tab_lines == 0 && space_lines == 0 {
+        return None;
+    }
+    if tab_lines > space_lines {
+        return Some(IndentStyle::Tabs);
+    }
+
+    // Prefer the larger width on ties, so 2/4 mixes settle on 4
+    let width = (2..widths.len())
+        .max_by_key(|&width| (widths[width], width))
+        .filter(|&width| widths[width] > 0)
+        .unwrap_or(4);
+    Some(IndentStyle::Spaces(width))
+}
+
+/// Leading tabs and spaces of `line`.
+pub fn leading_whitespace(line: &str) -> String {
+    line.chars()
+        .take_while(|ch| *ch == ' ' || *ch == '\t')
+        .collect()
+}
+
+/// Whether the line after `line` should be indented one level deeper.
+pub fn opens_block(line: &str, language: Language) -> bool {
+    let trimmed = line.trim_end();
+    if trimmed.ends_with('{') || trimmed.ends_with('[') || trimmed.ends_with('(') {
+        return true;
+    }
+    match language {
+        Language::Python | Language::Yaml => trimmed.ends_with(':'),
+        Language::Lua => {
+            trimmed.ends_with(" then")
+                || trimmed.ends_with(" do")
+                || trimmed == "else"
+                || trimmed.ends_with(')') && trimmed.contains("function")
+        }
+        Language::Ruby => trimmed.ends_with(" do") || trimmed.ends_with(" then"),
+        Language::Shell => trimmed.ends_with("then") || trimmed.ends_with("do"),
+        _ => false,
+    }
+}
+
+/// Whether `line` closes a block, sitting one level shallower than the
+/// block's body.
+pub fn closes_block(line: &str, language: Language) -> bool {
+    let trimmed = line.trim();
+    if trimmed.starts_with(['}', ']', ')']) {
+        return true;
+    }
+    let word = trimmed
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or("");
+    match language {
+        Language::Python => {
+            matches!(word, "else" | "elif" | "except" | "finally") && trimmed.ends_with(':')
+        }
+        Language::Lua => matches!(word, "end" | "else" | "elseif"),
+        Language::Ruby => matches!(word, "end" | "else" | "elsif" | "when" | "rescue"),
+        Language::Shell => matches!(word, "fi" | "done" | "esac" | "else" | "elif"),
+        _ => false,
+    }
+}
+
+/// Removes one indentation level from the end of `indent`.
+pub fn dedent(indent: &str, unit: &str) -> String {
+    if let Some(stripped) = indent.strip_suffix(unit) {
+        return stripped.to_string();
+    }
+    if let Some(stripped) = indent.strip_suffix('\t') {
+        return stripped.to_string();
+    }
+    let width = unit.len().max(1);
+    let spaces = indent.len() - indent.trim_end_matches(' ').len();
+    indent[..indent.len() - spaces.min(width)].to_string()
+}
+
+/// Adds or removes one indentation level at the start of `line`. Blank
+/// lines are left alone.
+pub fn shift(line: &str, unit: &str, outdent: bool) -> String {
+    if line.trim().is_empty() {
+        return line.to_string();
+    }
+    if !outdent {
+        return format!("{unit}{line}");
+    }
+    if let Some(stripped) = line.strip_prefix(unit) {
+        return stripped.to_string();
+    }
+    if let Some(stripped) = line.strip_prefix('\t') {
+        return stripped.to_string();
+    }
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    line[spaces.min(unit.len().max(1))..].to_string()
+}
+
+/// Recomputes the indentation of `lines[first..=last]`, starting from the
+/// closest non-blank line above them.
+pub fn reindent(
+    lines: &[&str],
+    first: usize,
+    last: usize,
+    unit: &str,
+    language: Language,
+) -> Vec<String> {
+    let mut next_indent = lines[..first.min(lines.len())]
+        .iter()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| {
+            let base = leading_whitespace(line);
+            if opens_block(line, language) {
+                base + unit
+            } else {
+                base
+            }
+        })
+        .unwrap_or_default();
+
+    let mut out = Vec::new();
+    for line in lines.iter().take(last + 1).skip(first) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            out.push(String::new());
+            continue;
+        }
+        let indent = if closes_block(trimmed, language) {
+            dedent(&next_indent, unit)
+        } else {
+            next_indent.clone()
+        };
+        next_indent = if opens_block(trimmed, language) {
+            format!("{indent}{unit}")
+        } else {
+            indent.clone()
+        };
+        out.push(format!("{indent}{trimmed}"));
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_space_width_from_nesting() {
+        let text = "fn main() {\n  if x {\n    y();\n  }\n}\n";
+        assert_eq!(detect(text.lines()), Some(IndentStyle::Spaces(2)));
+
+        let text = "def f():\n    return [\n        1,\n    ]\n";
+        assert_eq!(detect(text.lines()), Some(IndentStyle::Spaces(4)));
+    }
+
+    #[test]
+    fn detects_tabs_and_unindented_files() {
+        let text = "func main() {\n\tif x {\n\t\ty()\n\t}\n}\n";
+        assert_eq!(detect(text.lines()), Some(IndentStyle::Tabs));
+        assert_eq!(detect("a\nb\n".lines()), None);
+    }
+
+    #[test]
+    fn reindents_braces_and_closers() {
+        let lines = ["fn main() {", "if x {", "y();", "      }", "}"];
+        let out = reindent(&lines, 1, 4, "    ", Language::Rust);
+        assert_eq!(out, ["    if x {", "        y();", "    }", "}"]);
+
+        let lines = ["if x:", "  a()", "else:", "b()"];
+        let out = reindent(&lines, 1, 3, "  ", Language::Python);
+        assert_eq!(out, ["  a()", "else:", "  b()"]);
+    }
+}
@@ -0,0 +1,151 @@
+//! Jumps between places in files: the list of places left by jumping, to
+//! go back and forward through, and the brief highlight of the text a jump
+//! landed on.
+
+use crate::message::Message;
+use crate::ui::TextLayout;
+use iced::widget::canvas;
+use iced::{mouse, Color, Point, Rectangle, Renderer, Size, Theme};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Places kept to go back to, older ones are dropped.
+const KEEP: usize = 100;
+
+/// How long the text a jump landed on stays highlighted.
+pub const FLASH_DURATION: Duration = Duration::from_millis(700);
+
+/// A place in a file, 1-based like the status line shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct JumpList {
+    back: Vec<Location>,
+    forward: Vec<Location>,
+}
+
+impl JumpList {
+    /// Remembers `from`, the place a jump is leaving. Places gone back to
+    /// are forgotten, as a new jump starts a new path.
+    pub fn push(&mut self, from: Location) {
+        self.forward.clear();
+        if self.back.last() != Some(&from) {
+            self.back.push(from);
+        }
+        if self.back.len() > KEEP {
+            self.back.remove(0);
+        }
+    }
+
+    /// The place before `current`, which is kept to come forward to.
+    pub fn back(&mut self, current: Location) -> Option<Location> {
+        let to = self.back.pop()?;
+        self.forward.push(current);
+        Some(to)
+    }
+
+    /// The place gone back from last, `current` is kept to go back to.
+    pub fn forward(&mut self, current: Location) -> Option<Location> {
+        let to = self.forward.pop()?;
+        self.back.push(current);
+        Some(to)
+    }
+}
+
+/// Text highlighted after a jump: 0-based line and character columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpFlash {
+    pub path: PathBuf,
+    pub line: usize,
+    pub columns: Range<usize>,
+}
+
+/// Draws a [`JumpFlash`] over the editor.
+pub struct Flash {
+    rect: Option<Rectangle>,
+}
+
+impl Flash {
+    /// `text` is the flashed line. Nothing is drawn without a `layout`,
+    /// while word wrap is on.
+    pub fn new(layout: Option<&TextLayout>, flash: &JumpFlash, text: &str) -> Self {
+        let rect = layout.map(|layout| {
+            let start = layout
+                .point(flash.line, text, flash.columns.start)
+                .unwrap_or(Point::new(layout.text_left(), layout.line_top(flash.line)));
+            let matched: String = text
+                .chars()
+                .skip(flash.columns.start)
+                .take(flash.columns.len())
+                .collect();
+            Rectangle::new(
+                start,
+                Size::new(layout.width(&matched).max(2.0), layout.line_height()),
+            )
+        });
+        Self { rect }
+    }
+}
+
+impl canvas::Program<Message> for Flash {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        if let Some(rect) = self.rect {
+            frame.fill_rectangle(
+                rect.position(),
+                rect.size(),
+                Color {
+                    a: 0.35,
+                    ..crate::theme::ACCENT_YELLOW
+                },
+            );
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goes_back_and_forward() {
+        let at = |line| Location {
+            path: PathBuf::from("a.rs"),
+            line,
+            column: 1,
+        };
+        let mut jumps = JumpList::default();
+        jumps.push(at(1));
+        jumps.push(at(1));
+        jumps.push(at(5));
+        assert_eq!(jumps.back(at(9)), Some(at(5)));
+        assert_eq!(jumps.back(at(5)), Some(at(1)));
+        assert_eq!(jumps.back(at(1)), None);
+        assert_eq!(jumps.forward(at(1)), Some(at(5)));
+
+        // A new jump forgets the way forward
+        jumps.push(at(5));
+        assert_eq!(jumps.forward(at(7)), None);
+        assert_eq!(jumps.back(at(7)), Some(at(5)));
+    }
+}
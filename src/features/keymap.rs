@@ -0,0 +1,119 @@
+//! Key chords for user keymaps, written like `ctrl+shift+k` or `f5`.
+//!
+//! Chords are compared as normalized strings: lowercase, with modifiers in
+//! the order ctrl, alt, shift, cmd.
+
+use iced::keyboard::key::Named;
+use iced::keyboard::{Key, Modifiers};
+
+fn chord(modifiers: [bool; 4], key: &str) -> String {
+    let names = ["ctrl", "alt", "shift", "cmd"];
+    let mut out = String::new();
+    for (name, held) in names.iter().zip(modifiers) {
+        if held {
+            out.push_str(name);
+            out.push('+');
+        }
+    }
+    out.push_str(key);
+    out
+}
+
+/// Normalizes a chord written in a config file, or `None` if it names an
+/// unknown modifier.
+pub fn normalize(input: &str) -> Option<String> {
+    let input = input.trim().to_lowercase();
+    let (mods, key) = match input.rsplit_once('+') {
+        // `ctrl++` maps the plus key itself
+        Some((mods, "")) => (mods.strip_suffix('+')?, "+"),
+        Some((mods, key)) => (mods, key),
+        None => ("", input.as_str()),
+    };
+    let mut held = [false; 4];
+    for modifier in mods.split('+').filter(|m| !m.is_empty()) {
+        let idx = match modifier {
+            "ctrl" | "control" => 0,
+            "alt" | "option" => 1,
+            "shift" => 2,
+            "cmd" | "super" | "meta" | "logo" => 3,
+            _ => return None,
+        };
+        held[idx] = true;
+    }
+    let key = match key {
+        "esc" => "escape",
+        "return" => "enter",
+        "del" => "delete",
+        "pgup" => "pageup",
+        "pgdn" => "pagedown",
+        key => key,
+    };
+    (!key.is_empty()).then(|| chord(held, key))
+}
+
+/// The chord for a key press, in the same form as [`normalize`].
+pub fn chord_for(key: &Key, modifiers: Modifiers) -> Option<String> {
+    let name = match key {
+        Key::Character(c) => c.to_lowercase(),
+        Key::Named(named) => match named {
+            Named::Enter => "enter".to_string(),
+            Named::Tab => "tab".to_string(),
+            Named::Space => "space".to_string(),
+            Named::Escape => "escape".to_string(),
+            Named::Backspace => "backspace".to_string(),
+            Named::Delete => "delete".to_string(),
+            Named::Home => "home".to_string(),
+            Named::End => "end".to_string(),
+            Named::PageUp => "pageup".to_string(),
+            Named::PageDown => "pagedown".to_string(),
+            Named::ArrowUp => "up".to_string(),
+            Named::ArrowDown => "down".to_string(),
+            Named::ArrowLeft => "left".to_string(),
+            Named::ArrowRight => "right".to_string(),
+            Named::F1 => "f1".to_string(),
+            Named::F2 => "f2".to_string(),
+            Named::F3 => "f3".to_string(),
+            Named::F4 => "f4".to_string(),
+            Named::F5 => "f5".to_string(),
+            Named::F6 => "f6".to_string(),
+            Named::F7 => "f7".to_string(),
+            Named::F8 => "f8".to_string(),
+            Named::F9 => "f9".to_string(),
+            Named::F10 => "f10".to_string(),
+            Named::F11 => "f11".to_string(),
+            Named::F12 => "f12".to_string(),
+            _ => return None,
+        },
+        Key::Unidentified => return None,
+    };
+    let held = [
+        modifiers.control(),
+        modifiers.alt(),
+        modifiers.shift(),
+        modifiers.logo(),
+    ];
+    Some(chord(held, &name))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_config_and_key_presses_alike() {
+        assert_eq!(normalize("Shift+Ctrl+K").as_deref(), Some("ctrl+shift+k"));
+        assert_eq!(normalize("ctrl++").as_deref(), Some("ctrl++"));
+        assert_eq!(normalize("F5").as_deref(), Some("f5"));
+        assert_eq!(normalize("hyper+k"), None);
+
+        let pressed = chord_for(
+            &Key::Character("K".into()),
+            Modifiers::CTRL | Modifiers::SHIFT,
+        );
+        assert_eq!(pressed, normalize("ctrl+shift+k"));
+    }
+}
@@ -0,0 +1,58 @@
+//! Line ending detection and conversion.
+//!
+//! The editor works with `\n` only, so the style a file was read with is
+//! remembered and applied again when it is written.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::Crlf => "CRLF",
+        }
+    }
+}
+
+/// The style used by most lines of `text`, LF for files without any.
+pub fn detect(text: &str) -> LineEnding {
+    let newlines = text.matches('\n').count();
+    let crlf = text.matches("\r\n").count();
+    if crlf > newlines - crlf {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    }
+}
+
+/// Rewrites every line break of `text` in the given style.
+pub fn convert(text: &str, ending: LineEnding) -> String {
+    let text = text.replace("\r\n", "\n");
+    match ending {
+        LineEnding::Lf => text,
+        LineEnding::Crlf => text.replace('\n', "\r\n"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_majority_and_converts() {
+        assert_eq!(detect("a\r\nb\r\nc\n"), LineEnding::Crlf);
+        assert_eq!(detect("a\nb\r\nc\n"), LineEnding::Lf);
+        assert_eq!(detect("single line"), LineEnding::Lf);
+        assert_eq!(convert("a\r\nb\nc", LineEnding::Crlf), "a\r\nb\r\nc");
+        assert_eq!(convert("a\r\nb\n", LineEnding::Lf), "a\nb\n");
+    }
+}
@@ -0,0 +1,109 @@
+//! Soft line length limit: flags lines that run past a configured width and
+//! rewraps them on request.
+//!
+//! Limits come from the `line_length_limits` preference, a comma separated
+//! list holding a default width and `language:width` overrides, for example
+//! `100, python:88, markdown:0`. A width of 0 turns the check off.
+
+use crate::language::Language;
+
+/// Width used when reflowing a line in a language without a limit.
+pub const FALLBACK_LIMIT: usize = 100;
+
+/// Limit for `language`, if one is configured.
+pub fn limit_for(spec: &str, language: Language) -> Option<usize> {
+    let name = language.name().to_lowercase();
+    let mut limit = None;
+    for entry in spec.split(',').map(str::trim) {
+        match entry.split_once(':') {
+            Some((key, width)) if key.trim().to_lowercase() == name => {
+                return width.trim().parse().ok().filter(|&width| width > 0);
+            }
+            Some(_) => {}
+            None => limit = entry.parse().ok().or(limit),
+        }
+    }
+    limit.filter(|&width| width > 0)
+}
+
+/// Display width of `line`, with tabs counted as `tab_size` columns.
+pub fn width(line: &str, tab_size: usize) -> usize {
+    line.chars()
+        .map(|ch| if ch == '\t' { tab_size } else { 1 })
+        .sum()
+}
+
+/// Hint for a line that is wider than `limit`.
+pub fn hint(line: &str, limit: usize, tab_size: usize) -> Option<String> {
+    let width = width(line, tab_size);
+    (width > limit).then(|| format!("Line is {width} columns long (limit {limit})"))
+}
+
+/// Wraps `line` at word boundaries so each piece fits in `limit` columns.
+/// Continuation lines keep the indentation and any line comment marker of
+/// the original. Words longer than the limit are left whole.
+pub fn reflow(line: &str, limit: usize, tab_size: usize, language: Language) -> Vec<String> {
+    if width(line, tab_size) <= limit {
+        return vec![line.to_string()];
+    }
+
+    let indent_len = line.len() - line.trim_start().len();
+    let mut prefix = line[..indent_len].to_string();
+    let mut body = &line[indent_len..];
+    if let Some((marker, "")) = super::comments::markers(language) {
+        if let Some(rest) = body.strip_prefix(marker) {
+            prefix = format!("{prefix}{marker} ");
+            body = rest.trim_start();
+        }
+    }
+
+    if body.is_empty() {
+        return vec![line.to_string()];
+    }
+
+    let prefix_width = width(&prefix, tab_size);
+    let mut out = Vec::new();
+    let mut current = String::new();
+    for word in body.split_whitespace() {
+        if !current.is_empty()
+            && prefix_width + current.chars().count() + 1 + word.chars().count() > limit
+        {
+            out.push(format!("{prefix}{current}"));
+            current.clear();
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        out.push(format!("{prefix}{current}"));
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_overrides_default_limit() {
+        let spec = "100, python:88, markdown:0";
+        assert_eq!(limit_for(spec, Language::Rust), Some(100));
+        assert_eq!(limit_for(spec, Language::Python), Some(88));
+        assert_eq!(limit_for(spec, Language::Markdown), None);
+        assert_eq!(limit_for("", Language::Rust), None);
+    }
+
+    #[test]
+    fn reflow_keeps_indent_and_comment_marker() {
+        let line = "    // one two three four five";
+        let out = reflow(line, 20, 4, Language::Rust);
+        assert_eq!(out, ["    // one two three", "    // four five"]);
+        assert_eq!(reflow("short", 20, 4, Language::Rust), ["short"]);
+    }
+}
@@ -0,0 +1,145 @@
+//! Local history: a copy of every saved version of a file, kept in the
+//! config folder so earlier versions can be compared and restored without
+//! git.
+//!
+//! Each file gets a folder named after a hash of its path, holding one
+//! file per version named after the time it was saved. Versions keep the
+//! bytes that were written, in the file's own encoding and line endings.
+
+use crate::config::theme_manager::get_config_dir;
+use crate::features::encoding;
+use crate::features::file_io::stable_hash;
+use crate::features::line_endings::{self, LineEnding};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Versions kept per file, older ones are removed.
+const KEEP: usize = 50;
+
+pub fn history_dir() -> PathBuf {
+    get_config_dir().join("history")
+}
+
+fn versions_dir(path: &Path) -> PathBuf {
+    let hash = stable_hash(path.as_os_str().as_encoded_bytes());
+    history_dir().join(format!("{hash:016x}"))
+}
+
+/// A saved version of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub saved_at: SystemTime,
+    file: PathBuf,
+}
+
+impl Version {
+    /// Text of the version, decoded and with `\n` line endings like a
+    /// buffer.
+    pub fn read(&self) -> std::io::Result<String> {
+        let (text, _) = encoding::decode(&fs::read(&self.file)?);
+        Ok(line_endings::convert(&text, LineEnding::Lf))
+    }
+}
+
+/// Keeps `content`, the bytes just written to `path`, as its newest
+/// version unless it is the same as the newest one already kept.
+pub fn record(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let dir = versions_dir(path);
+    record_in(&dir, content, SystemTime::now())?;
+    // Names the file the folder belongs to, for anyone looking inside
+    fs::write(dir.join("path"), path.to_string_lossy().as_bytes())
+}
+
+fn record_in(dir: &Path, content: &[u8], now: SystemTime) -> std::io::Result<()> {
+    let versions = list_in(dir);
+    if let Some(newest) = versions.first() {
+        if fs::read(&newest.file).is_ok_and(|newest| newest == content) {
+            return Ok(());
+        }
+    }
+    fs::create_dir_all(dir)?;
+    let millis = now
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    fs::write(dir.join(format!("{millis}.txt")), content)?;
+    for old in versions.iter().skip(KEEP - 1) {
+        let _ = fs::remove_file(&old.file);
+    }
+    Ok(())
+}
+
+/// Versions kept of the file at `path`, newest first.
+pub fn list(path: &Path) -> Vec<Version> {
+    list_in(&versions_dir(path))
+}
+
+fn list_in(dir: &Path) -> Vec<Version> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<Version> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file = entry.path();
+            let millis: u64 = file
+                .file_name()?
+                .to_str()?
+                .strip_suffix(".txt")?
+                .parse()
+                .ok()?;
+            Some(Version {
+                saved_at: UNIX_EPOCH + Duration::from_millis(millis),
+                file,
+            })
+        })
+        .collect();
+    versions.sort_by_key(|version| std::cmp::Reverse(version.saved_at));
+    versions
+}
+
+/// Rough age such as `just now`, `5m ago` or `3d ago`.
+pub fn age_label(saved_at: SystemTime, now: SystemTime) -> String {
+    let secs = now.duration_since(saved_at).unwrap_or_default().as_secs();
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_changed_versions_newest_first() {
+        let dir = std::env::temp_dir().join(format!("pinel-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+
+        record_in(&dir, b"one\r\n", at(10)).unwrap();
+        record_in(&dir, b"one\r\n", at(20)).unwrap();
+        record_in(&dir, b"two", at(30)).unwrap();
+        let versions = list_in(&dir);
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].saved_at, at(30));
+        assert_eq!(versions[1].read().unwrap(), "one\n");
+
+        for secs in 0..KEEP as u64 {
+            record_in(&dir, secs.to_string().as_bytes(), at(100 + secs)).unwrap();
+        }
+        let versions = list_in(&dir);
+        assert_eq!(versions.len(), KEEP);
+        assert_eq!(versions.last().unwrap().saved_at, at(100));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(age_label(at(0), at(7200)), "2h ago");
+    }
+}
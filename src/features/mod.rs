@@ -1,15 +1,56 @@
 //! Feature modules grouped under a single namespace.
 
+pub mod activity;
+pub mod block;
+pub mod colors;
 pub mod command_input;
 pub mod command_palette;
+pub mod comments;
+pub mod conflicts;
+pub mod ctags;
+pub mod diff;
 pub mod editor_buffer;
+pub mod encoding;
+pub mod exclusions;
+pub mod export;
+pub mod file_io;
 pub mod file_tree;
+pub mod file_viewer;
 pub mod find_replace;
+pub mod fonts;
+pub mod formatter;
 pub mod fuzzy_finder;
+pub mod git;
 pub mod icons;
+pub mod indentation;
+pub mod jumps;
+pub mod keymap;
+pub mod line_endings;
+pub mod line_length;
+pub mod local_history;
 pub mod lsp;
+pub mod notifications;
+pub mod numbers;
+pub mod output;
+pub mod pairs;
+pub mod paths;
+pub mod recovery;
+pub mod rename;
 pub mod resources;
 pub mod search;
+pub mod selection;
+pub mod smooth_scroll;
+pub mod snippets;
+pub mod spelling;
+pub mod status_line;
+pub mod sticky_scroll;
+pub mod symbols;
 pub mod syntax;
+pub mod tags;
+pub mod tasks;
 pub mod terminal;
+pub mod text_stats;
+pub mod transforms;
 pub mod updater;
+pub mod whitespace;
+pub mod workers;
@@ -0,0 +1,158 @@
+//! Toast notifications and the history of past ones.
+//!
+//! Toasts stack in a corner of the window and go away on their own after
+//! a few seconds, errors staying a little longer. Every notification is
+//! also kept in a short history that can be opened from the palette.
+
+use crate::message::Message;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long info and success toasts stay up.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// How long warning and error toasts stay up.
+const ALERT_DURATION: Duration = Duration::from_secs(8);
+/// Toasts shown at once; older ones leave early.
+const MAX_TOASTS: usize = 4;
+/// Notifications kept in the history.
+const MAX_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub message: String,
+    pub severity: Severity,
+    pub shown_at: Instant,
+    /// Buttons shown on the toast, such as "Retry".
+    pub actions: Vec<(&'static str, Message)>,
+}
+
+impl Notification {
+    fn duration(&self) -> Duration {
+        match self.severity {
+            Severity::Info | Severity::Success => TOAST_DURATION,
+            Severity::Warning | Severity::Error => ALERT_DURATION,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Notifications {
+    toasts: Vec<Notification>,
+    /// Past notifications, newest first.
+    history: VecDeque<Notification>,
+    pub history_open: bool,
+}
+
+impl Notifications {
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        self.push_with_actions(severity, message, Vec::new());
+    }
+
+    pub fn push_with_actions(
+        &mut self,
+        severity: Severity,
+        message: impl Into<String>,
+        actions: Vec<(&'static str, Message)>,
+    ) {
+        let notification = Notification {
+            message: message.into(),
+            severity,
+            shown_at: Instant::now(),
+            actions,
+        };
+        self.history.push_front(notification.clone());
+        self.history.truncate(MAX_HISTORY);
+        self.toasts.push(notification);
+        let excess = self.toasts.len().saturating_sub(MAX_TOASTS);
+        self.toasts.drain(..excess);
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Severity::Info, message);
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(Severity::Success, message);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Severity::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Severity::Error, message);
+    }
+
+    /// Toasts currently shown, oldest first.
+    pub fn toasts(&self) -> &[Notification] {
+        &self.toasts
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter()
+    }
+
+    pub fn dismiss(&mut self, index: usize) {
+        if index < self.toasts.len() {
+            self.toasts.remove(index);
+        }
+    }
+
+    /// Removes toasts that have been shown long enough.
+    pub fn expire(&mut self, now: Instant) {
+        self.toasts
+            .retain(|toast| now.duration_since(toast.shown_at) < toast.duration());
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.history_open = false;
+    }
+}
+
+/// Rough age such as `just now`, `5m ago` or `2h ago`.
+pub fn age_label(shown_at: Instant, now: Instant) -> String {
+    let secs = now.duration_since(shown_at).as_secs();
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_toasts_but_keeps_history() {
+        let mut notifications = Notifications::default();
+        for n in 0..6 {
+            notifications.info(format!("saved {n}"));
+        }
+        notifications.error("write failed");
+        assert_eq!(notifications.toasts().len(), MAX_TOASTS);
+        assert_eq!(notifications.history().count(), 7);
+        assert_eq!(
+            notifications.history().next().map(|n| n.severity),
+            Some(Severity::Error)
+        );
+
+        let later = Instant::now() + TOAST_DURATION;
+        notifications.expire(later);
+        let left: Vec<_> = notifications.toasts().iter().map(|n| &n.message).collect();
+        assert_eq!(left, ["write failed"]);
+    }
+}
@@ -0,0 +1,111 @@
+//! Stepping the number under or after the cursor up or down, like Vim's
+//! Ctrl+A and Ctrl+X.
+//!
+//! Decimal numbers may be negative and keep their leading zeros. `0x`
+//! numbers are stepped as unsigned hex, keeping their width and case.
+
+use std::ops::Range;
+
+struct Number {
+    /// Character columns of the number, sign and prefix included.
+    range: Range<usize>,
+    hex: bool,
+}
+
+/// The first number in `chars` ending after column `col`.
+fn find(chars: &[char], col: usize) -> Option<Number> {
+    let mut idx = 0;
+    while idx < chars.len() {
+        let is_hex = chars[idx] == '0'
+            && matches!(chars.get(idx + 1), Some('x' | 'X'))
+            && chars.get(idx + 2).is_some_and(|c| c.is_ascii_hexdigit());
+        let (start, digits_start, hex) = if is_hex {
+            (idx, idx + 2, true)
+        } else if chars[idx].is_ascii_digit() {
+            let negative = idx > 0 && chars[idx - 1] == '-';
+            (if negative { idx - 1 } else { idx }, idx, false)
+        } else {
+            idx += 1;
+            continue;
+        };
+        let is_digit = |c: &char| {
+            if hex {
+                c.is_ascii_hexdigit()
+            } else {
+                c.is_ascii_digit()
+            }
+        };
+        let end = chars[digits_start..]
+            .iter()
+            .position(|c| !is_digit(c))
+            .map_or(chars.len(), |len| digits_start + len);
+        if end > col {
+            return Some(Number {
+                range: start..end,
+                hex,
+            });
+        }
+        idx = end;
+    }
+    None
+}
+
+/// Adds `delta` to the first number of `line` under or after column `col`.
+/// Returns the columns of the number and its new text, or `None` when
+/// there is no number there or it does not fit in 64 bits.
+pub fn step(line: &str, col: usize, delta: i64) -> Option<(Range<usize>, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let number = find(&chars, col)?;
+    let text: String = chars[number.range.clone()].iter().collect();
+
+    let stepped = if number.hex {
+        let (prefix, digits) = text.split_at(2);
+        let value = u64::from_str_radix(digits, 16).ok()?;
+        let value = value.wrapping_add(delta as u64);
+        let width = digits.len();
+        if digits.chars().any(|c| c.is_ascii_uppercase()) {
+            format!("{prefix}{value:0width$X}")
+        } else {
+            format!("{prefix}{value:0width$x}")
+        }
+    } else {
+        let value: i64 = text.parse().ok()?;
+        let value = value.saturating_add(delta);
+        let digits = text.trim_start_matches('-');
+        // Zero padding is kept, a plain number grows and shrinks freely
+        let width = if digits.len() > 1 && digits.starts_with('0') {
+            digits.len()
+        } else {
+            1
+        };
+        let sign = if value < 0 { "-" } else { "" };
+        format!("{sign}{:0width$}", value.unsigned_abs())
+    };
+    Some((number.range, stepped))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_numbers_after_the_cursor() {
+        assert_eq!(step("let x = 41;", 0, 1), Some((8..10, "42".to_string())));
+        assert_eq!(step("x = 1, y = 9", 6, 1), Some((11..12, "10".to_string())));
+        assert_eq!(step("a = -1", 0, 3), Some((4..6, "2".to_string())));
+        assert_eq!(step("a = 2", 0, -5), Some((4..5, "-3".to_string())));
+        assert_eq!(step("frame_007", 0, 1), Some((6..9, "008".to_string())));
+        assert_eq!(step("0x0f", 1, 1), Some((0..4, "0x10".to_string())));
+        assert_eq!(step("0xFF", 0, 1), Some((0..4, "0x100".to_string())));
+        assert_eq!(
+            step("0x00", 0, -1),
+            Some((0..4, "0xffffffffffffffff".to_string()))
+        );
+        assert_eq!(step("no numbers", 0, 1), None);
+        assert_eq!(step("1 2", 2, 1), Some((2..3, "3".to_string())));
+    }
+}
@@ -0,0 +1,253 @@
+//! Parsing of command output for the output panel: ANSI colors and
+//! clickable `path:line:col` and URL links.
+
+use iced::Color;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static LINK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"https?://[^\s<>()]+|(?:[A-Za-z]:)?[\w.\-/\\~]*\w\.\w+:(\d+)(?::(\d+))?")
+        .expect("valid link pattern")
+});
+
+/// A clickable location in the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    /// A file position, 1-based as printed by compilers.
+    File {
+        path: String,
+        line: usize,
+        column: usize,
+    },
+    Url(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub text: String,
+    pub color: Option<Color>,
+    pub link: Option<Link>,
+}
+
+/// Color set by SGR escapes, carried from one line to the next.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnsiState {
+    color: Option<Color>,
+}
+
+/// One of the 16 basic terminal colors.
+fn basic_color(index: u8) -> Color {
+    const COLORS: [(u8, u8, u8); 16] = [
+        (0x45, 0x47, 0x5a),
+        (0xf3, 0x8b, 0xa8),
+        (0xa6, 0xe3, 0xa1),
+        (0xf9, 0xe2, 0xaf),
+        (0x89, 0xb4, 0xfa),
+        (0xf5, 0xc2, 0xe7),
+        (0x94, 0xe2, 0xd5),
+        (0xba, 0xc2, 0xde),
+        (0x58, 0x5b, 0x70),
+        (0xf3, 0x8b, 0xa8),
+        (0xa6, 0xe3, 0xa1),
+        (0xf9, 0xe2, 0xaf),
+        (0x89, 0xb4, 0xfa),
+        (0xf5, 0xc2, 0xe7),
+        (0x94, 0xe2, 0xd5),
+        (0xa6, 0xad, 0xc8),
+    ];
+    let (r, g, b) = COLORS[index as usize % 16];
+    Color::from_rgb8(r, g, b)
+}
+
+/// A color of the 256 color palette.
+fn palette_color(index: u8) -> Color {
+    match index {
+        0..=15 => basic_color(index),
+        16..=231 => {
+            let index = index - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            Color::from_rgb8(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            Color::from_rgb8(gray, gray, gray)
+        }
+    }
+}
+
+impl AnsiState {
+    /// Applies the parameters of an SGR (`ESC [ ... m`) sequence. Only the
+    /// foreground color is kept.
+    fn apply(&mut self, params: &str) {
+        let mut codes = params
+            .split(';')
+            .map(|code| code.parse::<u8>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 | 39 => self.color = None,
+                30..=37 => self.color = Some(basic_color(code - 30)),
+                90..=97 => self.color = Some(basic_color(code - 90 + 8)),
+                38 => match codes.next() {
+                    Some(5) => self.color = codes.next().map(palette_color),
+                    Some(2) => {
+                        let (r, g, b) = (codes.next(), codes.next(), codes.next());
+                        if let (Some(r), Some(g), Some(b)) = (r, g, b) {
+                            self.color = Some(Color::from_rgb8(r, g, b));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Splits a line of output into colored segments, linking file positions
+/// and URLs. Escape sequences other than SGR are dropped.
+pub fn parse_line(line: &str, state: &mut AnsiState) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('\x1b') else {
+            push_linked(&mut segments, rest, state.color);
+            break;
+        };
+        push_linked(&mut segments, &rest[..start], state.color);
+        rest = &rest[start + 1..];
+
+        let Some(csi) = rest.strip_prefix('[') else {
+            // A lone escape, skip the character after it
+            let mut chars = rest.chars();
+            chars.next();
+            rest = chars.as_str();
+            continue;
+        };
+        let end = csi
+            .find(|c: char| c.is_ascii_alphabetic() || c == '~')
+            .unwrap_or(csi.len().saturating_sub(1));
+        if csi[end..].starts_with('m') {
+            state.apply(&csi[..end]);
+        }
+        rest = csi.get(end + 1..).unwrap_or("");
+    }
+    segments
+}
+
+fn push_linked(segments: &mut Vec<Segment>, text: &str, color: Option<Color>) {
+    let mut last = 0;
+    for found in LINK_RE.captures_iter(text) {
+        let whole = found.get(0).expect("match");
+        let link = match found.get(1) {
+            Some(line) => Link::File {
+                path: text[whole.start()..line.start() - 1].to_string(),
+                line: line.as_str().parse().unwrap_or(1),
+                column: found
+                    .get(2)
+                    .and_then(|col| col.as_str().parse().ok())
+                    .unwrap_or(1),
+            },
+            None => Link::Url(whole.as_str().trim_end_matches(['.', ',']).to_string()),
+        };
+        let end = match &link {
+            Link::Url(url) => whole.start() + url.len(),
+            Link::File { .. } => whole.end(),
+        };
+        if whole.start() > last {
+            segments.push(Segment {
+                text: text[last..whole.start()].to_string(),
+                color,
+                link: None,
+            });
+        }
+        segments.push(Segment {
+            text: text[whole.start()..end].to_string(),
+            color,
+            link: Some(link),
+        });
+        last = end;
+    }
+    if last < text.len() {
+        segments.push(Segment {
+            text: text[last..].to_string(),
+            color,
+            link: None,
+        });
+    }
+}
+
+/// Opens `url` in the default browser.
+pub fn open_url(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(url).spawn().map(|_| ())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_positions_and_strips_escapes() {
+        let mut state = AnsiState::default();
+        let line = "\x1b[1m\x1b[94m  --> \x1b[0msrc/main.rs:12:5";
+        let segments = parse_line(line, &mut state);
+        let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(text, "  --> src/main.rs:12:5");
+        assert_eq!(segments[0].color, Some(basic_color(12)));
+        assert_eq!(
+            segments[1].link,
+            Some(Link::File {
+                path: "src/main.rs".to_string(),
+                line: 12,
+                column: 5,
+            })
+        );
+
+        let segments = parse_line("see https://example.com/x.", &mut state);
+        assert_eq!(
+            segments[1].link,
+            Some(Link::Url("https://example.com/x".to_string()))
+        );
+    }
+
+    #[test]
+    fn finds_every_link_on_a_line() {
+        let links = |line: &str| -> Vec<Link> {
+            parse_line(line, &mut AnsiState::default())
+                .into_iter()
+                .filter_map(|segment| segment.link)
+                .collect()
+        };
+        let file = |path: &str, line, column| Link::File {
+            path: path.to_string(),
+            line,
+            column,
+        };
+
+        assert_eq!(
+            links(r"C:\src\lib.rs:3 failed, see https://x.io/a?b=1, then ./lib.rs:4:2."),
+            [
+                file(r"C:\src\lib.rs", 3, 1),
+                Link::Url("https://x.io/a?b=1".to_string()),
+                file("./lib.rs", 4, 2),
+            ]
+        );
+        assert_eq!(
+            links("at ~/app/index.test.js:10:15)"),
+            [file("~/app/index.test.js", 10, 15)]
+        );
+        assert!(links("finished at 12:30, ratio 1:2, see Cargo.toml").is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+//! Auto-closing of brackets and quotes while typing.
+//!
+//! Which pairs close is decided per language, and whole languages can be
+//! left out with the `auto_close_exclude` preference, a comma separated list
+//! of language names.
+
+use crate::language::Language;
+
+const BRACKETS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}')];
+const WITH_QUOTES: &[(char, char)] =
+    &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\'')];
+/// Lifetimes make `'` a poor pair in Rust.
+const WITH_DOUBLE_QUOTES: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"')];
+
+/// Pairs that auto-close in `language`, honouring the exclude list.
+pub fn pairs_for(language: Language, exclude: &str) -> &'static [(char, char)] {
+    let name = language.name().to_lowercase();
+    if exclude
+        .split(',')
+        .any(|entry| entry.trim().to_lowercase() == name)
+    {
+        return &[];
+    }
+    match language {
+        Language::Rust => WITH_DOUBLE_QUOTES,
+        Language::Markdown | Language::PlainText => BRACKETS,
+        _ => WITH_QUOTES,
+    }
+}
+
+/// Whether typing `open` should also insert its closer, given the
+/// characters around the cursor. Pairs are only added before whitespace,
+/// closers or the end of the line, and quotes never follow a word.
+pub fn should_close(open: char, prev: Option<char>, next: Option<char>) -> bool {
+    let before_ok =
+        next.is_none_or(|ch| ch.is_whitespace() || matches!(ch, ')' | ']' | '}' | ',' | ';'));
+    let is_quote = matches!(open, '"' | '\'');
+    before_ok && !(is_quote && prev.is_some_and(|ch| ch.is_alphanumeric() || ch == '_'))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closes_only_in_open_space() {
+        assert!(should_close('(', Some('f'), None));
+        assert!(should_close('[', None, Some(')')));
+        assert!(!should_close('(', None, Some('x')));
+        assert!(!should_close('\'', Some('n'), None));
+        assert!(should_close('"', Some(' '), Some(' ')));
+    }
+
+    #[test]
+    fn languages_can_be_excluded() {
+        assert!(!pairs_for(Language::Rust, "").contains(&('\'', '\'')));
+        assert!(pairs_for(Language::Python, "").contains(&('\'', '\'')));
+        assert!(pairs_for(Language::Python, "rust, Python").is_empty());
+    }
+}
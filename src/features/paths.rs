@@ -0,0 +1,136 @@
+//! Expansion of `~` and environment variables in paths typed by the user.
+//!
+//! Supports `~`, `$VAR`, `${VAR}` and Windows style `%VAR%`. Variables that
+//! are not set are left untouched so the mistake stays visible.
+
+use std::path::{Path, PathBuf};
+
+/// The user's home directory, from `HOME` or `USERPROFILE` on Windows.
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Expands `~` and environment variables in `input`.
+pub fn expand(input: &str) -> String {
+    expand_with(input, |name| {
+        if name == "HOME" {
+            home_dir().map(|home| home.to_string_lossy().to_string())
+        } else {
+            std::env::var(name).ok()
+        }
+    })
+}
+
+/// Expands `input` and makes it absolute, relative paths being joined onto
+/// `base` when one is given.
+pub fn resolve(input: &str, base: Option<&Path>) -> PathBuf {
+    let path = PathBuf::from(expand(input.trim()));
+    match base {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path,
+    }
+}
+
+fn is_var_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Expansion with a custom variable lookup, `HOME` also being used for `~`.
+fn expand_with(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    // `~` only means home at the start, followed by a separator or nothing
+    if let Some(after) = rest.strip_prefix('~') {
+        if after.is_empty() || after.starts_with(['/', '\\']) {
+            if let Some(home) = lookup("HOME") {
+                out.push_str(&home);
+                rest = after;
+            }
+        }
+    }
+
+    while let Some(idx) = rest.find(['$', '%']) {
+        out.push_str(&rest[..idx]);
+        let tail = &rest[idx..];
+
+        let (name, len) = if let Some(braced) = tail.strip_prefix("${") {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 3),
+                None => ("", 0),
+            }
+        } else if let Some(percent) = tail.strip_prefix('%') {
+            match percent.find('%') {
+                Some(end) if percent[..end].chars().all(is_var_char) => (&percent[..end], end + 2),
+                _ => ("", 0),
+            }
+        } else {
+            let name_len = tail[1..]
+                .find(|c: char| !is_var_char(c))
+                .unwrap_or(tail.len() - 1);
+            (&tail[1..1 + name_len], name_len + 1)
+        };
+
+        match lookup(name).filter(|_| !name.is_empty()) {
+            Some(value) => {
+                out.push_str(&value);
+                rest = &tail[len..];
+            }
+            None => {
+                out.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/cat".to_string()),
+            "USERPROFILE" => Some(r"C:\Users\cat".to_string()),
+            "PROJECT" => Some("pinel".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_tilde_only_at_start() {
+        assert_eq!(expand_with("~/src", lookup), "/home/cat/src");
+        assert_eq!(expand_with("~", lookup), "/home/cat");
+        assert_eq!(expand_with("~other/src", lookup), "~other/src");
+        assert_eq!(expand_with("a/~/b", lookup), "a/~/b");
+    }
+
+    #[test]
+    fn expands_unix_variables() {
+        assert_eq!(expand_with("$HOME/$PROJECT", lookup), "/home/cat/pinel");
+        assert_eq!(expand_with("${PROJECT}_old", lookup), "pinel_old");
+        assert_eq!(expand_with("$MISSING/x", lookup), "$MISSING/x");
+        assert_eq!(expand_with("cost$", lookup), "cost$");
+    }
+
+    #[test]
+    fn expands_windows_variables() {
+        assert_eq!(
+            expand_with(r"%USERPROFILE%\Documents", lookup),
+            r"C:\Users\cat\Documents"
+        );
+        assert_eq!(expand_with(r"~\Desktop", lookup), r"/home/cat\Desktop");
+        assert_eq!(expand_with("100% done", lookup), "100% done");
+        assert_eq!(expand_with("%MISSING%", lookup), "%MISSING%");
+    }
+}
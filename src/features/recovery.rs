@@ -0,0 +1,241 @@
+//! Crash recovery: unsaved buffers are copied to swap files in the
+//! config folder every so often and removed again on a clean exit.
+//!
+//! Swap files are named after the process that wrote them, so files whose
+//! process is gone were left behind by a crash and can be restored on the
+//! next launch, while a second running instance keeps its own.
+
+use crate::config::theme_manager::get_config_dir;
+use crate::features::file_io::stable_hash;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Seconds between two snapshots of the unsaved buffers.
+pub const INTERVAL_SECS: u64 = 30;
+
+/// First line of every swap file, followed by the buffer's path.
+const HEADER: &str = "pinel-recovery\t";
+
+pub fn recovery_dir() -> PathBuf {
+    get_config_dir().join("recovery")
+}
+
+/// A buffer left behind by a previous session.
+#[derive(Debug, Clone)]
+pub struct Recovered {
+    /// File the buffer belongs to, `None` for untitled buffers.
+    pub path: Option<PathBuf>,
+    pub content: String,
+    swap: PathBuf,
+}
+
+impl Recovered {
+    pub fn name(&self) -> String {
+        self.path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string())
+    }
+
+    /// Deletes the swap file once the buffer was restored or discarded.
+    pub fn remove(&self) {
+        let _ = fs::remove_file(&self.swap);
+    }
+}
+
+/// Unsaved buffer to snapshot. `key` tells buffers apart, including
+/// untitled ones that share a path.
+pub struct Snapshot<'a> {
+    pub key: String,
+    pub path: Option<&'a Path>,
+    pub content: String,
+}
+
+/// Swap files written by this process, with a hash of what they hold.
+#[derive(Debug, Default)]
+pub struct Recovery {
+    written: HashMap<String, u64>,
+}
+
+impl Recovery {
+    /// Writes swap files for buffers that changed since the last call and
+    /// removes those of buffers that were saved or closed.
+    pub fn sync(&mut self, snapshots: Vec<Snapshot>) -> std::io::Result<()> {
+        self.sync_in(&recovery_dir(), snapshots)
+    }
+
+    fn sync_in(&mut self, dir: &Path, snapshots: Vec<Snapshot>) -> std::io::Result<()> {
+        let mut seen = HashMap::new();
+        for snapshot in snapshots {
+            let hash = stable_hash(snapshot.content.as_bytes());
+            if self.written.get(&snapshot.key) != Some(&hash) {
+                fs::create_dir_all(dir)?;
+                let path = snapshot
+                    .path
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let content = format!("{HEADER}{path}\n{}", snapshot.content);
+                fs::write(swap_path(dir, &snapshot.key), content)?;
+            }
+            seen.insert(snapshot.key, hash);
+        }
+        for key in self.written.keys() {
+            if !seen.contains_key(key) {
+                let _ = fs::remove_file(swap_path(dir, key));
+            }
+        }
+        self.written = seen;
+        Ok(())
+    }
+
+    /// Removes every swap file of this process, on a clean exit.
+    pub fn clear(&mut self) {
+        self.clear_in(&recovery_dir());
+    }
+
+    fn clear_in(&mut self, dir: &Path) {
+        for key in self.written.keys() {
+            let _ = fs::remove_file(swap_path(dir, key));
+        }
+        self.written.clear();
+    }
+}
+
+fn swap_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!(
+        "{}-{:016x}.swp",
+        std::process::id(),
+        stable_hash(key.as_bytes())
+    ))
+}
+
+/// Buffers left behind by sessions that did not exit cleanly.
+pub fn orphaned() -> Vec<Recovered> {
+    orphaned_in(&recovery_dir())
+}
+
+fn orphaned_in(dir: &Path) -> Vec<Recovered> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut recovered = Vec::new();
+    for entry in entries.flatten() {
+        let swap = entry.path();
+        let Some(pid) = swap
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| name.ends_with(".swp"))
+            .and_then(|name| name.split('-').next())
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == std::process::id() || process_alive(pid) {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&swap) else {
+            continue;
+        };
+        let Some((path, content)) = text
+            .strip_prefix(HEADER)
+            .and_then(|rest| rest.split_once('\n'))
+        else {
+            continue;
+        };
+        recovered.push(Recovered {
+            path: (!path.is_empty()).then(|| PathBuf::from(path)),
+            content: content.to_string(),
+            swap,
+        });
+    }
+    recovered
+}
+
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Asks `tasklist` for the process, which lists nothing but a notice when
+/// there is none. If it can't run, the owner is taken to be alive so a
+/// running instance never loses its swap files.
+#[cfg(not(unix))]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .output()
+        .map_or(true, |output| {
+            String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\""))
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_files_follow_buffers_and_outlive_crashes() {
+        let dir = std::env::temp_dir().join(format!("pinel-recovery-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let snapshot = |key: &str, content: &str| Snapshot {
+            key: key.to_string(),
+            path: Some(Path::new("/src/main.rs")),
+            content: content.to_string(),
+        };
+        let swaps = || fs::read_dir(&dir).map_or(0, |entries| entries.count());
+
+        let mut recovery = Recovery::default();
+        recovery
+            .sync_in(&dir, vec![snapshot("a", "one"), snapshot("b", "two")])
+            .unwrap();
+        assert_eq!(swaps(), 2);
+        // This process is alive, its own files are not orphaned
+        assert!(orphaned_in(&dir).is_empty());
+
+        recovery
+            .sync_in(&dir, vec![snapshot("a", "three")])
+            .unwrap();
+        assert_eq!(swaps(), 1);
+        let swap = swap_path(&dir, "a");
+        assert!(fs::read_to_string(&swap).unwrap().ends_with("\nthree"));
+
+        // A swap file left by a process that has exited
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let dead = child.id();
+        child.wait().unwrap();
+        let left = dir.join(format!("{dead}-{:016x}.swp", stable_hash(b"a")));
+        fs::copy(&swap, &left).unwrap();
+        let recovered = orphaned_in(&dir);
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(
+            recovered[0].path.as_deref(),
+            Some(Path::new("/src/main.rs"))
+        );
+        assert_eq!(recovered[0].content, "three");
+        recovered[0].remove();
+        assert!(!left.exists());
+
+        recovery.clear_in(&dir);
+        assert_eq!(swaps(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,101 @@
+//! Renaming a symbol everywhere it appears in a document, as a whole
+//! word so `count` is left alone when renaming `co`.
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Whether `name` can replace a symbol.
+pub fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(is_identifier_char)
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// A rename waiting for confirmation.
+#[derive(Debug, Clone)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+    pub count: usize,
+    /// 1-based numbers and new text of the lines that change.
+    pub preview: Vec<(usize, String)>,
+}
+
+impl Rename {
+    pub fn new(text: &str, from: &str, to: &str) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            count: occurrences(text, from).len(),
+            preview: preview(text, from, to),
+        }
+    }
+}
+
+/// Byte offsets of `name` in `text` where it is a whole word.
+pub fn occurrences(text: &str, name: &str) -> Vec<usize> {
+    if name.is_empty() {
+        return Vec::new();
+    }
+    text.match_indices(name)
+        .filter(|(idx, _)| {
+            let before = text[..*idx].chars().next_back();
+            let after = text[idx + name.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// `text` with every whole word `from` replaced by `to`.
+pub fn apply(text: &str, from: &str, to: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for idx in occurrences(text, from) {
+        out.push_str(&text[last..idx]);
+        out.push_str(to);
+        last = idx + from.len();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+fn preview(text: &str, from: &str, to: &str) -> Vec<(usize, String)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !occurrences(line, from).is_empty())
+        .map(|(idx, line)| (idx + 1, apply(line, from, to)))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renames_whole_words_only() {
+        let text = "let co = 1;\nlet count = co + co_2;\nprint(co)\n";
+        let rename = Rename::new(text, "co", "total");
+        assert_eq!(rename.count, 3);
+        assert_eq!(
+            rename.preview,
+            [
+                (1, "let total = 1;".to_string()),
+                (2, "let count = total + co_2;".to_string()),
+                (3, "print(total)".to_string()),
+            ]
+        );
+        assert_eq!(
+            apply(text, "co", "total"),
+            "let total = 1;\nlet count = total + co_2;\nprint(total)\n"
+        );
+        assert!(is_identifier("new_name"));
+        assert!(!is_identifier("2fast"));
+        assert!(!is_identifier("a b"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::features::exclusions::Exclusions;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use std::path::PathBuf;
@@ -15,18 +16,29 @@ pub struct SearchResult {
     pub matches: Vec<SearchMatch>,
 }
 
-pub fn search_workspace(root: &PathBuf, query: &str) -> Vec<SearchResult> {
-    use ignore::WalkBuilder;
+/// Walks the files under `root` that are not hidden, ignored by git or
+/// left out by `exclusions`, unless it shows hidden files.
+fn walk(root: &PathBuf, exclusions: &Exclusions) -> ignore::Walk {
+    let exclusions = exclusions.clone();
+    let skip_hidden = !exclusions.shows_hidden();
+    ignore::WalkBuilder::new(root)
+        .hidden(skip_hidden)
+        .git_ignore(skip_hidden)
+        .git_global(skip_hidden)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|kind| kind.is_dir());
+            !exclusions.excludes(entry.path(), is_dir)
+        })
+        .build()
+}
+
+pub fn search_workspace(root: &PathBuf, query: &str, exclusions: &Exclusions) -> Vec<SearchResult> {
     use std::fs;
 
     let query_lower = query.to_lowercase();
     let mut results = Vec::new();
 
-    let walker = WalkBuilder::new(root)
-        .hidden(true)
-        .git_ignore(true)
-        .git_global(true)
-        .build();
+    let walker = walk(root, exclusions);
 
     for entry in walker.flatten() {
         let path = entry.path();
@@ -64,16 +76,12 @@ pub fn search_workspace(root: &PathBuf, query: &str) -> Vec<SearchResult> {
     results
 }
 
-pub fn collect_all_files(root: &PathBuf) -> Vec<(String, PathBuf)> {
-    use ignore::WalkBuilder;
-
+/// Every file under `root` that is not hidden, ignored by git or left out
+/// by `exclusions`.
+pub fn collect_all_files(root: &PathBuf, exclusions: &Exclusions) -> Vec<(String, PathBuf)> {
     let mut files = Vec::new();
 
-    let walker = WalkBuilder::new(root)
-        .hidden(true)
-        .git_ignore(true)
-        .git_global(true)
-        .build();
+    let walker = walk(root, exclusions);
 
     for entry in walker.flatten() {
         let path = entry.path();
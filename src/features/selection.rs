@@ -0,0 +1,190 @@
+//! Scopes used by Expand Selection: word, string, bracket contents, the
+//! brackets themselves, the line and finally the whole document.
+//!
+//! Ranges are byte offsets into the document, end exclusive.
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
+
+fn word_around(text: &str, start: usize, end: usize) -> (usize, usize) {
+    let bytes = text.as_bytes();
+    let mut word_start = start;
+    while word_start > 0 && is_word_byte(bytes[word_start - 1]) {
+        word_start -= 1;
+    }
+    let mut word_end = end;
+    while word_end < bytes.len() && is_word_byte(bytes[word_end]) {
+        word_end += 1;
+    }
+    (word_start, word_end)
+}
+
+fn closer_of(open: u8) -> Option<u8> {
+    match open {
+        b'(' => Some(b')'),
+        b'[' => Some(b']'),
+        b'{' => Some(b'}'),
+        _ => None,
+    }
+}
+
+/// Innermost bracket pair whose contents hold `start..end`, as the offsets
+/// of the opening and closing bracket.
+fn enclosing_brackets(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    for open in (0..start).rev() {
+        match bytes[open] {
+            b')' | b']' | b'}' => depth += 1,
+            b'(' | b'[' | b'{' if depth > 0 => depth -= 1,
+            b'(' | b'[' | b'{' => {
+                let close = matching_closer(bytes, open)?;
+                if close >= end {
+                    return Some((open, close));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn matching_closer(bytes: &[u8], open: usize) -> Option<usize> {
+    let wanted = closer_of(bytes[open])?;
+    let mut depth = 0usize;
+    for (idx, &byte) in bytes.iter().enumerate().skip(open + 1) {
+        match byte {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' if depth > 0 => depth -= 1,
+            b')' | b']' | b'}' => return (byte == wanted).then_some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Quoted strings on the line holding `start`, as quote offsets.
+fn strings_on_line(text: &str, line_start: usize, line_end: usize) -> Vec<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut open: Option<usize> = None;
+    let mut idx = line_start;
+    while idx < line_end {
+        match (bytes[idx], open) {
+            (b'\\', Some(_)) => idx += 1,
+            (quote @ (b'"' | b'\'' | b'`'), None) => {
+                // An apostrophe inside a word is not a quote
+                let in_word = quote == b'\'' && idx > line_start && is_word_byte(bytes[idx - 1]);
+                if !in_word {
+                    open = Some(idx);
+                }
+            }
+            (quote, Some(at)) if quote == bytes[at] => {
+                spans.push((at, idx));
+                open = None;
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    spans
+}
+
+/// Offsets of the innermost brackets or quotes around `start..end`.
+pub fn surrounding_pair(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let (start, end) = (start.min(text.len()), end.min(text.len()));
+    let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    let line_end = text[end..].find('\n').map_or(text.len(), |idx| end + idx);
+    strings_on_line(text, line_start, line_end)
+        .into_iter()
+        .filter(|&(open, close)| open < start && close >= end)
+        .chain(enclosing_brackets(text, start, end))
+        .max_by_key(|&(open, _)| open)
+}
+
+/// The smallest scope that strictly contains `start..end`.
+pub fn expand(text: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let (start, end) = (start.min(text.len()), end.min(text.len()));
+    let mut candidates = vec![word_around(text, start, end)];
+
+    let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    let line_end = text[end..].find('\n').map_or(text.len(), |idx| end + idx);
+    for (open, close) in strings_on_line(text, line_start, line_end) {
+        candidates.push((open + 1, close));
+        candidates.push((open, close + 1));
+    }
+
+    if let Some((open, close)) = enclosing_brackets(text, start, end) {
+        candidates.push((open + 1, close));
+        candidates.push((open, close + 1));
+    }
+
+    let trimmed = &text[line_start..line_end];
+    let indent = trimmed.len() - trimmed.trim_start().len();
+    candidates.push((line_start + indent, line_start + trimmed.trim_end().len()));
+    candidates.push((line_start, line_end));
+    candidates.push((0, text.len()));
+
+    candidates
+        .into_iter()
+        .filter(|&(s, e)| s <= start && e >= end && (s, e) != (start, end))
+        .min_by_key(|&(s, e)| e - s)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(text: &str, at: usize, count: usize) -> Vec<&str> {
+        let mut range = (at, at);
+        let mut out = Vec::new();
+        for _ in 0..count {
+            range = expand(text, range.0, range.1).unwrap();
+            out.push(&text[range.0..range.1]);
+        }
+        out
+    }
+
+    #[test]
+    fn grows_from_word_through_string_and_brackets() {
+        let text = "fn main() {\n    call(\"hello world\", 2);\n}";
+        let at = text.find("world").unwrap();
+        assert_eq!(
+            steps(text, at, 6),
+            [
+                "world",
+                "hello world",
+                "\"hello world\"",
+                "\"hello world\", 2",
+                "(\"hello world\", 2)",
+                "call(\"hello world\", 2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn reaches_block_and_document() {
+        let text = "fn main() {\n    x();\n}";
+        let at = text.find('x').unwrap();
+        let out = steps(text, at, 5);
+        assert_eq!(out[2], "    x();");
+        assert_eq!(out[3], "\n    x();\n");
+        assert_eq!(out[4], "{\n    x();\n}");
+        assert_eq!(expand(text, 0, text.len()), None);
+    }
+
+    #[test]
+    fn finds_innermost_surrounding_pair() {
+        let text = "f(\"a b\", [c])";
+        let at = text.find('b').unwrap();
+        assert_eq!(surrounding_pair(text, at, at), Some((2, 6)));
+        let at = text.find('c').unwrap();
+        assert_eq!(surrounding_pair(text, at, at + 1), Some((9, 11)));
+        assert_eq!(surrounding_pair("plain", 2, 2), None);
+    }
+}
@@ -0,0 +1,170 @@
+//! Animated scrolling for jumps that land outside the editor's view, so it
+//! is clear which way and how far the view went.
+//!
+//! The editor widget can't be scrolled to an offset from outside, but it
+//! scrolls to keep its cursor in view. Each step of an animation moves the
+//! cursor to the line that puts the top of the view where that step needs
+//! it, keeps the scroll, and moves the cursor back.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Duration Toggle Smooth Scrolling turns the animation on with.
+pub const DEFAULT_DURATION_MS: u64 = 200;
+
+/// Lines the editor keeps between its cursor and the edges of the view.
+const EDITOR_MARGIN: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Fast at first, slowing down at the end.
+    #[default]
+    EaseOut,
+    /// Slow at both ends.
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "linear" => Self::Linear,
+            "ease_in_out" => Self::EaseInOut,
+            _ => Self::EaseOut,
+        }
+    }
+
+    /// Name used in the preferences file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::EaseOut => "ease_out",
+            Self::EaseInOut => "ease_in_out",
+        }
+    }
+
+    /// Share of the distance covered after `t`, the share of the time.
+    fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Self::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// A scroll of the editor at `path` on its way to a 0-based position.
+#[derive(Debug, Clone)]
+pub struct ScrollAnimation {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    from: f32,
+    to: f32,
+    started: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl ScrollAnimation {
+    /// Starts scrolling from the view's top at `from` to where the editor
+    /// would scroll for a cursor on `line`, or `None` when the line is
+    /// already in view.
+    pub fn new(
+        path: PathBuf,
+        (line, column): (usize, usize),
+        from: f32,
+        line_height: f32,
+        viewport_height: f32,
+        duration: Duration,
+        easing: Easing,
+    ) -> Option<Self> {
+        let margin = EDITOR_MARGIN * line_height;
+        let line_top = line as f32 * line_height;
+        let to = if line_top < from + margin {
+            (line_top - margin).max(0.0)
+        } else if line_top + line_height > from + viewport_height - margin {
+            line_top + line_height + margin - viewport_height
+        } else {
+            return None;
+        };
+        Some(Self {
+            path,
+            line,
+            column,
+            from,
+            to,
+            started: Instant::now(),
+            duration,
+            easing,
+        })
+    }
+
+    /// Top of the view at `now`, or `None` once the animation is over.
+    pub fn top_at(&self, now: Instant) -> Option<f32> {
+        let elapsed = now.duration_since(self.started);
+        if elapsed >= self.duration {
+            return None;
+        }
+        let t = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        Some(self.from + (self.to - self.from) * self.easing.apply(t))
+    }
+
+    /// Line the cursor has to move to for the editor to scroll its view's
+    /// top to `top`.
+    pub fn anchor_line(&self, top: f32, line_height: f32, viewport_height: f32) -> usize {
+        let line = if self.to > self.from {
+            (top + viewport_height) / line_height - 1.0 - EDITOR_MARGIN
+        } else {
+            top / line_height + EDITOR_MARGIN
+        };
+        line.max(0.0).round() as usize
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrolls_toward_the_target() {
+        for easing in [Easing::Linear, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(Easing::parse(easing.key()), easing);
+        }
+
+        let animation = |line| {
+            ScrollAnimation::new(
+                PathBuf::from("a.rs"),
+                (line, 0),
+                200.0,
+                10.0,
+                100.0,
+                Duration::from_millis(200),
+                Easing::Linear,
+            )
+        };
+        // Lines 20 to 29 are in view
+        assert!(animation(25).is_none());
+        let down = animation(100).unwrap();
+        assert_eq!(down.to, 930.0);
+        assert_eq!(down.anchor_line(930.0, 10.0, 100.0), 100);
+        let up = animation(5).unwrap();
+        assert_eq!(up.to, 30.0);
+        assert_eq!(up.anchor_line(30.0, 10.0, 100.0), 5);
+
+        assert_eq!(down.top_at(down.started), Some(200.0));
+        assert_eq!(
+            down.top_at(down.started + Duration::from_millis(100)),
+            Some(565.0)
+        );
+        assert_eq!(down.top_at(down.started + Duration::from_millis(200)), None);
+    }
+}
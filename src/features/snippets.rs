@@ -0,0 +1,155 @@
+//! Sharing code: uploads the selection or a file to a paste service and
+//! hands back the link. The default endpoint creates a secret GitHub Gist,
+//! which needs a token with the `gist` scope.
+//!
+//! The settings live in `~/.config/pinel/snippets.lua`, written the same
+//! way as the WakaTime ones.
+
+use crate::config::theme_manager::get_config_dir;
+use std::fs;
+use std::path::PathBuf;
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone)]
+pub struct SnippetConfig {
+    pub endpoint: String,
+    pub token: String,
+}
+
+impl Default for SnippetConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://api.github.com/gists".to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+fn get_snippets_path() -> PathBuf {
+    get_config_dir().join("snippets.lua")
+}
+
+pub fn load() -> SnippetConfig {
+    fs::read_to_string(get_snippets_path())
+        .map(|content| from_lua(&content))
+        .unwrap_or_default()
+}
+
+pub fn save(cfg: &SnippetConfig) -> Result<(), std::io::Error> {
+    let path = get_snippets_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, to_lua(cfg))
+}
+
+fn to_lua(cfg: &SnippetConfig) -> String {
+    format!(
+        "return {{\n endpoint = \"{}\",\n token = \"{}\",\n}}\n",
+        cfg.endpoint, cfg.token
+    )
+}
+
+fn from_lua(content: &str) -> SnippetConfig {
+    let mut cfg = SnippetConfig::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = value
+            .trim()
+            .trim_end_matches(',')
+            .trim_matches('"')
+            .trim_matches('\'')
+            .to_string();
+        match key.trim() {
+            "endpoint" => cfg.endpoint = value,
+            "token" => cfg.token = value,
+            _ => {}
+        }
+    }
+    cfg
+}
+
+/// The link in a paste service's reply: `html_url` for gists, `url` for
+/// services answering with JSON, or the whole reply when it is just a link.
+fn link_in(reply: &str) -> Option<String> {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(reply) {
+        return ["html_url", "url"]
+            .iter()
+            .find_map(|key| json.get(key)?.as_str())
+            .map(str::to_string);
+    }
+    let reply = reply.trim();
+    (reply.starts_with("http://") || reply.starts_with("https://")).then(|| reply.to_string())
+}
+
+/// Uploads `content` as a secret snippet named `name` and returns its link.
+pub async fn upload(cfg: SnippetConfig, name: String, content: String) -> Result<String, String> {
+    let endpoint = cfg.endpoint.trim();
+    if endpoint.is_empty() {
+        return Err("no snippet endpoint is set".to_string());
+    }
+    let client = reqwest::Client::builder()
+        .user_agent(format!("pinel/{}", CURRENT_VERSION))
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let body = serde_json::json!({
+        "description": name,
+        "public": false,
+        "files": { name.as_str(): { "content": content } },
+    });
+    let mut request = client
+        .post(endpoint)
+        .header("Accept", "application/vnd.github+json")
+        .json(&body);
+    if !cfg.token.trim().is_empty() {
+        request = request.bearer_auth(cfg.token.trim());
+    }
+
+    let response = request.send().await.map_err(|err| err.to_string())?;
+    let status = response.status();
+    let reply = response.text().await.map_err(|err| err.to_string())?;
+    if !status.is_success() {
+        return Err(format!("the service answered {status}"));
+    }
+    link_in(&reply).ok_or_else(|| "the reply had no link".to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_settings_and_reply_links() {
+        let cfg = SnippetConfig {
+            endpoint: "https://paste.example/api".to_string(),
+            token: "abc".to_string(),
+        };
+        let read = from_lua(&to_lua(&cfg));
+        assert_eq!(read.endpoint, cfg.endpoint);
+        assert_eq!(read.token, cfg.token);
+        assert_eq!(from_lua("").endpoint, SnippetConfig::default().endpoint);
+
+        assert_eq!(
+            link_in(
+                r#"{"url":"https://api.github.com/gists/1","html_url":"https://gist.github.com/1"}"#
+            )
+            .as_deref(),
+            Some("https://gist.github.com/1")
+        );
+        assert_eq!(
+            link_in("https://paste.example/x\n").as_deref(),
+            Some("https://paste.example/x")
+        );
+        assert_eq!(link_in("{\"id\":3}"), None);
+        assert_eq!(link_in("error"), None);
+    }
+}
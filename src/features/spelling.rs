@@ -0,0 +1,394 @@
+//! Spell checking of comments, strings and prose.
+//!
+//! Words come from hunspell `.dic` files in the `dictionaries` folder of
+//! the config folder, or else from the system's English hunspell
+//! dictionary. Affix rules are not applied; common English endings are
+//! stripped instead. Words added while editing go to `.pinel/words.txt` in
+//! the open folder, or to `words.txt` in the config folder.
+
+use crate::config::theme_manager::get_config_dir;
+use crate::features::comments;
+use crate::language::Language;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Suggestions offered for a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+/// Longer words only get suggestions one edit away.
+const MAX_TWO_EDIT_LEN: usize = 12;
+
+const SYSTEM_DICTIONARIES: &[&str] = &[
+    "/usr/share/hunspell/en_US.dic",
+    "/usr/share/myspell/en_US.dic",
+    "/usr/share/myspell/dicts/en_US.dic",
+    "/Library/Spelling/en_US.dic",
+];
+
+/// Suffixes stripped when a word is not in the dictionary as written,
+/// with what replaces them.
+const SUFFIXES: &[(&str, &str)] = &[
+    ("'s", ""),
+    ("ies", "y"),
+    ("ied", "y"),
+    ("es", ""),
+    ("s", ""),
+    ("ed", ""),
+    ("ed", "e"),
+    ("ing", ""),
+    ("ing", "e"),
+    ("ly", ""),
+    ("er", ""),
+    ("est", ""),
+];
+
+/// A misspelled word, by 0-based character columns on its line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+}
+
+/// A misspelled word and the replacements offered for it.
+#[derive(Debug, Clone)]
+pub struct Suggestions {
+    /// 0-based line of the word.
+    pub line: usize,
+    pub misspelling: Misspelling,
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+    /// Words added by the user, personal and for the open folder.
+    added: HashSet<String>,
+}
+
+pub fn dictionaries_dir() -> PathBuf {
+    get_config_dir().join("dictionaries")
+}
+
+/// Loads the configured dictionaries and the user's personal words.
+pub fn load() -> Dictionary {
+    let mut dictionary = Dictionary::default();
+    let configured: Vec<PathBuf> = fs::read_dir(dictionaries_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dic"))
+        .collect();
+    let files = if configured.is_empty() {
+        SYSTEM_DICTIONARIES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+            .into_iter()
+            .collect()
+    } else {
+        configured
+    };
+    for path in files {
+        if let Ok(bytes) = fs::read(&path) {
+            add_dic_words(&String::from_utf8_lossy(&bytes), &mut dictionary.words);
+        }
+    }
+    dictionary.load_added(None);
+    dictionary
+}
+
+/// Adds the words of a hunspell `.dic` file, whose first line is the word
+/// count and whose entries may end in `/FLAGS`.
+fn add_dic_words(content: &str, words: &mut HashSet<String>) {
+    for line in content.lines() {
+        let word = line.split('/').next().unwrap_or("").trim();
+        if !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()) {
+            words.insert(word.to_lowercase());
+        }
+    }
+}
+
+/// File words added by the user are kept in.
+fn words_path(root: Option<&Path>) -> PathBuf {
+    match root {
+        Some(root) => root.join(".pinel").join("words.txt"),
+        None => get_config_dir().join("words.txt"),
+    }
+}
+
+impl Dictionary {
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Reloads the personal words and those of the folder at `root`.
+    pub fn load_added(&mut self, root: Option<&Path>) {
+        self.added.clear();
+        let paths =
+            std::iter::once(words_path(None)).chain(root.map(|root| words_path(Some(root))));
+        for path in paths {
+            if let Ok(content) = fs::read_to_string(path) {
+                add_dic_words(&content, &mut self.added);
+            }
+        }
+    }
+
+    /// Accepts `word` from now on, remembering it for the folder at `root`
+    /// or, without one, for every file.
+    pub fn add(&mut self, word: &str, root: Option<&Path>) -> std::io::Result<()> {
+        let path = words_path(root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{word}")?;
+        self.added.insert(word.to_lowercase());
+        Ok(())
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.words.contains(word) || self.added.contains(word)
+    }
+
+    pub fn is_known(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.contains(&word)
+            || SUFFIXES.iter().any(|(suffix, replacement)| {
+                word.strip_suffix(suffix)
+                    .filter(|stem| stem.chars().count() > 1)
+                    .is_some_and(|stem| self.contains(&format!("{stem}{replacement}")))
+            })
+    }
+
+    /// Known words one edit away from `word`, or two for shorter words,
+    /// in the case `word` was written in.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let mut found = Vec::new();
+        let first = edits(&lower);
+        for candidate in &first {
+            if self.contains(candidate) && !found.contains(candidate) {
+                found.push(candidate.clone());
+            }
+        }
+        if found.is_empty() && lower.chars().count() <= MAX_TWO_EDIT_LEN {
+            'outer: for edit in &first {
+                for candidate in edits(edit) {
+                    if self.contains(&candidate) && !found.contains(&candidate) {
+                        found.push(candidate);
+                        if found.len() == MAX_SUGGESTIONS {
+                            break 'outer;
+                        }
+                    }
+                }
+            }
+        }
+        found.truncate(MAX_SUGGESTIONS);
+        found
+            .into_iter()
+            .map(|candidate| match_case(word, &candidate))
+            .collect()
+    }
+
+    /// Misspelled words in the comments and strings of a line of code, or
+    /// anywhere on a line of prose.
+    pub fn check_line(&self, line: &str, language: Language) -> Vec<Misspelling> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut misspellings = Vec::new();
+        for (from, to) in checked_ranges(line, language) {
+            for (offset, word) in words(line, from, to) {
+                if !self.is_known(word) {
+                    let start = line[..offset].chars().count();
+                    misspellings.push(Misspelling {
+                        start,
+                        end: start + word.chars().count(),
+                        word: word.to_string(),
+                    });
+                }
+            }
+        }
+        misspellings
+    }
+}
+
+/// Strings one deletion, transposition, replacement or insertion away.
+fn edits(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let letters = ('a'..='z').chain(['\'']);
+    let mut out = Vec::new();
+    for i in 0..chars.len() {
+        let mut deleted = chars.clone();
+        deleted.remove(i);
+        out.push(deleted.into_iter().collect());
+    }
+    for i in 1..chars.len() {
+        let mut swapped = chars.clone();
+        swapped.swap(i - 1, i);
+        out.push(swapped.into_iter().collect());
+    }
+    for i in 0..chars.len() {
+        for letter in letters.clone().filter(|&letter| letter != chars[i]) {
+            let mut replaced = chars.clone();
+            replaced[i] = letter;
+            out.push(replaced.into_iter().collect());
+        }
+    }
+    for i in 0..=chars.len() {
+        for letter in letters.clone() {
+            let mut inserted = chars.clone();
+            inserted.insert(i, letter);
+            out.push(inserted.into_iter().collect());
+        }
+    }
+    out
+}
+
+fn match_case(original: &str, candidate: &str) -> String {
+    if original.chars().count() > 1 && original.chars().all(|c| !c.is_lowercase()) {
+        return candidate.to_uppercase();
+    }
+    let mut chars = candidate.chars();
+    match (original.chars().next(), chars.next()) {
+        (Some(first), Some(c)) if first.is_uppercase() => c.to_uppercase().chain(chars).collect(),
+        _ => candidate.to_string(),
+    }
+}
+
+/// Byte ranges of `line` to check: comments and quoted strings in code,
+/// everything but inline code spans in prose.
+fn checked_ranges(line: &str, language: Language) -> Vec<(usize, usize)> {
+    if matches!(language, Language::Markdown | Language::PlainText) {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        let mut in_code = false;
+        for (idx, c) in line.char_indices().filter(|(_, c)| *c == '`') {
+            if !in_code {
+                ranges.push((start, idx));
+            }
+            in_code = !in_code;
+            start = idx + c.len_utf8();
+        }
+        if !in_code {
+            ranges.push((start, line.len()));
+        }
+        return ranges;
+    }
+    if language == Language::Json {
+        return Vec::new();
+    }
+
+    let marker = comments::markers(language).map(|(open, _)| open);
+    let c_like = marker == Some("//");
+    let trimmed = line.trim_start();
+    if c_like && (trimmed.starts_with("/*") || trimmed.starts_with('*')) {
+        return vec![(line.len() - trimmed.len(), line.len())];
+    }
+    // Single quotes are characters and lifetimes in these languages
+    let quotes: &[char] = match language {
+        Language::Rust | Language::C | Language::Cpp | Language::Java | Language::Go => &['"'],
+        _ => &['"', '\''],
+    };
+
+    let mut ranges = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if marker.is_some_and(|marker| line[idx..].starts_with(marker)) {
+            ranges.push((idx, line.len()));
+            break;
+        }
+        if !quotes.contains(&c) {
+            continue;
+        }
+        let start = idx + 1;
+        let mut end = line.len();
+        while let Some((next, n)) = chars.next() {
+            if n == '\\' {
+                chars.next();
+            } else if n == c {
+                end = next;
+                break;
+            }
+        }
+        ranges.push((start, end));
+    }
+    ranges
+}
+
+/// Words in `line[from..to]` with their byte offsets. Parts of identifiers,
+/// paths, acronyms and camelCase names are left out.
+fn words(line: &str, from: usize, to: usize) -> Vec<(usize, &str)> {
+    let text = &line[from..to];
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let is_joiner = |c: char| c.is_alphanumeric() || matches!(c, '_' | '/' | '\\' | '@');
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].1.is_alphabetic() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len()
+            && (chars[i].1.is_alphabetic()
+                || (chars[i].1 == '\'' && chars.get(i + 1).is_some_and(|(_, c)| c.is_alphabetic())))
+        {
+            i += 1;
+        }
+        let before = start.checked_sub(1).map(|b| chars[b].1);
+        let after = chars.get(i).map(|(_, c)| *c);
+        // `file.rs`, `std::fs` and `e.g` are not words on their own
+        let dotted = |c: Option<char>, neighbour: Option<char>| {
+            matches!(c, Some('.' | ':')) && neighbour.is_some_and(|c| c.is_alphanumeric())
+        };
+        let word_start = chars[start].0;
+        let word_end = chars.get(i).map_or(text.len(), |(idx, _)| *idx);
+        let word = &text[word_start..word_end];
+        let skip = before.is_some_and(is_joiner)
+            || after.is_some_and(is_joiner)
+            || dotted(before, start.checked_sub(2).map(|b| chars[b].1))
+            || dotted(after, chars.get(i + 1).map(|(_, c)| *c))
+            || word.chars().count() < 2
+            || word.chars().skip(1).any(char::is_uppercase);
+        if !skip {
+            out.push((from + word_start, word));
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_comments_and_suggests_words() {
+        let mut dictionary = Dictionary::default();
+        add_dic_words("4\nthe/S\nfile\nopen/DG\nstay\n", &mut dictionary.words);
+
+        let line = r#"let teh = open("file.rs"); // Opens teh fileName files"#;
+        let misspellings = dictionary.check_line(line, Language::Rust);
+        let found: Vec<_> = misspellings.iter().map(|m| m.word.as_str()).collect();
+        assert_eq!(found, ["teh"]);
+        assert_eq!(misspellings[0].start, 36);
+
+        assert!(dictionary.check_line("teh", Language::Json).is_empty());
+        assert_eq!(
+            dictionary.check_line("Stayed `teh`", Language::Markdown),
+            []
+        );
+        assert_eq!(dictionary.suggest("Teh"), ["The"]);
+        assert_eq!(dictionary.suggest("fiel"), ["file"]);
+    }
+}
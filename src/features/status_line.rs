@@ -0,0 +1,145 @@
+//! Segments for the status line at the bottom of the editor.
+//!
+//! Which segments are shown is controlled by the `status_segments`
+//! preference, a comma separated list of segment keys.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Segments enabled when the preference is missing.
+pub const DEFAULT_SEGMENTS: &str = "branch,language,indent,line_ending,encoding,size";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSegment {
+    Branch,
+    Language,
+    Indent,
+    LineEnding,
+    Encoding,
+    Size,
+    /// Words in the document, or in the selection while there is one.
+    Words,
+}
+
+impl StatusSegment {
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "branch" => Some(Self::Branch),
+            "language" => Some(Self::Language),
+            "indent" => Some(Self::Indent),
+            "line_ending" => Some(Self::LineEnding),
+            "encoding" => Some(Self::Encoding),
+            "size" => Some(Self::Size),
+            "words" => Some(Self::Words),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the `status_segments` preference, skipping unknown keys.
+pub fn parse_segments(spec: &str) -> Vec<StatusSegment> {
+    let mut segments = Vec::new();
+    for key in spec.split(',') {
+        if let Some(segment) = StatusSegment::from_key(key.trim()) {
+            if !segments.contains(&segment) {
+                segments.push(segment);
+            }
+        }
+    }
+    segments
+}
+
+/// Returns the checked out branch of the git repository containing `start`.
+///
+/// Reads `.git/HEAD` directly so no git binary is needed. A detached HEAD is
+/// shown as the short commit hash.
+pub fn git_branch(start: &Path) -> Option<String> {
+    let mut dir = Some(start);
+    while let Some(current) = dir {
+        if let Some(git) = git_dir(current) {
+            let head = fs::read_to_string(git.join("HEAD")).ok()?;
+            let head = head.trim();
+            return match head.strip_prefix("ref: ") {
+                Some(reference) => Some(
+                    reference
+                        .strip_prefix("refs/heads/")
+                        .unwrap_or(reference)
+                        .to_string(),
+                ),
+                None => Some(head.chars().take(7).collect()),
+            };
+        }
+        dir = current.parent();
+    }
+    None
+}
+
+/// The git directory of a repository rooted at `dir`. In worktrees and
+/// submodules `.git` is a file pointing to it with a `gitdir:` line.
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+    let git = dir.join(".git");
+    if git.is_dir() {
+        return Some(git);
+    }
+    let pointer = fs::read_to_string(&git).ok()?;
+    let target = pointer
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))?
+        .trim();
+    // Relative paths are relative to the directory holding `.git`
+    Some(dir.join(target))
+}
+
+/// Formats a byte count as `B`, `KB` or `MB`.
+pub fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes_f = bytes as f64;
+    if bytes_f < KB {
+        format!("{bytes} B")
+    } else if bytes_f < KB * KB {
+        format!("{:.1} KB", bytes_f / KB)
+    } else {
+        format!("{:.1} MB", bytes_f / (KB * KB))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_segments_and_sizes() {
+        assert_eq!(
+            parse_segments("branch, nope,,size,branch"),
+            [StatusSegment::Branch, StatusSegment::Size]
+        );
+        assert!(parse_segments("").is_empty());
+        assert_eq!(parse_segments(DEFAULT_SEGMENTS).len(), 6);
+
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    fn follows_gitdir_files() {
+        let dir = std::env::temp_dir().join(format!("pinel-status-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let worktree = dir.join("worktree");
+        let git = dir.join("repo").join(".git").join("worktrees").join("wt");
+        fs::create_dir_all(worktree.join("src")).unwrap();
+        fs::create_dir_all(&git).unwrap();
+        fs::write(git.join("HEAD"), "ref: refs/heads/feature\n").unwrap();
+        fs::write(worktree.join(".git"), "gitdir: ../repo/.git/worktrees/wt\n").unwrap();
+
+        assert_eq!(
+            git_branch(&worktree.join("src")).as_deref(),
+            Some("feature")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
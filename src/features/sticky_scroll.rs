@@ -0,0 +1,100 @@
+//! Headers of the blocks enclosing the top of the editor, pinned above
+//! the text while scrolling through a long function or impl.
+//!
+//! Blocks are found from indentation: every line above that is indented
+//! less than the one below it opens a block enclosing it.
+
+use crate::features::editor_buffer::EditorBuffer;
+
+/// Most headers pinned at once.
+pub const MAX_HEADERS: usize = 5;
+/// Lines looked at above the top line. Headers run every time the editor
+/// is drawn, so this keeps it cheap deep inside huge blocks.
+const MAX_SCAN: usize = 2000;
+
+fn indent_of(line: &str) -> Option<usize> {
+    let text = line.trim_end_matches(['\n', '\r']);
+    if text.trim().is_empty() {
+        return None;
+    }
+    Some(
+        text.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum(),
+    )
+}
+
+/// Whether a line only closes a block, like `}` or `])`.
+fn closes_block(line: &str) -> bool {
+    line.trim_start().starts_with(['}', ')', ']'])
+}
+
+/// 0-based header lines of the blocks enclosing `line`, outermost first.
+pub fn headers(buffer: &EditorBuffer, line: usize) -> Vec<usize> {
+    let line_count = buffer.line_count();
+    // A blank line belongs to the block of the next line with text
+    let Some(mut indent) = (line..line_count).find_map(|idx| indent_of(&buffer.line(idx))) else {
+        return Vec::new();
+    };
+    let mut headers = Vec::new();
+    let end = line.min(line_count);
+    for idx in (end.saturating_sub(MAX_SCAN)..end).rev() {
+        if indent == 0 {
+            break;
+        }
+        let text = buffer.line(idx);
+        match indent_of(&text) {
+            Some(level) if level < indent && !closes_block(&text) => {
+                headers.push(idx);
+                indent = level;
+            }
+            _ => {}
+        }
+    }
+    headers.reverse();
+    if headers.len() > MAX_HEADERS {
+        headers.drain(..headers.len() - MAX_HEADERS);
+    }
+    headers
+}
+
+/// Headers pinned while `first` is the top line on screen. Each header
+/// covers a line, so the headers of the first line they leave visible
+/// are used.
+pub fn pinned(buffer: &EditorBuffer, first: usize) -> Vec<usize> {
+    let mut pinned: Vec<usize> = Vec::new();
+    for _ in 0..MAX_HEADERS {
+        let next: Vec<usize> = headers(buffer, first + pinned.len())
+            .into_iter()
+            .filter(|header| *header < first + pinned.len())
+            .collect();
+        if next.len() <= pinned.len() {
+            break;
+        }
+        pinned = next;
+    }
+    pinned
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_enclosing_headers() {
+        let buffer = EditorBuffer::from_text(
+            "impl App {\n    fn a() {\n        one();\n    }\n\n    fn b() {\n        if x {\n\n            two();\n        }\n    }\n}\n",
+        );
+        assert_eq!(headers(&buffer, 2), [0, 1]);
+        assert_eq!(headers(&buffer, 8), [0, 5, 6]);
+        assert_eq!(headers(&buffer, 7), [0, 5, 6]);
+        assert_eq!(headers(&buffer, 4), [0]);
+        assert!(headers(&buffer, 0).is_empty());
+        assert_eq!(pinned(&buffer, 6), [0, 5, 6]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Symbols of a document: functions, types and headings found with a few
+//! line based patterns per language. Used by the `@` mode of the fuzzy
+//! finder to jump around the current file.
+
+use crate::language::Language;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: &'static str,
+    /// 0-based line of the definition.
+    pub line: usize,
+}
+
+/// A definition pattern. The `name` (or `name2`) group is the symbol name;
+/// a `kind` group overrides the fixed kind.
+struct Pattern {
+    kind: &'static str,
+    regex: Regex,
+}
+
+fn patterns(list: &[(&'static str, &str)]) -> Vec<Pattern> {
+    list.iter()
+        .map(|(kind, regex)| Pattern {
+            kind,
+            regex: Regex::new(regex).expect("valid symbol pattern"),
+        })
+        .collect()
+}
+
+static RUST: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[
+        (
+            "fn",
+            r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern\s+\S+)\s+)*(?P<kind>fn|struct|enum|union|trait|type|mod|const|static)\s+(?P<name>[A-Za-z_]\w*)",
+        ),
+        ("macro", r"^\s*macro_rules!\s+(?P<name>\w+)"),
+        (
+            "impl",
+            r"^\s*(?:unsafe\s+)?impl(?:<[^{]*?>)?\s+(?P<name>[^{]+?)\s*(?:where\b.*)?\{?\s*$",
+        ),
+    ])
+});
+
+static PYTHON: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[(
+        "def",
+        r"^\s*(?:async\s+)?(?P<kind>def|class)\s+(?P<name>\w+)",
+    )])
+});
+
+static JAVASCRIPT: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[
+        (
+            "function",
+            r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?P<kind>function|class|interface|type|enum)\*?\s+(?P<name>[\w$]+)",
+        ),
+        (
+            "function",
+            r"^\s*(?:export\s+)?(?:const|let|var)\s+(?P<name>[\w$]+)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*(?::[^=]+)?=>|[\w$]+\s*=>)",
+        ),
+    ])
+});
+
+static GO: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[
+        ("func", r"^func\s+(?:\([^)]*\)\s*)?(?P<name>\w+)"),
+        ("type", r"^type\s+(?P<name>\w+)"),
+    ])
+});
+
+static C_LIKE: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[
+        (
+            "struct",
+            r"^\s*(?:typedef\s+)?(?:(?:public|private|protected|abstract|final|static)\s+)*(?P<kind>struct|class|enum|union|interface|record|namespace)\s+(?P<name>\w+)",
+        ),
+        (
+            "function",
+            r"^(?:[\w:<>,\*&~]+\s+)+\**(?P<name>[\w:~]+)\s*\([^;]*$",
+        ),
+        (
+            "method",
+            r"^\s+(?:(?:public|private|protected|static|final|abstract|synchronized|override)\s+)+[\w<>\[\],\s]+?\s+(?P<name>\w+)\s*\([^;]*$",
+        ),
+    ])
+});
+
+static LUA: Lazy<Vec<Pattern>> =
+    Lazy::new(|| patterns(&[("function", r"^\s*(?:local\s+)?function\s+(?P<name>[\w.:]+)")]));
+
+static RUBY: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[(
+        "def",
+        r"^\s*(?P<kind>def|class|module)\s+(?P<name>[\w.:]+[?!=]?)",
+    )])
+});
+
+static SHELL: Lazy<Vec<Pattern>> = Lazy::new(|| {
+    patterns(&[(
+        "function",
+        r"^\s*(?:function\s+(?P<name>[\w-]+)|(?P<name2>[\w-]+)\s*\(\s*\))",
+    )])
+});
+
+static MARKDOWN: Lazy<Vec<Pattern>> =
+    Lazy::new(|| patterns(&[("heading", r"^#{1,6}\s+(?P<name>.+?)\s*#*\s*$")]));
+
+/// Keywords the C-like function pattern mistakes for return types.
+const CONTROL_WORDS: &[&str] = &["if", "for", "while", "switch", "return", "else", "catch"];
+
+/// Finds the symbols defined in `text`, in document order.
+pub fn extract(text: &str, language: Language) -> Vec<Symbol> {
+    let patterns: &[Pattern] = match language {
+        Language::Rust => &RUST,
+        Language::Python => &PYTHON,
+        Language::JavaScript
+        | Language::JavaScriptJsx
+        | Language::TypeScript
+        | Language::TypeScriptJsx => &JAVASCRIPT,
+        Language::Go => &GO,
+        Language::C | Language::Cpp | Language::Java => &C_LIKE,
+        Language::Lua => &LUA,
+        Language::Ruby => &RUBY,
+        Language::Shell => &SHELL,
+        Language::Markdown => &MARKDOWN,
+        _ => return Vec::new(),
+    };
+
+    let mut symbols = Vec::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let Some((pattern, captures)) = patterns
+            .iter()
+            .find_map(|pattern| Some((pattern, pattern.regex.captures(line)?)))
+        else {
+            continue;
+        };
+        let Some(name) = captures.name("name").or_else(|| captures.name("name2")) else {
+            continue;
+        };
+        let name = name.as_str().trim();
+        if CONTROL_WORDS.contains(&name) {
+            continue;
+        }
+        let kind = match captures.name("kind") {
+            Some(kind) => kind_name(kind.as_str()),
+            None => pattern.kind,
+        };
+        symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            line: line_idx,
+        });
+    }
+    symbols
+}
+
+/// A `'static` copy of a kind keyword captured from the text.
+fn kind_name(keyword: &str) -> &'static str {
+    const KINDS: &[&str] = &[
+        "fn",
+        "struct",
+        "enum",
+        "union",
+        "trait",
+        "type",
+        "mod",
+        "const",
+        "static",
+        "def",
+        "class",
+        "function",
+        "interface",
+        "module",
+        "record",
+        "namespace",
+    ];
+    KINDS
+        .iter()
+        .find(|kind| **kind == keyword)
+        .copied()
+        .unwrap_or("symbol")
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(text: &str, language: Language) -> Vec<(String, &'static str, usize)> {
+        extract(text, language)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.line))
+            .collect()
+    }
+
+    #[test]
+    fn finds_definitions_per_language() {
+        let rust =
+            "pub struct App {\n}\n\nimpl Default for App {\n    pub(crate) async fn run() {}\n}\n";
+        assert_eq!(
+            names(rust, Language::Rust),
+            [
+                ("App".to_string(), "struct", 0),
+                ("Default for App".to_string(), "impl", 3),
+                ("run".to_string(), "fn", 4),
+            ]
+        );
+
+        let c = "static int add(int a, int b)\n{\n    if (a) {\n        return add(a, b);\n";
+        assert_eq!(names(c, Language::C), [("add".to_string(), "function", 0)]);
+
+        let ts = "export const load = async (id: string) => {\nclass Store {}\n";
+        assert_eq!(
+            names(ts, Language::TypeScript),
+            [
+                ("load".to_string(), "function", 0),
+                ("Store".to_string(), "class", 1),
+            ]
+        );
+    }
+}
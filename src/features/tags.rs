@@ -0,0 +1,203 @@
+//! Tag helpers for HTML-like languages: closing tag completion and
+//! keeping an opening tag's closing partner in sync while it is renamed.
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Whether files with this extension get tag completion.
+pub fn is_tag_language(ext: &str) -> bool {
+    matches!(
+        ext.to_lowercase().as_str(),
+        "html" | "htm" | "xml" | "xhtml" | "svg" | "jsx" | "tsx" | "vue" | "svelte"
+    )
+}
+
+/// An opening or closing tag found in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Tag {
+    name: String,
+    /// Byte offset of the first character of the name.
+    name_start: usize,
+    closing: bool,
+    self_closing: bool,
+}
+
+pub fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')
+}
+
+/// Scans `text` for tags, ignoring comments, doctypes and processing
+/// instructions.
+fn scan_tags(text: &str) -> Vec<Tag> {
+    let mut tags = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while let Some(rel) = text[i..].find('<') {
+        let lt = i + rel;
+        let rest = &text[lt + 1..];
+        if rest.starts_with("!--") {
+            i = match rest.find("-->") {
+                Some(end) => lt + 1 + end + 3,
+                None => text.len(),
+            };
+            continue;
+        }
+
+        let closing = rest.starts_with('/');
+        let name_start = lt + 1 + usize::from(closing);
+        let name_len: usize = text[name_start..]
+            .chars()
+            .take_while(|c| is_name_char(*c))
+            .map(char::len_utf8)
+            .sum();
+        if name_len == 0 || !text[name_start..].starts_with(|c: char| c.is_alphabetic()) {
+            i = lt + 1;
+            continue;
+        }
+        let name = text[name_start..name_start + name_len].to_string();
+
+        // Find the end of the tag, skipping quoted attribute values and JSX braces
+        let mut j = name_start + name_len;
+        let mut quote: Option<u8> = None;
+        let mut braces = 0usize;
+        let mut end = None;
+        while j < bytes.len() {
+            let b = bytes[j];
+            match quote {
+                Some(q) if b == q => quote = None,
+                Some(_) => {}
+                None => match b {
+                    b'"' | b'\'' => quote = Some(b),
+                    b'{' => braces += 1,
+                    b'}' => braces = braces.saturating_sub(1),
+                    b'>' if braces == 0 => {
+                        end = Some(j);
+                        break;
+                    }
+                    b'<' if braces == 0 => break,
+                    _ => {}
+                },
+            }
+            j += 1;
+        }
+
+        let self_closing = end.is_some_and(|e| e > 0 && bytes[e - 1] == b'/')
+            || VOID_ELEMENTS.contains(&name.to_lowercase().as_str());
+        tags.push(Tag {
+            name,
+            name_start,
+            closing,
+            self_closing,
+        });
+        i = end.map(|e| e + 1).unwrap_or(name_start + name_len);
+    }
+
+    tags
+}
+
+/// Name of the innermost tag left open in `before`, used to complete `</`.
+pub fn unclosed_tag(before: &str) -> Option<String> {
+    let mut stack: Vec<String> = Vec::new();
+    for tag in scan_tags(before) {
+        if tag.closing {
+            if let Some(pos) = stack.iter().rposition(|open| *open == tag.name) {
+                stack.truncate(pos);
+            }
+        } else if !tag.self_closing {
+            stack.push(tag.name);
+        }
+    }
+    stack.pop()
+}
+
+/// An opening tag name under the cursor and the position of its closing
+/// partner's name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagPair {
+    pub name: String,
+    pub open_start: usize,
+    pub close_start: usize,
+}
+
+/// Finds the opening tag whose name contains byte offset `cursor`, along
+/// with its matching closing tag.
+pub fn tag_pair_at(text: &str, cursor: usize) -> Option<TagPair> {
+    let tags = scan_tags(text);
+    let open_idx = tags.iter().position(|tag| {
+        !tag.closing
+            && !tag.self_closing
+            && cursor >= tag.name_start
+            && cursor <= tag.name_start + tag.name.len()
+    })?;
+    let open = &tags[open_idx];
+
+    let mut depth = 0usize;
+    for tag in &tags[open_idx + 1..] {
+        if tag.name != open.name || tag.self_closing {
+            continue;
+        }
+        if !tag.closing {
+            depth += 1;
+        } else if depth == 0 {
+            return Some(TagPair {
+                name: open.name.clone(),
+                open_start: open.name_start,
+                close_start: tag.name_start,
+            });
+        } else {
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Reads the tag name starting at byte offset `start`.
+pub fn name_at(text: &str, start: usize) -> &str {
+    let rest = text.get(start..).unwrap_or("");
+    let len: usize = rest
+        .chars()
+        .take_while(|c| is_name_char(*c))
+        .map(char::len_utf8)
+        .sum();
+    &rest[..len]
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_innermost_open_tag() {
+        let before = "<div class=\"a\"><span><br><img src=\"x\" />";
+        assert_eq!(unclosed_tag(before), Some("span".to_string()));
+    }
+
+    #[test]
+    fn skips_closed_tags_and_comments() {
+        let before = "<ul><li>one</li><!-- <p> --><li>";
+        assert_eq!(unclosed_tag(before), Some("li".to_string()));
+        assert_eq!(unclosed_tag("<p></p>"), None);
+    }
+
+    #[test]
+    fn handles_jsx_attributes() {
+        let before = "<Button onClick={() => a > b}>";
+        assert_eq!(unclosed_tag(before), Some("Button".to_string()));
+    }
+
+    #[test]
+    fn finds_matching_close_with_nesting() {
+        let text = "<div><div></div></div>";
+        let pair = tag_pair_at(text, 2).unwrap();
+        assert_eq!(pair.open_start, 1);
+        assert_eq!(pair.close_start, 18);
+        assert_eq!(name_at(text, pair.close_start), "div");
+    }
+}
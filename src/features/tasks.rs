@@ -0,0 +1,170 @@
+//! Project tasks such as builds and test runs.
+//!
+//! Tasks are read from `.pinel/tasks.json` in the open folder:
+//!
+//! ```json
+//! { "tasks": [{ "name": "Build", "command": "cargo build" }] }
+//! ```
+//!
+//! Without that file, common tasks are offered for Cargo and npm projects.
+//! Commands run through the platform shell from the folder root.
+
+use iced::futures::{stream, SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TaskSpec {
+    pub name: String,
+    pub command: String,
+}
+
+#[derive(Deserialize)]
+struct TasksFile {
+    tasks: Vec<TaskSpec>,
+}
+
+/// Progress of a running task.
+#[derive(Debug, Clone)]
+pub enum TaskEvent {
+    Line(String),
+    /// The exit code, or an error message when the task couldn't run.
+    Finished(Result<i32, String>),
+}
+
+pub fn tasks_path(root: &Path) -> PathBuf {
+    root.join(".pinel").join("tasks.json")
+}
+
+/// Tasks for the folder at `root`, from its tasks file or detected from
+/// the project files.
+pub fn load(root: &Path) -> Result<Vec<TaskSpec>, String> {
+    match std::fs::read_to_string(tasks_path(root)) {
+        Ok(content) => serde_json::from_str::<TasksFile>(&content)
+            .map(|file| file.tasks)
+            .map_err(|e| format!("Invalid .pinel/tasks.json: {e}")),
+        Err(_) => Ok(detect(root)),
+    }
+}
+
+fn detect(root: &Path) -> Vec<TaskSpec> {
+    let task = |name: &str, command: &str| TaskSpec {
+        name: name.to_string(),
+        command: command.to_string(),
+    };
+    let mut tasks = Vec::new();
+    if root.join("Cargo.toml").is_file() {
+        tasks.push(task("cargo build", "cargo build"));
+        tasks.push(task("cargo test", "cargo test"));
+        tasks.push(task("cargo run", "cargo run"));
+        tasks.push(task("cargo clippy", "cargo clippy"));
+    }
+    let scripts = std::fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|package| package.get("scripts")?.as_object().cloned());
+    for script in scripts.into_iter().flat_map(|scripts| scripts.into_iter()) {
+        let name = format!("npm run {}", script.0);
+        tasks.push(task(&name, &name));
+    }
+    tasks
+}
+
+/// Runs `command` in `cwd`, streaming stdout and stderr line by line.
+pub fn run(command: String, cwd: PathBuf) -> impl Stream<Item = TaskEvent> {
+    iced::stream::channel(100, async move |mut output| {
+        let mut process = if cfg!(windows) {
+            let mut process = tokio::process::Command::new("cmd");
+            process.args(["/C", &command]);
+            process
+        } else {
+            let mut process = tokio::process::Command::new("sh");
+            process.args(["-c", &command]);
+            process
+        };
+        process
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = match process.spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                let _ = output.send(TaskEvent::Finished(Err(err.to_string()))).await;
+                return;
+            }
+        };
+        let stdout = child.stdout.take().map(|out| lines(BufReader::new(out)));
+        let stderr = child.stderr.take().map(|err| lines(BufReader::new(err)));
+
+        // Interleave both pipes until they are closed
+        let mut lines = Box::pin(stream::select(
+            stream::iter(stdout).flatten(),
+            stream::iter(stderr).flatten(),
+        ));
+        while let Some(line) = lines.next().await {
+            let _ = output.send(TaskEvent::Line(line)).await;
+        }
+
+        let status = child.wait().await.map_err(|e| e.to_string());
+        let code = status.map(|status| status.code().unwrap_or(-1));
+        let _ = output.send(TaskEvent::Finished(code)).await;
+    })
+}
+
+/// Lines read from a pipe, ending at the first read error.
+fn lines<R>(reader: R) -> impl Stream<Item = String>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    stream::unfold(reader.lines(), async |mut lines| {
+        let line = lines.next_line().await.ok().flatten()?;
+        Some((line, lines))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_tasks_file_or_detects_tasks() {
+        let root = std::env::temp_dir().join(format!("pinel-tasks-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(".pinel")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "").unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"scripts": {"lint": "eslint ."}}"#,
+        )
+        .unwrap();
+
+        let names: Vec<String> = load(&root).unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names[0], "cargo build");
+        assert_eq!(names.last().map(String::as_str), Some("npm run lint"));
+
+        std::fs::write(
+            tasks_path(&root),
+            r#"{ "tasks": [{ "name": "Build", "command": "make" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            load(&root).unwrap(),
+            [TaskSpec {
+                name: "Build".to_string(),
+                command: "make".to_string(),
+            }]
+        );
+        std::fs::write(tasks_path(&root), "{").unwrap();
+        assert!(load(&root).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,70 @@
+//! Line, word and character counts for the status line and the
+//! "Word Count" command.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    pub lines: usize,
+    pub words: usize,
+    pub chars: usize,
+}
+
+impl Counts {
+    /// Summary such as `12 lines, 80 words, 431 characters`.
+    pub fn summary(self) -> String {
+        format!(
+            "{} {}, {} {}, {} {}",
+            self.lines,
+            plural(self.lines, "line", "lines"),
+            self.words,
+            plural(self.words, "word", "words"),
+            self.chars,
+            plural(self.chars, "character", "characters"),
+        )
+    }
+}
+
+fn plural(count: usize, one: &'static str, many: &'static str) -> &'static str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+/// Counts `text`. Words are runs of non-whitespace, so `don't` and
+/// `x + y` count as one and three words.
+pub fn count(text: &str) -> Counts {
+    Counts {
+        lines: if text.is_empty() {
+            0
+        } else {
+            text.lines().count()
+        },
+        words: text.split_whitespace().count(),
+        chars: text.chars().count(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_lines_words_and_chars() {
+        let counts = count("# Title\n\nOne café, two\n");
+        assert_eq!(
+            counts,
+            Counts {
+                lines: 3,
+                words: 5,
+                chars: 23,
+            }
+        );
+        assert_eq!(count("").summary(), "0 lines, 0 words, 0 characters");
+        assert_eq!(count("word").summary(), "1 line, 1 word, 4 characters");
+    }
+}
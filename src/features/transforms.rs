@@ -0,0 +1,162 @@
+//! Rewrites of the selected text from the palette: sorting and reversing
+//! lines, changing case and base64.
+//!
+//! Line transforms work on whole lines, so they are given every line the
+//! selection touches. The others get exactly the selected text.
+
+use crate::message::Transform;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl Transform {
+    /// Whether the transform rearranges whole lines.
+    pub fn works_on_lines(self) -> bool {
+        matches!(
+            self,
+            Self::SortAscending | Self::SortDescending | Self::SortUnique | Self::Reverse
+        )
+    }
+}
+
+/// Applies `transform` to `text`, or `None` when base64 decoding fails or
+/// does not give back text.
+pub fn apply(transform: Transform, text: &str) -> Option<String> {
+    Some(match transform {
+        Transform::SortAscending
+        | Transform::SortDescending
+        | Transform::SortUnique
+        | Transform::Reverse => rearrange_lines(transform, text),
+        Transform::UpperCase => text.to_uppercase(),
+        Transform::LowerCase => text.to_lowercase(),
+        Transform::TitleCase => title_case(text),
+        Transform::Base64Encode => base64_encode(text.as_bytes()),
+        Transform::Base64Decode => String::from_utf8(base64_decode(text)?).ok()?,
+    })
+}
+
+fn rearrange_lines(transform: Transform, text: &str) -> String {
+    // A final line break stays at the end rather than sorting to the top
+    let (body, newline) = match text.strip_suffix('\n') {
+        Some(body) => (body, "\n"),
+        None => (text, ""),
+    };
+    let mut lines: Vec<&str> = body.split('\n').collect();
+    match transform {
+        Transform::SortAscending => lines.sort_unstable(),
+        Transform::SortDescending => lines.sort_unstable_by(|a, b| b.cmp(a)),
+        Transform::SortUnique => {
+            lines.sort_unstable();
+            lines.dedup();
+        }
+        _ => lines.reverse(),
+    }
+    format!("{}{newline}", lines.join("\n"))
+}
+
+/// Capitalizes the first letter of every word and lowers the rest. An
+/// apostrophe does not start a new word, so "don't" becomes "Don't".
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_word = false;
+    for c in text.chars() {
+        if in_word {
+            result.extend(c.to_lowercase());
+        } else {
+            result.extend(c.to_uppercase());
+        }
+        in_word = c.is_alphanumeric() || (in_word && matches!(c, '\'' | '’'));
+    }
+    result
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (idx, byte)| {
+            group | (u32::from(*byte) << (16 - 8 * idx))
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let sextet = (group >> (18 - 6 * idx)) & 0x3F;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes standard base64, skipping whitespace. Padding may be left out.
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let digits = digits
+        .strip_suffix(b"==")
+        .or_else(|| digits.strip_suffix(b"="))
+        .unwrap_or(&digits);
+    if digits.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        let mut group = 0u32;
+        for (idx, digit) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|c| c == digit)?;
+            group |= (value as u32) << (18 - 6 * idx);
+        }
+        for idx in 0..chunk.len() - 1 {
+            decoded.push((group >> (16 - 8 * idx)) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transforms_text() {
+        let lines = "pear\napple\npear\nfig\n";
+        assert_eq!(
+            apply(Transform::SortAscending, lines).unwrap(),
+            "apple\nfig\npear\npear\n"
+        );
+        assert_eq!(
+            apply(Transform::SortDescending, lines).unwrap(),
+            "pear\npear\nfig\napple\n"
+        );
+        assert_eq!(
+            apply(Transform::SortUnique, lines).unwrap(),
+            "apple\nfig\npear\n"
+        );
+        assert_eq!(apply(Transform::Reverse, "a\nb\nc").unwrap(), "c\nb\na");
+
+        assert_eq!(
+            apply(Transform::TitleCase, "don't STOP me-now").unwrap(),
+            "Don't Stop Me-Now"
+        );
+
+        for text in ["", "f", "fo", "foo", "foob", "fooba", "foobar", "héllo ✓"] {
+            let encoded = apply(Transform::Base64Encode, text).unwrap();
+            assert_eq!(apply(Transform::Base64Decode, &encoded).unwrap(), text);
+        }
+        assert_eq!(
+            apply(Transform::Base64Encode, "foobar").unwrap(),
+            "Zm9vYmFy"
+        );
+        assert_eq!(apply(Transform::Base64Encode, "fo").unwrap(), "Zm8=");
+        assert_eq!(apply(Transform::Base64Decode, "Zm9v\nYg").unwrap(), "foob");
+        assert_eq!(apply(Transform::Base64Decode, "Zm9v!"), None);
+        assert_eq!(apply(Transform::Base64Decode, "/w=="), None);
+    }
+}
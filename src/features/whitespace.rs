@@ -0,0 +1,201 @@
+//! Drawing spaces, tabs and line ends, so mixed indentation and trailing
+//! whitespace can be seen.
+//!
+//! The editor widget has no way to draw them itself, so the markers are
+//! drawn on a transparent canvas stacked over it, at the positions the
+//! widget lays its text out at.
+
+use crate::features::editor_buffer::EditorBuffer;
+use crate::ui::TextLayout;
+use iced::widget::canvas;
+use iced::{mouse, Color, Point, Rectangle, Renderer, Theme};
+
+/// Which whitespace is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderWhitespace {
+    #[default]
+    Off,
+    All,
+    /// Only spaces and tabs at the end of lines.
+    Trailing,
+}
+
+impl RenderWhitespace {
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "all" => Self::All,
+            "trailing" => Self::Trailing,
+            _ => Self::Off,
+        }
+    }
+
+    /// Name used in the preferences file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::All => "all",
+            Self::Trailing => "trailing",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::All => "All",
+            Self::Trailing => "Trailing",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::All,
+            Self::All => Self::Trailing,
+            Self::Trailing => Self::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Space,
+    Tab,
+    Newline,
+}
+
+impl Marker {
+    fn glyph(self) -> &'static str {
+        match self {
+            Self::Space => "\u{b7}",
+            Self::Tab => "\u{2192}",
+            Self::Newline => "\u{b6}",
+        }
+    }
+}
+
+/// Markers of `line`, without its line break, by character column.
+/// `newline` tells whether a line break follows it.
+pub fn markers(line: &str, newline: bool, mode: RenderWhitespace) -> Vec<(usize, Marker)> {
+    let chars: Vec<char> = line.chars().collect();
+    let from = match mode {
+        RenderWhitespace::Off => return Vec::new(),
+        RenderWhitespace::All => 0,
+        RenderWhitespace::Trailing => chars
+            .iter()
+            .rposition(|c| *c != ' ' && *c != '\t')
+            .map_or(0, |idx| idx + 1),
+    };
+    let mut markers: Vec<(usize, Marker)> = chars
+        .iter()
+        .enumerate()
+        .skip(from)
+        .filter_map(|(col, c)| match c {
+            ' ' => Some((col, Marker::Space)),
+            '\t' => Some((col, Marker::Tab)),
+            _ => None,
+        })
+        .collect();
+    if newline && mode == RenderWhitespace::All {
+        markers.push((chars.len(), Marker::Newline));
+    }
+    markers
+}
+
+/// Whitespace markers of the visible lines of an editor.
+pub struct Overlay {
+    glyphs: Vec<(Point, Marker)>,
+    font: iced::Font,
+    size: f32,
+    color: Color,
+}
+
+impl Overlay {
+    /// Markers for the lines on screen. Nothing is drawn without a
+    /// `layout`, while word wrap is on.
+    pub fn new(
+        layout: Option<&TextLayout>,
+        buffer: &EditorBuffer,
+        mode: RenderWhitespace,
+        font: iced::Font,
+        size: f32,
+        color: Color,
+    ) -> Self {
+        let mut glyphs = Vec::new();
+        if let Some(layout) = layout.filter(|_| mode != RenderWhitespace::Off) {
+            for idx in layout.visible_lines(buffer.line_count()) {
+                let line = buffer.line(idx);
+                let text = line.trim_end_matches(['\n', '\r']);
+                let newline = text.len() < line.len();
+                for (col, marker) in markers(text, newline, mode) {
+                    if let Some(point) = layout.point(idx, text, col) {
+                        glyphs.push((Point::new(point.x, point.y + 2.0), marker));
+                    }
+                }
+            }
+        }
+        Self {
+            glyphs,
+            font,
+            size,
+            color,
+        }
+    }
+}
+
+impl<Message> canvas::Program<Message> for Overlay {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        for (position, marker) in &self.glyphs {
+            frame.fill_text(canvas::Text {
+                content: marker.glyph().to_string(),
+                position: *position,
+                color: self.color,
+                size: self.size.into(),
+                font: self.font,
+                ..canvas::Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_whitespace_markers() {
+        assert_eq!(
+            markers("\tlet a = 1; ", true, RenderWhitespace::All),
+            [
+                (0, Marker::Tab),
+                (4, Marker::Space),
+                (6, Marker::Space),
+                (8, Marker::Space),
+                (11, Marker::Space),
+                (12, Marker::Newline),
+            ]
+        );
+        assert_eq!(
+            markers("\tlet a = 1; \t", true, RenderWhitespace::Trailing),
+            [(11, Marker::Space), (12, Marker::Tab)]
+        );
+        assert_eq!(
+            markers("  ", false, RenderWhitespace::Trailing),
+            [(0, Marker::Space), (1, Marker::Space)]
+        );
+        assert!(markers("a b", true, RenderWhitespace::Off).is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+//! A fixed pool of threads for blocking work: reading and writing files,
+//! indexing and searching the workspace, git and formatters.
+//!
+//! Tasks run on the async runtime that also drives the language servers,
+//! so blocking inside them holds everything else up. `run` hands a job to
+//! the pool over a channel and resolves once the job posts its result
+//! back, which `iced::Task::perform` turns into a message for `update`.
+
+use crate::message::Message;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Workers kept, whatever the number of cores.
+const MIN_WORKERS: usize = 2;
+const MAX_WORKERS: usize = 8;
+
+fn pool() -> &'static mpsc::Sender<Job> {
+    static POOL: OnceLock<mpsc::Sender<Job>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let count = thread::available_parallelism()
+            .map_or(MIN_WORKERS, |count| count.get())
+            .clamp(MIN_WORKERS, MAX_WORKERS);
+        for idx in 0..count {
+            let receiver = Arc::clone(&receiver);
+            let _ = thread::Builder::new()
+                .name(format!("pinel-worker-{idx}"))
+                .spawn(move || loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => return,
+                    };
+                    let Ok(job) = job else {
+                        return;
+                    };
+                    // A panicking job drops its result, the worker lives on
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                });
+        }
+        sender
+    })
+}
+
+/// Runs `job` on the worker pool and waits for its result, `None` when
+/// the job panicked.
+pub async fn run<T: Send + 'static>(job: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let job: Job = Box::new(move || {
+        let _ = sender.send(job());
+    });
+    // No workers could be started: do the work here rather than never
+    if let Err(mpsc::SendError(job)) = pool().send(job) {
+        job();
+    }
+    // The sender is dropped unused when the job panics
+    receiver.await.ok()
+}
+
+/// Runs `job` on the worker pool and turns its result into a message. A
+/// job that panics reports [`Message::WorkerFailed`] instead.
+pub fn perform<T: Send + 'static>(
+    job: impl FnOnce() -> T + Send + 'static,
+    to_message: impl FnOnce(T) -> Message + Send + 'static,
+) -> iced::Task<Message> {
+    iced::Task::perform(run(job), move |result| match result {
+        Some(value) => to_message(value),
+        None => Message::WorkerFailed,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_jobs_on_worker_threads() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let jobs = (0..16).map(|idx| {
+            run(move || {
+                let name = thread::current().name().unwrap_or_default().to_string();
+                (idx * 2, name)
+            })
+        });
+        for (idx, job) in jobs.enumerate() {
+            let (value, name) = runtime.block_on(job).unwrap();
+            assert_eq!(value, idx * 2);
+            assert!(name.starts_with("pinel-worker-"), "{name}");
+        }
+
+        // A panicking job gives no result and leaves the pool working
+        let failed = runtime.block_on(run(|| -> usize { panic!("job failed") }));
+        assert_eq!(failed, None);
+        assert_eq!(runtime.block_on(run(|| 7)), Some(7));
+    }
+}
@@ -0,0 +1,206 @@
+//! Detects the language of a file from its name, extension or shebang line.
+//!
+//! The result is shared by the syntax highlighter, autocomplete and the
+//! status bar so they always agree on what a file is.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    JavaScriptJsx,
+    TypeScript,
+    TypeScriptJsx,
+    Go,
+    C,
+    Cpp,
+    Java,
+    Lua,
+    Ruby,
+    Perl,
+    Markdown,
+    Json,
+    Toml,
+    Yaml,
+    Html,
+    Css,
+    Shell,
+    Makefile,
+    PlainText,
+}
+
+impl Language {
+    /// Human readable name, as shown in the status bar.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Python => "Python",
+            Self::JavaScript => "JavaScript",
+            Self::JavaScriptJsx => "JavaScript JSX",
+            Self::TypeScript => "TypeScript",
+            Self::TypeScriptJsx => "TypeScript JSX",
+            Self::Go => "Go",
+            Self::C => "C",
+            Self::Cpp => "C++",
+            Self::Java => "Java",
+            Self::Lua => "Lua",
+            Self::Ruby => "Ruby",
+            Self::Perl => "Perl",
+            Self::Markdown => "Markdown",
+            Self::Json => "JSON",
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+            Self::Html => "HTML",
+            Self::Css => "CSS",
+            Self::Shell => "Shell",
+            Self::Makefile => "Makefile",
+            Self::PlainText => "Plain Text",
+        }
+    }
+
+    /// Extension the syntax highlighter uses to pick a grammar.
+    pub fn syntax(self) -> &'static str {
+        match self {
+            Self::Rust => "rs",
+            Self::Python => "py",
+            Self::JavaScript => "js",
+            Self::JavaScriptJsx => "jsx",
+            Self::TypeScript => "ts",
+            Self::TypeScriptJsx => "tsx",
+            Self::Go => "go",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::Java => "java",
+            Self::Lua => "lua",
+            Self::Ruby => "rb",
+            Self::Perl => "pl",
+            Self::Markdown => "md",
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+            Self::Html => "html",
+            Self::Css => "css",
+            Self::Shell => "sh",
+            Self::Makefile => "Makefile",
+            Self::PlainText => "txt",
+        }
+    }
+
+    /// Key of the keyword and type lists used by autocomplete, if it has any.
+    pub fn completion_id(self) -> Option<&'static str> {
+        match self {
+            Self::Rust => Some("rust"),
+            Self::JavaScript => Some("javascript"),
+            Self::TypeScript | Self::TypeScriptJsx | Self::JavaScriptJsx => Some("typescript"),
+            Self::Python => Some("python"),
+            _ => None,
+        }
+    }
+
+    fn from_extension(ext: &str) -> Option<Self> {
+        let language = match ext.to_lowercase().as_str() {
+            "rs" => Self::Rust,
+            "py" | "pyw" | "pyi" => Self::Python,
+            "js" | "mjs" | "cjs" => Self::JavaScript,
+            "jsx" => Self::JavaScriptJsx,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::TypeScriptJsx,
+            "go" => Self::Go,
+            "c" | "h" => Self::C,
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => Self::Cpp,
+            "java" => Self::Java,
+            "lua" => Self::Lua,
+            "rb" => Self::Ruby,
+            "pl" | "pm" => Self::Perl,
+            "md" | "markdown" => Self::Markdown,
+            "json" | "jsonc" => Self::Json,
+            "toml" => Self::Toml,
+            "yaml" | "yml" => Self::Yaml,
+            "html" | "htm" | "xhtml" => Self::Html,
+            "css" => Self::Css,
+            "sh" | "bash" | "zsh" => Self::Shell,
+            "mk" | "make" => Self::Makefile,
+            "txt" | "text" => Self::PlainText,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    fn from_file_name(name: &str) -> Option<Self> {
+        let language = match name {
+            "Makefile" | "makefile" | "GNUmakefile" => Self::Makefile,
+            ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" | "PKGBUILD" => Self::Shell,
+            "Cargo.lock" => Self::Toml,
+            "Gemfile" | "Rakefile" => Self::Ruby,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Reads an interpreter from a `#!` line, looking through `env`.
+    fn from_shebang(first_line: &str) -> Option<Self> {
+        let command = first_line.strip_prefix("#!")?;
+        let mut words = command.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            program = words.find(|word| !word.starts_with('-'))?;
+        }
+
+        let language = match program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "python" => Self::Python,
+            "node" | "deno" | "bun" => Self::JavaScript,
+            "sh" | "bash" | "zsh" | "dash" | "ksh" => Self::Shell,
+            "lua" | "luajit" => Self::Lua,
+            "ruby" => Self::Ruby,
+            "perl" => Self::Perl,
+            "make" => Self::Makefile,
+            _ => return None,
+        };
+        Some(language)
+    }
+}
+
+/// Detects the language of `path`, falling back to the shebang in
+/// `first_line` for files without a known extension or name.
+pub fn detect(path: &Path, first_line: &str) -> Language {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(Language::from_extension)
+        .or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(Language::from_file_name)
+        })
+        .or_else(|| Language::from_shebang(first_line))
+        .unwrap_or(Language::PlainText)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_extension_then_file_name() {
+        assert_eq!(detect(Path::new("src/main.rs"), ""), Language::Rust);
+        assert_eq!(detect(Path::new("App.TSX"), ""), Language::TypeScriptJsx);
+        assert_eq!(detect(Path::new("Makefile"), ""), Language::Makefile);
+        assert_eq!(detect(Path::new("notes"), ""), Language::PlainText);
+    }
+
+    #[test]
+    fn reads_shebang_through_env() {
+        let script = Path::new("bin/deploy");
+        assert_eq!(detect(script, "#!/bin/bash"), Language::Shell);
+        assert_eq!(detect(script, "#!/usr/bin/env python3"), Language::Python);
+        assert_eq!(
+            detect(script, "#!/usr/bin/env -S node --no-warnings"),
+            Language::JavaScript
+        );
+    }
+}
@@ -51,7 +51,7 @@ pub fn ensure_lsp_paths() {
     // Collect candidate directories in priority order (highest first).
     let mut candidates: LinkedList<PathBuf> = LinkedList::new();
 
-    let home = match home_dir() {
+    let home = match crate::features::paths::home_dir() {
         Some(h) => h,
         None => {
             eprintln!("[lsp_setup] HOME not set – skipping PATH augmentation");
@@ -224,11 +224,6 @@ pub fn lsp_server_status() -> Vec<(&'static str, Option<PathBuf>)> {
 // Internal helpers
 // ---------------------------------------------------------------------------
 
-/// Returns the user's home directory from the `HOME` environment variable.
-fn home_dir() -> Option<PathBuf> {
-    std::env::var_os("HOME").map(PathBuf::from)
-}
-
 /// Appends `path` to `list` only if it is an existing directory.
 fn push_if_exists(list: &mut LinkedList<PathBuf>, path: PathBuf) {
     if path.is_dir() {
@@ -2,8 +2,10 @@ use iced::window;
 
 mod app;
 mod autocomplete;
+mod cli;
 mod config;
 mod features;
+mod language;
 mod lsp_setup;
 mod message;
 mod scripting;
@@ -33,12 +35,18 @@ fn main() -> iced::Result {
     let window_width = prefs.window_width.max(640.0);
     let window_height = prefs.window_height.max(480.0);
 
-    iced::application(app::App::new, app::App::update, app::App::view)
+    let mut application = iced::application(app::App::new, app::App::update, app::App::view)
         .title("Pinel")
         .subscription(|app| app.subscription())
         .font(FIRA_CODE_BOLD)
         .font(FIRA_CODE_REGULAR)
-        .font(SF_PRO)
+        .font(SF_PRO);
+    // Fonts dropped in the config folder, e.g. a Nerd Font for icons
+    for font in features::fonts::user_fonts() {
+        application = application.font(font);
+    }
+
+    application
         .default_font(iced::Font {
             family: iced::font::Family::Name("SF Pro"),
             ..iced::Font::DEFAULT
@@ -47,6 +55,8 @@ fn main() -> iced::Result {
         .window(window::Settings {
             size: [window_width, window_height].into(),
             icon: Some(icon),
+            // Closing asks about unsaved files first, see `Message::QuitRequested`
+            exit_on_close_request: false,
             ..Default::default()
         })
         .run()
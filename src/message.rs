@@ -1,3 +1,5 @@
+use crate::features::encoding::Encoding;
+use crate::features::git::GitChange;
 use crate::features::search::SearchResult;
 use iced_code_editor::LspOverlayMessage;
 use iced_term::Event as TerminalEvent;
@@ -11,36 +13,185 @@ pub enum Message {
     LspOverlay(LspOverlayMessage),
     /// Content changed notification (text, is_modified) for bookkeeping
     CodeEditorContentChanged,
+    /// Indent, outdent, reindent, comment or reflow the selected lines
+    EditLines(LineEdit),
+    /// Move or copy the selected lines one line up or down
+    MoveLines {
+        up: bool,
+        duplicate: bool,
+    },
+    /// Copy the selection right after itself, or the cursor line below it
+    DuplicateSelection,
+    /// Join the selected lines, or the cursor line and the one below it
+    JoinLines,
+    /// Sort, change the case of or base64 the selection or whole buffer
+    TransformSelection(Transform),
+    /// Add `delta` to the number after the cursor or on each selected line,
+    /// or `delta` times its place among them when `sequential`
+    StepNumbers {
+        delta: i64,
+        sequential: bool,
+    },
+    /// Grow or shrink the selection through words, strings and brackets
+    ExpandSelection,
+    ShrinkSelection,
+    /// Remove the brackets or quotes around the cursor
+    DeleteSurroundingPair,
+    /// Ask for text to add to each line of the selected block
+    PromptBlockEdit(BlockEdit),
+    ModifiersChanged(iced::keyboard::Modifiers),
+    /// Identifiers harvested from a buffer in the background for autocomplete
+    BufferWordsHarvested(PathBuf, Vec<String>),
     FileClicked(PathBuf),
-    FileOpened(PathBuf, String),
+    FileOpened(PathBuf, String, Encoding),
     SensitiveFileOpenConfirm(bool),
+    LargeFileOpen(LargeFileAction),
+    LargePasteConfirm(bool),
+    /// Answer to the prompt before Replace All writes the folder's files
+    ReplaceAllConfirm(bool),
+    /// Quit, asking first when files have unsaved changes
+    QuitRequested,
+    QuitConfirm(QuitAction),
     FolderToggled(PathBuf),
     FileTreeRefresh,
     ToggleSidebar,
+    /// Show or hide dotfiles and excluded files in the tree and finders
+    ToggleHiddenFiles,
+    /// Expand the file tree down to a file and select it
+    RevealInTree(PathBuf),
+    /// Copy a file's path, or its path in the open folder when `relative`
+    CopyPath {
+        path: PathBuf,
+        relative: bool,
+    },
     OpenFileDialog,
     OpenFolderDialog,
     FolderOpened(PathBuf),
+    /// Files of the folder at the path, listed in the background.
+    /// Files of the workspace for search and the index, and for the finder.
+    WorkspaceIndexed(
+        PathBuf,
+        Vec<(String, PathBuf)>,
+        Vec<crate::features::fuzzy_finder::FileEntry>,
+    ),
+    /// A job on the worker pool panicked before giving its result
+    WorkerFailed,
+    /// Opens a file, or a new buffer for a missing one, at a 1-based
+    /// position.
+    OpenLocation {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+    },
+    /// Saves the active file as UTF-8 from now on.
+    ConvertToUtf8,
+    /// Shows line, word and character counts of the document and selection.
+    ShowWordCount,
+    /// Writes swap files for unsaved buffers.
+    RecoveryTick,
+    /// Restores (`true`) or discards the buffers of a crashed session.
+    RestoreRecovery(bool),
+    /// Shows or hides the editing time dashboard.
+    ToggleActivity,
+    /// Shows or hides the saved versions of the active file.
+    ToggleLocalHistory,
+    /// Compares the active file with one of the versions listed in the
+    /// local history panel, by index.
+    CompareWithVersion(usize),
+    RestoreVersion(usize),
+    /// Jumps to the definition of the symbol at the cursor.
+    GoToDefinition,
+    /// Opens the command line with `rename` and the symbol at the cursor.
+    StartRename,
+    /// Applies (`true`) or drops the previewed rename.
+    RenameConfirm(bool),
+    /// Definitions of a symbol found without a language server.
+    DefinitionsFound(String, Vec<crate::features::ctags::Location>),
+    ToggleSpellCheck,
+    /// Moves the cursor to the next misspelled word.
+    NextMisspelling,
+    /// Offers replacements for the misspelled word at the cursor.
+    ShowSpellingSuggestions,
+    /// Replaces the misspelled word with a suggestion, or keeps it (`None`).
+    FixSpelling(Option<String>),
+    /// Adds the misspelled word to the dictionary of the open folder.
+    AddWordToDictionary,
+    /// An image or binary file read for a read-only viewer tab.
+    FileViewerOpened(PathBuf, crate::features::file_viewer::Viewer),
+    /// Saves the active file with these line endings from now on.
+    SetLineEnding(crate::features::line_endings::LineEnding),
+    /// A file or folder dragged onto the window.
+    PathDropped(PathBuf),
+    /// Compares the active buffer with the file at this path, or with its
+    /// own file on disk.
+    CompareActiveFile(Option<PathBuf>),
+    CompareActiveFileDialog,
+    /// Keeps the buffer's lines of a diff hunk or takes the other side's.
+    DiffHunkChoice {
+        hunk: usize,
+        take_theirs: bool,
+    },
+    /// Writes the chosen hunks into the buffer and closes the diff.
+    DiffApply,
+    DiffClose,
     SaveFile,
+    FormatDocument,
+    CloseOutputPanel,
+    /// Output of an external formatter, saved afterwards when `save` is set.
+    DocumentFormatted {
+        path: PathBuf,
+        result: Result<String, String>,
+        save: bool,
+    },
+    RunTask(String),
+    StopTask,
+    TaskEvent(crate::features::tasks::TaskEvent),
+    OutputLinkClicked(crate::features::output::Link),
     SaveCurrentFileAs(PathBuf),
     CurrentFileSavedAs(PathBuf),
-    FileSaved(Result<(), String>),
+    FileSaved(PathBuf, Result<(), crate::features::file_io::FileError>),
+    FileOpenFailed(PathBuf, crate::features::file_io::FileError),
+    /// Writes the tab with this path again after a failed save.
+    RetrySave(PathBuf),
+    /// Selects the tab with this path and asks where to save it.
+    SaveTabAs(PathBuf),
     InputLog(String),
 
     TabSelected(usize),
     TabClosed(usize),
+    /// Close several tabs from the tab menu
+    CloseTabs(CloseTabs),
+    /// Pins or unpins the tab, moving it to the end of the pinned tabs.
+    TogglePinTab(usize),
+    /// Opens or closes the list of every open tab.
+    ToggleTabList,
+    TabListClicked(usize),
     CloseActiveTab,
+    /// Opens the buffer switcher or moves its highlight.
+    BufferSwitcherNext(i32),
+    BufferSwitcherSelect,
+    BufferSwitcherClicked(usize),
+    ReopenClosedTab,
 
     SidebarResizeStart,
     SidebarResizing(f32),
     SidebarResizeEnd,
 
     PreviewMarkdown,
+    /// Keep the markdown preview scrolled along with the editor
+    TogglePreviewScrollLock,
     MarkdownLinkClicked(iced::widget::markdown::Uri),
 
     ToggleSearch,
     SearchQueryChanged(String),
     SearchCompleted(Vec<SearchResult>),
     SearchResultClicked(PathBuf, usize),
+    /// Returns to the place the last jump left.
+    JumpBack,
+    /// Goes to the place the last Go Back left.
+    JumpForward,
+    /// Ends the highlight of the text a jump landed on.
+    JumpFlashEnded,
     /// File finding (Cmd+T, legacy)
     ToggleFileFinder,
     FileFinderQueryChanged(String),
@@ -51,6 +202,11 @@ pub enum Message {
     FuzzyFinderQueryChanged(String),
     FuzzyFinderSelect,
     FuzzyFinderNavigate(i32),
+    /// Create the file typed into the fuzzy finder and open it
+    FuzzyFinderCreateFile(PathBuf),
+    /// A symbol picked in the fuzzy finder, by 0-based line.
+    FuzzyFinderSymbolClicked(usize),
+    GoToSymbol,
     /// Fullscreen and window management stuff
     ToggleFullscreen(iced::window::Mode),
     EscapePressed,
@@ -59,6 +215,15 @@ pub enum Message {
     CommandPaletteQueryChanged(String),
     CommandPaletteSelect(String),
     CommandPaletteNavigate(i32),
+    /// Source control panel (Cmd+Shift+G)
+    ToggleGitPanel,
+    GitStatusLoaded(Result<Vec<GitChange>, String>),
+    GitStage(String),
+    GitUnstage(String),
+    GitCommitMessageChanged(String),
+    GitCommit,
+    GitCommitted(Result<(), String>),
+    GitOperationFinished(Result<(), String>),
     /// Embedded terminal events
     TerminalEvent(TerminalEvent),
     /// Terminal panel (Cmd/Ctrl+J)
@@ -75,38 +240,100 @@ pub enum Message {
     ReplaceOne,
     ReplaceAll,
     ToggleCaseSensitive,
+    TogglePreserveCase,
+    FindScopeSelected(crate::features::find_replace::Scope),
+    /// Matches found for a workspace wide find, in the background.
+    FindCounted {
+        query: String,
+        matches: usize,
+        files: usize,
+    },
+    /// Files on disk changed by a workspace wide replace.
+    FilesReplaced(crate::features::find_replace::Replaced),
     /// Settings panel
     ToggleSettings,
     SettingsNavigate(String),
     SettingsTabSizeChanged(String),
     SettingsToggleUseSpaces,
+    SettingsToggleDetectIndentation,
+    SettingsToggleAutoClose,
+    SettingsCycleBackups,
+    CycleRenderWhitespace,
+    ToggleStickyScroll,
+    ToggleSmoothScroll,
+    /// Moves a smooth scroll one step along.
+    ScrollAnimationTick,
+    /// A pinned block header was clicked, with its 0-based line.
+    StickyHeaderClicked(usize),
+    /// Open the color picker for the literal at a 0-based line and column.
+    PickColor(usize, usize),
+    /// Replaces the merge conflict starting at this 0-based line.
+    ResolveConflict {
+        line: usize,
+        resolution: Resolution,
+    },
+    ColorPicked(iced::Color),
+    ColorPickCancelled,
+    ToggleWordWrap,
     SettingsSavePreferences,
     SettingsSelectTheme(String),
     SettingsReloadTheme,
     SettingsLineNumberWidthChanged(String),
+    SettingsScrolloffChanged(String),
+    SettingsFontFamilySelected(String),
+    /// Change the editor font size, saved to the preferences.
+    Zoom(Zoom),
+    /// Cut, copy or paste in the active editor with the system clipboard.
+    Clipboard(Clipboard),
+    /// Text read from the clipboard for pasting.
+    ClipboardRead(Option<String>),
     /// Vim-style command input
     ToggleCommandInput,
     CommandInputChanged(String),
     CommandInputSubmit,
+    CommandInputComplete,
+    /// A normalized key chord, see `features::keymap`.
+    UserKeyPressed(String),
     /// Window resize event
     WindowResized(u32, u32),
+    /// The window got focus back, open files may have changed meanwhile.
+    WindowFocused,
     /// New file
     NewFile,
     SaveAs,
+    /// Asks where to export the selection, or the active file, as HTML.
+    ExportHtml,
+    /// Writes an exported page to this path.
+    ExportHtmlWrite(PathBuf, String),
     /// WakaTime
     WakaTimeApiKeyChanged(String),
     WakaTimeApiKeyHoverStart,
     WakaTimeApiKeyHoverEnd,
     WakaTimeApiUrlChanged(String),
     SaveWakaTimeSettings,
+    /// Snippet sharing settings
+    SnippetEndpointChanged(String),
+    SnippetTokenChanged(String),
+    SaveSnippetSettings,
+    /// Uploads the selection, or the active file, to the snippet service.
+    ShareSnippet,
+    /// The snippet's link, or why the upload failed.
+    SnippetShared(Result<String, String>),
 
-    DismissNotification,
+    DismissNotification(usize),
+    /// Drops toasts that have been shown long enough.
+    NotificationTick,
+    ToggleNotificationHistory,
+    ClearNotifications,
     LspTick,
+    /// Typing paused long enough to run a query, see `App::debounce`.
+    Debounced(Debounce),
 
     // Developer mode
     ToggleDeveloperPanel,
     ClearDeveloperLogs,
     SettingsToggleDeveloperMode,
+    SettingsToggleUpdateCheck,
     ToggleLsp,
 
     // Updater
@@ -114,3 +341,104 @@ pub enum Message {
     UpdateAvailable(crate::features::updater::UpdateInfo),
     DismissUpdateBanner,
 }
+
+/// How to open a file that is over the large file threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LargeFileAction {
+    Open,
+    /// Open without syntax highlighting or a language server.
+    LargeFileMode,
+    /// Open only the beginning of the file in an unsaved tab.
+    Preview,
+    Cancel,
+}
+
+/// Answer to the unsaved changes prompt shown when quitting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuitAction {
+    SaveAndQuit,
+    QuitWithoutSaving,
+    Cancel,
+}
+
+/// Direction of an editor font size change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zoom {
+    In,
+    Out,
+    /// Back to the default size.
+    Reset,
+}
+
+/// Clipboard commands of the active editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Clipboard {
+    Cut,
+    Copy,
+    Paste,
+}
+
+/// Queries run once typing pauses rather than on every keystroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Debounce {
+    Autocomplete,
+    Find,
+    FuzzyFinder,
+}
+
+/// Text added to every line of the block between the selection's ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockEdit {
+    /// Insert at the block's left column.
+    Insert,
+    /// Append at the block's right column, padding short lines.
+    Append,
+}
+
+/// Tabs closed together from a tab's menu. Pinned tabs and tabs with
+/// unsaved changes are left open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseTabs {
+    /// Every tab but this one.
+    Others(usize),
+    /// The tabs after this one.
+    ToTheRight(usize),
+    /// Every tab without unsaved changes.
+    Saved,
+}
+
+/// Which side of a merge conflict replaces it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Current,
+    Incoming,
+    Both,
+}
+
+/// Line based edit applied to the selected lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEdit {
+    Indent,
+    Outdent,
+    /// Recompute indentation from the surrounding code.
+    Reindent,
+    /// Comment or uncomment the lines.
+    ToggleComment,
+    /// Wrap lines that are over the line length limit.
+    Reflow,
+}
+
+/// Rewrite of the selected text, or of the whole buffer without a selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    SortAscending,
+    SortDescending,
+    /// Sort, dropping repeated lines.
+    SortUnique,
+    Reverse,
+    UpperCase,
+    LowerCase,
+    TitleCase,
+    Base64Encode,
+    Base64Decode,
+}
@@ -1,14 +1,84 @@
-use mlua::{Lua, Result as LuaResult};
+use mlua::{Lua, Result as LuaResult, Value};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+/// Actions are palette command names, user command names, or command bar
+/// lines starting with `:`.
 #[derive(Debug, Clone)]
 pub enum EditorCommand {
     UseBuiltinTheme(String),
     SetThemeColor { name: String, value: String },
     SetSidebarVisible(bool),
     SetSidebarWidth(f32),
+    MapKey { keys: String, action: String },
+    AddCommand { name: String, actions: Vec<String> },
+    AddAutoCommand(AutoCommand),
+}
+
+/// Editor events autocommands run on, named like Vim's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorEvent {
+    /// A file was opened in a tab.
+    BufOpen,
+    /// A file is about to be written, its buffer can still be changed.
+    BufWritePre,
+    /// A file was written.
+    BufWritePost,
+    /// A folder was opened as the workspace.
+    FolderOpened,
+}
+
+impl EditorEvent {
+    /// Parses an event name. `open` and `save` are kept from before the
+    /// Vim names, for BufOpen and BufWritePost.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "BufOpen" | "open" => Some(Self::BufOpen),
+            "BufWritePre" => Some(Self::BufWritePre),
+            "BufWritePost" | "save" => Some(Self::BufWritePost),
+            "FolderOpened" => Some(Self::FolderOpened),
+            _ => None,
+        }
+    }
+}
+
+/// An action run when `event` happens to a file or folder matching `pattern`.
+#[derive(Debug, Clone)]
+pub struct AutoCommand {
+    pub event: EditorEvent,
+    /// File or folder name glob where `*` matches anything, e.g. `*.rs`.
+    pub pattern: String,
+    pub action: String,
+}
+
+impl AutoCommand {
+    pub fn matches(&self, event: EditorEvent, path: &Path) -> bool {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        self.event == event && glob_matches(&self.pattern, &name)
+    }
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
 }
 
 #[derive(Debug, Clone)]
@@ -118,8 +188,75 @@ pub fn eval_script(source: &str) -> Result<Vec<EditorCommand>, String> {
         ui.set("set_sidebar_width", f).map_err(|e| e.to_string())?;
     }
 
+    let keymap = lua.create_table().map_err(|e| e.to_string())?;
+    let command = lua.create_table().map_err(|e| e.to_string())?;
+
+    {
+        let commands = Arc::clone(&commands);
+        let f = lua
+            .create_function(
+                move |_, (keys, action): (String, String)| -> LuaResult<()> {
+                    commands
+                        .lock()
+                        .unwrap()
+                        .push(EditorCommand::MapKey { keys, action });
+                    Ok(())
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        keymap.set("set", f).map_err(|e| e.to_string())?;
+    }
+
+    {
+        let commands = Arc::clone(&commands);
+        let f = lua
+            .create_function(
+                move |lua, (name, actions): (String, Value)| -> LuaResult<()> {
+                    // A single action or a list of them
+                    let actions = match actions {
+                        Value::Table(list) => {
+                            list.sequence_values::<String>().collect::<LuaResult<_>>()?
+                        }
+                        other => vec![lua.unpack::<String>(other)?],
+                    };
+                    commands
+                        .lock()
+                        .unwrap()
+                        .push(EditorCommand::AddCommand { name, actions });
+                    Ok(())
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        command.set("add", f).map_err(|e| e.to_string())?;
+    }
+
+    {
+        let commands = Arc::clone(&commands);
+        let f = lua
+            .create_function(
+                move |_, (event, pattern, action): (String, String, String)| -> LuaResult<()> {
+                    let Some(event) = EditorEvent::from_name(&event) else {
+                        return Err(mlua::Error::runtime(format!("unknown event '{event}'")));
+                    };
+                    commands
+                        .lock()
+                        .unwrap()
+                        .push(EditorCommand::AddAutoCommand(AutoCommand {
+                            event,
+                            pattern,
+                            action,
+                        }));
+                    Ok(())
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        pinel.set("autocmd", f).map_err(|e| e.to_string())?;
+    }
+
     pinel.set("theme", theme).map_err(|e| e.to_string())?;
     pinel.set("ui", ui).map_err(|e| e.to_string())?;
+    pinel.set("keymap", keymap).map_err(|e| e.to_string())?;
+    pinel.set("command", command).map_err(|e| e.to_string())?;
     lua.globals().set("pinel", pinel).map_err(|e| e.to_string())?;
 
     lua.load(source).exec().map_err(|e| e.to_string())?;
@@ -127,3 +264,40 @@ pub fn eval_script(source: &str) -> Result<Vec<EditorCommand>, String> {
     let queued_commands = commands.lock().unwrap().clone();
     Ok(queued_commands)
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queues_keymaps_commands_and_autocommands() {
+        let source = r#"
+            pinel.keymap.set("ctrl+shift+k", "Delete Line")
+            pinel.command.add("Tidy", { ":%s/\\s+$//", "Save File" })
+            pinel.autocmd("save", "*.rs", ":%s/\\t/    /g")
+        "#;
+        let commands = eval_script(source).unwrap();
+        assert!(
+            matches!(&commands[0], EditorCommand::MapKey { keys, .. } if keys == "ctrl+shift+k")
+        );
+        assert!(
+            matches!(&commands[1], EditorCommand::AddCommand { actions, .. } if actions.len() == 2)
+        );
+        let EditorCommand::AddAutoCommand(autocommand) = &commands[2] else {
+            panic!("expected an autocommand");
+        };
+        assert!(autocommand.matches(EditorEvent::BufWritePost, Path::new("src/main.rs")));
+        assert!(!autocommand.matches(EditorEvent::BufWritePost, Path::new("main.rs.bak")));
+        assert!(!autocommand.matches(EditorEvent::BufOpen, Path::new("main.rs")));
+        assert_eq!(
+            EditorEvent::from_name("BufWritePre"),
+            Some(EditorEvent::BufWritePre)
+        );
+
+        assert!(eval_script(r#"pinel.autocmd("close", "*", "Quit")"#).is_err());
+    }
+}
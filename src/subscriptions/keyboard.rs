@@ -1,13 +1,13 @@
 //! Keyboard event subscription handlers.
 
-use crate::message::Message;
+use crate::message::{Clipboard, LineEdit, Message, Zoom};
 use iced::keyboard::Key;
 use iced::window;
 use iced::{Event, Subscription};
 
 /// Emits keyboard shortcut messages for global editor actions.
 pub fn shortcuts() -> Subscription<Message> {
-    iced::event::listen_with(|event, _status, _id| match event {
+    iced::event::listen_with(|event, status, _id| match event {
         Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. }) => {
             let primary = modifiers.command() || modifiers.control();
             let navigation_msg = match &key {
@@ -18,6 +18,16 @@ pub fn shortcuts() -> Subscription<Message> {
                     Some(Message::FuzzyFinderNavigate(1))
                 }
                 Key::Named(iced::keyboard::key::Named::Enter) => Some(Message::FuzzyFinderSelect),
+                Key::Named(iced::keyboard::key::Named::Tab) if modifiers.is_empty() => {
+                    Some(Message::CommandInputComplete)
+                }
+                Key::Named(iced::keyboard::key::Named::Tab) if modifiers.control() => {
+                    Some(Message::BufferSwitcherNext(if modifiers.shift() {
+                        -1
+                    } else {
+                        1
+                    }))
+                }
                 _ => None,
             };
 
@@ -35,20 +45,40 @@ pub fn shortcuts() -> Subscription<Message> {
                         "p" | "P" => return Some(Message::ToggleCommandPalette),
                         "s" | "S" => return Some(Message::ToggleSettings),
                         "o" | "O" => return Some(Message::OpenFolderDialog),
+                        "g" | "G" => return Some(Message::ToggleGitPanel),
+                        "t" | "T" => return Some(Message::ReopenClosedTab),
+                        "d" | "D" => return Some(Message::DuplicateSelection),
+                        "+" => return Some(Message::Zoom(Zoom::In)),
                         _ => {}
                     }
                 } else if primary {
+                    // Clipboard keys not taken by a focused widget, like Cmd
+                    // on macOS and cutting, which the editor leaves alone
+                    let free = status == iced::event::Status::Ignored;
                     match c.as_str() {
+                        "x" | "X" if free => return Some(Message::Clipboard(Clipboard::Cut)),
+                        "c" | "C" if free => return Some(Message::Clipboard(Clipboard::Copy)),
+                        "v" | "V" if free => return Some(Message::Clipboard(Clipboard::Paste)),
                         "b" | "r" => return Some(Message::ToggleSidebar),
                         "o" | "O" => return Some(Message::OpenFileDialog),
                         "w" | "W" => return Some(Message::CloseActiveTab),
                         "s" | "S" => return Some(Message::SaveFile),
                         "t" | "T" => return Some(Message::ToggleFileFinder),
+                        "p" | "P" => return Some(Message::ToggleFuzzyFinder),
                         "j" | "J" => return Some(Message::ToggleTerminal),
                         "f" | "F" => return Some(Message::ToggleFindReplace),
                         "n" | "N" => return Some(Message::NewFile),
+                        "g" | "G" => return Some(Message::ToggleCommandInput),
+                        "/" => return Some(Message::EditLines(LineEdit::ToggleComment)),
+                        "=" | "+" => return Some(Message::Zoom(Zoom::In)),
+                        "-" => return Some(Message::Zoom(Zoom::Out)),
+                        "0" => return Some(Message::Zoom(Zoom::Reset)),
                         _ => {}
                     }
+                } else if modifiers.alt() && modifiers.shift() && c.eq_ignore_ascii_case("f") {
+                    return Some(Message::FormatDocument);
+                } else if modifiers.alt() && c.as_str() == "z" {
+                    return Some(Message::ToggleWordWrap);
                 }
             }
 
@@ -58,8 +88,45 @@ pub fn shortcuts() -> Subscription<Message> {
                 }
             }
 
+            if modifiers.alt() && !primary && !modifiers.shift() {
+                match key {
+                    Key::Named(iced::keyboard::key::Named::ArrowLeft) => {
+                        return Some(Message::JumpBack);
+                    }
+                    Key::Named(iced::keyboard::key::Named::ArrowRight) => {
+                        return Some(Message::JumpForward);
+                    }
+                    _ => {}
+                }
+            }
+
+            if modifiers.is_empty() {
+                match key {
+                    Key::Named(iced::keyboard::key::Named::F2) => {
+                        return Some(Message::StartRename);
+                    }
+                    Key::Named(iced::keyboard::key::Named::F12) => {
+                        return Some(Message::GoToDefinition);
+                    }
+                    _ => {}
+                }
+            }
+
             None
         }
+        Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+            Some(Message::ModifiersChanged(modifiers))
+        }
+        _ => None,
+    })
+}
+
+/// Emits every key chord, for keymaps defined in init.lua.
+pub fn user_keymaps() -> Subscription<Message> {
+    iced::event::listen_with(|event, _status, _id| match event {
+        Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+            crate::features::keymap::chord_for(&key, modifiers).map(Message::UserKeyPressed)
+        }
         _ => None,
     })
 }
@@ -3,6 +3,12 @@
 use crate::message::Message;
 use iced::{window, Event, Subscription};
 
+/// Turns the window's close button and OS logout or shutdown requests into
+/// the same quit flow as the Quit command.
+pub fn close_requests() -> Subscription<Message> {
+    window::close_requests().map(|_| Message::QuitRequested)
+}
+
 /// Emits window resize messages to persist size preferences.
 pub fn resizes() -> Subscription<Message> {
     iced::event::listen_with(|event, _status, _id| match event {
@@ -13,3 +19,19 @@ pub fn resizes() -> Subscription<Message> {
         _ => None,
     })
 }
+
+/// Emits a message when the window gets focus back.
+pub fn focus_changes() -> Subscription<Message> {
+    iced::event::listen_with(|event, _status, _id| match event {
+        Event::Window(window::Event::Focused) => Some(Message::WindowFocused),
+        _ => None,
+    })
+}
+
+/// Emits a message for every file or folder dropped onto the window.
+pub fn file_drops() -> Subscription<Message> {
+    iced::event::listen_with(|event, _status, _id| match event {
+        Event::Window(window::Event::FileDropped(path)) => Some(Message::PathDropped(path)),
+        _ => None,
+    })
+}
@@ -1,7 +1,133 @@
-use iced::Element;
+use iced::advanced::widget::operation::{self, Operation};
+use iced::widget::scrollable::AbsoluteOffset;
+use iced::{Element, Point, Rectangle, Vector};
+use iced_code_editor::CodeEditor;
+use std::ops::Range;
 
 use crate::message::Message;
 
+/// Width of the line-number gutter of `iced_code_editor`.
+const GUTTER_WIDTH: f32 = 45.0;
+/// Space between the gutter and the text.
+const TEXT_PADDING: f32 = 5.0;
+
 pub fn empty_editor<'a>() -> Element<'a, Message> {
     iced::widget::text("").into()
 }
+
+/// Scrolls the editor inside the container `id` to `top` and `left`.
+///
+/// Tabs share the editor's scroll state, so a tab switched to starts out
+/// where the last one was scrolled. The editor scrolls to offsets only to
+/// follow its cursor, this sets its scrollbars directly instead.
+pub fn restore_scroll(id: iced::widget::Id, top: f32, left: f32) -> iced::Task<Message> {
+    struct RestoreScroll {
+        top: Option<f32>,
+        left: Option<f32>,
+    }
+
+    impl Operation for RestoreScroll {
+        fn traverse(&mut self, operate: &mut dyn FnMut(&mut dyn Operation)) {
+            operate(self);
+        }
+
+        fn scrollable(
+            &mut self,
+            _id: Option<&iced::widget::Id>,
+            bounds: Rectangle,
+            content_bounds: Rectangle,
+            _translation: Vector,
+            state: &mut dyn operation::Scrollable,
+        ) {
+            // The text scrolls vertically, the scrollbar under it horizontally
+            let offset = if content_bounds.height > bounds.height {
+                AbsoluteOffset {
+                    x: None,
+                    y: self.top.take(),
+                }
+            } else if content_bounds.width > bounds.width {
+                AbsoluteOffset {
+                    x: self.left.take(),
+                    y: None,
+                }
+            } else {
+                return;
+            };
+            state.scroll_to(offset);
+        }
+    }
+
+    let restore = RestoreScroll {
+        top: Some(top),
+        left: Some(left),
+    };
+    iced::advanced::widget::operate(operation::scope(id, restore)).discard()
+}
+
+/// Where an editor draws its text, for canvases stacked over it.
+///
+/// Wrapped lines are not laid out here, so there is no layout while word
+/// wrap is on.
+pub struct TextLayout<'a> {
+    editor: &'a CodeEditor,
+    left: f32,
+    scroll_x: f32,
+    scroll_y: f32,
+    line_height: f32,
+}
+
+impl<'a> TextLayout<'a> {
+    /// Layout of `editor`, scrolled `scroll_x` pixels to the right.
+    pub fn new(editor: &'a CodeEditor, scroll_x: f32) -> Option<Self> {
+        let line_height = editor.line_height();
+        if editor.wrap_enabled() || line_height <= 0.0 {
+            return None;
+        }
+        let gutter = if editor.line_numbers_enabled() {
+            GUTTER_WIDTH
+        } else {
+            0.0
+        };
+        Some(Self {
+            editor,
+            left: gutter + TEXT_PADDING,
+            scroll_x,
+            scroll_y: editor.viewport_scroll(),
+            line_height,
+        })
+    }
+
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
+    /// Left edge of the text, right of the gutter.
+    pub fn text_left(&self) -> f32 {
+        self.left
+    }
+
+    /// Lines on screen, of the `line_count` in the buffer.
+    pub fn visible_lines(&self, line_count: usize) -> Range<usize> {
+        let first = (self.scroll_y / self.line_height) as usize;
+        let visible = (self.editor.viewport_height() / self.line_height) as usize + 2;
+        first.min(line_count)..(first + visible).min(line_count)
+    }
+
+    /// Top of line `line` of the buffer.
+    pub fn line_top(&self, line: usize) -> f32 {
+        line as f32 * self.line_height - self.scroll_y
+    }
+
+    /// Top left corner of column `col` of `text`, line `line` of the
+    /// buffer, or `None` when it is scrolled under the gutter.
+    pub fn point(&self, line: usize, text: &str, col: usize) -> Option<Point> {
+        let prefix: String = text.chars().take(col).collect();
+        let x = self.left + self.editor.measure_text_width(&prefix) - self.scroll_x;
+        (x >= self.left).then_some(Point::new(x, self.line_top(line)))
+    }
+
+    /// Width of `text` as the editor lays it out.
+    pub fn width(&self, text: &str) -> f32 {
+        self.editor.measure_text_width(text)
+    }
+}
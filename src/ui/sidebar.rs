@@ -1,12 +1,12 @@
 use iced::widget::image;
-use iced::widget::{button, column, container, row, scrollable, text};
+use iced::widget::{button, column, container, row, scrollable, text, Space};
 use iced::{Element, Length};
 
 use crate::features::file_tree::{FileEntry, FileTree};
 use crate::features::icons::{get_file_icon, get_folder_icon, icon_handle, IconAsset};
 use crate::message::Message;
 use crate::theme::*;
-use crate::ui::styles::{sidebar_container_style, tree_button_style};
+use crate::ui::styles::{sidebar_container_style, tree_button_style, tree_selected_button_style};
 
 /// Create an icon element from embedded bytes.
 fn icon_widget<'a>(icon: IconAsset) -> Element<'a, Message> {
@@ -37,11 +37,35 @@ pub fn view_sidebar<'a>(file_tree: Option<&'a FileTree>, width: f32) -> Element<
 }
 
 fn view_file_tree(tree: &FileTree) -> Element<'_, Message> {
-    let mut items: Vec<Element<'_, Message>> = Vec::new();
+    let mut items: Vec<Element<'_, Message>> = vec![view_tree_header(tree)];
     render_entries(&tree.entries, tree, 0, &mut items);
     column(items).spacing(4).into()
 }
 
+/// The folder name and the button showing or hiding hidden files.
+fn view_tree_header(tree: &FileTree) -> Element<'_, Message> {
+    let name = tree
+        .root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_uppercase())
+        .unwrap_or_default();
+    let hidden_label = if tree.exclusions.shows_hidden() {
+        "Hide Hidden"
+    } else {
+        "Show Hidden"
+    };
+    row![
+        text(name).size(11).color(theme().text_muted),
+        Space::new().width(Length::Fill),
+        button(text(hidden_label).size(11))
+            .padding([1, 6])
+            .style(tree_button_style)
+            .on_press(Message::ToggleHiddenFiles),
+    ]
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
 fn view_empty_sidebar<'a>() -> Element<'a, Message> {
     container(
         column![
@@ -105,6 +129,11 @@ fn render_entries<'a>(
             }
             FileEntry::File { path, name } => {
                 let icon: Element<'_, Message> = icon_widget(get_file_icon(name));
+                let style = if tree.selected.as_ref() == Some(path) {
+                    tree_selected_button_style
+                } else {
+                    tree_button_style
+                };
 
                 let btn = button(
                     row![
@@ -115,7 +144,7 @@ fn render_entries<'a>(
                     .spacing(6)
                     .align_y(iced::Alignment::Center),
                 )
-                .style(tree_button_style)
+                .style(style)
                 .on_press(Message::FileClicked(path.clone()))
                 .padding(iced::Padding {
                     top: 6.0,
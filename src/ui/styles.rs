@@ -31,6 +31,22 @@ pub fn tree_button_style(_theme: &Theme, status: ButtonStatus) -> ButtonStyle {
     }
 }
 
+/// Tree row of the selected file, lit like a hovered one.
+pub fn tree_selected_button_style(_theme: &Theme, status: ButtonStatus) -> ButtonStyle {
+    let background = match status {
+        ButtonStatus::Pressed => theme().bg_pressed,
+        _ => theme().bg_hover,
+    };
+
+    ButtonStyle {
+        background: Some(Background::Color(background)),
+        text_color: theme().text_primary,
+        border: Border::default(),
+        shadow: Default::default(),
+        snap: false,
+    }
+}
+
 pub fn tab_button_style(is_active: bool) -> impl Fn(&Theme, ButtonStatus) -> ButtonStyle {
     move |_theme, status| {
         let (background, text_color) = if is_active {
@@ -69,6 +85,24 @@ pub fn tab_close_button_style(_theme: &Theme, _status: ButtonStatus) -> ButtonSt
     }
 }
 
+/// Menu opened by right clicking a tab.
+pub fn context_menu_style(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(lighten(theme().bg_tab_bar, 0.04))),
+        border: Border {
+            color: Color::from_rgba(1.0, 1.0, 1.0, 0.10),
+            width: 1.0,
+            radius: 6.0.into(),
+        },
+        shadow: iced::Shadow {
+            color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+            offset: Vector::new(0.0, 6.0),
+            blur_radius: 18.0,
+        },
+        ..Default::default()
+    }
+}
+
 pub fn editor_container_style(_theme: &Theme) -> container::Style {
     let t = theme();
     container::Style {
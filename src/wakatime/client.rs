@@ -2,13 +2,23 @@ use std::process::{Command, Stdio};
 
 use super::config::WakaTimeConfig;
 
-pub fn send_heartbeat(entity: &str, is_write: bool, cfg: &WakaTimeConfig) -> std::io::Result<()> {
+/// Activity in one file, reported through `wakatime-cli`.
+pub struct Heartbeat<'a> {
+    pub entity: &'a str,
+    /// Language name, left for WakaTime to detect when `None`.
+    pub language: Option<&'a str>,
+    /// Project name used when WakaTime can't find one from the repository.
+    pub project: Option<&'a str>,
+    pub is_write: bool,
+}
+
+pub fn send_heartbeat(heartbeat: &Heartbeat, cfg: &WakaTimeConfig) -> std::io::Result<()> {
     if cfg.api_key.trim().is_empty() {
         return Ok(());
     }
 
     let mut cmd = Command::new("wakatime-cli");
-    cmd.arg("--entity").arg(entity);
+    cmd.arg("--entity").arg(heartbeat.entity);
     cmd.arg("--plugin").arg("pinel/0.1.0");
     cmd.arg("--key").arg(cfg.api_key.trim());
 
@@ -16,7 +26,15 @@ pub fn send_heartbeat(entity: &str, is_write: bool, cfg: &WakaTimeConfig) -> std
         cmd.arg("--api-url").arg(cfg.api_url.trim());
     }
 
-    if is_write {
+    if let Some(language) = heartbeat.language {
+        cmd.arg("--language").arg(language);
+    }
+
+    if let Some(project) = heartbeat.project {
+        cmd.arg("--alternate-project").arg(project);
+    }
+
+    if heartbeat.is_write {
         cmd.arg("--write");
     }
 
@@ -18,8 +18,8 @@ impl Default for WakaTimeConfig {
 }
 
 fn get_config_dir() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home).join(".config").join("pinel")
+    let home = crate::features::paths::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home.join(".config").join("pinel")
 }
 
 fn get_wakatime_path() -> PathBuf {